@@ -0,0 +1,155 @@
+// Generates the minimal PE files used by the tests in src/domain/pe_version.rs.
+// Run with `node fixtures/pe_version/generate.cjs` from src-tauri.
+const fs = require('fs');
+const path = require('path');
+
+const FILE_ALIGNMENT = 0x200;
+const SECTION_ALIGNMENT = 0x1000;
+const RSRC_RVA = 0x1000;
+const RT_VERSION = 16;
+const RT_ICON = 3;
+
+const align = (n, a) => Math.ceil(n / a) * a;
+const pad4 = (buf) => Buffer.concat([buf, Buffer.alloc(align(buf.length, 4) - buf.length)]);
+const utf16z = (s) => Buffer.from(s + '\0', 'utf16le');
+
+// VS_VERSIONINFO, StringFileInfo, StringTable and String all share this layout
+function block(key, value, isText, children = []) {
+    const header = Buffer.alloc(6);
+    const body = Buffer.concat([
+        pad4(Buffer.concat([header, utf16z(key)])),
+        pad4(value),
+        ...children.map(pad4),
+    ]);
+    body.writeUInt16LE(body.length, 0);
+    body.writeUInt16LE(isText ? value.length / 2 : value.length, 2);
+    body.writeUInt16LE(isText ? 1 : 0, 4);
+    return body;
+}
+
+function fixedFileInfo() {
+    const buf = Buffer.alloc(52);
+    buf.writeUInt32LE(0xfeef04bd, 0);
+    buf.writeUInt32LE(0x00010000, 4);
+    return buf;
+}
+
+function versionInfo(tables) {
+    const stringTables = Object.entries(tables).map(([lang, strings]) =>
+        block(
+            lang,
+            Buffer.alloc(0),
+            true,
+            Object.entries(strings).map(([key, value]) => block(key, utf16z(value), true)),
+        ),
+    );
+    return block('VS_VERSION_INFO', fixedFileInfo(), false, [
+        block('StringFileInfo', Buffer.alloc(0), true, stringTables),
+    ]);
+}
+
+// Resource tree with a single type -> name 1 -> language entry
+function resourceSection(type, data) {
+    const dir = (id, offset) => {
+        const buf = Buffer.alloc(24);
+        buf.writeUInt16LE(1, 14);
+        buf.writeUInt32LE(id, 16);
+        buf.writeUInt32LE(offset, 20);
+        return buf;
+    };
+    const dataEntryOffset = 24 * 3;
+    const dataOffset = dataEntryOffset + 16;
+    const dataEntry = Buffer.alloc(16);
+    dataEntry.writeUInt32LE(RSRC_RVA + dataOffset, 0);
+    dataEntry.writeUInt32LE(data.length, 4);
+    return Buffer.concat([
+        dir(type, 0x80000000 + 24),
+        dir(1, 0x80000000 + 48),
+        dir(0x411, dataEntryOffset),
+        dataEntry,
+        data,
+    ]);
+}
+
+function pe(is64, rsrc) {
+    const optionalHeaderSize = is64 ? 240 : 224;
+    const headersSize = align(0x40 + 4 + 20 + optionalHeaderSize + 40, FILE_ALIGNMENT);
+    const rawSize = align(rsrc.length, FILE_ALIGNMENT);
+
+    const dos = Buffer.alloc(0x40);
+    dos.write('MZ', 0, 'ascii');
+    dos.writeUInt32LE(0x40, 0x3c);
+
+    const coff = Buffer.alloc(24);
+    coff.write('PE\0\0', 0, 'ascii');
+    coff.writeUInt16LE(is64 ? 0x8664 : 0x14c, 4);
+    coff.writeUInt16LE(1, 6);
+    coff.writeUInt16LE(optionalHeaderSize, 20);
+    coff.writeUInt16LE(0x0102, 22);
+
+    const optional = Buffer.alloc(optionalHeaderSize);
+    optional.writeUInt16LE(is64 ? 0x20b : 0x10b, 0);
+    optional.writeUInt32LE(SECTION_ALIGNMENT, 32);
+    optional.writeUInt32LE(FILE_ALIGNMENT, 36);
+    optional.writeUInt32LE(RSRC_RVA + align(rsrc.length, SECTION_ALIGNMENT), 56);
+    optional.writeUInt32LE(headersSize, 60);
+    optional.writeUInt16LE(2, 68);
+    const dataDirectories = is64 ? 112 : 96;
+    optional.writeUInt32LE(16, dataDirectories - 4);
+    optional.writeUInt32LE(RSRC_RVA, dataDirectories + 2 * 8);
+    optional.writeUInt32LE(rsrc.length, dataDirectories + 2 * 8 + 4);
+
+    const section = Buffer.alloc(40);
+    section.write('.rsrc', 0, 'ascii');
+    section.writeUInt32LE(rsrc.length, 8);
+    section.writeUInt32LE(RSRC_RVA, 12);
+    section.writeUInt32LE(rawSize, 16);
+    section.writeUInt32LE(headersSize, 20);
+    section.writeUInt32LE(0x40000040, 36);
+
+    const headers = Buffer.concat([dos, coff, optional, section]);
+    return Buffer.concat([
+        headers,
+        Buffer.alloc(headersSize - headers.length),
+        rsrc,
+        Buffer.alloc(rawSize - rsrc.length),
+    ]);
+}
+
+const fixtures = {
+    'pe32_ja.exe': pe(
+        false,
+        resourceSection(
+            RT_VERSION,
+            versionInfo({
+                '041104b0': {
+                    CompanyName: 'サンプルブランド',
+                    FileDescription: 'サクラノ詩',
+                    ProductName: 'サクラノ詩 -櫻の森の上を舞う-',
+                    FileVersion: '1.0.0.0',
+                },
+            }),
+        ),
+    ),
+    'pe32plus_multi_language.exe': pe(
+        true,
+        resourceSection(
+            RT_VERSION,
+            versionInfo({
+                '040904b0': {
+                    CompanyName: 'Sample Brand',
+                    ProductName: 'Sakura no Uta',
+                },
+                '041104b0': {
+                    CompanyName: 'サンプルブランド',
+                    ProductName: 'サクラノ詩',
+                },
+            }),
+        ),
+    ),
+    'no_version.exe': pe(false, resourceSection(RT_ICON, Buffer.alloc(16))),
+};
+
+for (const [name, data] of Object.entries(fixtures)) {
+    fs.writeFileSync(path.join(__dirname, name), data);
+}
@@ -0,0 +1,515 @@
+//! ゲーム名の候補を絞り込むための索引
+//!
+//! 走査ではファイルごとに全ゲーム名との編集距離を求めていたため、ゲーム名の文字の転置索引から
+//! 類似度の上限を求め、結果に関わりうるゲームだけ編集距離を求める。
+//! 2 文字以上の n-gram の一致数は LCS の上限にならず結果が変わりうるため、1 文字単位で数える
+
+use std::collections::HashMap;
+
+use super::{
+    all_game_cache::{AllGameCache, AllGameCacheOne},
+    distance::{get_comparable_distance, to_comparable_distance},
+    normalize::normalize,
+};
+
+/// 文字ごとの出現回数
+fn count_chars(s: &str) -> Vec<(char, u32)> {
+    let mut chars: Vec<char> = s.chars().collect();
+    chars.sort_unstable();
+    let mut counts: Vec<(char, u32)> = vec![];
+    for ch in chars {
+        match counts.last_mut() {
+            Some((last, count)) if *last == ch => *count += 1,
+            _ => counts.push((ch, 1)),
+        }
+    }
+    counts
+}
+
+struct IndexedName {
+    char_len: usize,
+    byte_len: usize,
+}
+
+struct Query<'a> {
+    text: &'a str,
+    char_len: usize,
+    /// ゲームごとの共通する文字数。LCS の長さの上限になる
+    common: Vec<u32>,
+}
+
+/// 閾値以下の候補のうち上位の類似度
+struct NearestScores {
+    limit: usize,
+    scores: Vec<f32>,
+}
+
+impl NearestScores {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            scores: Vec::with_capacity(limit + 1),
+        }
+    }
+
+    fn push(&mut self, score: f32) {
+        if self.excludes(score) {
+            return;
+        }
+        let position = self.scores.partition_point(|v| *v >= score);
+        self.scores.insert(position, score);
+        self.scores.truncate(self.limit);
+    }
+
+    /// 上位に入りえない類似度か。同じ類似度は先に現れたゲームが残るため、並びを決められるよう除かない
+    fn excludes(&self, score: f32) -> bool {
+        self.limit == 0
+            || (self.scores.len() >= self.limit && self.scores.last().is_some_and(|v| score < *v))
+    }
+}
+
+/// 正規化したゲーム名の索引。`AllGameCache` ごとに一度作り、走査するファイルの間で使い回す
+pub struct GameCandidateIndex {
+    games: AllGameCache,
+    names: Vec<IndexedName>,
+    positions: HashMap<i32, usize>,
+    /// 文字ごとの、その文字を含むゲームの位置と出現回数
+    postings: HashMap<char, Vec<(u32, u32)>>,
+    /// 詳細を取得済みのゲームの正規化したブランド名
+    brandnames: HashMap<i32, String>,
+}
+
+impl GameCandidateIndex {
+    pub fn new(games: AllGameCache) -> Self {
+        let mut names = Vec::with_capacity(games.len());
+        let mut positions = HashMap::with_capacity(games.len());
+        let mut postings: HashMap<char, Vec<(u32, u32)>> = HashMap::new();
+        for (position, game) in games.iter().enumerate() {
+            for (ch, count) in count_chars(&game.gamename) {
+                postings
+                    .entry(ch)
+                    .or_default()
+                    .push((position as u32, count));
+            }
+            names.push(IndexedName {
+                char_len: game.gamename.chars().count(),
+                byte_len: game.gamename.len(),
+            });
+            positions.entry(game.id).or_insert(position);
+        }
+        Self {
+            games,
+            names,
+            positions,
+            postings,
+            brandnames: HashMap::new(),
+        }
+    }
+
+    /// ゲーム ID ごとのブランド名を添える。空のブランド名は除く
+    pub fn with_brandnames(mut self, brandnames: HashMap<i32, String>) -> Self {
+        self.brandnames = brandnames
+            .into_iter()
+            .map(|(id, brandname)| (id, normalize(&brandname)))
+            .filter(|(_, brandname)| !brandname.is_empty())
+            .collect();
+        self
+    }
+
+    pub fn brandname(&self, id: i32) -> Option<&str> {
+        self.brandnames.get(&id).map(String::as_str)
+    }
+
+    pub fn get(&self, id: i32) -> Option<&AllGameCacheOne> {
+        self.positions
+            .get(&id)
+            .map(|position| &self.games[*position])
+    }
+
+    fn query<'a>(&self, text: &'a str) -> Query<'a> {
+        let mut common = vec![0; self.games.len()];
+        for (ch, count) in count_chars(text) {
+            if let Some(postings) = self.postings.get(&ch) {
+                for (position, game_count) in postings {
+                    common[*position as usize] += count.min(*game_count);
+                }
+            }
+        }
+        Query {
+            text,
+            char_len: text.chars().count(),
+            common,
+        }
+    }
+
+    /// 共通する文字がすべて LCS に使われたとした類似度。共通する文字がなければ実際の類似度と等しい
+    fn upper_bound(&self, query: &Query, position: usize) -> f32 {
+        let name = &self.names[position];
+        let lcs = query.common[position] as usize;
+        to_comparable_distance(
+            query.char_len + name.char_len - 2 * lcs,
+            query.text.len().max(name.byte_len),
+        )
+    }
+
+    /// 各クエリとの類似度の最大値を、結果に関わりうるゲームについてだけ求め、索引の順に返す
+    ///
+    /// 閾値を超えるゲームと、閾値以下で類似度が正のゲームのうち `is_ignored` でない上位 `limit` 件を含む。
+    /// 全ゲームと比べた場合と同じ候補と並びになる
+    pub fn score(
+        &self,
+        queries: &[&str],
+        threshold: f32,
+        limit: usize,
+        is_ignored: impl Fn(i32) -> bool,
+    ) -> Vec<(&AllGameCacheOne, f32)> {
+        let queries: Vec<Query> = queries.iter().map(|v| self.query(v)).collect();
+        let mut nearest = NearestScores::new(limit);
+        let is_nearest = |game: &AllGameCacheOne, score: f32| {
+            score > 0.0 && score <= threshold && !is_ignored(game.id)
+        };
+
+        let mut scores = vec![];
+        let mut pending = vec![];
+        for (position, game) in self.games.iter().enumerate() {
+            let mut upper_bound: f32 = 0.0;
+            let mut is_exact = true;
+            for query in queries.iter() {
+                upper_bound = upper_bound.max(self.upper_bound(query, position));
+                is_exact &= query.common[position] == 0;
+            }
+            if is_exact {
+                if is_nearest(game, upper_bound) {
+                    nearest.push(upper_bound);
+                }
+                scores.push((position, upper_bound));
+            } else {
+                pending.push((position, upper_bound));
+            }
+        }
+
+        // 上限の高い順に編集距離を求め、残りが閾値にも上位にも届かなくなったら打ち切る
+        pending.sort_by(|a, b| b.1.total_cmp(&a.1));
+        for (position, upper_bound) in pending {
+            if upper_bound <= threshold && (upper_bound <= 0.0 || nearest.excludes(upper_bound)) {
+                break;
+            }
+            let game = &self.games[position];
+            if upper_bound <= threshold && is_ignored(game.id) {
+                continue;
+            }
+            let score = queries.iter().fold(0.0f32, |val, query| {
+                if query.common[position] == 0 {
+                    val.max(self.upper_bound(query, position))
+                } else {
+                    val.max(get_comparable_distance(query.text, &game.gamename))
+                }
+            });
+            if is_nearest(game, score) {
+                nearest.push(score);
+            }
+            scores.push((position, score));
+        }
+
+        scores.sort_by_key(|(position, _)| *position);
+        scores
+            .into_iter()
+            .filter(|(_, score)| *score > threshold || (*score > 0.0 && !nearest.excludes(*score)))
+            .map(|(position, score)| (&self.games[position], score))
+            .collect()
+    }
+
+    /// いずれかの文字列の文字をすべて含むゲームを索引の順に返す。文字列を名前に含むゲームはすべて入る
+    pub fn games_with_chars_of(&self, texts: &[&str]) -> Vec<&AllGameCacheOne> {
+        let queries: Vec<Query> = texts.iter().map(|v| self.query(v)).collect();
+        self.games
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| {
+                queries
+                    .iter()
+                    .any(|query| query.common[*position] as usize == query.char_len)
+            })
+            .map(|(_, game)| game)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::domain::normalize::normalize;
+
+    const SYLLABLES: &[&str] = &[
+        "さ",
+        "く",
+        "ら",
+        "の",
+        "う",
+        "た",
+        "こ",
+        "い",
+        "す",
+        "る",
+        "おと",
+        "め",
+        "ほし",
+        "そら",
+        "つき",
+        "ゆめ",
+        "ひかり",
+        "がーる",
+        "ろーど",
+        "ふぁんたじあ",
+        "恋",
+        "詩",
+        "森",
+        "雪",
+        "桜",
+        "空",
+        "夏",
+        "夜",
+        "月",
+        "星",
+        "魔",
+        "法",
+        "学",
+        "園",
+        "物",
+        "語",
+        "a",
+        "b",
+        "x",
+        "fate",
+        "night",
+        "2",
+    ];
+
+    /// 再現できる擬似乱数
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, n: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) as usize) % n
+        }
+
+        fn title(&mut self) -> String {
+            let len = 2 + self.next(8);
+            (0..len)
+                .map(|_| SYLLABLES[self.next(SYLLABLES.len())])
+                .collect()
+        }
+    }
+
+    fn corpus(size: usize, seed: u64) -> AllGameCache {
+        let mut rng = Lcg(seed);
+        let mut games: AllGameCache = [
+            "サクラノ詩 -櫻の森の上を舞う-",
+            "サクラノ刻 -櫻の森の下を歩む-",
+            "恋する乙女と守護の楯",
+            "Fate/stay night",
+            "pieces/渡り鳥のソムニウム",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, v)| AllGameCacheOne::new(i as i32 + 1, normalize(v)))
+        .collect();
+        while games.len() < size {
+            let id = games.len() as i32 + 1;
+            games.push(AllGameCacheOne::new(id, rng.title()));
+        }
+        games
+    }
+
+    /// 実際のゲーム名を少し変えたものと無関係なものを混ぜた、フォルダ名・ファイル名の組
+    fn queries(games: &AllGameCache, size: usize, seed: u64) -> Vec<Vec<String>> {
+        let mut rng = Lcg(seed);
+        (0..size)
+            .map(|_| {
+                let game = &games[rng.next(games.len())].gamename;
+                let mut chars: Vec<char> = game.chars().collect();
+                if chars.len() > 2 {
+                    chars.remove(rng.next(chars.len()));
+                }
+                let parent: String = chars.into_iter().collect();
+                let parent = match rng.next(3) {
+                    0 => parent,
+                    1 => format!("{}体験版", parent),
+                    _ => rng.title(),
+                };
+                vec![rng.title(), parent, "game".to_string()]
+            })
+            .collect()
+    }
+
+    fn score_all<'a>(games: &'a AllGameCache, queries: &[&str]) -> Vec<(&'a AllGameCacheOne, f32)> {
+        games
+            .iter()
+            .map(|game| {
+                let score = queries.iter().fold(0.0f32, |val, query| {
+                    val.max(get_comparable_distance(query, &game.gamename))
+                });
+                (game, score)
+            })
+            .collect()
+    }
+
+    /// 閾値を超える候補と閾値以下の上位の候補の ID と類似度
+    type TopResults = (Vec<(i32, f32)>, Vec<(i32, f32)>);
+
+    /// 走査と同じ規則で、閾値を超える候補と閾値以下の上位の候補を求める
+    fn top_results(
+        scores: Vec<(&AllGameCacheOne, f32)>,
+        threshold: f32,
+        limit: usize,
+        is_ignored: impl Fn(i32) -> bool,
+    ) -> TopResults {
+        let (mut above, mut nearest): (Vec<_>, Vec<_>) = scores
+            .into_iter()
+            .filter(|(game, score)| !is_ignored(game.id) && *score > 0.0)
+            .map(|(game, score)| (game.id, score))
+            .partition(|(_, score)| *score > threshold);
+        for v in [&mut above, &mut nearest] {
+            v.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            v.truncate(limit);
+        }
+        (above, nearest)
+    }
+
+    #[test]
+    fn test_score_returns_same_top_results_as_full_scan() {
+        let games = corpus(2000, 1);
+        let index = GameCandidateIndex::new(games.clone());
+        let is_ignored = |id: i32| id % 7 == 0;
+        for (threshold, limit) in [(0.8, 3), (0.5, 5), (0.2, 1), (0.8, 0)] {
+            for query in queries(&games, 100, 2) {
+                let query: Vec<&str> = query.iter().map(String::as_str).collect();
+                assert_eq!(
+                    top_results(
+                        index.score(&query, threshold, limit, is_ignored),
+                        threshold,
+                        limit,
+                        is_ignored
+                    ),
+                    top_results(score_all(&games, &query), threshold, limit, is_ignored),
+                    "query: {:?}, threshold: {}, limit: {}",
+                    query,
+                    threshold,
+                    limit
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_score_keeps_ignored_games_above_threshold() {
+        let index = GameCandidateIndex::new(vec![
+            AllGameCacheOne::new(1, normalize("ゲームA")),
+            AllGameCacheOne::new(2, normalize("ゲームA")),
+        ]);
+        let scores = index.score(&[&normalize("ゲームA")], 0.8, 1, |id| id == 1);
+        assert_eq!(
+            scores.iter().map(|(v, _)| v.id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_score_handles_empty_names() {
+        let index = GameCandidateIndex::new(vec![
+            AllGameCacheOne::new(1, String::new()),
+            AllGameCacheOne::new(2, normalize("ゲーム")),
+        ]);
+        let games = vec![
+            AllGameCacheOne::new(1, String::new()),
+            AllGameCacheOne::new(2, normalize("ゲーム")),
+        ];
+        for query in [vec![""], vec!["", "げーむ"]] {
+            assert_eq!(
+                top_results(index.score(&query, 0.8, 3, |_| false), 0.8, 3, |_| false),
+                top_results(score_all(&games, &query), 0.8, 3, |_| false)
+            );
+        }
+    }
+
+    #[test]
+    fn test_games_with_chars_of_includes_every_partial_name() {
+        let games = corpus(3000, 3);
+        let index = GameCandidateIndex::new(games.clone());
+        for query in queries(&games, 200, 4) {
+            let texts = [query[0].as_str(), query[1].as_str()];
+            let expected: Vec<i32> = games
+                .iter()
+                .filter(|game| texts.iter().any(|v| game.gamename.contains(v)))
+                .map(|game| game.id)
+                .collect();
+            let found: Vec<i32> = index
+                .games_with_chars_of(&texts)
+                .into_iter()
+                .map(|game| game.id)
+                .filter(|id| expected.contains(id))
+                .collect();
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn test_get_returns_first_game_with_id() {
+        let index = GameCandidateIndex::new(vec![
+            AllGameCacheOne::new(1, "a".to_string()),
+            AllGameCacheOne::new(1, "b".to_string()),
+        ]);
+        assert_eq!(index.get(1).map(|v| v.gamename.as_str()), Some("a"));
+        assert!(index.get(2).is_none());
+    }
+
+    #[test]
+    fn test_with_brandnames_normalizes_and_skips_empty() {
+        let index = GameCandidateIndex::new(vec![AllGameCacheOne::new(1, "a".to_string())])
+            .with_brandnames(HashMap::from([
+                (1, "ＳＡＧＡ　ＰＬＡＮＥＴＳ".to_string()),
+                (2, "".to_string()),
+            ]));
+        assert_eq!(index.brandname(1), Some(normalize("SAGA PLANETS").as_str()));
+        assert_eq!(index.brandname(2), None);
+    }
+
+    /// `cargo test --release bench_ -- --ignored --nocapture` で全件との比較と速さを比べる
+    #[test]
+    #[ignore]
+    fn bench_score_against_full_scan() {
+        let games = corpus(30000, 5);
+        let queries = queries(&games, 2000, 6);
+
+        let start = Instant::now();
+        for query in queries.iter() {
+            let query: Vec<&str> = query.iter().map(String::as_str).collect();
+            top_results(score_all(&games, &query), 0.8, 3, |_| false);
+        }
+        let full_scan = start.elapsed();
+
+        let start = Instant::now();
+        let index = GameCandidateIndex::new(games.clone());
+        let build = start.elapsed();
+        for query in queries.iter() {
+            let query: Vec<&str> = query.iter().map(String::as_str).collect();
+            top_results(index.score(&query, 0.8, 3, |_| false), 0.8, 3, |_| false);
+        }
+        let indexed = start.elapsed();
+
+        println!(
+            "{} games x {} files: full scan {:?}, index {:?} (build {:?})",
+            games.len(),
+            queries.len(),
+            full_scan,
+            indexed,
+            build
+        );
+    }
+}
@@ -4,7 +4,17 @@ use serde::{Deserialize, Serialize};
 
 use super::Id;
 
-#[allow(dead_code)]
+#[derive(new, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub id: Id<Collection>,
+    pub name: String,
+    pub sort_order: i32,
+    pub element_count: i32,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
 #[derive(new, Debug)]
 pub struct NewCollection {
     pub name: String,
@@ -32,6 +42,7 @@ pub struct CollectionElement {
     pub thumbnail_height: Option<i32>,
     pub created_at: DateTime<Local>,
     pub updated_at: DateTime<Local>,
+    pub tags: Vec<String>,
 }
 
 #[derive(new, Debug)]
@@ -72,7 +72,12 @@ pub fn get_comparable_distance(a: &str, b: &str) -> f32 {
     let distance = Distance::new(a, b);
     let distance_value = distance.onp();
 
-    1.0 - (distance_value as f32 / a.len().max(b.len()) as f32)
+    to_comparable_distance(distance_value, a.len().max(b.len()))
+}
+
+/// 編集距離と長い方のバイト数から類似度を求める
+pub fn to_comparable_distance(distance_value: usize, max_len: usize) -> f32 {
+    1.0 - (distance_value as f32 / max_len as f32)
 }
 
 pub fn find_nearest<'a>(key: &str, list: &'a [(String, String)]) -> (Option<&'a str>, f32) {
@@ -0,0 +1,581 @@
+//! 他のランチャーやストアが記録したプレイ時間の読み込み
+//!
+//! いずれもローカルに保存されたファイルのみを読み、ネットワークには接続しない
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use anyhow::Context;
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{collection::CollectionElement, file::parse_local_datetime, Id};
+
+/// Playnite の Steam ライブラリプラグインの ID。このプラグインのゲームは GameId が Steam の App ID
+const PLAYNITE_STEAM_PLUGIN_ID: &str = "cb91dfc9-b977-43bf-8e70-55f46e410fab";
+
+/// プレイ時間の取り込み元
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExternalPlayTimeSource {
+    /// Playnite のライブラリを JSON で書き出したもの
+    Playnite,
+    /// Vnite のゲームのデータベースを JSON で書き出したもの
+    Vnite,
+    /// Steam の userdata/<id>/config/localconfig.vdf
+    Steam,
+}
+
+impl ExternalPlayTimeSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExternalPlayTimeSource::Playnite => "playnite",
+            ExternalPlayTimeSource::Vnite => "vnite",
+            ExternalPlayTimeSource::Steam => "steam",
+        }
+    }
+}
+
+impl FromStr for ExternalPlayTimeSource {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "playnite" => Ok(ExternalPlayTimeSource::Playnite),
+            "vnite" => Ok(ExternalPlayTimeSource::Vnite),
+            "steam" => Ok(ExternalPlayTimeSource::Steam),
+            _ => Err(anyhow::anyhow!("unknown external play time source: {}", s)),
+        }
+    }
+}
+
+/// 取り込み元の1つのゲームの記録
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalPlayRecord {
+    /// 取り込み元でのゲームの ID
+    pub external_id: String,
+    pub title: Option<String>,
+    pub steam_app_id: Option<u32>,
+    pub exe_path: Option<String>,
+    pub total_seconds: i64,
+    pub last_played_at: Option<DateTime<Local>>,
+    /// 開始と終了の日時が分かるプレイの記録
+    pub sessions: Vec<(DateTime<Local>, DateTime<Local>)>,
+}
+
+/// 取り込み済みの記録。同じ記録を再び取り込んだ場合は増えた分だけを加える
+#[derive(Debug, Clone)]
+pub struct ExternalPlayTimeImport {
+    pub source: ExternalPlayTimeSource,
+    pub external_id: String,
+    pub collection_element_id: Id<CollectionElement>,
+    /// 取り込み元の合計プレイ時間のうち反映済みのもの
+    pub imported_seconds: i64,
+    /// 取り込んだセッションのうち最も新しいものの終了日時
+    pub last_session_ended_at: Option<DateTime<Local>>,
+}
+
+/// ファイルの内容を読む。プレイ時間もセッションもない記録は含めない
+pub fn parse_external_play_records(
+    source: ExternalPlayTimeSource,
+    contents: &str,
+) -> anyhow::Result<Vec<ExternalPlayRecord>> {
+    // Windows で書き出したファイルは BOM 付きのことがある
+    let contents = contents.trim_start_matches('\u{feff}');
+    let records = match source {
+        ExternalPlayTimeSource::Playnite => parse_playnite_library(contents)?,
+        ExternalPlayTimeSource::Vnite => parse_vnite_database(contents)?,
+        ExternalPlayTimeSource::Steam => parse_steam_localconfig(contents)?,
+    };
+    Ok(records
+        .into_iter()
+        .filter(|v| v.total_seconds > 0 || !v.sessions.is_empty())
+        .collect())
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+struct PlayniteGame {
+    id: Option<String>,
+    name: Option<String>,
+    /// 秒
+    playtime: Option<u64>,
+    last_activity: Option<String>,
+    plugin_id: Option<String>,
+    game_id: Option<String>,
+    install_directory: Option<String>,
+    game_actions: Option<Vec<PlayniteGameAction>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "PascalCase")]
+struct PlayniteGameAction {
+    path: Option<String>,
+}
+
+/// ゲームの配列か、`Games` にゲームの配列を持つオブジェクト
+fn parse_playnite_library(contents: &str) -> anyhow::Result<Vec<ExternalPlayRecord>> {
+    let value: Value = serde_json::from_str(contents).context("invalid Playnite library")?;
+    let games = match value {
+        Value::Array(v) => v,
+        Value::Object(mut v) => match v.remove("Games").or_else(|| v.remove("games")) {
+            Some(Value::Array(v)) => v,
+            _ => anyhow::bail!("Playnite library has no games"),
+        },
+        _ => anyhow::bail!("Playnite library has no games"),
+    };
+
+    let mut records = vec![];
+    for game in games {
+        let game: PlayniteGame = serde_json::from_value(game).context("invalid Playnite game")?;
+        let Some(external_id) = non_empty(game.id.clone()).or_else(|| non_empty(game.name.clone()))
+        else {
+            continue;
+        };
+        let steam_app_id = game
+            .plugin_id
+            .as_deref()
+            .filter(|v| v.eq_ignore_ascii_case(PLAYNITE_STEAM_PLUGIN_ID))
+            .and(game.game_id.as_deref())
+            .and_then(|v| v.trim().parse().ok());
+        let install_dir = non_empty(game.install_directory);
+        // アクションのパスは {InstallDir} からの相対で書かれることが多い
+        let exe_path = game
+            .game_actions
+            .unwrap_or_default()
+            .into_iter()
+            .find_map(|action| non_empty(action.path))
+            .map(|path| match install_dir.as_deref() {
+                Some(dir) => path.replace("{InstallDir}", dir),
+                None => path,
+            })
+            .filter(|path| !path.contains('{'));
+        records.push(ExternalPlayRecord {
+            external_id,
+            title: non_empty(game.name),
+            steam_app_id,
+            exe_path,
+            total_seconds: game.playtime.unwrap_or_default() as i64,
+            last_played_at: game.last_activity.as_deref().and_then(parse_local_datetime),
+            sessions: vec![],
+        });
+    }
+    Ok(records)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct VniteGame {
+    #[serde(rename = "_id")]
+    id: Option<String>,
+    metadata: VniteMetadata,
+    record: VniteRecord,
+    path: VnitePath,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct VniteMetadata {
+    name: Option<String>,
+    original_name: Option<String>,
+    steam_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct VniteRecord {
+    /// ミリ秒
+    play_time: Option<u64>,
+    last_run_date: Option<String>,
+    timers: Vec<VniteTimer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct VniteTimer {
+    start: Option<String>,
+    end: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct VnitePath {
+    game_path: Option<String>,
+}
+
+/// ドキュメントの配列、`rows[].doc` を持つ書き出し、ID からドキュメントへのオブジェクトのいずれか
+fn parse_vnite_database(contents: &str) -> anyhow::Result<Vec<ExternalPlayRecord>> {
+    let value: Value = serde_json::from_str(contents).context("invalid Vnite database")?;
+    let docs: Vec<(Option<String>, Value)> = match value {
+        Value::Array(v) => v.into_iter().map(|doc| (None, doc)).collect(),
+        Value::Object(mut v) => match v.remove("rows") {
+            Some(Value::Array(rows)) => rows
+                .into_iter()
+                .filter_map(|mut row| row.get_mut("doc").map(Value::take))
+                .map(|doc| (None, doc))
+                .collect(),
+            _ => v.into_iter().map(|(id, doc)| (Some(id), doc)).collect(),
+        },
+        _ => anyhow::bail!("Vnite database has no games"),
+    };
+
+    let mut records = vec![];
+    for (key, doc) in docs {
+        if !doc.is_object() {
+            continue;
+        }
+        let game: VniteGame = serde_json::from_value(doc).context("invalid Vnite game")?;
+        let Some(external_id) = non_empty(game.id).or(key) else {
+            continue;
+        };
+        let mut sessions: Vec<_> = game
+            .record
+            .timers
+            .iter()
+            .filter_map(|timer| {
+                let start = parse_local_datetime(timer.start.as_deref()?)?;
+                let end = parse_local_datetime(timer.end.as_deref()?)?;
+                (end > start).then_some((start, end))
+            })
+            .collect();
+        sessions.sort();
+        records.push(ExternalPlayRecord {
+            external_id,
+            // 日本語のタイトルは originalName に入る
+            title: non_empty(game.metadata.original_name).or(non_empty(game.metadata.name)),
+            steam_app_id: game
+                .metadata
+                .steam_id
+                .as_deref()
+                .and_then(|v| v.trim().parse().ok()),
+            exe_path: non_empty(game.path.game_path),
+            total_seconds: (game.record.play_time.unwrap_or_default() / 1000) as i64,
+            last_played_at: game
+                .record
+                .last_run_date
+                .as_deref()
+                .and_then(parse_local_datetime),
+            sessions,
+        });
+    }
+    Ok(records)
+}
+
+/// KeyValues 形式の値
+#[derive(Debug, Clone, PartialEq)]
+enum VdfValue {
+    String(String),
+    Object(Vec<(String, VdfValue)>),
+}
+
+impl VdfValue {
+    /// Steam は同じキーの大文字小文字を揃えずに書くことがある
+    fn get(&self, key: &str) -> Option<&VdfValue> {
+        match self {
+            VdfValue::Object(entries) => entries
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            VdfValue::String(_) => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            VdfValue::String(v) => Some(v),
+            VdfValue::Object(_) => None,
+        }
+    }
+}
+
+enum VdfToken {
+    Text(String),
+    Open,
+    Close,
+}
+
+fn tokenize_vdf(contents: &str) -> anyhow::Result<Vec<VdfToken>> {
+    let mut tokens = vec![];
+    let mut chars = contents.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' => tokens.push(VdfToken::Open),
+            '}' => tokens.push(VdfToken::Close),
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(v) => text.push(v),
+                            None => anyhow::bail!("unterminated string in vdf"),
+                        },
+                        Some(v) => text.push(v),
+                        None => anyhow::bail!("unterminated string in vdf"),
+                    }
+                }
+                tokens.push(VdfToken::Text(text));
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for v in chars.by_ref() {
+                    if v == '\n' {
+                        break;
+                    }
+                }
+            }
+            v if v.is_whitespace() => {}
+            v => {
+                let mut text = v.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '{' | '}' | '"') {
+                        break;
+                    }
+                    text.push(next);
+                    chars.next();
+                }
+                tokens.push(VdfToken::Text(text));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_vdf(contents: &str) -> anyhow::Result<VdfValue> {
+    fn parse_entries(
+        tokens: &mut std::vec::IntoIter<VdfToken>,
+        nested: bool,
+    ) -> anyhow::Result<Vec<(String, VdfValue)>> {
+        let mut entries = vec![];
+        loop {
+            let key = match tokens.next() {
+                Some(VdfToken::Text(v)) => v,
+                Some(VdfToken::Close) if nested => return Ok(entries),
+                None if !nested => return Ok(entries),
+                _ => anyhow::bail!("unexpected token in vdf"),
+            };
+            let value = match tokens.next() {
+                Some(VdfToken::Text(v)) => VdfValue::String(v),
+                Some(VdfToken::Open) => VdfValue::Object(parse_entries(tokens, true)?),
+                _ => anyhow::bail!("missing value for {} in vdf", key),
+            };
+            entries.push((key, value));
+        }
+    }
+    let mut tokens = tokenize_vdf(contents)?.into_iter();
+    Ok(VdfValue::Object(parse_entries(&mut tokens, false)?))
+}
+
+/// `UserLocalConfigStore/Software/Valve/Steam/apps/<App ID>` の Playtime (分) と LastPlayed (UNIX 時間)
+fn parse_steam_localconfig(contents: &str) -> anyhow::Result<Vec<ExternalPlayRecord>> {
+    let root = parse_vdf(contents).context("invalid localconfig.vdf")?;
+    let apps = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"]
+        .iter()
+        .try_fold(&root, |value, key| value.get(key));
+    let Some(VdfValue::Object(apps)) = apps else {
+        return Ok(vec![]);
+    };
+
+    // 同じ App ID が重複して書かれている場合は後のものを使う
+    let mut by_app_id: BTreeMap<u32, ExternalPlayRecord> = BTreeMap::new();
+    for (key, app) in apps {
+        let Ok(app_id) = key.parse::<u32>() else {
+            continue;
+        };
+        let minutes: i64 = app
+            .get("Playtime")
+            .and_then(VdfValue::as_str)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default();
+        let last_played_at = app
+            .get("LastPlayed")
+            .and_then(VdfValue::as_str)
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .and_then(|v| Local.timestamp_opt(v, 0).single());
+        by_app_id.insert(
+            app_id,
+            ExternalPlayRecord {
+                external_id: app_id.to_string(),
+                title: None,
+                steam_app_id: Some(app_id),
+                exe_path: None,
+                total_seconds: minutes * 60,
+                last_played_at,
+                sessions: vec![],
+            },
+        );
+    }
+    Ok(by_app_id.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_steam_localconfig() {
+        let contents = r#"
+"UserLocalConfigStore"
+{
+	"Software"
+	{
+		"valve"
+		{
+			"Steam"
+			{
+				// コメントは読み飛ばす
+				"apps"
+				{
+					"1238020"
+					{
+						"LastPlayed"		"1672621200"
+						"Playtime"		"95"
+						"cloud"
+						{
+							"last_sync_state"		"synchronized"
+						}
+					}
+					"431960"
+					{
+						"Playtime"		"0"
+					}
+				}
+			}
+		}
+	}
+}
+"#;
+        let records = parse_external_play_records(ExternalPlayTimeSource::Steam, contents).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].external_id, "1238020");
+        assert_eq!(records[0].steam_app_id, Some(1238020));
+        assert_eq!(records[0].total_seconds, 95 * 60);
+        assert_eq!(
+            records[0].last_played_at,
+            Local.timestamp_opt(1672621200, 0).single()
+        );
+    }
+
+    #[test]
+    fn test_parse_steam_localconfig_invalid() {
+        assert!(
+            parse_external_play_records(ExternalPlayTimeSource::Steam, r#""apps" { "1" "#).is_err()
+        );
+        // apps がない場合は空
+        assert_eq!(
+            parse_external_play_records(
+                ExternalPlayTimeSource::Steam,
+                r#""UserLocalConfigStore" {}"#
+            )
+            .unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_parse_playnite_library() {
+        let contents = r#"{
+            "Games": [
+                {
+                    "Id": "0d6a4d1e-0000-0000-0000-000000000001",
+                    "Name": "サクラノ刻",
+                    "Playtime": 7200,
+                    "LastActivity": "2023-01-02T10:00:00+09:00",
+                    "InstallDirectory": "C:\\Games\\sakura",
+                    "GameActions": [{ "Path": "{InstallDir}\\sakura.exe" }]
+                },
+                {
+                    "Id": "0d6a4d1e-0000-0000-0000-000000000002",
+                    "Name": "Steam Game",
+                    "Playtime": 60,
+                    "PluginId": "CB91DFC9-B977-43BF-8E70-55F46E410FAB",
+                    "GameId": "1238020"
+                },
+                { "Id": "0d6a4d1e-0000-0000-0000-000000000003", "Name": "未プレイ" }
+            ]
+        }"#;
+        let records =
+            parse_external_play_records(ExternalPlayTimeSource::Playnite, contents).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].title.as_deref(), Some("サクラノ刻"));
+        assert_eq!(
+            records[0].exe_path.as_deref(),
+            Some("C:\\Games\\sakura\\sakura.exe")
+        );
+        assert_eq!(records[0].steam_app_id, None);
+        assert_eq!(records[0].total_seconds, 7200);
+        assert_eq!(
+            records[0].last_played_at,
+            Some(
+                DateTime::parse_from_rfc3339("2023-01-02T01:00:00Z")
+                    .unwrap()
+                    .with_timezone(&Local)
+            )
+        );
+        assert_eq!(records[1].steam_app_id, Some(1238020));
+        assert_eq!(records[1].exe_path, None);
+    }
+
+    #[test]
+    fn test_parse_vnite_database() {
+        let contents = r#"{
+            "rows": [
+                {
+                    "id": "abc",
+                    "doc": {
+                        "_id": "abc",
+                        "metadata": { "name": "Sakura no Toki", "originalName": "サクラノ刻", "steamId": "" },
+                        "record": {
+                            "playTime": 5400000,
+                            "lastRunDate": "2023-01-03T12:00:00.000Z",
+                            "timers": [
+                                { "start": "2023-01-03T11:00:00.000Z", "end": "2023-01-03T12:00:00.000Z" },
+                                { "start": "2023-01-02T11:00:00.000Z", "end": "2023-01-02T11:30:00.000Z" },
+                                { "start": "2023-01-04T11:00:00.000Z", "end": "2023-01-04T10:00:00.000Z" }
+                            ]
+                        },
+                        "path": { "gamePath": "D:\\vn\\sakura\\sakura.exe" }
+                    }
+                },
+                { "id": "_design/index", "doc": { "views": {} } }
+            ]
+        }"#;
+        let records = parse_external_play_records(ExternalPlayTimeSource::Vnite, contents).unwrap();
+
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.external_id, "abc");
+        assert_eq!(record.title.as_deref(), Some("サクラノ刻"));
+        assert_eq!(record.steam_app_id, None);
+        assert_eq!(record.total_seconds, 5400);
+        // 終了が開始より前の記録は除き、古い順に並べる
+        assert_eq!(record.sessions.len(), 2);
+        assert!(record.sessions[0].0 < record.sessions[1].0);
+        assert_eq!(
+            (record.sessions[1].1 - record.sessions[1].0).num_seconds(),
+            3600
+        );
+    }
+
+    #[test]
+    fn test_parse_vnite_database_map() {
+        let contents = r#"{
+            "abc": { "metadata": { "name": "Game", "steamId": "431960" }, "record": { "playTime": 60000 } }
+        }"#;
+        let records = parse_external_play_records(ExternalPlayTimeSource::Vnite, contents).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].external_id, "abc");
+        assert_eq!(records[0].steam_app_id, Some(431960));
+        assert_eq!(records[0].total_seconds, 60);
+    }
+}
@@ -13,7 +13,7 @@ use std::{
     sync::Arc,
 };
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
 use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, ColorType, GenericImageView};
 use serde::{Deserialize, Serialize};
 use tauri::{async_runtime::JoinHandle, AppHandle};
@@ -35,8 +35,14 @@ use crate::infrastructure::util::get_save_root_abs_dir;
 
 use super::{
     all_game_cache::{AllGameCache, AllGameCacheOne},
+    candidate_index::GameCandidateIndex,
     collection::CollectionElement,
     distance::get_comparable_distance,
+    learned_match::LearnedGameMatches,
+    matching_rule::{MatchingRuleHit, MatchingRuleKind, MatchingRules},
+    normalize::normalize,
+    pe_version::{get_exe_version_info, PeVersionInfo},
+    scan::{GameCandidateMatch, ScanRule, SCAN_CANDIDATE_LIMIT, SCAN_MATCH_THRESHOLD},
     Id,
 };
 
@@ -50,77 +56,6 @@ impl WString for &str {
     }
 }
 
-const NOT_GAME_EQUALLY_WORD: [&str; 3] = ["bgi", "siglusengine", "nscripter"];
-
-const NOT_GAME_TERMS: [&str; 16] = [
-    "マニュアル",
-    "詳細設定",
-    "はじめに",
-    "サポート",
-    "セーブデータ",
-    "インストール",
-    "アンインストール",
-    "体験版",
-    "install",
-    "uninstall",
-    "autorun",
-    "削除",
-    "license",
-    "ライセンス",
-    "公式サイト",
-    "ホームページ",
-];
-/// ファイル名がインストーラーやマニュアル等に該当するか（完全に除外すべきワード）
-fn not_game_strictly(filename: &str) -> bool {
-    let filename_lower = filename.to_lowercase();
-    NOT_GAME_TERMS
-        .iter()
-        .any(|term| filename_lower.contains(term))
-}
-
-/// ファイル名がBGI等の汎用エンジン名か（ファイル名マッチのみスキップ、親フォルダ推定は継続）
-fn is_engine_name(filename: &str) -> bool {
-    let filename_lower = filename.to_lowercase();
-    NOT_GAME_EQUALLY_WORD
-        .iter()
-        .any(|word| filename_lower == *word)
-}
-
-const REMOVE_WORDS: [&str; 9] = [
-    "を起動",
-    "の起動",
-    "_単独動作版",
-    "「",
-    "」",
-    " ",
-    "　",
-    "ダウンロード版",
-    "DL版",
-];
-
-/// ファイル名から不要なワードを削除
-fn remove_word(filename: &str) -> String {
-    REMOVE_WORDS
-        .iter()
-        .fold(filename.to_string(), |acc, word| acc.replace(word, ""))
-}
-
-const IGNORE_GAME_ID: [i32; 4] = [2644, 63, 2797, 10419];
-
-// (string, i32)でstringがファイル名といっちした場合はこのゲームにする
-const EQUALLY_FILENAME_GAME_ID_PAIR: [(&str, i32); 1] = [("pieces", 27123)];
-const PATH_SPECIFIC_GAME_ID_RULES: [(&str, &str, i32); 2] =
-    [("枕", "サクラノ詩", 4529), ("nekoneko", "すみれ", 20178)];
-
-fn get_path_specific_game_id(grandparent: Option<&str>, parent: &str) -> Option<i32> {
-    PATH_SPECIFIC_GAME_ID_RULES
-        .iter()
-        .find(|(rule_grandparent, rule_parent, _)| {
-            grandparent == Some(*rule_grandparent) && parent == *rule_parent
-        })
-        .map(|(_, _, id)| *id)
-}
-
 pub fn get_file_name_without_extension(file_path: &str) -> Option<String> {
     let path = Path::new(file_path);
     if let Some(file_name) = path.file_name() {
@@ -134,20 +69,6 @@ pub fn get_file_name_without_extension(file_path: &str) -> Option<String> {
     None
 }
 
-pub fn normalize(s: &str) -> String {
-    let mut result = String::new();
-    for ch in s.chars() {
-        if ('Ａ'..='Ｚ').contains(&ch) || ('ａ'..='ｚ').contains(&ch) {
-            result.push((ch as u32 - 'Ａ' as u32 + 'A' as u32) as u8 as char);
-        } else if ('０'..='９').contains(&ch) {
-            result.push((ch as u32 - '０' as u32 + '0' as u32) as u8 as char);
-        } else {
-            result.push(ch);
-        }
-    }
-    result.to_lowercase()
-}
-
 pub fn get_file_paths_by_exts(
     explorer_dir_path: String,
     filter_exts: Vec<String>,
@@ -424,23 +345,77 @@ pub fn get_lnk_metadatas(lnk_file_paths: Vec<&str>) -> anyhow::Result<HashMap<&s
     Ok(metadatas)
 }
 
-pub fn get_most_probable_game_candidate(
-    id_name_pairs: &AllGameCache,
-    filepath: String,
-) -> anyhow::Result<Option<(AllGameCacheOne, String)>> {
-    let candidates: AllGameCache =
-        get_game_candidates_by_exe_path(id_name_pairs, &filepath, 0.8, 1)?;
-    Ok(candidates
-        .first()
-        .map(|candidate| (candidate.clone(), filepath)))
+pub fn get_most_probable_game_match(
+    rules: &MatchingRules,
+    learned: &LearnedGameMatches,
+    index: &GameCandidateIndex,
+    filepath: &str,
+    version_info: Option<&PeVersionInfo>,
+) -> anyhow::Result<GameCandidateMatch> {
+    match_game_candidates_by_exe_path(
+        rules,
+        learned,
+        index,
+        filepath,
+        version_info,
+        SCAN_MATCH_THRESHOLD,
+        SCAN_CANDIDATE_LIMIT,
+    )
 }
 
 pub fn get_game_candidates_by_exe_path(
-    id_name_pairs: &AllGameCache,
+    rules: &MatchingRules,
+    learned: &LearnedGameMatches,
+    index: &GameCandidateIndex,
     filepath: &str,
     threshould: f32,
     candidate_limit: usize,
 ) -> anyhow::Result<AllGameCache> {
+    let matched = match_game_candidates_by_exe_path(
+        rules,
+        learned,
+        index,
+        filepath,
+        get_exe_version_info(filepath).as_ref(),
+        threshould,
+        candidate_limit,
+    )?;
+    if !matched.rule.is_matched() {
+        return Ok(vec![]);
+    }
+    Ok(matched
+        .candidates
+        .into_iter()
+        .map(|(candidate, _)| candidate)
+        .collect())
+}
+
+/// ファイルのパスからゲームの候補を類似度とともに求める
+///
+/// ユーザーが直した紐づけがあれば他の規則より先に使う。
+/// 閾値を超える候補がない場合は、閾値以下で類似度の高い候補を `BelowThreshold` として返す。
+/// 類似度は索引で絞り込んだゲームについてだけ求める。
+/// 実行ファイルのバージョン情報があれば製品名・説明もゲーム名と比べ、
+/// 会社名とブランド名が一致する候補を閾値を超える候補の先頭にする
+pub fn match_game_candidates_by_exe_path(
+    rules: &MatchingRules,
+    learned: &LearnedGameMatches,
+    index: &GameCandidateIndex,
+    filepath: &str,
+    version_info: Option<&PeVersionInfo>,
+    threshould: f32,
+    candidate_limit: usize,
+) -> anyhow::Result<GameCandidateMatch> {
+    if let Some((_, game_id)) = learned.find(filepath) {
+        if let Some(candidate) = index.get(game_id) {
+            return Ok(GameCandidateMatch::new(
+                ScanRule::LearnedMatch,
+                vec![(candidate.clone(), 1.0)],
+                vec![],
+            ));
+        }
+    }
+
     // 親フォルダ名（直接の親）
     let parent = Path::new(&filepath)
         .parent()
@@ -461,44 +436,78 @@ pub fn get_game_candidates_by_exe_path(
         get_file_name_without_extension(filepath).ok_or(anyhow::anyhow!("can not get filename"))?;
     let filename = normalize(&filename);
 
+    let mut hits = vec![];
+
     // インストーラー・マニュアル等は親フォルダ名に関わらず完全除外
-    if not_game_strictly(&filename) {
-        return Ok(vec![]);
+    if let Some(term) = rules.find_not_game_term(&filename) {
+        hits.push(MatchingRuleHit::new(
+            MatchingRuleKind::NotGameTerm,
+            term.to_string(),
+            None,
+        ));
+        return Ok(GameCandidateMatch::new(ScanRule::NotGameWord, vec![], hits));
     }
 
     // BGI・SiglusEngine等の汎用エンジン名はファイル名判定のみスキップ、親フォルダ名判定は継続
-    let is_skip_filename_check =
-        is_engine_name(&filename) || filename == "game" || filename == "start";
-
-    let filename = remove_word(&filename);
+    let is_skip_filename_check = rules.is_generic_file_name(&filename);
+    if is_skip_filename_check {
+        hits.push(MatchingRuleHit::new(
+            MatchingRuleKind::GenericFileName,
+            filename.clone(),
+            None,
+        ));
+    }
 
-    let mut distance_pairs = vec![];
+    let (filename, removed_words) = rules.remove_words(&filename);
+    hits.extend(
+        removed_words
+            .into_iter()
+            .map(|word| MatchingRuleHit::new(MatchingRuleKind::RemoveWord, word.to_string(), None)),
+    );
+
+    // バージョン情報の製品名・説明。ファイル名と同じく汎用的な名前や除外する語を含むものは使わない
+    let version_names: Vec<String> = version_info
+        .into_iter()
+        .flat_map(|v| v.names())
+        .map(normalize)
+        .filter(|v| {
+            !v.is_empty() && !rules.is_generic_file_name(v) && rules.find_not_game_term(v).is_none()
+        })
+        .map(|v| rules.remove_words(&v).0)
+        .filter(|v| !v.is_empty())
+        .collect();
 
-    if let Some(id) = get_path_specific_game_id(grandparent.as_deref(), &parent) {
-        if let Some(candidate) = id_name_pairs.iter().find(|v| v.id == id) {
-            return Ok(vec![candidate.clone()]);
+    if let Some(rule) = rules.find_path_specific_rule(grandparent.as_deref(), &parent) {
+        if let Some(candidate) = index.get(rule.game_id) {
+            hits.push(MatchingRuleHit::new(
+                MatchingRuleKind::PathSpecific,
+                format!("{}/{}", rule.grandparent, rule.parent),
+                Some(rule.game_id),
+            ));
+            return Ok(GameCandidateMatch::new(
+                ScanRule::PathSpecific,
+                vec![(candidate.clone(), 1.0)],
+                hits,
+            ));
         }
     }
 
-    for (equally_filename, id) in EQUALLY_FILENAME_GAME_ID_PAIR {
-        if filename == *equally_filename {
-            if let Some(candidate) = id_name_pairs.iter().find(|v| v.id == id) {
-                return Ok(vec![candidate.clone()]);
-            }
+    if let Some(rule) = rules.find_equal_filename_rule(&filename) {
+        if let Some(candidate) = index.get(rule.game_id) {
+            hits.push(MatchingRuleHit::new(
+                MatchingRuleKind::EqualFilename,
+                rule.filename.clone(),
+                Some(rule.game_id),
+            ));
+            return Ok(GameCandidateMatch::new(
+                ScanRule::EqualFilename,
+                vec![(candidate.clone(), 1.0)],
+                hits,
+            ));
         }
     }
 
-    for pair in id_name_pairs.iter() {
-        let mut is_ignore = false;
-        for ignore_id in IGNORE_GAME_ID {
-            if pair.id == ignore_id {
-                is_ignore = true;
-            }
-        }
-        if is_ignore {
-            continue;
-        }
-
+    let get_distance = |pair: &AllGameCacheOne| {
         let mut val: f32 = 0.0;
         if !is_skip_filename_check {
             val = val.max(get_comparable_distance(&filename, &pair.gamename));
@@ -510,13 +519,61 @@ pub fn get_game_candidates_by_exe_path(
         if let Some(ref gp) = grandparent {
             val = val.max(get_comparable_distance(gp, &pair.gamename));
         }
+        for name in version_names.iter() {
+            val = val.max(get_comparable_distance(name, &pair.gamename));
+        }
+        val
+    };
+
+    let mut queries = vec![];
+    if !is_skip_filename_check {
+        queries.push(filename.as_str());
+    }
+    queries.push(parent.as_str());
+    if let Some(ref gp) = grandparent {
+        queries.push(gp.as_str());
+    }
+    queries.extend(version_names.iter().map(String::as_str));
+
+    let mut distance_pairs = vec![];
+    // 閾値以下の候補のうち類似度の高いもの
+    let mut nearest_pairs: Vec<(AllGameCacheOne, f32)> = vec![];
+
+    for (pair, val) in index.score(&queries, threshould, candidate_limit, |id| {
+        rules.is_ignored_game_id(id)
+    }) {
+        if rules.is_ignored_game_id(pair.id) {
+            // 規則がなければ候補になったものだけを当てはまった規則として残す
+            if val > threshould {
+                hits.push(MatchingRuleHit::new(
+                    MatchingRuleKind::IgnoreGameId,
+                    pair.gamename.clone(),
+                    Some(pair.id),
+                ));
+            }
+            continue;
+        }
+
         if val > threshould {
             distance_pairs.push((pair.clone(), val));
+        } else if val > 0.0
+            && (nearest_pairs.len() < candidate_limit
+                || nearest_pairs.last().is_some_and(|(_, v)| *v < val))
+        {
+            nearest_pairs.push((pair.clone(), val));
+            nearest_pairs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            nearest_pairs.truncate(candidate_limit);
         }
     }
 
+    let mut rule = ScanRule::Similarity;
     if distance_pairs.is_empty() {
-        for pair in id_name_pairs.iter() {
+        rule = ScanRule::PartialName;
+        let partial_names = [filename.as_str(), parent.as_str()]
+            .into_iter()
+            .filter(|v| v.len() > 5)
+            .collect::<Vec<_>>();
+        for pair in index.games_with_chars_of(&partial_names) {
             if filename.len() > 5 && pair.gamename.contains(&filename) {
                 distance_pairs.push((pair.clone(), filename.len() as f32));
             }
@@ -525,105 +582,78 @@ pub fn get_game_candidates_by_exe_path(
             }
         }
     }
-
-    distance_pairs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-
-    let mut res = vec![];
-    for (pair, _) in distance_pairs {
-        if res.len() < candidate_limit {
-            res.push(pair)
-        }
-    }
-    Ok(res)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    // ========================================
-    // not_game のテスト
-    // ========================================
-    #[test]
-    fn test_not_game_returns_true_for_installer() {
-        // Arrange & Act & Assert
-        assert!(not_game_strictly("インストール"));
-        assert!(not_game_strictly("アンインストール"));
-        assert!(not_game_strictly("install"));
-        assert!(not_game_strictly("uninstall"));
-    }
-
-    #[test]
-    fn test_not_game_returns_true_for_manual() {
-        assert!(not_game_strictly("マニュアル"));
-        assert!(not_game_strictly("はじめに"));
-        assert!(not_game_strictly("サポート"));
-    }
-
-    #[test]
-    fn test_not_game_returns_false_for_game() {
-        assert!(!not_game_strictly("game"));
-        assert!(!not_game_strictly("start"));
-        assert!(!not_game_strictly("サクラノ詩"));
-    }
-
-    #[test]
-    fn test_not_game_case_insensitive() {
-        // 大文字小文字を区別しない
-        assert!(not_game_strictly("INSTALL"));
-        assert!(not_game_strictly("Install"));
-    }
-
-    // ========================================
-    // remove_word のテスト
-    // ========================================
-    #[test]
-    fn test_remove_word_removes_launch_suffix() {
-        assert_eq!(remove_word("ゲームを起動"), "ゲーム");
-        assert_eq!(remove_word("ゲームの起動"), "ゲーム");
+    if distance_pairs.is_empty() {
+        return Ok(GameCandidateMatch::new(
+            ScanRule::BelowThreshold,
+            nearest_pairs,
+            hits,
+        ));
     }
 
-    #[test]
-    fn test_remove_word_removes_brackets() {
-        assert_eq!(remove_word("「タイトル」"), "タイトル");
-    }
+    distance_pairs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
 
-    #[test]
-    fn test_remove_word_removes_dl_suffix() {
-        assert_eq!(remove_word("ゲームダウンロード版"), "ゲーム");
-        assert_eq!(remove_word("ゲームDL版"), "ゲーム");
+    // 会社名とブランド名が一致する候補が先頭でなければ先頭にする
+    let company = version_info
+        .and_then(|v| v.company_name.as_deref())
+        .map(normalize)
+        .filter(|v| !v.is_empty());
+    let brand_position = company.and_then(|company| {
+        distance_pairs.iter().position(|(pair, _)| {
+            index
+                .brandname(pair.id)
+                .is_some_and(|brandname| is_same_brand(&company, brandname))
+        })
+    });
+    let is_promoted_by_brand = brand_position.is_some_and(|position| position > 0);
+    if let Some(position) = brand_position {
+        let pair = distance_pairs.remove(position);
+        distance_pairs.insert(0, pair);
     }
+    distance_pairs.truncate(candidate_limit);
 
-    #[test]
-    fn test_remove_word_no_change() {
-        assert_eq!(remove_word("普通のゲーム名"), "普通のゲーム名");
+    // 部分一致の場合は並び順に一致した文字数を使ったため、類似度を求め直す
+    if rule == ScanRule::PartialName {
+        distance_pairs = distance_pairs
+            .into_iter()
+            .map(|(pair, _)| {
+                let val = get_distance(&pair);
+                (pair, val)
+            })
+            .collect();
     }
-
-    // ========================================
-    // normalize のテスト
-    // ========================================
-    #[test]
-    fn test_normalize_fullwidth_to_halfwidth() {
-        // 全角英字→半角
-        assert_eq!(normalize("ＡＢＣＤ"), "abcd");
-        assert_eq!(normalize("ａｂｃｄ"), "abcd");
+    if is_promoted_by_brand {
+        rule = ScanRule::BrandName;
     }
+    Ok(GameCandidateMatch::new(rule, distance_pairs, hits))
+}
 
-    #[test]
-    fn test_normalize_fullwidth_numbers() {
-        // 全角数字→半角
-        assert_eq!(normalize("１２３"), "123");
+/// 正規化した会社名とブランド名が同じブランドを指すか
+///
+/// 会社名には「株式会社」等が付くことがあるため、2 文字以上であれば一方が他方を含む場合も同じとする
+fn is_same_brand(company: &str, brandname: &str) -> bool {
+    if company == brandname {
+        return true;
     }
+    let (shorter, longer) = if company.chars().count() < brandname.chars().count() {
+        (company, brandname)
+    } else {
+        (brandname, company)
+    };
+    shorter.chars().count() >= 2 && longer.contains(shorter)
+}
 
-    #[test]
-    fn test_normalize_mixed() {
-        assert_eq!(normalize("Ｈｅｌｌｏ１２３"), "hello123");
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    #[test]
-    fn test_normalize_japanese_unchanged() {
-        // 日本語はそのまま
-        assert_eq!(normalize("こんにちは"), "こんにちは");
+    /// 走査と同じくゲーム名を正規化したキャッシュの索引
+    fn normalized_index(cache: Vec<AllGameCacheOne>) -> GameCandidateIndex {
+        GameCandidateIndex::new(
+            cache
+                .into_iter()
+                .map(|pair| AllGameCacheOne::new(pair.id, normalize(&pair.gamename)))
+                .collect(),
+        )
     }
 
     // ========================================
@@ -719,16 +749,20 @@ mod tests {
     #[test]
     fn test_get_game_candidates_by_exe_path() {
         let res = get_game_candidates_by_exe_path(
-            &vec![AllGameCacheOne::new(
-                27123,
-                "pieces/渡り鳥のソムニウム".to_string(),
-            )]
-            .into_iter()
-            .map(|pair| AllGameCacheOne {
-                id: pair.id,
-                gamename: normalize(&pair.gamename),
-            })
-            .collect(),
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            &GameCandidateIndex::new(
+                vec![AllGameCacheOne::new(
+                    27123,
+                    "pieces/渡り鳥のソムニウム".to_string(),
+                )]
+                .into_iter()
+                .map(|pair| AllGameCacheOne {
+                    id: pair.id,
+                    gamename: normalize(&pair.gamename),
+                })
+                .collect(),
+            ),
             "W:/others/software/Whirlpool/pieces/pieces.exe",
             0.5,
             3,
@@ -740,8 +774,15 @@ mod tests {
 
     #[test]
     fn test_get_game_candidates_empty_cache() {
-        let res =
-            get_game_candidates_by_exe_path(&vec![], "C:/Games/SomeGame/game.exe", 0.5, 3).unwrap();
+        let res = get_game_candidates_by_exe_path(
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            &GameCandidateIndex::new(vec![]),
+            "C:/Games/SomeGame/game.exe",
+            0.5,
+            3,
+        )
+        .unwrap();
         assert!(res.is_empty());
     }
 
@@ -749,7 +790,12 @@ mod tests {
     fn test_get_game_candidates_below_threshold() {
         // 全く関係ないゲーム名 -> 閾値未満でヒットしない
         let res = get_game_candidates_by_exe_path(
-            &vec![AllGameCacheOne::new(1, "まったく別のゲーム".to_string())],
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            &GameCandidateIndex::new(vec![AllGameCacheOne::new(
+                1,
+                "まったく別のゲーム".to_string(),
+            )]),
             "C:/Games/TotallyDifferent/game.exe",
             0.9, // 高い閾値
             3,
@@ -762,14 +808,21 @@ mod tests {
 
     #[test]
     fn test_get_game_candidates_multiple_candidates() {
-        let cache = vec![
+        let index = normalized_index(vec![
             AllGameCacheOne::new(1, "ゲームA".to_string()),
             AllGameCacheOne::new(2, "ゲームB".to_string()),
             AllGameCacheOne::new(3, "ゲームC".to_string()),
-        ];
+        ]);
         // 親フォルダ名が "ゲームA" に近い場合
-        let res =
-            get_game_candidates_by_exe_path(&cache, "C:/Games/ゲームA/start.exe", 0.5, 5).unwrap();
+        let res = get_game_candidates_by_exe_path(
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            &index,
+            "C:/Games/ゲームA/start.exe",
+            0.5,
+            5,
+        )
+        .unwrap();
         // 少なくとも1件ヒットするはず
         assert!(!res.is_empty());
         // 最初の候補が "ゲームA" であること
@@ -779,13 +832,228 @@ mod tests {
     #[test]
     fn test_get_game_candidates_not_game_filter() {
         // インストーラーやマニュアルは除外される
-        let cache = vec![AllGameCacheOne::new(1, "ゲームA".to_string())];
-        let res = get_game_candidates_by_exe_path(&cache, "C:/Games/ゲームA/install.exe", 0.5, 3)
-            .unwrap();
+        let index = normalized_index(vec![AllGameCacheOne::new(1, "ゲームA".to_string())]);
+        let res = get_game_candidates_by_exe_path(
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            &index,
+            "C:/Games/ゲームA/install.exe",
+            0.5,
+            3,
+        )
+        .unwrap();
         // "install" は not_game でtrue -> 空配列
         assert!(res.is_empty());
     }
 
+    #[test]
+    fn test_match_game_candidates_reports_rule() {
+        let rules = MatchingRules::bundled().unwrap();
+        let index = normalized_index(vec![AllGameCacheOne::new(1, "ゲームA".to_string())]);
+        let res = match_game_candidates_by_exe_path(
+            &rules,
+            &LearnedGameMatches::default(),
+            &index,
+            "C:/Games/ゲームA/install.exe",
+            None,
+            0.5,
+            3,
+        )
+        .unwrap();
+        assert_eq!(res.rule, ScanRule::NotGameWord);
+        assert_eq!(
+            res.hits,
+            vec![MatchingRuleHit::new(
+                MatchingRuleKind::NotGameTerm,
+                "install".to_string(),
+                None
+            )]
+        );
+
+        let res = match_game_candidates_by_exe_path(
+            &rules,
+            &LearnedGameMatches::default(),
+            &index,
+            "C:/Games/ゲームA/start.exe",
+            None,
+            0.5,
+            3,
+        )
+        .unwrap();
+        assert_eq!(res.rule, ScanRule::Similarity);
+        assert_eq!(res.most_probable().map(|v| v.id), Some(1));
+        assert!(res.candidates[0].1 > 0.5);
+        assert_eq!(res.hits[0].kind, MatchingRuleKind::GenericFileName);
+    }
+
+    #[test]
+    fn test_match_game_candidates_keeps_nearest_below_threshold() {
+        let rules = MatchingRules::bundled().unwrap();
+        let index = normalized_index(vec![
+            AllGameCacheOne::new(1, "ゲームA".to_string()),
+            AllGameCacheOne::new(2, "まったく別の作品".to_string()),
+        ]);
+        let res = match_game_candidates_by_exe_path(
+            &rules,
+            &LearnedGameMatches::default(),
+            &index,
+            "C:/Games/ゲームAB/start.exe",
+            None,
+            0.99,
+            3,
+        )
+        .unwrap();
+        // 閾値を超えなくても類似度の高い候補は残すが、紐づけはしない
+        assert_eq!(res.rule, ScanRule::BelowThreshold);
+        assert!(res.most_probable().is_none());
+        assert_eq!(res.candidates.first().map(|(v, _)| v.id), Some(1));
+        assert!(res.candidates.windows(2).all(|v| v[0].1 >= v[1].1));
+    }
+
+    #[test]
+    fn test_match_game_candidates_prefers_learned_match() {
+        use crate::domain::learned_match::{LearnedGameMatch, LearnedGameMatchKey};
+
+        let rules = MatchingRules::bundled().unwrap();
+        let learned = LearnedGameMatches::new(&[LearnedGameMatch::new(
+            Id::new(1),
+            LearnedGameMatchKey::new(normalize("ゲームA"), "install".to_string()),
+            2,
+            Local::now(),
+        )]);
+        let index = normalized_index(vec![
+            AllGameCacheOne::new(1, "ゲームa".to_string()),
+            AllGameCacheOne::new(2, "まったく別の作品".to_string()),
+        ]);
+        // 規則では除外されるファイルでも、ユーザーが直した紐づけを使う
+        let res = match_game_candidates_by_exe_path(
+            &rules,
+            &learned,
+            &index,
+            "C:/Games/ゲームA/Install.exe",
+            None,
+            0.5,
+            3,
+        )
+        .unwrap();
+        assert_eq!(res.rule, ScanRule::LearnedMatch);
+        assert_eq!(res.most_probable().map(|v| v.id), Some(2));
+
+        // 紐づけ先がキャッシュにない場合は通常どおり判定する
+        let res = match_game_candidates_by_exe_path(
+            &rules,
+            &learned,
+            &normalized_index(vec![AllGameCacheOne::new(1, "ゲームa".to_string())]),
+            "C:/Games/ゲームA/Install.exe",
+            None,
+            0.5,
+            3,
+        )
+        .unwrap();
+        assert_eq!(res.rule, ScanRule::NotGameWord);
+    }
+
+    #[test]
+    fn test_match_game_candidates_uses_version_info() {
+        let rules = MatchingRules::bundled().unwrap();
+        let index = normalized_index(vec![
+            AllGameCacheOne::new(4529, "サクラノ詩 -櫻の森の上を舞う-".to_string()),
+            AllGameCacheOne::new(100, "まったく別の作品".to_string()),
+        ]);
+        let version_info = PeVersionInfo::read_from(&mut std::io::Cursor::new(include_bytes!(
+            "../../fixtures/pe_version/pe32_ja.exe"
+        )))
+        .unwrap()
+        .unwrap();
+
+        // ファイル名もフォルダ名もゲーム名と似ていない
+        let res = match_game_candidates_by_exe_path(
+            &rules,
+            &LearnedGameMatches::default(),
+            &index,
+            "C:/Games/Data/BGI.exe",
+            None,
+            0.8,
+            3,
+        )
+        .unwrap();
+        assert!(res.most_probable().is_none());
+
+        // 製品名でゲームと紐づく
+        let res = match_game_candidates_by_exe_path(
+            &rules,
+            &LearnedGameMatches::default(),
+            &index,
+            "C:/Games/Data/BGI.exe",
+            Some(&version_info),
+            0.8,
+            3,
+        )
+        .unwrap();
+        assert_eq!(res.rule, ScanRule::Similarity);
+        assert_eq!(res.most_probable().map(|v| v.id), Some(4529));
+    }
+
+    #[test]
+    fn test_match_game_candidates_promotes_same_brand() {
+        let rules = MatchingRules::bundled().unwrap();
+        let index = normalized_index(vec![
+            AllGameCacheOne::new(1, "サクラノ詩".to_string()),
+            AllGameCacheOne::new(2, "サクラノ詩 -櫻の森の上を舞う-".to_string()),
+        ])
+        .with_brandnames(HashMap::from([
+            (1, "別のブランド".to_string()),
+            (2, "サンプルブランド".to_string()),
+        ]));
+        let version_info = |company_name: &str| PeVersionInfo {
+            product_name: Some("サクラノ詩".to_string()),
+            file_description: None,
+            company_name: Some(company_name.to_string()),
+        };
+
+        let res = match_game_candidates_by_exe_path(
+            &rules,
+            &LearnedGameMatches::default(),
+            &index,
+            "C:/Games/Data/BGI.exe",
+            Some(&version_info("株式会社サンプルブランド")),
+            0.5,
+            3,
+        )
+        .unwrap();
+        assert_eq!(res.rule, ScanRule::BrandName);
+        assert_eq!(
+            res.candidates.iter().map(|(v, _)| v.id).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+
+        // 会社名がどのブランドとも一致しなければ類似度の順のまま
+        let res = match_game_candidates_by_exe_path(
+            &rules,
+            &LearnedGameMatches::default(),
+            &index,
+            "C:/Games/Data/BGI.exe",
+            Some(&version_info("Unknown Soft")),
+            0.5,
+            3,
+        )
+        .unwrap();
+        assert_eq!(res.rule, ScanRule::Similarity);
+        assert_eq!(res.most_probable().map(|v| v.id), Some(1));
+    }
+
+    #[test]
+    fn test_is_same_brand() {
+        assert!(is_same_brand("サンプルブランド", "サンプルブランド"));
+        assert!(is_same_brand(
+            "株式会社サンプルブランド",
+            "サンプルブランド"
+        ));
+        assert!(is_same_brand("ab", "abc"));
+        assert!(!is_same_brand("a", "abc"));
+        assert!(!is_same_brand("別のブランド", "サンプルブランド"));
+    }
+
     // ========================================
     // 汎用エンジン名ファイルでの親フォルダ名推定のテスト（新規）
     // ========================================
@@ -793,17 +1061,19 @@ mod tests {
     // RED: BGI.exeはnot_game対象だが、親フォルダ名「サクラノ詩」から正しく推定できるべき
     #[test]
     fn test_get_game_candidates_bgi_from_parent_folder() {
-        let cache = vec![
+        let index = normalized_index(vec![
             AllGameCacheOne::new(4529, "サクラノ詩 -櫻の森の上を舞う-".to_string()),
             AllGameCacheOne::new(11396, "サクラノ詩 春ノ雪".to_string()),
             AllGameCacheOne::new(
                 39075,
                 "サクラノ詩 -櫻の森の上を舞う- 10th Anniversary Edition".to_string(),
             ),
-        ];
+        ]);
         // パス：e:\VisualNovel\枕\サクラノ詩\BGI.exe
         let res = get_game_candidates_by_exe_path(
-            &cache,
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            &index,
             "E:\\VisualNovel\\枕\\サクラノ詩\\BGI.exe",
             0.8,
             1,
@@ -818,16 +1088,18 @@ mod tests {
 
     #[test]
     fn test_get_game_candidates_path_specific_rule_returns_only_exact_candidate() {
-        let cache = vec![
+        let index = normalized_index(vec![
             AllGameCacheOne::new(4529, "サクラノ詩 -櫻の森の上を舞う-".to_string()),
             AllGameCacheOne::new(11396, "サクラノ詩 春ノ雪".to_string()),
             AllGameCacheOne::new(
                 39075,
                 "サクラノ詩 -櫻の森の上を舞う- 10th Anniversary Edition".to_string(),
             ),
-        ];
+        ]);
         let res = get_game_candidates_by_exe_path(
-            &cache,
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            &index,
             "E:\\VisualNovel\\枕\\サクラノ詩\\BGI.exe",
             0.2,
             5,
@@ -840,12 +1112,14 @@ mod tests {
 
     #[test]
     fn test_get_game_candidates_bgi_from_exact_derived_parent_folder() {
-        let cache = vec![
+        let index = normalized_index(vec![
             AllGameCacheOne::new(4529, "サクラノ詩 -櫻の森の上を舞う-".to_string()),
             AllGameCacheOne::new(11396, "サクラノ詩 春ノ雪".to_string()),
-        ];
+        ]);
         let res = get_game_candidates_by_exe_path(
-            &cache,
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            &index,
             "E:\\VisualNovel\\枕\\サクラノ詩 春ノ雪\\BGI.exe",
             0.8,
             1,
@@ -857,12 +1131,14 @@ mod tests {
 
     #[test]
     fn test_get_game_candidates_sumire_from_specific_folder() {
-        let cache = vec![
+        let index = normalized_index(vec![
             AllGameCacheOne::new(373, "すみれ".to_string()),
             AllGameCacheOne::new(20178, "すみれ".to_string()),
-        ];
+        ]);
         let res = get_game_candidates_by_exe_path(
-            &cache,
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            &index,
             "E:\\VisualNovel\\nekoneko\\すみれ\\すみれ.exe",
             0.8,
             1,
@@ -875,13 +1151,15 @@ mod tests {
     // RED: SiglusEngine.exe はnot_game対象ではないが、親フォルダ名から正しく推定できるべき
     #[test]
     fn test_get_game_candidates_siglusengine_from_parent_folder() {
-        let cache = vec![AllGameCacheOne::new(
+        let index = normalized_index(vec![AllGameCacheOne::new(
             29016,
             "summer pockets reflection blue".to_string(),
-        )];
+        )]);
         // パス：e:\VisualNovel\key\Summer Pockets REFLECTION BLUE\SiglusEngine.exe
         let res = get_game_candidates_by_exe_path(
-            &cache,
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            &index,
             "E:\\VisualNovel\\key\\Summer Pockets REFLECTION BLUE\\SiglusEngine.exe",
             0.8,
             1,
@@ -946,6 +1224,73 @@ mod tests {
         assert_eq!(parsed[0].minutes, 10.0);
         assert_eq!(parsed[1].minutes, 20.0);
     }
+
+    #[test]
+    fn test_play_history_started_at_formats() {
+        let history = |start_date: &str| PlayHistory {
+            minutes: 1.0,
+            start_date: start_date.to_string(),
+        };
+        let expected = Local.with_ymd_and_hms(2023, 12, 25, 10, 0, 0).unwrap();
+
+        assert_eq!(history("2023-12-25T10:00:00").started_at(), Some(expected));
+        assert_eq!(
+            history("2023-12-25 10:00:00.123")
+                .started_at()
+                .map(|v| v.timestamp()),
+            Some(expected.timestamp())
+        );
+        assert_eq!(history(&expected.to_rfc3339()).started_at(), Some(expected));
+        assert_eq!(
+            history("2023-12-25").started_at(),
+            Some(Local.with_ymd_and_hms(2023, 12, 25, 0, 0, 0).unwrap())
+        );
+        assert_eq!(history("yesterday").started_at(), None);
+    }
+
+    #[test]
+    fn test_parse_play_histories_skips_invalid_lines() {
+        let content = "{\"minutes\":10.0,\"startDate\":\"2023-01-01\"}\nbroken\n\n{\"minutes\":5.5,\"startDate\":\"2023-01-02\"}\n";
+
+        let (histories, invalid_count) = parse_play_histories(content);
+
+        assert_eq!(histories.len(), 2);
+        assert_eq!(histories[1].minutes, 5.5);
+        assert_eq!(invalid_count, 1);
+    }
+
+    #[test]
+    fn test_find_legacy_play_history_files() {
+        let root = std::env::temp_dir().join(format!(
+            "launcherg_legacy_play_histories_{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(PLAY_HISTORIES_ROOT_DIR)).unwrap();
+        for path in [
+            root.join("12.jsonl"),
+            root.join("settings.jsonl"),
+            root.join(PLAY_HISTORIES_ROOT_DIR).join("3.jsonl"),
+            root.join(PLAY_HISTORIES_ROOT_DIR).join("4.jsonl.imported"),
+        ] {
+            fs::write(path, "").unwrap();
+        }
+
+        let files = find_legacy_play_history_files(&root);
+
+        assert_eq!(
+            files,
+            vec![
+                (3, root.join(PLAY_HISTORIES_ROOT_DIR).join("3.jsonl")),
+                (12, root.join("12.jsonl")),
+            ]
+        );
+        assert_eq!(
+            get_imported_play_history_path(&files[1].1),
+            root.join("12.jsonl.imported")
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
 }
 
 const ICONS_ROOT_DIR: &str = "game-icons";
@@ -1120,16 +1465,45 @@ pub fn save_exe_file_png(
 }
 
 const PLAY_HISTORIES_ROOT_DIR: &str = "play-histories";
-pub fn get_play_history_path(
-    handle: &Arc<AppHandle>,
+const IMPORTED_PLAY_HISTORY_EXTENSION: &str = "jsonl.imported";
+
+/// 以前のバージョンのプレイ履歴(`<id>.jsonl`)の置き場所
+///
+/// `play-histories` フォルダを作りつつ保存ルート直下に書いていたため、両方を探す
+pub fn get_legacy_play_history_paths(
+    save_root_dir: &Path,
     collection_element_id: &Id<CollectionElement>,
-) -> String {
-    let dir = Path::new(&get_save_root_abs_dir(handle)).join(PLAY_HISTORIES_ROOT_DIR);
-    fs::create_dir_all(dir).unwrap();
-    Path::new(&get_save_root_abs_dir(handle))
-        .join(format!("{}.jsonl", collection_element_id.value))
-        .to_string_lossy()
-        .to_string()
+) -> [PathBuf; 2] {
+    let filename = format!("{}.jsonl", collection_element_id.value);
+    [
+        save_root_dir.join(&filename),
+        save_root_dir.join(PLAY_HISTORIES_ROOT_DIR).join(&filename),
+    ]
+}
+
+/// まだ取り込んでいない以前のバージョンのプレイ履歴をエレメント ID の順に返す
+pub fn find_legacy_play_history_files(save_root_dir: &Path) -> Vec<(i32, PathBuf)> {
+    let mut files: Vec<(i32, PathBuf)> = [
+        save_root_dir.to_path_buf(),
+        save_root_dir.join(PLAY_HISTORIES_ROOT_DIR),
+    ]
+    .iter()
+    .filter_map(|dir| fs::read_dir(dir).ok())
+    .flat_map(|entries| entries.filter_map(Result::ok))
+    .map(|entry| entry.path())
+    .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "jsonl"))
+    .filter_map(|path| {
+        let id = path.file_stem()?.to_str()?.parse::<i32>().ok()?;
+        Some((id, path))
+    })
+    .collect();
+    files.sort();
+    files
+}
+
+/// 取り込み済みの印として付け替えるファイル名
+pub fn get_imported_play_history_path(path: &Path) -> PathBuf {
+    path.with_extension(IMPORTED_PLAY_HISTORY_EXTENSION)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1139,6 +1513,47 @@ pub struct PlayHistory {
     pub start_date: String,
 }
 
+impl PlayHistory {
+    /// 記録を始めた日時
+    pub fn started_at(&self) -> Option<DateTime<Local>> {
+        parse_local_datetime(&self.start_date)
+    }
+}
+
+/// 他のアプリが書いた日時を読む。日付のみの場合はその日の 0 時。タイムゾーンがない場合はローカル時刻とみなす
+pub fn parse_local_datetime(value: &str) -> Option<DateTime<Local>> {
+    let value = value.trim();
+    if let Ok(v) = DateTime::parse_from_rfc3339(value) {
+        return Some(v.with_timezone(&Local));
+    }
+    let naive = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+/// JSONL のプレイ履歴を読む。読めない行は飛ばし、その数も返す
+pub fn parse_play_histories(content: &str) -> (Vec<PlayHistory>, usize) {
+    let mut invalid_count = 0;
+    let histories = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let history = serde_json::from_str::<PlayHistory>(line).ok();
+            if history.is_none() {
+                invalid_count += 1;
+            }
+            history
+        })
+        .collect();
+    (histories, invalid_count)
+}
+
 pub fn get_file_created_at_sync(path: &str) -> Option<DateTime<Local>> {
     let metadata = fs::metadata(path).ok();
     metadata.and_then(|meta| {
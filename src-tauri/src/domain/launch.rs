@@ -0,0 +1,466 @@
+use std::collections::BTreeMap;
+
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+use super::{collection::CollectionElement, Id};
+
+/// エレメントに紐づく名前付きの起動対象 (設定ツール、英語パッチ版、64bit 版など)
+#[derive(new, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchTarget {
+    pub id: Id<LaunchTarget>,
+    pub collection_element_id: Id<CollectionElement>,
+    pub name: String,
+    pub path: String,
+    pub is_default: bool,
+    pub sort_order: i32,
+}
+
+#[derive(new, Debug, Clone)]
+pub struct NewLaunchTarget {
+    pub collection_element_id: Id<CollectionElement>,
+    pub name: String,
+    pub path: String,
+}
+
+/// 実際に起動するパス
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedLaunchPath {
+    pub path: String,
+    /// エレメント本体の exe_path / lnk_path を使う場合は None
+    pub launch_target_id: Option<i32>,
+}
+
+/// 起動するパスを決定する
+///
+/// `launch_target_id` が指定されていればその起動対象、なければ既定の起動対象、
+/// どちらもなければエレメント本体の exe_path / lnk_path を使う
+pub fn resolve_launch_path(
+    element: &CollectionElement,
+    targets: &[LaunchTarget],
+    launch_target_id: Option<i32>,
+) -> anyhow::Result<ResolvedLaunchPath> {
+    let target = match launch_target_id {
+        Some(id) => Some(
+            targets
+                .iter()
+                .find(|v| v.id.value == id)
+                .ok_or_else(|| anyhow::anyhow!("起動対象が見つかりません: {}", id))?,
+        ),
+        None => targets.iter().find(|v| v.is_default),
+    };
+    if let Some(target) = target {
+        return Ok(ResolvedLaunchPath {
+            path: target.path.clone(),
+            launch_target_id: Some(target.id.value),
+        });
+    }
+
+    match (&element.exe_path, &element.lnk_path) {
+        (Some(p), _) => Ok(ResolvedLaunchPath {
+            path: p.clone(),
+            launch_target_id: None,
+        }),
+        (None, Some(p)) => Ok(ResolvedLaunchPath {
+            path: p.clone(),
+            launch_target_id: None,
+        }),
+        (None, None) => Err(anyhow::anyhow!(
+            "実行ファイルまたはショートカットが見つかりません"
+        )),
+    }
+}
+
+/// ラッパーコマンドのテンプレートで使えるプレースホルダー
+pub const WRAPPER_EXE_PLACEHOLDER: &str = "{exe}";
+pub const WRAPPER_ARGS_PLACEHOLDER: &str = "{args}";
+pub const WRAPPER_DIR_PLACEHOLDER: &str = "{dir}";
+
+/// エレメントごとの起動設定
+#[derive(new, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchProfile {
+    pub arguments: Vec<String>,
+    /// 作業ディレクトリ。None の場合は実行ファイルのあるディレクトリ
+    pub working_directory: Option<String>,
+    pub environment: BTreeMap<String, String>,
+    /// `LEProc.exe -run {exe}` のようなラッパーコマンドのテンプレート
+    ///
+    /// `{exe}` は起動対象のパス、`{args}` は引数、`{dir}` は作業ディレクトリに置き換える。
+    /// `{args}` がない場合、引数は末尾に追加する
+    pub wrapper_command: Option<String>,
+}
+
+impl LaunchProfile {
+    pub fn validate(&self) -> Result<(), String> {
+        for key in self.environment.keys() {
+            if key.is_empty() || key.contains('=') {
+                return Err(format!("環境変数名が不正です: `{}`", key));
+            }
+        }
+        if let Some(wrapper_command) = self.wrapper_command.as_ref() {
+            let words = split_command_line(wrapper_command)?;
+            if words.is_empty() {
+                return Err("ラッパーコマンドが空です".to_string());
+            }
+            if !wrapper_command.contains(WRAPPER_EXE_PLACEHOLDER) {
+                return Err(format!(
+                    "ラッパーコマンドに {} が含まれていません",
+                    WRAPPER_EXE_PLACEHOLDER
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 起動するコマンド。`std::process::Command` に渡す直前の形
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub current_dir: Option<String>,
+    pub envs: Vec<(String, String)>,
+    /// 起動したプロセスがゲーム本体かどうか。
+    /// ショートカットやラッパー経由の場合は別途ゲームのプロセスを探す必要がある
+    pub is_direct: bool,
+}
+
+/// 起動パスと起動設定から実行するコマンドを組み立てる
+pub fn build_launch_command(
+    path: &str,
+    profile: Option<&LaunchProfile>,
+) -> Result<LaunchCommand, String> {
+    let default_profile = LaunchProfile::default();
+    let profile = profile.unwrap_or(&default_profile);
+    let is_lnk = path.to_lowercase().ends_with(".lnk");
+
+    let current_dir = match profile.working_directory.as_ref() {
+        Some(dir) if !dir.trim().is_empty() => Some(dir.trim().to_string()),
+        _ if is_lnk => None,
+        _ => Some(
+            std::path::Path::new(path)
+                .parent()
+                .filter(|v| !v.as_os_str().is_empty())
+                .ok_or_else(|| "親ディレクトリが見つかりません".to_string())?
+                .to_string_lossy()
+                .to_string(),
+        ),
+    };
+    let envs: Vec<(String, String)> = profile
+        .environment
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    if let Some(wrapper_command) = profile.wrapper_command.as_ref() {
+        let dir = current_dir.clone().unwrap_or_default();
+        let mut words = vec![];
+        let mut has_args_placeholder = false;
+        for word in split_command_line(wrapper_command)? {
+            if word == WRAPPER_ARGS_PLACEHOLDER {
+                has_args_placeholder = true;
+                words.extend(profile.arguments.iter().cloned());
+                continue;
+            }
+            if word.contains(WRAPPER_ARGS_PLACEHOLDER) {
+                has_args_placeholder = true;
+            }
+            words.push(
+                word.replace(WRAPPER_EXE_PLACEHOLDER, path)
+                    .replace(WRAPPER_DIR_PLACEHOLDER, &dir)
+                    .replace(WRAPPER_ARGS_PLACEHOLDER, &profile.arguments.join(" ")),
+            );
+        }
+        if !has_args_placeholder {
+            words.extend(profile.arguments.iter().cloned());
+        }
+        if words.is_empty() {
+            return Err("ラッパーコマンドが空です".to_string());
+        }
+        let program = words.remove(0);
+        return Ok(LaunchCommand {
+            program,
+            args: words,
+            current_dir,
+            envs,
+            is_direct: false,
+        });
+    }
+
+    if is_lnk {
+        // .lnkファイルの場合、cmd /c startを使用
+        let mut args = vec![
+            "/c".to_string(),
+            "start".to_string(),
+            "".to_string(),
+            path.to_string(),
+        ];
+        args.extend(profile.arguments.iter().cloned());
+        return Ok(LaunchCommand {
+            program: "cmd".to_string(),
+            args,
+            current_dir,
+            envs,
+            is_direct: false,
+        });
+    }
+
+    Ok(LaunchCommand {
+        program: path.to_string(),
+        args: profile.arguments.clone(),
+        current_dir,
+        envs,
+        is_direct: true,
+    })
+}
+
+/// コマンドラインを空白で分割する。ダブルクォートで囲んだ部分は1語として扱う
+pub fn split_command_line(source: &str) -> Result<Vec<String>, String> {
+    let mut words = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_word = false;
+    for c in source.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_word = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_word {
+                    words.push(std::mem::take(&mut current));
+                    has_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_word = true;
+            }
+        }
+    }
+    if in_quotes {
+        return Err("ダブルクォートが閉じられていません".to_string());
+    }
+    if has_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    fn element(exe_path: Option<&str>, lnk_path: Option<&str>) -> CollectionElement {
+        CollectionElement::new(
+            Id::new(1),
+            "テストゲーム".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+            "2024-01-01".to_string(),
+            false,
+            exe_path.map(|v| v.to_string()),
+            lnk_path.map(|v| v.to_string()),
+            None,
+            None,
+            None,
+            None,
+            0,
+            0,
+            None,
+            None,
+            Local::now(),
+            Local::now(),
+            vec![],
+        )
+    }
+
+    fn target(id: i32, path: &str, is_default: bool) -> LaunchTarget {
+        LaunchTarget::new(
+            Id::new(id),
+            Id::new(1),
+            format!("target{}", id),
+            path.to_string(),
+            is_default,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_resolve_launch_path_uses_element_path_without_targets() {
+        let resolved =
+            resolve_launch_path(&element(Some("C:/game/game.exe"), None), &[], None).unwrap();
+        assert_eq!(resolved.path, "C:/game/game.exe");
+        assert_eq!(resolved.launch_target_id, None);
+
+        let resolved =
+            resolve_launch_path(&element(None, Some("C:/game/game.lnk")), &[], None).unwrap();
+        assert_eq!(resolved.path, "C:/game/game.lnk");
+    }
+
+    #[test]
+    fn test_resolve_launch_path_prefers_default_target() {
+        let targets = vec![
+            target(10, "C:/game/config.exe", false),
+            target(11, "C:/game/game_x64.exe", true),
+        ];
+        let resolved =
+            resolve_launch_path(&element(Some("C:/game/game.exe"), None), &targets, None).unwrap();
+        assert_eq!(resolved.path, "C:/game/game_x64.exe");
+        assert_eq!(resolved.launch_target_id, Some(11));
+    }
+
+    #[test]
+    fn test_resolve_launch_path_explicit_target() {
+        let targets = vec![
+            target(10, "C:/game/config.exe", false),
+            target(11, "C:/game/game_x64.exe", true),
+        ];
+        let resolved = resolve_launch_path(&element(None, None), &targets, Some(10)).unwrap();
+        assert_eq!(resolved.path, "C:/game/config.exe");
+        assert_eq!(resolved.launch_target_id, Some(10));
+    }
+
+    #[test]
+    fn test_resolve_launch_path_errors() {
+        let targets = vec![target(10, "C:/game/config.exe", false)];
+        assert!(
+            resolve_launch_path(&element(Some("C:/game/game.exe"), None), &targets, Some(99))
+                .is_err()
+        );
+        assert!(resolve_launch_path(&element(None, None), &targets, None).is_err());
+    }
+
+    fn profile(
+        arguments: &[&str],
+        working_directory: Option<&str>,
+        wrapper_command: Option<&str>,
+    ) -> LaunchProfile {
+        LaunchProfile::new(
+            arguments.iter().map(|v| v.to_string()).collect(),
+            working_directory.map(|v| v.to_string()),
+            BTreeMap::new(),
+            wrapper_command.map(|v| v.to_string()),
+        )
+    }
+
+    #[test]
+    fn test_build_launch_command_without_profile() {
+        let command = build_launch_command("C:/Games/test/game.exe", None).unwrap();
+        assert_eq!(
+            command,
+            LaunchCommand {
+                program: "C:/Games/test/game.exe".to_string(),
+                args: vec![],
+                current_dir: Some("C:/Games/test".to_string()),
+                envs: vec![],
+                is_direct: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_launch_command_lnk() {
+        let profile = profile(&["-window"], None, None);
+        let command = build_launch_command("C:/Games/test.lnk", Some(&profile)).unwrap();
+        assert_eq!(command.program, "cmd");
+        assert_eq!(
+            command.args,
+            vec!["/c", "start", "", "C:/Games/test.lnk", "-window"]
+        );
+        assert_eq!(command.current_dir, None);
+        assert!(!command.is_direct);
+    }
+
+    #[test]
+    fn test_build_launch_command_arguments_working_dir_and_env() {
+        let mut profile = profile(&["-lang", "en"], Some(" D:/save "), None);
+        profile
+            .environment
+            .insert("__COMPAT_LAYER".to_string(), "RunAsInvoker".to_string());
+
+        let command = build_launch_command("C:/Games/test/game.exe", Some(&profile)).unwrap();
+
+        assert_eq!(command.program, "C:/Games/test/game.exe");
+        assert_eq!(command.args, vec!["-lang", "en"]);
+        assert_eq!(command.current_dir, Some("D:/save".to_string()));
+        assert_eq!(
+            command.envs,
+            vec![("__COMPAT_LAYER".to_string(), "RunAsInvoker".to_string())]
+        );
+        assert!(command.is_direct);
+    }
+
+    #[test]
+    fn test_build_launch_command_wrapper_appends_args() {
+        let profile = profile(
+            &["-lang", "en"],
+            None,
+            Some(r#""C:/Program Files/LE/LEProc.exe" -run {exe}"#),
+        );
+
+        let command = build_launch_command("C:/Games/test/game.exe", Some(&profile)).unwrap();
+
+        assert_eq!(command.program, "C:/Program Files/LE/LEProc.exe");
+        assert_eq!(
+            command.args,
+            vec!["-run", "C:/Games/test/game.exe", "-lang", "en"]
+        );
+        assert_eq!(command.current_dir, Some("C:/Games/test".to_string()));
+        assert!(!command.is_direct);
+    }
+
+    #[test]
+    fn test_build_launch_command_wrapper_with_placeholders() {
+        let profile = profile(
+            &["-a", "-b"],
+            Some("D:/work"),
+            Some(r#"runner --cwd={dir} {exe} -- {args} --end"#),
+        );
+
+        let command = build_launch_command("C:/Games/test/game.exe", Some(&profile)).unwrap();
+
+        assert_eq!(command.program, "runner");
+        assert_eq!(
+            command.args,
+            vec![
+                "--cwd=D:/work",
+                "C:/Games/test/game.exe",
+                "--",
+                "-a",
+                "-b",
+                "--end"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(
+            split_command_line(r#"  "C:/Program Files/a.exe"  -x "" y "#).unwrap(),
+            vec!["C:/Program Files/a.exe", "-x", "", "y"]
+        );
+        assert!(split_command_line(r#"a "b"#).is_err());
+    }
+
+    #[test]
+    fn test_launch_profile_validate() {
+        assert!(profile(&[], None, Some("LEProc.exe -run {exe}"))
+            .validate()
+            .is_ok());
+        assert!(profile(&[], None, Some("LEProc.exe -run"))
+            .validate()
+            .is_err());
+        assert!(profile(&[], None, Some("  ")).validate().is_err());
+
+        let mut invalid_env = profile(&[], None, None);
+        invalid_env
+            .environment
+            .insert("A=B".to_string(), "1".to_string());
+        assert!(invalid_env.validate().is_err());
+    }
+}
@@ -0,0 +1,336 @@
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+use super::{collection::CollectionElement, launch::split_command_line, Id};
+
+/// フックの実行タイミング
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LaunchHookTiming {
+    /// ゲーム起動前
+    PreLaunch,
+    /// ゲーム終了後の後片付けが終わった後
+    PostExit,
+}
+
+impl LaunchHookTiming {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LaunchHookTiming::PreLaunch => "pre_launch",
+            LaunchHookTiming::PostExit => "post_exit",
+        }
+    }
+}
+
+impl std::str::FromStr for LaunchHookTiming {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pre_launch" => Ok(LaunchHookTiming::PreLaunch),
+            "post_exit" => Ok(LaunchHookTiming::PostExit),
+            _ => Err(anyhow::anyhow!("unknown launch hook timing: {}", s)),
+        }
+    }
+}
+
+pub const MAX_LAUNCH_HOOK_TIMEOUT_SECONDS: u32 = 60 * 60;
+
+/// ゲームの起動前・終了後に実行するユーザー定義のコマンド
+///
+/// `collection_element_id` が None のフックはすべてのゲームで実行する
+#[allow(clippy::too_many_arguments)]
+#[derive(new, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchHook {
+    pub id: Id<LaunchHook>,
+    pub collection_element_id: Option<Id<CollectionElement>>,
+    pub timing: LaunchHookTiming,
+    pub name: String,
+    /// `{exe}` は起動するパス、`{dir}` はそのディレクトリ、`{element_id}` はエレメントIDに置き換える
+    pub command: String,
+    pub timeout_seconds: u32,
+    /// 起動前フックが失敗したときにゲームの起動を中止するか
+    pub cancel_launch_on_failure: bool,
+    pub sort_order: i32,
+}
+
+#[derive(new, Debug, Clone)]
+pub struct NewLaunchHook {
+    pub collection_element_id: Option<Id<CollectionElement>>,
+    pub timing: LaunchHookTiming,
+    pub name: String,
+    pub command: String,
+    pub timeout_seconds: u32,
+    pub cancel_launch_on_failure: bool,
+}
+
+impl NewLaunchHook {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("フック名が空です".to_string());
+        }
+        if split_command_line(&self.command)?.is_empty() {
+            return Err("コマンドが空です".to_string());
+        }
+        if self.timeout_seconds == 0 || self.timeout_seconds > MAX_LAUNCH_HOOK_TIMEOUT_SECONDS {
+            return Err(format!(
+                "タイムアウトは1秒から{}秒の間で指定してください",
+                MAX_LAUNCH_HOOK_TIMEOUT_SECONDS
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// フックのコマンドに埋め込む起動中のゲームの情報
+#[derive(new, Debug, Clone)]
+pub struct LaunchHookContext {
+    pub element_id: i32,
+    pub path: String,
+}
+
+/// 実行するフックのコマンド
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchHookCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub envs: Vec<(String, String)>,
+}
+
+/// フックの実行結果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LaunchHookOutcome {
+    /// タイムアウトや起動失敗の場合は None
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+    /// コマンド自体を起動できなかった場合のエラー
+    pub spawn_error: Option<String>,
+}
+
+impl LaunchHookOutcome {
+    pub fn is_success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// 指定したタイミングで実行するフックを実行順に並べる。全体のフックが先、エレメントのフックが後
+pub fn select_launch_hooks(hooks: &[LaunchHook], timing: LaunchHookTiming) -> Vec<&LaunchHook> {
+    let mut selected: Vec<&LaunchHook> = hooks.iter().filter(|v| v.timing == timing).collect();
+    selected.sort_by_key(|v| (v.collection_element_id.is_some(), v.sort_order, v.id.value));
+    selected
+}
+
+/// フックのコマンド文字列から実行するコマンドを組み立てる
+pub fn build_launch_hook_command(
+    hook: &LaunchHook,
+    context: &LaunchHookContext,
+) -> Result<LaunchHookCommand, String> {
+    let dir = std::path::Path::new(&context.path)
+        .parent()
+        .map(|v| v.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let element_id = context.element_id.to_string();
+    let mut words: Vec<String> = split_command_line(&hook.command)?
+        .into_iter()
+        .map(|v| {
+            v.replace("{exe}", &context.path)
+                .replace("{dir}", &dir)
+                .replace("{element_id}", &element_id)
+        })
+        .collect();
+    if words.is_empty() {
+        return Err("コマンドが空です".to_string());
+    }
+    let program = words.remove(0);
+    Ok(LaunchHookCommand {
+        program,
+        args: words,
+        envs: vec![
+            ("LAUNCHERG_ELEMENT_ID".to_string(), element_id),
+            ("LAUNCHERG_GAME_PATH".to_string(), context.path.clone()),
+            (
+                "LAUNCHERG_HOOK_TIMING".to_string(),
+                hook.timing.as_str().to_string(),
+            ),
+        ],
+    })
+}
+
+/// 起動前フックの結果からゲームの起動を中止すべきか判定する
+pub fn should_cancel_launch(hook: &LaunchHook, outcome: &LaunchHookOutcome) -> bool {
+    hook.timing == LaunchHookTiming::PreLaunch
+        && hook.cancel_launch_on_failure
+        && !outcome.is_success()
+}
+
+/// ログに出力する実行結果の要約
+pub fn format_launch_hook_outcome(hook: &LaunchHook, outcome: &LaunchHookOutcome) -> String {
+    let status = if let Some(e) = outcome.spawn_error.as_ref() {
+        format!("failed to spawn: {}", e)
+    } else if outcome.timed_out {
+        format!("timed out after {}s", hook.timeout_seconds)
+    } else {
+        match outcome.exit_code {
+            Some(code) => format!("exit code {}", code),
+            None => "terminated by signal".to_string(),
+        }
+    };
+    let mut message = format!(
+        "[launch_hook] {} `{}` ({}): {}",
+        hook.timing.as_str(),
+        hook.name,
+        hook.command,
+        status
+    );
+    if !outcome.stdout.trim().is_empty() {
+        message.push_str(&format!("\nstdout: {}", outcome.stdout.trim_end()));
+    }
+    if !outcome.stderr.trim().is_empty() {
+        message.push_str(&format!("\nstderr: {}", outcome.stderr.trim_end()));
+    }
+    message
+}
+
+/// 実行しなかったフックのログ出力用メッセージを組み立てる
+pub fn format_skipped_launch_hook(hook: &LaunchHook, reason: &str) -> String {
+    format!(
+        "[launch_hook] {} `{}` ({}): skipped, {}",
+        hook.timing.as_str(),
+        hook.name,
+        hook.command,
+        reason
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(
+        id: i32,
+        element_id: Option<i32>,
+        timing: LaunchHookTiming,
+        command: &str,
+    ) -> LaunchHook {
+        LaunchHook::new(
+            Id::new(id),
+            element_id.map(Id::new),
+            timing,
+            format!("hook{}", id),
+            command.to_string(),
+            30,
+            true,
+            id,
+        )
+    }
+
+    #[test]
+    fn test_select_launch_hooks_runs_global_first() {
+        let hooks = vec![
+            hook(1, Some(10), LaunchHookTiming::PreLaunch, "a"),
+            hook(2, None, LaunchHookTiming::PostExit, "b"),
+            hook(3, None, LaunchHookTiming::PreLaunch, "c"),
+            hook(4, Some(10), LaunchHookTiming::PreLaunch, "d"),
+        ];
+
+        let selected: Vec<i32> = select_launch_hooks(&hooks, LaunchHookTiming::PreLaunch)
+            .into_iter()
+            .map(|v| v.id.value)
+            .collect();
+
+        assert_eq!(selected, vec![3, 1, 4]);
+    }
+
+    #[test]
+    fn test_build_launch_hook_command_replaces_placeholders() {
+        let hook = hook(
+            1,
+            None,
+            LaunchHookTiming::PostExit,
+            r#""C:/tools/backup.exe" --src "{dir}/save" --id={element_id}"#,
+        );
+        let context = LaunchHookContext::new(42, "C:/Games/test/game.exe".to_string());
+
+        let command = build_launch_hook_command(&hook, &context).unwrap();
+
+        assert_eq!(command.program, "C:/tools/backup.exe");
+        assert_eq!(command.args, vec!["--src", "C:/Games/test/save", "--id=42"]);
+        assert!(command
+            .envs
+            .contains(&("LAUNCHERG_HOOK_TIMING".to_string(), "post_exit".to_string())));
+    }
+
+    #[test]
+    fn test_should_cancel_launch() {
+        let pre = hook(1, None, LaunchHookTiming::PreLaunch, "a");
+        let mut not_cancel = pre.clone();
+        not_cancel.cancel_launch_on_failure = false;
+        let post = hook(2, None, LaunchHookTiming::PostExit, "b");
+        let failed = LaunchHookOutcome {
+            exit_code: Some(1),
+            ..Default::default()
+        };
+        let timed_out = LaunchHookOutcome {
+            timed_out: true,
+            ..Default::default()
+        };
+        let succeeded = LaunchHookOutcome {
+            exit_code: Some(0),
+            ..Default::default()
+        };
+
+        assert!(should_cancel_launch(&pre, &failed));
+        assert!(should_cancel_launch(&pre, &timed_out));
+        assert!(!should_cancel_launch(&pre, &succeeded));
+        assert!(!should_cancel_launch(&not_cancel, &failed));
+        assert!(!should_cancel_launch(&post, &failed));
+    }
+
+    #[test]
+    fn test_new_launch_hook_validate() {
+        let valid = NewLaunchHook::new(
+            None,
+            LaunchHookTiming::PreLaunch,
+            "mount".to_string(),
+            "mount.bat".to_string(),
+            30,
+            false,
+        );
+        assert!(valid.validate().is_ok());
+
+        let mut empty_command = valid.clone();
+        empty_command.command = "  ".to_string();
+        assert!(empty_command.validate().is_err());
+
+        let mut no_timeout = valid.clone();
+        no_timeout.timeout_seconds = 0;
+        assert!(no_timeout.validate().is_err());
+    }
+
+    #[test]
+    fn test_format_launch_hook_outcome() {
+        let hook = hook(1, None, LaunchHookTiming::PreLaunch, "mount.bat");
+        let outcome = LaunchHookOutcome {
+            exit_code: Some(2),
+            stderr: "drive not found\n".to_string(),
+            ..Default::default()
+        };
+
+        let message = format_launch_hook_outcome(&hook, &outcome);
+
+        assert!(message.contains("exit code 2"));
+        assert!(message.contains("stderr: drive not found"));
+    }
+
+    #[test]
+    fn test_format_skipped_launch_hook() {
+        let hook = hook(1, None, LaunchHookTiming::PostExit, "restore.bat");
+
+        let message = format_skipped_launch_hook(&hook, "game process was not found");
+
+        assert!(message.contains("restore.bat"));
+        assert!(message.ends_with("skipped, game process was not found"));
+    }
+}
@@ -0,0 +1,200 @@
+//! ユーザーが手で直したファイルとゲームの紐づけ
+//!
+//! 親フォルダ名とファイル名の組から ErogameScape の ID を引き、走査で他の規則より先に使う
+
+use std::{collections::HashMap, path::Path};
+
+use chrono::{DateTime, Local};
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+use super::{file::get_file_name_without_extension, normalize::normalize, Id};
+
+/// 書き出し・読み込みできるファイルの版
+pub const SUPPORTED_LEARNED_GAME_MATCHES_VERSION: u32 = 1;
+
+/// 紐づけのキーにする正規化した親フォルダ名とファイル名（拡張子なし）
+#[derive(new, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LearnedGameMatchKey {
+    pub parent_folder: String,
+    pub file_stem: String,
+}
+
+impl LearnedGameMatchKey {
+    pub fn from_path(filepath: &str) -> Option<Self> {
+        let parent_folder = Path::new(filepath)
+            .parent()
+            .and_then(|v| v.file_name())
+            .map(|name| normalize(name.to_string_lossy().as_ref()))?;
+        let file_stem = normalize(&get_file_name_without_extension(filepath)?);
+        if parent_folder.is_empty() || file_stem.is_empty() {
+            return None;
+        }
+        Some(Self::new(parent_folder, file_stem))
+    }
+}
+
+/// 書き出しファイルの1件
+#[derive(new, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewLearnedGameMatch {
+    #[serde(flatten)]
+    pub key: LearnedGameMatchKey,
+    pub game_id: i32,
+}
+
+impl NewLearnedGameMatch {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.key.parent_folder.is_empty() || self.key.file_stem.is_empty() {
+            anyhow::bail!("learned game match needs a parent folder and a file stem");
+        }
+        if normalize(&self.key.parent_folder) != self.key.parent_folder
+            || normalize(&self.key.file_stem) != self.key.file_stem
+        {
+            anyhow::bail!(
+                "learned game match `{}/{}` is not normalized",
+                self.key.parent_folder,
+                self.key.file_stem
+            );
+        }
+        if self.game_id <= 0 {
+            anyhow::bail!(
+                "learned game match has an invalid game id: {}",
+                self.game_id
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(new, Debug, Clone)]
+pub struct LearnedGameMatch {
+    pub id: Id<LearnedGameMatch>,
+    pub key: LearnedGameMatchKey,
+    pub game_id: i32,
+    pub updated_at: DateTime<Local>,
+}
+
+/// 紐づけを共有するためのファイル
+#[derive(new, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LearnedGameMatchesFile {
+    pub version: u32,
+    pub matches: Vec<NewLearnedGameMatch>,
+}
+
+impl LearnedGameMatchesFile {
+    pub fn parse(json: &str) -> anyhow::Result<Self> {
+        let file: Self = serde_json::from_str(json)?;
+        if file.version != SUPPORTED_LEARNED_GAME_MATCHES_VERSION {
+            anyhow::bail!(
+                "unsupported learned game matches version: {} (supported: {})",
+                file.version,
+                SUPPORTED_LEARNED_GAME_MATCHES_VERSION
+            );
+        }
+        Ok(file)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LearnedGameMatches {
+    game_ids: HashMap<LearnedGameMatchKey, i32>,
+}
+
+impl LearnedGameMatches {
+    /// 正規化の仕方が変わっても引けるよう、保存済みのキーも正規化し直す
+    pub fn new(matches: &[LearnedGameMatch]) -> Self {
+        Self {
+            game_ids: matches
+                .iter()
+                .map(|v| {
+                    let key = LearnedGameMatchKey::new(
+                        normalize(&v.key.parent_folder),
+                        normalize(&v.key.file_stem),
+                    );
+                    (key, v.game_id)
+                })
+                .collect(),
+        }
+    }
+
+    pub fn find(&self, filepath: &str) -> Option<(&LearnedGameMatchKey, i32)> {
+        let key = LearnedGameMatchKey::from_path(filepath)?;
+        self.game_ids
+            .get_key_value(&key)
+            .map(|(key, game_id)| (key, *game_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_from_path_normalizes_parent_folder_and_file_stem() {
+        let key = LearnedGameMatchKey::from_path("/games/ＧａｍｅＡ/Start１.exe").unwrap();
+        assert_eq!(
+            key,
+            LearnedGameMatchKey::new("gamea".into(), "start1".into())
+        );
+
+        assert!(LearnedGameMatchKey::from_path("start.exe").is_none());
+    }
+
+    #[test]
+    fn test_find_by_path() {
+        let matches = LearnedGameMatches::new(&[LearnedGameMatch::new(
+            Id::new(1),
+            LearnedGameMatchKey::new("gamea".into(), "launcher".into()),
+            100,
+            Local::now(),
+        )]);
+
+        assert_eq!(
+            matches.find("/games/GameA/Launcher.exe").map(|v| v.1),
+            Some(100)
+        );
+        assert!(matches.find("/games/GameB/Launcher.exe").is_none());
+        assert!(matches.find("/games/GameA/config.exe").is_none());
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let file = LearnedGameMatchesFile::parse(
+            r#"{"version":1,"matches":[{"parentFolder":"gamea","fileStem":"launcher","gameId":100}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            file.matches,
+            vec![NewLearnedGameMatch::new(
+                LearnedGameMatchKey::new("gamea".into(), "launcher".into()),
+                100
+            )]
+        );
+
+        assert!(LearnedGameMatchesFile::parse(r#"{"version":2,"matches":[]}"#).is_err());
+    }
+
+    #[test]
+    fn test_new_learned_game_match_validate() {
+        let valid = NewLearnedGameMatch::new(
+            LearnedGameMatchKey::new("gamea".into(), "launcher".into()),
+            100,
+        );
+        assert!(valid.validate().is_ok());
+
+        let not_normalized = NewLearnedGameMatch::new(
+            LearnedGameMatchKey::new("GameA".into(), "launcher".into()),
+            100,
+        );
+        assert!(not_normalized.validate().is_err());
+
+        let invalid_id = NewLearnedGameMatch::new(
+            LearnedGameMatchKey::new("gamea".into(), "launcher".into()),
+            0,
+        );
+        assert!(invalid_id.validate().is_err());
+    }
+}
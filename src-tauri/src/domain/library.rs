@@ -0,0 +1,237 @@
+//! 監視するライブラリのフォルダ
+//!
+//! フォルダに追加された実行ファイルとショートカットをゲームとして登録し、
+//! 一致の確からしさが足りないものは候補として残す
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use derive_new::new;
+
+use super::{
+    collection::CollectionElement, distance::get_comparable_distance,
+    file::get_file_name_without_extension, normalize::normalize, Id,
+};
+
+pub const LIBRARY_AUTO_REGISTER_THRESHOLD_SETTING_KEY: &str = "library_auto_register_threshold";
+pub const DEFAULT_LIBRARY_AUTO_REGISTER_THRESHOLD: f32 = 0.9;
+
+#[derive(new, Debug, Clone)]
+pub struct LibraryRoot {
+    pub id: Id<LibraryRoot>,
+    pub path: String,
+    pub created_at: DateTime<Local>,
+}
+
+/// 自動で登録しなかったゲームの候補
+#[derive(new, Debug, Clone)]
+pub struct LibrarySuggestion {
+    pub id: Id<LibrarySuggestion>,
+    pub library_root_id: Id<LibraryRoot>,
+    pub file_path: String,
+    /// 候補のゲームの批評空間の ID
+    pub game_id: i32,
+    pub gamename: String,
+    pub confidence: f32,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(new, Debug, Clone)]
+pub struct NewLibrarySuggestion {
+    pub library_root_id: Id<LibraryRoot>,
+    pub file_path: String,
+    pub game_id: i32,
+    pub gamename: String,
+    pub confidence: f32,
+}
+
+/// インストール先が監視中のフォルダから削除されたゲーム
+#[derive(new, Debug, Clone)]
+pub struct RemovedInstall {
+    pub collection_element_id: Id<CollectionElement>,
+    pub gamename: String,
+    /// 見つからなくなった実行ファイルかショートカットのパス
+    pub path: String,
+    pub detected_at: DateTime<Local>,
+}
+
+/// 設定値から自動登録の閾値を読み込む。未設定・範囲外・不正な値の場合は既定値
+pub fn parse_library_auto_register_threshold(value: Option<&str>) -> f32 {
+    value
+        .and_then(|v| v.trim().parse::<f32>().ok())
+        .filter(|v| (0.0..=1.0).contains(v))
+        .unwrap_or(DEFAULT_LIBRARY_AUTO_REGISTER_THRESHOLD)
+}
+
+/// 登録の対象になる実行ファイルかショートカットか
+pub fn is_library_target_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|v| v.to_str())
+        .is_some_and(|v| v.eq_ignore_ascii_case("exe") || v.eq_ignore_ascii_case("lnk"))
+}
+
+/// パスを含む監視中のフォルダ。入れ子になっている場合は最も深いもの
+pub fn find_library_root<'a>(roots: &'a [LibraryRoot], path: &Path) -> Option<&'a LibraryRoot> {
+    roots
+        .iter()
+        .filter(|root| path.starts_with(&root.path))
+        .max_by_key(|root| Path::new(&root.path).components().count())
+}
+
+/// ゲーム名とファイル名・親フォルダ名・その親のフォルダ名の近さのうち最も大きいもの
+pub fn get_match_confidence(gamename: &str, file_path: &str) -> f32 {
+    let gamename = normalize(gamename);
+    let path = Path::new(file_path);
+    let folder_names = path
+        .ancestors()
+        .skip(1)
+        .take(2)
+        .filter_map(|v| v.file_name())
+        .map(|v| v.to_string_lossy().to_string());
+    get_file_name_without_extension(file_path)
+        .into_iter()
+        .chain(folder_names)
+        .map(|name| get_comparable_distance(&normalize(&name), &gamename))
+        .fold(0.0, f32::max)
+}
+
+/// 削除されたパスの下にあり、すでに存在しないインストール先か
+pub fn is_install_removed(install_path: &str, removed_path: &Path) -> bool {
+    let install_path = Path::new(install_path);
+    install_path.starts_with(removed_path) && !install_path.exists()
+}
+
+/// ファイルの変更をまとめる。インストール中は同じフォルダへの書き込みが続くため、
+/// 一定時間変更がなくなったパスだけを取り出す
+#[derive(Debug)]
+pub struct LibraryEventDebouncer {
+    quiet: Duration,
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl LibraryEventDebouncer {
+    pub fn new(quiet: Duration) -> Self {
+        Self {
+            quiet,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, path: PathBuf, now: Instant) {
+        self.pending.insert(path, now);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// 最後の変更から `quiet` だけ経ったパスを取り出す
+    pub fn take_ready(&mut self, now: Instant) -> Vec<PathBuf> {
+        let mut ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, at)| now.saturating_duration_since(**at) >= self.quiet)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in ready.iter() {
+            self.pending.remove(path);
+        }
+        ready.sort();
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(id: i32, path: &str) -> LibraryRoot {
+        LibraryRoot::new(Id::new(id), path.to_string(), Local::now())
+    }
+
+    #[test]
+    fn test_parse_library_auto_register_threshold() {
+        assert_eq!(parse_library_auto_register_threshold(Some("0.75")), 0.75);
+        assert_eq!(
+            parse_library_auto_register_threshold(None),
+            DEFAULT_LIBRARY_AUTO_REGISTER_THRESHOLD
+        );
+        assert_eq!(
+            parse_library_auto_register_threshold(Some("1.5")),
+            DEFAULT_LIBRARY_AUTO_REGISTER_THRESHOLD
+        );
+        assert_eq!(
+            parse_library_auto_register_threshold(Some("abc")),
+            DEFAULT_LIBRARY_AUTO_REGISTER_THRESHOLD
+        );
+    }
+
+    #[test]
+    fn test_is_library_target_file() {
+        assert!(is_library_target_file(Path::new("games/sakura/sakura.EXE")));
+        assert!(is_library_target_file(Path::new("desktop/サクラノ刻.lnk")));
+        assert!(!is_library_target_file(Path::new(
+            "games/sakura/readme.txt"
+        )));
+        assert!(!is_library_target_file(Path::new("games/sakura")));
+    }
+
+    #[test]
+    fn test_find_library_root_prefers_deepest() {
+        let roots = vec![
+            root(1, "games"),
+            root(2, "games/erogames"),
+            root(3, "other"),
+        ];
+
+        let found = find_library_root(&roots, Path::new("games/erogames/sakura/sakura.exe"));
+        assert_eq!(found.map(|v| v.id.value), Some(2));
+
+        let found = find_library_root(&roots, Path::new("games/sakura/sakura.exe"));
+        assert_eq!(found.map(|v| v.id.value), Some(1));
+
+        // 名前の先頭が一致するだけのフォルダは含まない
+        assert!(find_library_root(&roots, Path::new("games2/sakura.exe")).is_none());
+    }
+
+    #[test]
+    fn test_get_match_confidence() {
+        assert_eq!(
+            get_match_confidence("サクラノ刻", "games/サクラノ刻/game.exe"),
+            1.0
+        );
+        assert!(get_match_confidence("サクラノ刻", "games/tools/setup.exe") < 0.5);
+    }
+
+    #[test]
+    fn test_library_event_debouncer() {
+        let quiet = Duration::from_secs(5);
+        let mut debouncer = LibraryEventDebouncer::new(quiet);
+        let start = Instant::now();
+
+        debouncer.push(PathBuf::from("games/a/a.exe"), start);
+        debouncer.push(PathBuf::from("games/b/b.exe"), start);
+        // 書き込みが続くパスは待ち直す
+        debouncer.push(
+            PathBuf::from("games/a/a.exe"),
+            start + Duration::from_secs(3),
+        );
+
+        assert_eq!(
+            debouncer.take_ready(start + Duration::from_secs(5)),
+            vec![PathBuf::from("games/b/b.exe")]
+        );
+        assert!(debouncer
+            .take_ready(start + Duration::from_secs(7))
+            .is_empty());
+        assert_eq!(
+            debouncer.take_ready(start + Duration::from_secs(8)),
+            vec![PathBuf::from("games/a/a.exe")]
+        );
+        assert!(debouncer.is_empty());
+    }
+}
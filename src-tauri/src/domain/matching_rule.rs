@@ -0,0 +1,670 @@
+//! ファイルとゲームを紐づけるときの規則
+//!
+//! アプリに同梱した規則ファイルに、DB に保存したユーザーの上書きを重ねて使う
+
+use std::{collections::HashSet, str::FromStr};
+
+use anyhow::Context;
+use chrono::{DateTime, Local};
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+use super::{normalize::normalize, Id};
+
+/// 読み込める規則ファイルの版
+pub const SUPPORTED_MATCHING_RULES_VERSION: u32 = 1;
+
+const BUNDLED_MATCHING_RULES: &str = include_str!("../../rules/matching_rules.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchingRuleKind {
+    /// インストーラーやマニュアル等のファイル名に含まれる単語
+    NotGameTerm,
+    /// ファイル名では判定せず親フォルダ名で判定する汎用エンジン等のファイル名
+    GenericFileName,
+    /// 比較の前にファイル名から取り除く単語
+    RemoveWord,
+    /// 候補にしないゲーム
+    IgnoreGameId,
+    /// ファイル名が一致したらこのゲームにする
+    EqualFilename,
+    /// 親の親フォルダ名と親フォルダ名が一致したらこのゲームにする
+    PathSpecific,
+    /// 同じゲームのファイルが競合したときに選ばない単語
+    IgnoreWordOnConflict,
+    /// 同じゲームのファイルが競合したときに優先する単語
+    PreferredWordOnConflict,
+}
+
+impl MatchingRuleKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchingRuleKind::NotGameTerm => "notGameTerm",
+            MatchingRuleKind::GenericFileName => "genericFileName",
+            MatchingRuleKind::RemoveWord => "removeWord",
+            MatchingRuleKind::IgnoreGameId => "ignoreGameId",
+            MatchingRuleKind::EqualFilename => "equalFilename",
+            MatchingRuleKind::PathSpecific => "pathSpecific",
+            MatchingRuleKind::IgnoreWordOnConflict => "ignoreWordOnConflict",
+            MatchingRuleKind::PreferredWordOnConflict => "preferredWordOnConflict",
+        }
+    }
+}
+
+impl FromStr for MatchingRuleKind {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "notGameTerm" => Ok(MatchingRuleKind::NotGameTerm),
+            "genericFileName" => Ok(MatchingRuleKind::GenericFileName),
+            "removeWord" => Ok(MatchingRuleKind::RemoveWord),
+            "ignoreGameId" => Ok(MatchingRuleKind::IgnoreGameId),
+            "equalFilename" => Ok(MatchingRuleKind::EqualFilename),
+            "pathSpecific" => Ok(MatchingRuleKind::PathSpecific),
+            "ignoreWordOnConflict" => Ok(MatchingRuleKind::IgnoreWordOnConflict),
+            "preferredWordOnConflict" => Ok(MatchingRuleKind::PreferredWordOnConflict),
+            _ => Err(anyhow::anyhow!("unknown matching rule kind: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchingRuleAction {
+    Add,
+    Remove,
+}
+
+impl MatchingRuleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchingRuleAction::Add => "add",
+            MatchingRuleAction::Remove => "remove",
+        }
+    }
+}
+
+impl FromStr for MatchingRuleAction {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "add" => Ok(MatchingRuleAction::Add),
+            "remove" => Ok(MatchingRuleAction::Remove),
+            _ => Err(anyhow::anyhow!("unknown matching rule action: {}", s)),
+        }
+    }
+}
+
+#[derive(new, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EqualFilenameRule {
+    pub filename: String,
+    pub game_id: i32,
+}
+
+#[derive(new, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathSpecificRule {
+    pub grandparent: String,
+    pub parent: String,
+    pub game_id: i32,
+}
+
+/// 比較する文字列はいずれも `normalize` 済みのもの
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchingRules {
+    pub version: u32,
+    pub not_game_terms: Vec<String>,
+    pub generic_file_names: Vec<String>,
+    pub remove_words: Vec<String>,
+    pub ignore_game_ids: Vec<i32>,
+    pub equal_filenames: Vec<EqualFilenameRule>,
+    pub path_specific_rules: Vec<PathSpecificRule>,
+    pub ignore_words_on_conflict: Vec<String>,
+    pub preferred_words_on_conflict: Vec<String>,
+}
+
+/// 走査で当てはまった規則
+#[derive(new, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchingRuleHit {
+    pub kind: MatchingRuleKind,
+    pub value: String,
+    pub game_id: Option<i32>,
+}
+
+/// DB に保存したユーザーによる規則の追加・削除
+#[derive(new, Debug, Clone)]
+pub struct MatchingRuleOverride {
+    pub id: Id<MatchingRuleOverride>,
+    pub kind: MatchingRuleKind,
+    pub action: MatchingRuleAction,
+    /// 単語・ファイル名・親フォルダ名のいずれか
+    pub value: String,
+    pub grandparent: Option<String>,
+    pub game_id: Option<i32>,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(new, Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewMatchingRuleOverride {
+    pub kind: MatchingRuleKind,
+    pub action: MatchingRuleAction,
+    #[serde(default)]
+    pub value: String,
+    pub grandparent: Option<String>,
+    pub game_id: Option<i32>,
+}
+
+impl NewMatchingRuleOverride {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let kind = self.kind.as_str();
+        if self.kind == MatchingRuleKind::IgnoreGameId {
+            if self.game_id.is_none() {
+                anyhow::bail!("{} needs a game id", kind);
+            }
+            return Ok(());
+        }
+        if self.value.is_empty() {
+            anyhow::bail!("{} needs a value", kind);
+        }
+        if !matches!(
+            self.kind,
+            MatchingRuleKind::EqualFilename | MatchingRuleKind::PathSpecific
+        ) {
+            return Ok(());
+        }
+        if self.action == MatchingRuleAction::Add && self.game_id.is_none() {
+            anyhow::bail!("{} needs a game id", kind);
+        }
+        if self.kind == MatchingRuleKind::PathSpecific
+            && self.grandparent.as_deref().unwrap_or_default().is_empty()
+        {
+            anyhow::bail!("{} needs a grandparent folder name", kind);
+        }
+        Ok(())
+    }
+}
+
+fn validate_words(name: &str, words: &[String]) -> anyhow::Result<()> {
+    let mut seen = HashSet::new();
+    for word in words {
+        if word.is_empty() {
+            anyhow::bail!("{} contains a word without letters or digits", name);
+        }
+        if *word != normalize(word) {
+            anyhow::bail!(
+                "{} contains `{}` which never matches a normalized name",
+                name,
+                word
+            );
+        }
+        if !seen.insert(word) {
+            anyhow::bail!("{} contains `{}` more than once", name, word);
+        }
+    }
+    Ok(())
+}
+
+fn validate_game_id(name: &str, game_id: i32) -> anyhow::Result<()> {
+    if game_id <= 0 {
+        anyhow::bail!("{} contains an invalid game id {}", name, game_id);
+    }
+    Ok(())
+}
+
+fn add_word(words: &mut Vec<String>, word: &str) {
+    if !words.iter().any(|v| v == word) {
+        words.push(word.to_string());
+    }
+}
+
+impl MatchingRules {
+    /// 規則ファイルを読み込み、正規化してから検証する
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        let rules: MatchingRules =
+            serde_json::from_str(contents).context("invalid matching rules file")?;
+        let rules = rules.normalized();
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    /// 規則の単語・ファイル名・フォルダ名を比べる相手と同じく正規化する
+    pub fn normalized(mut self) -> Self {
+        for words in [
+            &mut self.not_game_terms,
+            &mut self.generic_file_names,
+            &mut self.remove_words,
+            &mut self.ignore_words_on_conflict,
+            &mut self.preferred_words_on_conflict,
+        ] {
+            for word in words.iter_mut() {
+                *word = normalize(word);
+            }
+        }
+        for rule in self.equal_filenames.iter_mut() {
+            rule.filename = normalize(&rule.filename);
+        }
+        for rule in self.path_specific_rules.iter_mut() {
+            rule.grandparent = normalize(&rule.grandparent);
+            rule.parent = normalize(&rule.parent);
+        }
+        self
+    }
+
+    pub fn bundled() -> anyhow::Result<Self> {
+        Self::parse(BUNDLED_MATCHING_RULES)
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.version != SUPPORTED_MATCHING_RULES_VERSION {
+            anyhow::bail!(
+                "matching rules version {} is not supported (expected {})",
+                self.version,
+                SUPPORTED_MATCHING_RULES_VERSION
+            );
+        }
+        validate_words("notGameTerms", &self.not_game_terms)?;
+        validate_words("genericFileNames", &self.generic_file_names)?;
+        validate_words("removeWords", &self.remove_words)?;
+        validate_words("ignoreWordsOnConflict", &self.ignore_words_on_conflict)?;
+        validate_words(
+            "preferredWordsOnConflict",
+            &self.preferred_words_on_conflict,
+        )?;
+
+        let mut ignore_game_ids = HashSet::new();
+        for game_id in self.ignore_game_ids.iter() {
+            validate_game_id("ignoreGameIds", *game_id)?;
+            if !ignore_game_ids.insert(game_id) {
+                anyhow::bail!("ignoreGameIds contains {} more than once", game_id);
+            }
+        }
+        let filenames: Vec<String> = self
+            .equal_filenames
+            .iter()
+            .map(|rule| rule.filename.clone())
+            .collect();
+        validate_words("equalFilenames", &filenames)?;
+        for rule in self.equal_filenames.iter() {
+            validate_game_id("equalFilenames", rule.game_id)?;
+        }
+        let mut paths = HashSet::new();
+        for rule in self.path_specific_rules.iter() {
+            validate_words(
+                "pathSpecificRules",
+                &[rule.grandparent.clone(), rule.parent.clone()],
+            )?;
+            validate_game_id("pathSpecificRules", rule.game_id)?;
+            if !paths.insert((&rule.grandparent, &rule.parent)) {
+                anyhow::bail!(
+                    "pathSpecificRules contains {}/{} more than once",
+                    rule.grandparent,
+                    rule.parent
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// ユーザーの上書きを正規化して古い順に重ねる。同じファイル名・フォルダの規則は置き換える
+    pub fn apply_overrides(mut self, overrides: &[MatchingRuleOverride]) -> Self {
+        for item in overrides {
+            let value = normalize(&item.value);
+            let value = value.as_str();
+            let is_add = item.action == MatchingRuleAction::Add;
+            let words = match item.kind {
+                MatchingRuleKind::NotGameTerm => Some(&mut self.not_game_terms),
+                MatchingRuleKind::GenericFileName => Some(&mut self.generic_file_names),
+                MatchingRuleKind::RemoveWord => Some(&mut self.remove_words),
+                MatchingRuleKind::IgnoreWordOnConflict => Some(&mut self.ignore_words_on_conflict),
+                MatchingRuleKind::PreferredWordOnConflict => {
+                    Some(&mut self.preferred_words_on_conflict)
+                }
+                _ => None,
+            };
+            if let Some(words) = words {
+                match is_add {
+                    true => add_word(words, value),
+                    false => words.retain(|v| v != value),
+                }
+                continue;
+            }
+
+            match item.kind {
+                MatchingRuleKind::IgnoreGameId => {
+                    let Some(game_id) = item.game_id else {
+                        continue;
+                    };
+                    self.ignore_game_ids.retain(|v| *v != game_id);
+                    if is_add {
+                        self.ignore_game_ids.push(game_id);
+                    }
+                }
+                MatchingRuleKind::EqualFilename => {
+                    self.equal_filenames.retain(|rule| rule.filename != value);
+                    if let (true, Some(game_id)) = (is_add, item.game_id) {
+                        self.equal_filenames
+                            .push(EqualFilenameRule::new(value.to_string(), game_id));
+                    }
+                }
+                MatchingRuleKind::PathSpecific => {
+                    let grandparent = normalize(item.grandparent.as_deref().unwrap_or_default());
+                    self.path_specific_rules
+                        .retain(|rule| rule.grandparent != grandparent || rule.parent != value);
+                    if let (true, Some(game_id)) = (is_add, item.game_id) {
+                        self.path_specific_rules.push(PathSpecificRule::new(
+                            grandparent,
+                            value.to_string(),
+                            game_id,
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+        self
+    }
+
+    /// ファイル名に含まれるインストーラーやマニュアル等の単語
+    pub fn find_not_game_term(&self, filename: &str) -> Option<&str> {
+        let filename_lower = filename.to_lowercase();
+        self.not_game_terms
+            .iter()
+            .find(|term| filename_lower.contains(term.as_str()))
+            .map(|term| term.as_str())
+    }
+
+    pub fn is_generic_file_name(&self, filename: &str) -> bool {
+        self.generic_file_names.contains(&filename.to_lowercase())
+    }
+
+    /// ファイル名から不要な単語を取り除き、取り除いた単語とともに返す
+    pub fn remove_words(&self, filename: &str) -> (String, Vec<&str>) {
+        let mut removed = vec![];
+        let filename = self
+            .remove_words
+            .iter()
+            .fold(filename.to_string(), |acc, word| {
+                if acc.contains(word.as_str()) {
+                    removed.push(word.as_str());
+                    acc.replace(word.as_str(), "")
+                } else {
+                    acc
+                }
+            });
+        (filename, removed)
+    }
+
+    pub fn is_ignored_game_id(&self, game_id: i32) -> bool {
+        self.ignore_game_ids.contains(&game_id)
+    }
+
+    pub fn find_equal_filename_rule(&self, filename: &str) -> Option<&EqualFilenameRule> {
+        self.equal_filenames
+            .iter()
+            .find(|rule| rule.filename == filename)
+    }
+
+    pub fn find_path_specific_rule(
+        &self,
+        grandparent: Option<&str>,
+        parent: &str,
+    ) -> Option<&PathSpecificRule> {
+        self.path_specific_rules
+            .iter()
+            .find(|rule| grandparent == Some(rule.grandparent.as_str()) && parent == rule.parent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> MatchingRules {
+        MatchingRules::bundled().unwrap()
+    }
+
+    fn new_override(
+        kind: MatchingRuleKind,
+        action: MatchingRuleAction,
+        value: &str,
+        game_id: Option<i32>,
+    ) -> MatchingRuleOverride {
+        MatchingRuleOverride::new(
+            Id::new(1),
+            kind,
+            action,
+            value.to_string(),
+            None,
+            game_id,
+            Local::now(),
+        )
+    }
+
+    // ========================================
+    // not_game のテスト
+    // ========================================
+    #[test]
+    fn test_not_game_returns_true_for_installer() {
+        // Arrange & Act & Assert
+        assert!(rules()
+            .find_not_game_term(&normalize("インストール"))
+            .is_some());
+        assert!(rules()
+            .find_not_game_term(&normalize("アンインストール"))
+            .is_some());
+        assert!(rules().find_not_game_term("install").is_some());
+        assert!(rules().find_not_game_term("uninstall").is_some());
+    }
+
+    #[test]
+    fn test_not_game_returns_true_for_manual() {
+        assert!(rules()
+            .find_not_game_term(&normalize("マニュアル"))
+            .is_some());
+        assert!(rules().find_not_game_term(&normalize("はじめに")).is_some());
+        assert!(rules().find_not_game_term(&normalize("サポート")).is_some());
+        // 半角カタカナも同じ単語として扱う
+        assert!(rules().find_not_game_term(&normalize("ﾏﾆｭｱﾙ")).is_some());
+    }
+
+    #[test]
+    fn test_not_game_returns_false_for_game() {
+        assert!(rules().find_not_game_term("game").is_none());
+        assert!(rules().find_not_game_term("start").is_none());
+        assert!(rules()
+            .find_not_game_term(&normalize("サクラノ詩"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_not_game_case_insensitive() {
+        // 大文字小文字を区別しない
+        assert!(rules().find_not_game_term("INSTALL").is_some());
+        assert!(rules().find_not_game_term("Install").is_some());
+    }
+
+    // ========================================
+    // remove_word のテスト
+    // ========================================
+    #[test]
+    fn test_remove_word_removes_launch_suffix() {
+        assert_eq!(
+            rules().remove_words(&normalize("ゲームを起動")).0,
+            normalize("ゲーム")
+        );
+        assert_eq!(
+            rules().remove_words(&normalize("ゲームの起動")).0,
+            normalize("ゲーム")
+        );
+    }
+
+    #[test]
+    fn test_remove_word_removes_brackets() {
+        // 括弧は正規化で取り除く
+        assert_eq!(
+            rules().remove_words(&normalize("「タイトル」")).0,
+            normalize("タイトル")
+        );
+    }
+
+    #[test]
+    fn test_remove_word_removes_dl_suffix() {
+        assert_eq!(
+            rules().remove_words(&normalize("ゲームダウンロード版")).0,
+            normalize("ゲーム")
+        );
+        assert_eq!(
+            rules().remove_words(&normalize("ゲームDL版")).0,
+            normalize("ゲーム")
+        );
+    }
+
+    #[test]
+    fn test_remove_word_no_change() {
+        assert_eq!(
+            rules().remove_words(&normalize("普通のゲーム名")).0,
+            normalize("普通のゲーム名")
+        );
+    }
+
+    #[test]
+    fn test_remove_words_reports_removed_words() {
+        let rules = rules();
+        let (filename, removed) = rules.remove_words(&normalize("「タイトル」を起動"));
+        assert_eq!(filename, normalize("タイトル"));
+        assert_eq!(removed, vec!["を起動"]);
+    }
+
+    // ========================================
+    // 規則ファイルの検証のテスト
+    // ========================================
+    #[test]
+    fn test_bundled_rules_are_valid() {
+        let rules = rules();
+        assert_eq!(rules.version, SUPPORTED_MATCHING_RULES_VERSION);
+        assert!(rules.is_generic_file_name("BGI"));
+        assert!(rules.is_ignored_game_id(2644));
+        assert_eq!(
+            rules
+                .find_path_specific_rule(Some("枕"), &normalize("サクラノ詩"))
+                .map(|v| v.game_id),
+            Some(4529)
+        );
+        assert!(rules
+            .find_path_specific_rule(None, &normalize("サクラノ詩"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_rules() {
+        let mut unsupported = rules();
+        unsupported.version = SUPPORTED_MATCHING_RULES_VERSION + 1;
+        assert!(unsupported.validate().is_err());
+
+        let mut duplicated = rules();
+        duplicated.not_game_terms.push("install".to_string());
+        assert!(duplicated.validate().is_err());
+
+        // 大文字はファイル名と一致しない
+        let mut not_normalized = rules();
+        not_normalized
+            .ignore_words_on_conflict
+            .push("Config2".to_string());
+        assert!(not_normalized.validate().is_err());
+
+        let mut invalid_id = rules();
+        invalid_id
+            .equal_filenames
+            .push(EqualFilenameRule::new("sample".to_string(), 0));
+        assert!(invalid_id.validate().is_err());
+
+        // 記号だけの単語は正規化すると何とも一致しない
+        let symbol_only = rules().apply_overrides(&[new_override(
+            MatchingRuleKind::RemoveWord,
+            MatchingRuleAction::Add,
+            "☆",
+            None,
+        )]);
+        assert!(symbol_only.validate().is_err());
+
+        assert!(MatchingRules::parse(r#"{"version": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_apply_overrides() {
+        let rules = rules().apply_overrides(&[
+            new_override(
+                MatchingRuleKind::NotGameTerm,
+                MatchingRuleAction::Add,
+                "ツール",
+                None,
+            ),
+            new_override(
+                MatchingRuleKind::NotGameTerm,
+                MatchingRuleAction::Remove,
+                "体験版",
+                None,
+            ),
+            new_override(
+                MatchingRuleKind::IgnoreGameId,
+                MatchingRuleAction::Remove,
+                "",
+                Some(2644),
+            ),
+            // 同じファイル名の規則は置き換える
+            new_override(
+                MatchingRuleKind::EqualFilename,
+                MatchingRuleAction::Add,
+                "pieces",
+                Some(100),
+            ),
+        ]);
+
+        assert!(rules.find_not_game_term(&normalize("設定ツール")).is_some());
+        assert!(rules
+            .find_not_game_term(&normalize("ゲーム体験版"))
+            .is_none());
+        assert!(!rules.is_ignored_game_id(2644));
+        assert_eq!(
+            rules.find_equal_filename_rule("pieces").map(|v| v.game_id),
+            Some(100)
+        );
+        assert_eq!(rules.equal_filenames.len(), 1);
+        assert!(rules.validate().is_ok());
+    }
+
+    #[test]
+    fn test_new_override_validate() {
+        let word = NewMatchingRuleOverride::new(
+            MatchingRuleKind::RemoveWord,
+            MatchingRuleAction::Add,
+            "".to_string(),
+            None,
+            None,
+        );
+        assert!(word.validate().is_err());
+
+        let path = NewMatchingRuleOverride::new(
+            MatchingRuleKind::PathSpecific,
+            MatchingRuleAction::Add,
+            "すみれ".to_string(),
+            None,
+            Some(20178),
+        );
+        assert!(path.validate().is_err());
+
+        let path = NewMatchingRuleOverride::new(
+            MatchingRuleKind::PathSpecific,
+            MatchingRuleAction::Remove,
+            "すみれ".to_string(),
+            Some("nekoneko".to_string()),
+            None,
+        );
+        assert!(path.validate().is_ok());
+    }
+}
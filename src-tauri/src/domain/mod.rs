@@ -4,15 +4,32 @@ use derive_new::new;
 use serde::{Deserialize, Serialize};
 
 pub mod all_game_cache;
+pub mod candidate_index;
 pub mod collection;
 pub mod distance;
 pub mod explored_cache;
+pub mod external_play_time;
 pub mod file;
+pub mod input_activity;
+pub mod launch;
+pub mod launch_hook;
+pub mod learned_match;
+pub mod library;
+pub mod matching_rule;
+pub mod normalize;
+pub mod pe_version;
+pub mod play_day;
+pub mod play_session;
+pub mod play_stats;
 
 pub mod process;
 
 pub mod explorer;
 pub mod repository;
+pub mod scan;
+pub mod smart_collection;
+pub mod tag;
+pub mod tracking_checkpoint;
 pub mod windows;
 
 #[derive(new, Debug, Clone, Copy, Serialize, Deserialize)]
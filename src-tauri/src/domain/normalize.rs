@@ -0,0 +1,268 @@
+//! ゲーム名・フォルダ名・ファイル名を比べるための正規化
+//!
+//! ファイルとゲームの紐づけ、キーの近さの計算、ゲームのキャッシュの検索で同じ正規化を使う
+
+use unicode_normalization::UnicodeNormalization;
+
+/// 旧字体・異体字と新字体の組
+const KANJI_VARIANTS: &[(char, char)] = &[
+    ('亞', '亜'),
+    ('假', '仮'),
+    ('會', '会'),
+    ('傳', '伝'),
+    ('兒', '児'),
+    ('內', '内'),
+    ('兩', '両'),
+    ('劍', '剣'),
+    ('勞', '労'),
+    ('區', '区'),
+    ('參', '参'),
+    ('單', '単'),
+    ('嚴', '厳'),
+    ('國', '国'),
+    ('圓', '円'),
+    ('圖', '図'),
+    ('團', '団'),
+    ('壞', '壊'),
+    ('壹', '壱'),
+    ('聲', '声'),
+    ('處', '処'),
+    ('變', '変'),
+    ('學', '学'),
+    ('實', '実'),
+    ('寫', '写'),
+    ('將', '将'),
+    ('專', '専'),
+    ('對', '対'),
+    ('嶋', '島'),
+    ('峯', '峰'),
+    ('廣', '広'),
+    ('彈', '弾'),
+    ('從', '従'),
+    ('德', '徳'),
+    ('戀', '恋'),
+    ('惡', '悪'),
+    ('應', '応'),
+    ('戰', '戦'),
+    ('擧', '挙'),
+    ('數', '数'),
+    ('條', '条'),
+    ('來', '来'),
+    ('櫻', '桜'),
+    ('樂', '楽'),
+    ('榮', '栄'),
+    ('權', '権'),
+    ('歡', '歓'),
+    ('歲', '歳'),
+    ('歷', '歴'),
+    ('歸', '帰'),
+    ('氣', '気'),
+    ('淺', '浅'),
+    ('澤', '沢'),
+    ('滿', '満'),
+    ('燈', '灯'),
+    ('爲', '為'),
+    ('狀', '状'),
+    ('獨', '独'),
+    ('畫', '画'),
+    ('當', '当'),
+    ('發', '発'),
+    ('盡', '尽'),
+    ('眞', '真'),
+    ('禮', '礼'),
+    ('稱', '称'),
+    ('絲', '糸'),
+    ('經', '経'),
+    ('繪', '絵'),
+    ('續', '続'),
+    ('總', '総'),
+    ('聽', '聴'),
+    ('舊', '旧'),
+    ('莊', '荘'),
+    ('螢', '蛍'),
+    ('號', '号'),
+    ('覺', '覚'),
+    ('觀', '観'),
+    ('譯', '訳'),
+    ('證', '証'),
+    ('讀', '読'),
+    ('豐', '豊'),
+    ('邊', '辺'),
+    ('邉', '辺'),
+    ('醫', '医'),
+    ('釋', '釈'),
+    ('鐵', '鉄'),
+    ('關', '関'),
+    ('險', '険'),
+    ('隱', '隠'),
+    ('雙', '双'),
+    ('靈', '霊'),
+    ('體', '体'),
+    ('驛', '駅'),
+    ('髮', '髪'),
+    ('鬪', '闘'),
+    ('麥', '麦'),
+    ('黑', '黒'),
+    ('齋', '斎'),
+    ('齒', '歯'),
+    ('龍', '竜'),
+    ('龜', '亀'),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizeOptions {
+    /// 旧字体を新字体にそろえる
+    pub fold_kanji_variants: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            fold_kanji_variants: true,
+        }
+    }
+}
+
+fn is_kana(ch: char) -> bool {
+    ('\u{3041}'..='\u{309F}').contains(&ch) || ('\u{30A0}'..='\u{30FF}').contains(&ch)
+}
+
+/// カタカナをひらがなにそろえる。対応するひらがながない文字はそのまま
+fn fold_kana(ch: char) -> char {
+    match ch {
+        'ァ'..='ヶ' | 'ヽ' | 'ヾ' => char::from_u32(ch as u32 - 0x60).unwrap_or(ch),
+        _ => ch,
+    }
+}
+
+fn is_dash(ch: char) -> bool {
+    matches!(
+        ch,
+        '-' | '\u{2010}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}' | '\u{2212}' | '─'
+    )
+}
+
+fn fold_kanji_variant(ch: char) -> char {
+    KANJI_VARIANTS
+        .iter()
+        .find(|(variant, _)| *variant == ch)
+        .map(|(_, kanji)| *kanji)
+        .unwrap_or(ch)
+}
+
+/// 既定の設定で正規化する
+pub fn normalize(s: &str) -> String {
+    normalize_with(s, &NormalizeOptions::default())
+}
+
+/// NFKC で全角英数字・半角カタカナ等をそろえ、小文字にしてカタカナをひらがなにそろえる。
+/// かなに挟まれた長音記号の異体は `ー` にし、それ以外の記号と空白は取り除く
+pub fn normalize_with(s: &str, options: &NormalizeOptions) -> String {
+    let chars: Vec<char> = s.nfkc().flat_map(char::to_lowercase).collect();
+    let mut result = String::with_capacity(s.len());
+    for (i, ch) in chars.iter().copied().enumerate() {
+        let is_between_kana =
+            i > 0 && is_kana(chars[i - 1]) && chars.get(i + 1).copied().is_some_and(is_kana);
+        let ch = if is_dash(ch) && is_between_kana {
+            'ー'
+        } else {
+            fold_kana(ch)
+        };
+        if !ch.is_alphanumeric() {
+            continue;
+        }
+        let ch = match options.fold_kanji_variants {
+            true => fold_kanji_variant(ch),
+            false => ch,
+        };
+        result.push(ch);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_fullwidth_to_halfwidth() {
+        // 全角英字→半角
+        assert_eq!(normalize("ＡＢＣＤ"), "abcd");
+        assert_eq!(normalize("ａｂｃｄ"), "abcd");
+    }
+
+    #[test]
+    fn test_normalize_fullwidth_numbers() {
+        // 全角数字→半角
+        assert_eq!(normalize("１２３"), "123");
+    }
+
+    #[test]
+    fn test_normalize_mixed() {
+        assert_eq!(normalize("Ｈｅｌｌｏ１２３"), "hello123");
+    }
+
+    #[test]
+    fn test_normalize_japanese_unchanged() {
+        // ひらがなはそのまま
+        assert_eq!(normalize("こんにちは"), "こんにちは");
+    }
+
+    #[test]
+    fn test_normalize_folds_kana() {
+        assert_eq!(normalize("ｻｸﾗﾉｳﾀ"), normalize("サクラノうた"));
+        assert_eq!(normalize("ｻｸﾗﾉｳﾀ"), "さくらのうた");
+        assert_eq!(normalize("ｶﾞｰﾙ"), "がーる");
+        assert_eq!(normalize("ヴァルキリー"), "ゔぁるきりー");
+    }
+
+    #[test]
+    fn test_normalize_canonicalizes_long_vowel_marks() {
+        assert_eq!(normalize("ゲ－ム"), "げーむ");
+        assert_eq!(normalize("ゲ―ム"), "げーむ");
+        assert_eq!(normalize("ゲ-ム"), "げーむ");
+        // かなに挟まれていなければ記号として取り除く
+        assert_eq!(normalize("Fate-stay night"), "fatestaynight");
+        assert_eq!(normalize("ゲーム -サブ-"), "げーむさぶ");
+    }
+
+    #[test]
+    fn test_normalize_strips_punctuation() {
+        assert_eq!(normalize("恋する☆乙女♪！"), "恋する乙女");
+        assert_eq!(normalize("ゲーム〜サブタイトル～"), "げーむさぶたいとる");
+        assert_eq!(normalize("「タイトル」 ・ 2"), "たいとる2");
+        assert_eq!(normalize("ＤＬ版"), "dl版");
+    }
+
+    #[test]
+    fn test_normalize_folds_kanji_variants() {
+        assert_eq!(
+            normalize("サクラノ詩 -櫻の森の上を舞う-"),
+            "さくらの詩桜の森の上を舞う"
+        );
+        assert_eq!(
+            normalize_with(
+                "櫻",
+                &NormalizeOptions {
+                    fold_kanji_variants: false
+                }
+            ),
+            "櫻"
+        );
+    }
+
+    #[test]
+    fn test_normalize_is_idempotent() {
+        for s in ["ｻｸﾗﾉｳﾀ", "ゲ－ム☆", "ＤＬ版", "櫻の森", "Ｈｅｌｌｏ １２３"]
+        {
+            assert_eq!(normalize(&normalize(s)), normalize(s));
+        }
+    }
+
+    #[test]
+    fn test_kanji_variants_do_not_chain() {
+        for (_, kanji) in KANJI_VARIANTS {
+            assert!(KANJI_VARIANTS.iter().all(|(variant, _)| variant != kanji));
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! 実行ファイルのバージョン情報
+//!
+//! PE のリソースから VS_VERSIONINFO を読み、製品名・説明・会社名を取り出す。
+//! Windows 以外でも読めるよう API を使わず、ヘッダーとバージョン情報のリソースだけを読む
+
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+};
+
+const RT_VERSION: u32 = 16;
+const RESOURCE_DIRECTORY_INDEX: usize = 2;
+/// 壊れたファイルで大きな領域を読まないための上限
+const MAX_DIRECTORY_ENTRIES: usize = 4096;
+const MAX_VERSION_INFO_SIZE: usize = 64 * 1024;
+/// 日本語の文字列表を優先する
+const PREFERRED_LANGUAGE: &str = "0411";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeVersionInfo {
+    pub product_name: Option<String>,
+    pub file_description: Option<String>,
+    pub company_name: Option<String>,
+}
+
+impl PeVersionInfo {
+    /// ゲーム名と比べる名前
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        [&self.product_name, &self.file_description]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// バージョン情報がなければ None。大きな実行ファイルも全体は読まない
+    pub fn read_from<R: Read + Seek>(reader: &mut R) -> anyhow::Result<Option<Self>> {
+        read_version_info(reader)
+    }
+
+    pub fn read(path: &str) -> anyhow::Result<Option<Self>> {
+        Self::read_from(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+/// 拡張子が exe のファイルのバージョン情報。読めない場合は None
+pub fn get_exe_version_info(path: &str) -> Option<PeVersionInfo> {
+    if !path.to_lowercase().ends_with(".exe") {
+        return None;
+    }
+    PeVersionInfo::read(path).ok().flatten()
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> anyhow::Result<u16> {
+    bytes
+        .get(offset..offset + 2)
+        .map(|v| u16::from_le_bytes([v[0], v[1]]))
+        .ok_or_else(|| anyhow::anyhow!("unexpected end of data at {}", offset))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> anyhow::Result<u32> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|v| u32::from_le_bytes([v[0], v[1], v[2], v[3]]))
+        .ok_or_else(|| anyhow::anyhow!("unexpected end of data at {}", offset))
+}
+
+fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, len: usize) -> anyhow::Result<Vec<u8>> {
+    let mut buf = vec![0; len];
+    reader.seek(SeekFrom::Start(offset))?;
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+struct Section {
+    virtual_address: u32,
+    raw_size: u32,
+    raw_pointer: u32,
+}
+
+fn rva_to_offset(sections: &[Section], rva: u32) -> Option<u64> {
+    sections.iter().find_map(|section| {
+        let delta = rva.checked_sub(section.virtual_address)?;
+        if delta >= section.raw_size {
+            return None;
+        }
+        Some(section.raw_pointer as u64 + delta as u64)
+    })
+}
+
+/// リソースディレクトリの項目。`id` が None のものは名前付き
+struct ResourceEntry {
+    id: Option<u32>,
+    offset: u32,
+    is_directory: bool,
+}
+
+fn read_resource_directory<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+) -> anyhow::Result<Vec<ResourceEntry>> {
+    let header = read_at(reader, offset, 16)?;
+    let count = u16_at(&header, 12)? as usize + u16_at(&header, 14)? as usize;
+    if count > MAX_DIRECTORY_ENTRIES {
+        anyhow::bail!("too many resource entries: {}", count);
+    }
+    let entries = read_at(reader, offset + 16, count * 8)?;
+    (0..count)
+        .map(|i| {
+            let name = u32_at(&entries, i * 8)?;
+            let data = u32_at(&entries, i * 8 + 4)?;
+            Ok(ResourceEntry {
+                id: (name & 0x8000_0000 == 0).then_some(name),
+                offset: data & 0x7fff_ffff,
+                is_directory: data & 0x8000_0000 != 0,
+            })
+        })
+        .collect()
+}
+
+fn read_version_info<R: Read + Seek>(reader: &mut R) -> anyhow::Result<Option<PeVersionInfo>> {
+    let dos_header = read_at(reader, 0, 64)?;
+    if &dos_header[..2] != b"MZ" {
+        anyhow::bail!("not a PE file");
+    }
+    let pe_offset = u32_at(&dos_header, 0x3c)? as u64;
+    let coff_header = read_at(reader, pe_offset, 24)?;
+    if &coff_header[..4] != b"PE\0\0" {
+        anyhow::bail!("PE signature not found");
+    }
+    let section_count = u16_at(&coff_header, 6)? as usize;
+    let optional_header_size = u16_at(&coff_header, 20)? as usize;
+    let optional_header = read_at(reader, pe_offset + 24, optional_header_size)?;
+    let data_directories = match u16_at(&optional_header, 0)? {
+        0x10b => 96,
+        0x20b => 112,
+        magic => anyhow::bail!("unknown optional header magic: {:#x}", magic),
+    };
+    let directory_count = u32_at(&optional_header, data_directories - 4)? as usize;
+    if directory_count <= RESOURCE_DIRECTORY_INDEX {
+        return Ok(None);
+    }
+    let resource_rva = u32_at(
+        &optional_header,
+        data_directories + RESOURCE_DIRECTORY_INDEX * 8,
+    )?;
+    if resource_rva == 0 {
+        return Ok(None);
+    }
+
+    let section_table = read_at(
+        reader,
+        pe_offset + 24 + optional_header_size as u64,
+        section_count * 40,
+    )?;
+    let sections = (0..section_count)
+        .map(|i| {
+            Ok(Section {
+                virtual_address: u32_at(&section_table, i * 40 + 12)?,
+                raw_size: u32_at(&section_table, i * 40 + 16)?,
+                raw_pointer: u32_at(&section_table, i * 40 + 20)?,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let resource_offset = rva_to_offset(&sections, resource_rva)
+        .ok_or_else(|| anyhow::anyhow!("resource directory is outside the sections"))?;
+
+    // 種類 -> 名前 -> 言語 の順にたどり、名前と言語は最初のものを使う
+    let Some(version) = read_resource_directory(reader, resource_offset)?
+        .into_iter()
+        .find(|v| v.id == Some(RT_VERSION) && v.is_directory)
+    else {
+        return Ok(None);
+    };
+    let mut entry = version;
+    for _ in 0..2 {
+        if !entry.is_directory {
+            break;
+        }
+        let Some(next) = read_resource_directory(reader, resource_offset + entry.offset as u64)?
+            .into_iter()
+            .next()
+        else {
+            return Ok(None);
+        };
+        entry = next;
+    }
+    if entry.is_directory {
+        anyhow::bail!("version resource is nested too deep");
+    }
+
+    let data_entry = read_at(reader, resource_offset + entry.offset as u64, 16)?;
+    let data_rva = u32_at(&data_entry, 0)?;
+    let data_size = (u32_at(&data_entry, 4)? as usize).min(MAX_VERSION_INFO_SIZE);
+    let data_offset = rva_to_offset(&sections, data_rva)
+        .ok_or_else(|| anyhow::anyhow!("version resource is outside the sections"))?;
+    let data = read_at(reader, data_offset, data_size)?;
+    parse_version_info(&data).map(Some)
+}
+
+/// VS_VERSIONINFO・StringFileInfo・StringTable・String に共通する入れ子の構造
+struct VersionBlock<'a> {
+    key: String,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+fn utf16_until_nul(bytes: &[u8]) -> (String, usize) {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|v| u16::from_le_bytes([v[0], v[1]]))
+        .take_while(|v| *v != 0)
+        .collect();
+    (String::from_utf16_lossy(&units), units.len() * 2)
+}
+
+/// `bytes` に並ぶブロックを読む。長さが壊れているところで打ち切る
+fn parse_blocks(bytes: &[u8]) -> Vec<VersionBlock<'_>> {
+    let mut blocks = vec![];
+    let mut offset = 0;
+    while offset + 6 <= bytes.len() {
+        let Ok(len) = u16_at(bytes, offset) else {
+            break;
+        };
+        let len = len as usize;
+        if len < 6 || offset + len > bytes.len() {
+            break;
+        }
+        let block = &bytes[offset..offset + len];
+        let value_len = u16_at(block, 2).unwrap_or(0) as usize;
+        let is_text = u16_at(block, 4).unwrap_or(0) == 1;
+        let (key, key_len) = utf16_until_nul(&block[6..]);
+        let value_start = align4(6 + key_len + 2).min(len);
+        // 文字列の値の長さは文字数で書かれるが、バイト数で書くツールもあるため末尾までを値とする
+        let value_end = match is_text {
+            true if value_len > 0 => len,
+            true => value_start,
+            false => (value_start + value_len).min(len),
+        };
+        let children_start = align4(value_end).min(len);
+        blocks.push(VersionBlock {
+            key,
+            value: &block[value_start..value_end],
+            children: if is_text && value_len > 0 {
+                &[]
+            } else {
+                &block[children_start..]
+            },
+        });
+        offset = align4(offset + len);
+    }
+    blocks
+}
+
+fn parse_version_info(data: &[u8]) -> anyhow::Result<PeVersionInfo> {
+    let root = parse_blocks(data)
+        .into_iter()
+        .find(|v| v.key == "VS_VERSION_INFO")
+        .ok_or_else(|| anyhow::anyhow!("VS_VERSION_INFO not found"))?;
+    let mut tables: Vec<VersionBlock> = parse_blocks(root.children)
+        .into_iter()
+        .filter(|v| v.key == "StringFileInfo")
+        .flat_map(|v| parse_blocks(v.children))
+        .collect();
+    tables.sort_by_key(|v| !v.key.to_lowercase().starts_with(PREFERRED_LANGUAGE));
+
+    let find = |name: &str| {
+        tables.iter().find_map(|table| {
+            parse_blocks(table.children)
+                .into_iter()
+                .filter(|v| v.key == name)
+                .map(|v| utf16_until_nul(v.value).0.trim().to_string())
+                .find(|v| !v.is_empty())
+        })
+    };
+    Ok(PeVersionInfo {
+        product_name: find("ProductName"),
+        file_description: find("FileDescription"),
+        company_name: find("CompanyName"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> anyhow::Result<Option<PeVersionInfo>> {
+        PeVersionInfo::read_from(&mut Cursor::new(bytes))
+    }
+
+    const PE32_JA: &[u8] = include_bytes!("../../fixtures/pe_version/pe32_ja.exe");
+    const PE32PLUS_MULTI_LANGUAGE: &[u8] =
+        include_bytes!("../../fixtures/pe_version/pe32plus_multi_language.exe");
+    const NO_VERSION: &[u8] = include_bytes!("../../fixtures/pe_version/no_version.exe");
+
+    #[test]
+    fn test_parse_pe32_version_info() {
+        let info = parse(PE32_JA).unwrap().unwrap();
+        assert_eq!(
+            info,
+            PeVersionInfo {
+                product_name: Some("サクラノ詩 -櫻の森の上を舞う-".to_string()),
+                file_description: Some("サクラノ詩".to_string()),
+                company_name: Some("サンプルブランド".to_string()),
+            }
+        );
+        assert_eq!(
+            info.names().collect::<Vec<_>>(),
+            vec!["サクラノ詩 -櫻の森の上を舞う-", "サクラノ詩"]
+        );
+    }
+
+    #[test]
+    fn test_parse_pe32plus_prefers_japanese_table() {
+        let info = parse(PE32PLUS_MULTI_LANGUAGE).unwrap().unwrap();
+        assert_eq!(info.product_name.as_deref(), Some("サクラノ詩"));
+        assert_eq!(info.company_name.as_deref(), Some("サンプルブランド"));
+        assert_eq!(info.file_description, None);
+    }
+
+    #[test]
+    fn test_parse_without_version_resource() {
+        assert_eq!(parse(NO_VERSION).unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_invalid_files() {
+        assert!(parse(b"").is_err());
+        assert!(parse(&[0; 64]).is_err());
+        // ヘッダーの途中で切れたファイル
+        assert!(parse(&PE32_JA[..200]).is_err());
+        // リソースの途中で切れたファイル
+        assert!(parse(&PE32_JA[..0x220]).is_err());
+    }
+
+    #[test]
+    fn test_get_exe_version_info() {
+        let path = std::env::temp_dir().join("launcherg_pe_version_test.exe");
+        std::fs::write(&path, PE32_JA).unwrap();
+        let info = get_exe_version_info(&path.to_string_lossy());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            info.and_then(|v| v.company_name).as_deref(),
+            Some("サンプルブランド")
+        );
+
+        assert_eq!(get_exe_version_info("/not/found/game.exe"), None);
+        assert_eq!(get_exe_version_info("/not/found/game.lnk"), None);
+    }
+}
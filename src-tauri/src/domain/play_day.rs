@@ -0,0 +1,387 @@
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+
+use super::play_session::{covers_played_seconds, PlaySession, PlayedRange};
+
+pub const DAY_BOUNDARY_SETTING_KEY: &str = "day_boundary";
+
+/// 日ごとのプレイ時間を区切る時刻。深夜のプレイを前日として数えるために使う
+///
+/// 設定値は `HH:MM` 形式で、既定は 00:00
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DayBoundary {
+    minutes: u32,
+}
+
+impl DayBoundary {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || format!("日付の区切りは HH:MM 形式で指定してください: {}", value);
+        let (hours, minutes) = value.trim().split_once(':').ok_or_else(invalid)?;
+        let hours: u32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+        if hours >= 24 || minutes >= 60 {
+            return Err(invalid());
+        }
+        Ok(Self {
+            minutes: hours * 60 + minutes,
+        })
+    }
+
+    /// アプリ設定の値から読み込む。未設定や不正な値の場合は 00:00
+    pub fn from_setting(value: Option<&str>) -> Self {
+        value.and_then(|v| Self::parse(v).ok()).unwrap_or_default()
+    }
+
+    pub fn to_setting_value(self) -> String {
+        format!("{:02}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+
+    /// 日時が属するプレイ日
+    pub fn play_date(&self, at: DateTime<Local>) -> NaiveDate {
+        (at.naive_local() - chrono::Duration::minutes(self.minutes as i64)).date()
+    }
+
+    /// プレイ日の始まりの日時。夏時間の切り替えで存在しない時刻の場合は None
+    pub fn day_start(&self, date: NaiveDate) -> Option<DateTime<Local>> {
+        let start = date.and_hms_opt(0, 0, 0)? + chrono::Duration::minutes(self.minutes as i64);
+        Local.from_local_datetime(&start).earliest()
+    }
+
+    /// プレイ日 `from` から `to` まで(どちらも含む)の日時の範囲。終わりは含まない
+    pub fn date_range(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        Some((self.day_start(from)?, self.day_start(to.succ_opt()?)?))
+    }
+}
+
+/// `end_time` で終わる `seconds` 秒のプレイ時間をプレイ日ごとに分ける
+pub fn split_play_time_by_local_date(
+    end_time: DateTime<Local>,
+    seconds: i32,
+    day_boundary: DayBoundary,
+) -> Vec<(NaiveDate, i32)> {
+    if seconds <= 0 {
+        return Vec::new();
+    }
+
+    let mut remaining_seconds = seconds as i64;
+    let mut cursor = end_time - chrono::Duration::seconds(remaining_seconds);
+    let mut results: Vec<(NaiveDate, i32)> = Vec::new();
+
+    while remaining_seconds > 0 {
+        let play_date = day_boundary.play_date(cursor);
+        let seconds_for_date = play_date
+            .succ_opt()
+            .and_then(|next_date| day_boundary.day_start(next_date))
+            .map(|next_day_start| {
+                let seconds_until_next_date = (next_day_start - cursor).num_seconds().max(1);
+                remaining_seconds.min(seconds_until_next_date)
+            })
+            .unwrap_or(remaining_seconds);
+
+        if let Some((_, existing_seconds)) = results.iter_mut().find(|(date, _)| *date == play_date)
+        {
+            *existing_seconds += seconds_for_date as i32;
+        } else {
+            results.push((play_date, seconds_for_date as i32));
+        }
+
+        cursor += chrono::Duration::seconds(seconds_for_date);
+        remaining_seconds -= seconds_for_date;
+    }
+
+    results
+}
+
+/// セッションのプレイ時間をプレイ日ごとに分ける
+///
+/// プレイしていた範囲がプレイ時間のすべてを表している場合は範囲ごとに分け、
+/// そうでなければ `ended_at` まで続けてプレイしたものとして分ける
+pub fn split_played_ranges_by_local_date(
+    played_ranges: &[PlayedRange],
+    ended_at: DateTime<Local>,
+    played_seconds: i32,
+    day_boundary: DayBoundary,
+) -> Vec<(NaiveDate, i32)> {
+    if !covers_played_seconds(played_ranges, played_seconds) {
+        return split_play_time_by_local_date(ended_at, played_seconds, day_boundary);
+    }
+    let mut results: Vec<(NaiveDate, i32)> = Vec::new();
+    for (started_at, ended_at) in played_ranges {
+        let seconds = (*ended_at - *started_at).num_seconds() as i32;
+        for (play_date, daily_seconds) in
+            split_play_time_by_local_date(*ended_at, seconds, day_boundary)
+        {
+            match results.iter_mut().find(|(date, _)| *date == play_date) {
+                Some((_, existing)) => *existing += daily_seconds,
+                None => results.push((play_date, daily_seconds)),
+            }
+        }
+    }
+    results
+}
+
+/// 日付の区切りを変えたときに、終了済みのセッションの時間を移す量をエレメントとプレイ日ごとに求める
+///
+/// プレイした時刻が分からないセッション(範囲を記録していない古い追跡のセッションで、ポーズや中断があるもの)は移さない
+pub fn compute_daily_play_time_rebucket(
+    sessions: &[PlaySession],
+    from: DayBoundary,
+    to: DayBoundary,
+) -> Vec<(i32, NaiveDate, i32)> {
+    let mut deltas: Vec<(i32, NaiveDate, i32)> = Vec::new();
+    let mut add = |element_id: i32, play_date: NaiveDate, seconds: i32| match deltas
+        .iter_mut()
+        .find(|(id, date, _)| *id == element_id && *date == play_date)
+    {
+        Some((_, _, existing)) => *existing += seconds,
+        None => deltas.push((element_id, play_date, seconds)),
+    };
+    for session in sessions {
+        let Some(ended_at) = session.ended_at else {
+            continue;
+        };
+        if !session.has_known_played_ranges() {
+            continue;
+        }
+        let element_id = session.collection_element_id.value;
+        let split = |day_boundary| {
+            split_played_ranges_by_local_date(
+                &session.played_ranges,
+                ended_at,
+                session.played_seconds,
+                day_boundary,
+            )
+        };
+        for (play_date, seconds) in split(from) {
+            add(element_id, play_date, -seconds);
+        }
+        for (play_date, seconds) in split(to) {
+            add(element_id, play_date, seconds);
+        }
+    }
+    deltas.retain(|(_, _, seconds)| *seconds != 0);
+    deltas.sort_by_key(|(id, date, _)| (*id, *date));
+    deltas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Id;
+
+    #[test]
+    fn test_parse_day_boundary() {
+        assert_eq!(
+            DayBoundary::parse("04:00"),
+            Ok(DayBoundary { minutes: 240 })
+        );
+        assert_eq!(
+            DayBoundary::parse(" 5:30 "),
+            Ok(DayBoundary { minutes: 330 })
+        );
+        assert!(DayBoundary::parse("24:00").is_err());
+        assert!(DayBoundary::parse("04:60").is_err());
+        assert!(DayBoundary::parse("4").is_err());
+        assert_eq!(
+            DayBoundary::from_setting(Some("abc")),
+            DayBoundary::default()
+        );
+        assert_eq!(DayBoundary::from_setting(None), DayBoundary::default());
+    }
+
+    #[test]
+    fn test_to_setting_value() {
+        assert_eq!(
+            DayBoundary::parse("4:30").unwrap().to_setting_value(),
+            "04:30"
+        );
+        assert_eq!(DayBoundary::default().to_setting_value(), "00:00");
+    }
+
+    #[test]
+    fn test_play_date_before_boundary_is_previous_day() {
+        let boundary = DayBoundary::parse("04:00").unwrap();
+        let late_night = Local.with_ymd_and_hms(2026, 1, 2, 3, 59, 59).unwrap();
+        let morning = Local.with_ymd_and_hms(2026, 1, 2, 4, 0, 0).unwrap();
+
+        assert_eq!(
+            boundary.play_date(late_night),
+            NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()
+        );
+        assert_eq!(
+            boundary.play_date(morning),
+            NaiveDate::from_ymd_opt(2026, 1, 2).unwrap()
+        );
+        assert_eq!(
+            DayBoundary::default().play_date(late_night),
+            NaiveDate::from_ymd_opt(2026, 1, 2).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_day_start() {
+        let boundary = DayBoundary::parse("04:00").unwrap();
+        assert_eq!(
+            boundary.day_start(NaiveDate::from_ymd_opt(2026, 1, 2).unwrap()),
+            Some(Local.with_ymd_and_hms(2026, 1, 2, 4, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_date_range() {
+        let boundary = DayBoundary::parse("04:00").unwrap();
+        assert_eq!(
+            boundary.date_range(
+                NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 1, 3).unwrap()
+            ),
+            Some((
+                Local.with_ymd_and_hms(2026, 1, 2, 4, 0, 0).unwrap(),
+                Local.with_ymd_and_hms(2026, 1, 4, 4, 0, 0).unwrap()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_split_play_time_by_local_date_same_day() {
+        let end_time = Local
+            .with_ymd_and_hms(2026, 1, 2, 12, 0, 10)
+            .earliest()
+            .unwrap();
+
+        let result = split_play_time_by_local_date(end_time, 10, DayBoundary::default());
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0],
+            (NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), 10)
+        );
+    }
+
+    #[test]
+    fn test_split_play_time_by_local_date_across_midnight() {
+        let end_time = Local
+            .with_ymd_and_hms(2026, 1, 2, 0, 0, 5)
+            .earliest()
+            .unwrap();
+
+        let result = split_play_time_by_local_date(end_time, 10, DayBoundary::default());
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], (NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), 5));
+        assert_eq!(result[1], (NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), 5));
+    }
+
+    #[test]
+    fn test_split_play_time_by_local_date_with_day_boundary() {
+        let boundary = DayBoundary::parse("04:00").unwrap();
+        let late_night = Local
+            .with_ymd_and_hms(2026, 1, 2, 0, 0, 5)
+            .earliest()
+            .unwrap();
+        assert_eq!(
+            split_play_time_by_local_date(late_night, 10, boundary),
+            vec![(NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), 10)]
+        );
+
+        let morning = Local
+            .with_ymd_and_hms(2026, 1, 2, 4, 0, 5)
+            .earliest()
+            .unwrap();
+        assert_eq!(
+            split_play_time_by_local_date(morning, 10, boundary),
+            vec![
+                (NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), 5),
+                (NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_daily_play_time_rebucket_moves_late_night_play() {
+        let ended_at = Local
+            .with_ymd_and_hms(2026, 1, 2, 1, 0, 0)
+            .earliest()
+            .unwrap();
+        let session = |id: i32, ended_at: Option<DateTime<Local>>| {
+            PlaySession::new(
+                Id::new(id),
+                Id::new(7),
+                Local.with_ymd_and_hms(2026, 1, 1, 22, 0, 0).unwrap(),
+                ended_at,
+                None,
+                None,
+                3 * 60 * 60,
+                0,
+            )
+        };
+        let sessions = vec![session(1, Some(ended_at)), session(2, None)];
+
+        let deltas = compute_daily_play_time_rebucket(
+            &sessions,
+            DayBoundary::default(),
+            DayBoundary::parse("04:00").unwrap(),
+        );
+
+        assert_eq!(
+            deltas,
+            vec![
+                (7, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), 3600),
+                (7, NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), -3600),
+            ]
+        );
+        assert!(compute_daily_play_time_rebucket(
+            &sessions,
+            DayBoundary::default(),
+            DayBoundary::default()
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_compute_daily_play_time_rebucket_uses_played_ranges() {
+        let at = |day: u32, hour: u32| Local.with_ymd_and_hms(2026, 1, day, hour, 0, 0).unwrap();
+        // 22:00 から 02:00 までのうち 23:00 から 01:00 まで離席していた
+        let mut session = PlaySession::new(
+            Id::new(1),
+            Id::new(7),
+            at(1, 22),
+            Some(at(2, 2)),
+            Some(1000),
+            None,
+            2 * 60 * 60,
+            2 * 60 * 60,
+        );
+        session.played_ranges = vec![(at(1, 22), at(1, 23)), (at(2, 1), at(2, 2))];
+        let date = |day: u32| NaiveDate::from_ymd_opt(2026, 1, day).unwrap();
+
+        assert_eq!(
+            split_played_ranges_by_local_date(
+                &session.played_ranges,
+                at(2, 2),
+                session.played_seconds,
+                DayBoundary::default()
+            ),
+            vec![(date(1), 3600), (date(2), 3600)]
+        );
+        let deltas = compute_daily_play_time_rebucket(
+            &[session.clone()],
+            DayBoundary::default(),
+            DayBoundary::parse("04:00").unwrap(),
+        );
+        // 移すのは実際にプレイした時間だけで、合計は変わらない
+        assert_eq!(deltas, vec![(7, date(1), 3600), (7, date(2), -3600)]);
+
+        // 範囲を記録していない離席のあるセッションは、プレイした時刻が分からないので移さない
+        session.played_ranges = vec![];
+        assert!(compute_daily_play_time_rebucket(
+            &[session],
+            DayBoundary::default(),
+            DayBoundary::parse("04:00").unwrap(),
+        )
+        .is_empty());
+    }
+}
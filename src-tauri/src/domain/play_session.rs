@@ -0,0 +1,685 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Local, NaiveDate};
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+use super::{collection::CollectionElement, play_day::DayBoundary, Id};
+
+/// 実際にプレイしていた時間の範囲。終わりは含まない
+pub type PlayedRange = (DateTime<Local>, DateTime<Local>);
+
+/// 1回のプレイの記録
+#[allow(clippy::too_many_arguments)]
+#[derive(new, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaySession {
+    pub id: Id<PlaySession>,
+    pub collection_element_id: Id<CollectionElement>,
+    pub started_at: DateTime<Local>,
+    /// プレイ中の場合は None
+    pub ended_at: Option<DateTime<Local>>,
+    pub pid: Option<u32>,
+    pub exe_path: Option<String>,
+    pub played_seconds: i32,
+    pub paused_seconds: i32,
+    /// ポーズや中断を除いてプレイしていた範囲。記録していないセッションは空
+    #[new(default)]
+    #[serde(default)]
+    pub played_ranges: Vec<PlayedRange>,
+}
+
+impl PlaySession {
+    /// ID を含めて同じ記録か
+    pub fn is_same_record(&self, other: &PlaySession) -> bool {
+        self.id.value == other.id.value
+            && self.collection_element_id.value == other.collection_element_id.value
+            && self.started_at == other.started_at
+            && self.ended_at == other.ended_at
+            && self.pid == other.pid
+            && self.exe_path == other.exe_path
+            && self.played_seconds == other.played_seconds
+            && self.paused_seconds == other.paused_seconds
+    }
+
+    /// ゲームを起動して追跡したセッションか。取り込みや手入力のセッションは PID を持たない
+    pub fn is_tracked(&self) -> bool {
+        self.pid.is_some()
+    }
+
+    /// プレイした時間がどのプレイ日のものか分かるか
+    ///
+    /// 範囲を記録していない追跡のセッションは、ポーズや中断があると実際にプレイした時刻が分からない
+    pub fn has_known_played_ranges(&self) -> bool {
+        if covers_played_seconds(&self.played_ranges, self.played_seconds) || !self.is_tracked() {
+            return true;
+        }
+        let Some(ended_at) = self.ended_at else {
+            return false;
+        };
+        self.paused_seconds == 0
+            && (ended_at - self.started_at).num_seconds() - self.played_seconds as i64
+                <= UNTRACKED_GAP_TOLERANCE_SECONDS
+    }
+}
+
+/// 範囲を記録していないセッションで、起動やプロセスの検索の時間として許容する開始から終了までの余り
+const UNTRACKED_GAP_TOLERANCE_SECONDS: i64 = 60;
+
+/// `ended_at` で終わる `seconds` 秒のプレイを、直前の範囲 `previous` に続けて記録する範囲にする
+///
+/// tick ごとの時刻のずれで範囲が細切れにならないよう、1秒未満の隙間は直前の範囲につなげる
+pub fn next_played_range(
+    previous: Option<&PlayedRange>,
+    ended_at: DateTime<Local>,
+    seconds: i32,
+) -> PlayedRange {
+    let duration = chrono::Duration::seconds(seconds as i64);
+    let started_at = ended_at - duration;
+    match previous {
+        Some((_, previous_end)) if (started_at - *previous_end).num_milliseconds().abs() < 1000 => {
+            (*previous_end, *previous_end + duration)
+        }
+        _ => (started_at, ended_at),
+    }
+}
+
+/// 範囲のうち `since` から `until` までの部分
+fn clip_played_ranges(
+    ranges: &[PlayedRange],
+    since: DateTime<Local>,
+    until: DateTime<Local>,
+) -> Vec<PlayedRange> {
+    ranges
+        .iter()
+        .map(|(start, end)| ((*start).max(since), (*end).min(until)))
+        .filter(|(start, end)| start < end)
+        .collect()
+}
+
+fn played_ranges_seconds(ranges: &[PlayedRange]) -> i64 {
+    ranges
+        .iter()
+        .map(|(start, end)| (*end - *start).num_seconds())
+        .sum()
+}
+
+/// 範囲がプレイ時間のすべてを表しているか
+///
+/// 範囲を記録する前のバージョンから追跡を再開したセッションなどは、一部の範囲しか持たない
+pub fn covers_played_seconds(ranges: &[PlayedRange], played_seconds: i32) -> bool {
+    !ranges.is_empty() && played_ranges_seconds(ranges) == played_seconds as i64
+}
+
+#[derive(new, Debug, Clone)]
+pub struct NewPlaySession {
+    pub collection_element_id: Id<CollectionElement>,
+    pub started_at: DateTime<Local>,
+    pub pid: Option<u32>,
+    pub exe_path: Option<String>,
+}
+
+/// プレイセッションの集計
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaySessionSummary {
+    pub session_count: i32,
+    pub total_played_seconds: i64,
+    pub longest_played_seconds: i32,
+    pub average_played_seconds: i32,
+}
+
+/// セッション一覧から回数・合計・最長・平均のプレイ時間を集計する
+pub fn summarize_play_sessions(sessions: &[PlaySession]) -> PlaySessionSummary {
+    if sessions.is_empty() {
+        return PlaySessionSummary::default();
+    }
+    let total_played_seconds: i64 = sessions.iter().map(|v| v.played_seconds as i64).sum();
+    PlaySessionSummary {
+        session_count: sessions.len() as i32,
+        total_played_seconds,
+        longest_played_seconds: sessions.iter().map(|v| v.played_seconds).max().unwrap_or(0),
+        average_played_seconds: (total_played_seconds / sessions.len() as i64) as i32,
+    }
+}
+
+/// 手動で登録・編集する終了済みのセッション
+#[allow(clippy::too_many_arguments)]
+#[derive(new, Debug, Clone)]
+pub struct ManualPlaySession {
+    /// 取り消しなどで元のセッションを復元する場合の ID。None の場合は新しく採番する
+    pub restore_id: Option<Id<PlaySession>>,
+    pub collection_element_id: Id<CollectionElement>,
+    pub started_at: DateTime<Local>,
+    pub ended_at: DateTime<Local>,
+    pub pid: Option<u32>,
+    pub exe_path: Option<String>,
+    pub played_seconds: i32,
+    pub paused_seconds: i32,
+    /// 元のセッションから引き継いだプレイしていた範囲。手入力のセッションは空
+    #[new(default)]
+    pub played_ranges: Vec<PlayedRange>,
+}
+
+impl ManualPlaySession {
+    pub fn validate(&self, now: DateTime<Local>) -> Result<(), String> {
+        if self.started_at >= self.ended_at {
+            return Err("終了日時は開始日時より後にしてください".to_string());
+        }
+        if self.ended_at > now {
+            return Err("未来の日時は指定できません".to_string());
+        }
+        if self.played_seconds < 0 || self.paused_seconds < 0 {
+            return Err("プレイ時間に負の値は指定できません".to_string());
+        }
+        let duration = (self.ended_at - self.started_at).num_seconds();
+        if (self.played_seconds + self.paused_seconds) as i64 > duration {
+            return Err("プレイ時間が開始から終了までの時間を超えています".to_string());
+        }
+        Ok(())
+    }
+
+    /// 範囲を開始から終了までに切り詰める。切り詰めた範囲がプレイ時間と合わない場合は範囲を捨てる
+    pub fn trim_played_ranges(&mut self) {
+        let ranges = clip_played_ranges(&self.played_ranges, self.started_at, self.ended_at);
+        self.played_ranges = if covers_played_seconds(&ranges, self.played_seconds) {
+            ranges
+        } else {
+            vec![]
+        };
+    }
+
+    /// 記録済みのセッションに戻す。新しく採番する場合の ID は `id` を使う
+    pub fn to_play_session(&self, id: Id<PlaySession>) -> PlaySession {
+        PlaySession {
+            played_ranges: self.played_ranges.clone(),
+            ..PlaySession::new(
+                self.restore_id.clone().unwrap_or(id),
+                self.collection_element_id.clone(),
+                self.started_at,
+                Some(self.ended_at),
+                self.pid,
+                self.exe_path.clone(),
+                self.played_seconds,
+                self.paused_seconds,
+            )
+        }
+    }
+}
+
+impl TryFrom<&PlaySession> for ManualPlaySession {
+    type Error = String;
+    fn try_from(value: &PlaySession) -> Result<Self, Self::Error> {
+        let ended_at = value
+            .ended_at
+            .ok_or_else(|| "プレイ中のセッションは編集できません".to_string())?;
+        Ok(ManualPlaySession {
+            played_ranges: value.played_ranges.clone(),
+            ..ManualPlaySession::new(
+                Some(value.id.clone()),
+                value.collection_element_id.clone(),
+                value.started_at,
+                ended_at,
+                value.pid,
+                value.exe_path.clone(),
+                value.played_seconds,
+                value.paused_seconds,
+            )
+        })
+    }
+}
+
+/// セッションを `split_at` で2つに分け、後半を `moved_element_id` のゲームのセッションにする
+///
+/// プレイしていた範囲を記録したセッションはプレイ時間を範囲で分け、そうでなければ前後の長さの比で分ける。
+/// ポーズ時間は前後の長さの比で分ける。前半は元のセッションの ID を引き継ぐ
+pub fn split_play_session(
+    session: &PlaySession,
+    split_at: DateTime<Local>,
+    moved_element_id: Id<CollectionElement>,
+) -> Result<(ManualPlaySession, ManualPlaySession), String> {
+    let original = ManualPlaySession::try_from(session)?;
+    if split_at <= original.started_at || split_at >= original.ended_at {
+        return Err("分割する日時はセッションの開始から終了までの間にしてください".to_string());
+    }
+    let duration = (original.ended_at - original.started_at).num_seconds();
+    let first_duration = (split_at - original.started_at).num_seconds();
+    let ratio = |seconds: i32| (seconds as i64 * first_duration / duration) as i32;
+
+    let (first_ranges, second_ranges) =
+        if covers_played_seconds(&original.played_ranges, original.played_seconds) {
+            (
+                clip_played_ranges(&original.played_ranges, original.started_at, split_at),
+                clip_played_ranges(&original.played_ranges, split_at, original.ended_at),
+            )
+        } else {
+            (vec![], vec![])
+        };
+    let first_played = if first_ranges.is_empty() && second_ranges.is_empty() {
+        ratio(original.played_seconds)
+    } else {
+        played_ranges_seconds(&first_ranges) as i32
+    };
+    // 前後どちらもプレイ時間とポーズ時間の合計が長さを超えないようにする
+    let min_first_paused = (original.played_seconds + original.paused_seconds) as i64
+        - (duration - first_duration)
+        - first_played as i64;
+    let first_paused = (ratio(original.paused_seconds) as i64).clamp(
+        min_first_paused.max(0),
+        first_duration - first_played as i64,
+    ) as i32;
+    let first = ManualPlaySession {
+        ended_at: split_at,
+        played_seconds: first_played,
+        paused_seconds: first_paused,
+        played_ranges: first_ranges,
+        ..original.clone()
+    };
+    let second = ManualPlaySession {
+        restore_id: None,
+        collection_element_id: moved_element_id,
+        started_at: split_at,
+        played_seconds: original.played_seconds - first_played,
+        paused_seconds: original.paused_seconds - first_paused,
+        played_ranges: second_ranges,
+        ..original
+    };
+    Ok((first, second))
+}
+
+/// セッションの編集後の最初と最後のプレイ日時を求める
+///
+/// セッションを記録し始める前のプレイ時間が残っている場合(最も古いプレイ日が最初のセッションより前)は
+/// 現在の最初のプレイ日時を残す。最後のプレイ日時はセッションがなければ同様に現在の値を残す
+pub fn recompute_play_at(
+    sessions: &[PlaySession],
+    current_first_play_at: Option<DateTime<Local>>,
+    current_last_play_at: Option<DateTime<Local>>,
+    earliest_play_date: Option<NaiveDate>,
+    day_boundary: DayBoundary,
+) -> (Option<DateTime<Local>>, Option<DateTime<Local>>) {
+    let session_first = sessions.iter().map(|v| v.started_at).min();
+    let session_last = sessions
+        .iter()
+        .map(|v| v.ended_at.unwrap_or(v.started_at))
+        .max();
+    let has_earlier_play = match (earliest_play_date, session_first) {
+        (Some(date), Some(first)) => date < day_boundary.play_date(first),
+        (Some(_), None) => true,
+        (None, _) => false,
+    };
+    if !has_earlier_play {
+        return (session_first, session_last);
+    }
+    let first = match (current_first_play_at, session_first) {
+        (Some(current), Some(first)) => Some(current.min(first)),
+        (current, first) => current.or(first),
+    };
+    (first, session_last.or(current_last_play_at))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaySessionEditAction {
+    Add,
+    Edit,
+    Delete,
+    Split,
+    Undo,
+}
+
+impl PlaySessionEditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlaySessionEditAction::Add => "add",
+            PlaySessionEditAction::Edit => "edit",
+            PlaySessionEditAction::Delete => "delete",
+            PlaySessionEditAction::Split => "split",
+            PlaySessionEditAction::Undo => "undo",
+        }
+    }
+}
+
+impl FromStr for PlaySessionEditAction {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "add" => Ok(PlaySessionEditAction::Add),
+            "edit" => Ok(PlaySessionEditAction::Edit),
+            "delete" => Ok(PlaySessionEditAction::Delete),
+            "split" => Ok(PlaySessionEditAction::Split),
+            "undo" => Ok(PlaySessionEditAction::Undo),
+            _ => Err(anyhow::anyhow!("unknown play session edit action: {}", s)),
+        }
+    }
+}
+
+/// セッションの手動変更の記録。before を削除して after を登録した変更を表す
+#[allow(clippy::too_many_arguments)]
+#[derive(new, Debug, Clone)]
+pub struct PlaySessionAudit {
+    pub id: Id<PlaySessionAudit>,
+    pub action: PlaySessionEditAction,
+    pub before: Vec<PlaySession>,
+    pub after: Vec<PlaySession>,
+    /// 取り消しの記録の場合、取り消した変更
+    pub undo_of: Option<Id<PlaySessionAudit>>,
+    pub created_at: DateTime<Local>,
+    pub undone_at: Option<DateTime<Local>>,
+}
+
+/// エレメントの変更後の最初と最後のプレイ日時
+pub type ElementPlayAt = (
+    Id<CollectionElement>,
+    Option<DateTime<Local>>,
+    Option<DateTime<Local>>,
+);
+
+/// セッションの手動変更でまとめて反映する内容
+#[derive(Debug, Clone)]
+pub struct PlaySessionEdit {
+    pub action: PlaySessionEditAction,
+    pub removed: Vec<PlaySession>,
+    pub added: Vec<ManualPlaySession>,
+    /// エレメントごとの合計プレイ時間の増減
+    pub play_time_deltas: Vec<(Id<CollectionElement>, i32)>,
+    pub daily_play_time_deltas: Vec<(Id<CollectionElement>, NaiveDate, i32)>,
+    pub play_at: Vec<ElementPlayAt>,
+    pub undo_of: Option<Id<PlaySessionAudit>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn session(id: i32, played_seconds: i32) -> PlaySession {
+        let started_at = Local.with_ymd_and_hms(2026, 1, 2, 12, 0, 0).unwrap();
+        PlaySession::new(
+            Id::new(id),
+            Id::new(1),
+            started_at,
+            Some(started_at + chrono::Duration::seconds(played_seconds as i64)),
+            Some(1000),
+            Some("C:/Games/test/game.exe".to_string()),
+            played_seconds,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_summarize_play_sessions() {
+        let sessions = vec![session(1, 600), session(2, 15120), session(3, 300)];
+
+        let summary = summarize_play_sessions(&sessions);
+
+        assert_eq!(
+            summary,
+            PlaySessionSummary {
+                session_count: 3,
+                total_played_seconds: 16020,
+                longest_played_seconds: 15120,
+                average_played_seconds: 5340,
+            }
+        );
+    }
+
+    #[test]
+    fn test_summarize_play_sessions_empty() {
+        assert_eq!(summarize_play_sessions(&[]), PlaySessionSummary::default());
+    }
+
+    fn manual(started_hour: u32, ended_hour: u32, played_seconds: i32) -> ManualPlaySession {
+        ManualPlaySession::new(
+            None,
+            Id::new(1),
+            Local
+                .with_ymd_and_hms(2026, 1, 2, started_hour, 0, 0)
+                .unwrap(),
+            Local
+                .with_ymd_and_hms(2026, 1, 2, ended_hour, 0, 0)
+                .unwrap(),
+            None,
+            None,
+            played_seconds,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_manual_play_session_validate() {
+        let now = Local.with_ymd_and_hms(2026, 1, 3, 0, 0, 0).unwrap();
+
+        assert!(manual(10, 12, 7200).validate(now).is_ok());
+        assert!(manual(12, 10, 0).validate(now).is_err());
+        assert!(manual(10, 12, 7201).validate(now).is_err());
+        assert!(manual(10, 12, -1).validate(now).is_err());
+        assert!(manual(10, 12, 60)
+            .validate(Local.with_ymd_and_hms(2026, 1, 2, 11, 0, 0).unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_split_play_session_by_ratio() {
+        let mut original = session(5, 3000);
+        original.ended_at = Some(original.started_at + chrono::Duration::seconds(4000));
+        original.paused_seconds = 1000;
+        let split_at = original.started_at + chrono::Duration::seconds(1000);
+
+        let (first, second) = split_play_session(&original, split_at, Id::new(2)).unwrap();
+
+        assert_eq!(first.restore_id.map(|v| v.value), Some(5));
+        assert_eq!(first.collection_element_id.value, 1);
+        assert_eq!(first.ended_at, split_at);
+        assert_eq!((first.played_seconds, first.paused_seconds), (750, 250));
+        assert!(second.restore_id.is_none());
+        assert_eq!(second.collection_element_id.value, 2);
+        assert_eq!(second.started_at, split_at);
+        assert_eq!((second.played_seconds, second.paused_seconds), (2250, 750));
+    }
+
+    #[test]
+    fn test_split_play_session_by_played_ranges() {
+        let mut original = session(5, 3000);
+        let started_at = original.started_at;
+        let minutes = |v: i64| started_at + chrono::Duration::minutes(v);
+        original.ended_at = Some(minutes(100));
+        original.paused_seconds = 3000;
+        // 最初の 40 分と最後の 10 分だけプレイした
+        original.played_ranges = vec![(minutes(0), minutes(40)), (minutes(90), minutes(100))];
+
+        let (first, second) = split_play_session(&original, minutes(50), Id::new(2)).unwrap();
+
+        assert_eq!(first.played_seconds, 2400);
+        assert_eq!(first.played_ranges, vec![(minutes(0), minutes(40))]);
+        assert_eq!(second.played_seconds, 600);
+        assert_eq!(second.played_ranges, vec![(minutes(90), minutes(100))]);
+        assert_eq!(first.paused_seconds + second.paused_seconds, 3000);
+        let now = minutes(200);
+        assert!(first.validate(now).is_ok());
+        assert!(second.validate(now).is_ok());
+    }
+
+    #[test]
+    fn test_next_played_range_joins_adjacent_ticks() {
+        let started_at = Local.with_ymd_and_hms(2026, 1, 2, 10, 0, 0).unwrap();
+        let first = next_played_range(None, started_at + chrono::Duration::seconds(5), 5);
+        assert_eq!(
+            first,
+            (started_at, started_at + chrono::Duration::seconds(5))
+        );
+
+        // 1秒未満のずれは直前の範囲につなげる
+        let second = next_played_range(
+            Some(&first),
+            started_at + chrono::Duration::milliseconds(10_300),
+            5,
+        );
+        assert_eq!(
+            second,
+            (
+                started_at + chrono::Duration::seconds(5),
+                started_at + chrono::Duration::seconds(10)
+            )
+        );
+
+        // ポーズなどで空いた場合は新しい範囲にする
+        let third = next_played_range(Some(&second), started_at + chrono::Duration::seconds(60), 5);
+        assert_eq!(
+            third,
+            (
+                started_at + chrono::Duration::seconds(55),
+                started_at + chrono::Duration::seconds(60)
+            )
+        );
+    }
+
+    #[test]
+    fn test_has_known_played_ranges() {
+        let mut tracked = session(1, 3600);
+        assert!(tracked.has_known_played_ranges());
+
+        tracked.paused_seconds = 600;
+        tracked.ended_at = Some(tracked.started_at + chrono::Duration::seconds(4200));
+        assert!(!tracked.has_known_played_ranges());
+
+        tracked.played_ranges = vec![(
+            tracked.started_at,
+            tracked.started_at + chrono::Duration::seconds(3600),
+        )];
+        assert!(tracked.has_known_played_ranges());
+
+        // 範囲を記録する前から続いていたセッションは、一部の範囲しか持たない
+        tracked.played_ranges = vec![(
+            tracked.started_at,
+            tracked.started_at + chrono::Duration::seconds(600),
+        )];
+        assert!(!tracked.has_known_played_ranges());
+
+        let mut manual = session(2, 3600);
+        manual.pid = None;
+        manual.paused_seconds = 600;
+        assert!(manual.has_known_played_ranges());
+    }
+
+    #[test]
+    fn test_trim_played_ranges() {
+        let mut original = session(5, 3000);
+        let started_at = original.started_at;
+        let minutes = |v: i64| started_at + chrono::Duration::minutes(v);
+        original.ended_at = Some(minutes(100));
+        original.paused_seconds = 3000;
+        original.played_ranges = vec![(minutes(0), minutes(40)), (minutes(90), minutes(100))];
+        let original = ManualPlaySession::try_from(&original).unwrap();
+
+        // 終了を早めてプレイ時間も合わせた場合は、残った範囲を引き継ぐ
+        let mut shortened = ManualPlaySession {
+            ended_at: minutes(50),
+            played_seconds: 2400,
+            paused_seconds: 600,
+            ..original.clone()
+        };
+        shortened.trim_played_ranges();
+        assert_eq!(shortened.played_ranges, vec![(minutes(0), minutes(40))]);
+
+        // プレイ時間を変えた場合は、どの時刻にプレイしたか分からなくなる
+        let mut edited = ManualPlaySession {
+            played_seconds: 1800,
+            ..original
+        };
+        edited.trim_played_ranges();
+        assert!(edited.played_ranges.is_empty());
+    }
+
+    #[test]
+    fn test_split_play_session_rejects_out_of_range() {
+        let original = session(5, 3000);
+        assert!(split_play_session(&original, original.started_at, Id::new(2)).is_err());
+
+        let mut running = session(6, 3000);
+        running.ended_at = None;
+        assert!(split_play_session(
+            &running,
+            running.started_at + chrono::Duration::seconds(10),
+            Id::new(2)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_recompute_play_at_from_sessions() {
+        let sessions = vec![session(1, 600), session(2, 1200)];
+        let started_at = sessions[0].started_at;
+
+        assert_eq!(
+            recompute_play_at(
+                &sessions,
+                Some(started_at - chrono::Duration::days(30)),
+                None,
+                Some(started_at.date_naive()),
+                DayBoundary::default()
+            ),
+            (
+                Some(started_at),
+                Some(started_at + chrono::Duration::seconds(1200))
+            )
+        );
+        assert_eq!(
+            recompute_play_at(
+                &[],
+                Some(started_at),
+                Some(started_at),
+                None,
+                DayBoundary::default()
+            ),
+            (None, None)
+        );
+    }
+
+    #[test]
+    fn test_recompute_play_at_keeps_play_before_sessions() {
+        let sessions = vec![session(1, 600)];
+        let started_at = sessions[0].started_at;
+        let legacy_first = started_at - chrono::Duration::days(30);
+
+        assert_eq!(
+            recompute_play_at(
+                &sessions,
+                Some(legacy_first),
+                Some(started_at),
+                Some(legacy_first.date_naive()),
+                DayBoundary::default()
+            ),
+            (
+                Some(legacy_first),
+                Some(started_at + chrono::Duration::seconds(600))
+            )
+        );
+        assert_eq!(
+            recompute_play_at(
+                &[],
+                Some(legacy_first),
+                Some(started_at),
+                Some(legacy_first.date_naive()),
+                DayBoundary::default()
+            ),
+            (Some(legacy_first), Some(started_at))
+        );
+    }
+
+    #[test]
+    fn test_play_session_edit_action_round_trip() {
+        for action in [
+            PlaySessionEditAction::Add,
+            PlaySessionEditAction::Edit,
+            PlaySessionEditAction::Delete,
+            PlaySessionEditAction::Split,
+            PlaySessionEditAction::Undo,
+        ] {
+            assert_eq!(
+                action.as_str().parse::<PlaySessionEditAction>().unwrap(),
+                action
+            );
+        }
+        assert!("unknown".parse::<PlaySessionEditAction>().is_err());
+    }
+}
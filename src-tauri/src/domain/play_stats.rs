@@ -0,0 +1,81 @@
+use chrono::NaiveDate;
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+/// プレイ統計の集計を扱うリポジトリの対象
+#[derive(Clone)]
+pub struct PlayStats {}
+
+/// プレイ時間を合計する単位
+///
+/// 週は月曜日始まり。日付はいずれも日付の区切りで振り分け済みのプレイ日
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlayStatsPeriod {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// 集計単位ごとのプレイ時間の合計
+#[derive(new, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayTimeBucket {
+    /// 集計単位の最初の日
+    pub period_start: NaiveDate,
+    pub play_time_seconds: i64,
+    /// プレイしたゲームの数
+    pub game_count: i32,
+}
+
+/// ゲームごとのプレイ時間の合計
+#[derive(new, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GamePlayTime {
+    pub collection_element_id: i32,
+    pub gamename: String,
+    pub play_time_seconds: i64,
+    /// プレイした日数
+    pub play_days: i32,
+}
+
+/// ブランドや発売年などのまとまりごとのプレイ時間の合計
+#[derive(new, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupPlayTime {
+    pub key: String,
+    pub play_time_seconds: i64,
+    pub game_count: i32,
+}
+
+/// 連続してプレイした日の並び
+#[derive(new, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayStreak {
+    pub days: i32,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_play_stats_period_deserialize() {
+        let period: PlayStatsPeriod = serde_json::from_str(r#""month""#).unwrap();
+        assert_eq!(period, PlayStatsPeriod::Month);
+        assert!(serde_json::from_str::<PlayStatsPeriod>(r#""decade""#).is_err());
+    }
+
+    #[test]
+    fn test_play_time_bucket_serialize() {
+        let bucket = PlayTimeBucket::new(NaiveDate::from_ymd_opt(2026, 1, 5).unwrap(), 3600, 2);
+        let json = serde_json::to_string(&bucket).unwrap();
+        assert_eq!(
+            json,
+            r#"{"periodStart":"2026-01-05","playTimeSeconds":3600,"gameCount":2}"#
+        );
+    }
+}
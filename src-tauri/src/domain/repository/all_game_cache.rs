@@ -21,4 +21,6 @@ pub trait AllGameCacheRepository {
     async fn get_last_updated(&self) -> anyhow::Result<(i32, DateTime<Local>)>;
     async fn update(&self, cache: Vec<NewAllGameCacheOne>) -> anyhow::Result<()>;
     async fn delete_by_ids(&self, ids: Vec<i32>) -> anyhow::Result<()>;
+    /// 検索用のゲーム名を正規化し直し、変わった件数を返す
+    async fn refresh_normalized_gamenames(&self) -> anyhow::Result<u64>;
 }
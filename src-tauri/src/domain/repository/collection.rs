@@ -1,5 +1,15 @@
 use crate::domain::{
-    collection::{CollectionElement, NewCollectionElement, NewCollectionElementDetail},
+    collection::{
+        Collection, CollectionElement, NewCollection, NewCollectionElement,
+        NewCollectionElementDetail,
+    },
+    external_play_time::ExternalPlayTimeImport,
+    launch::{LaunchProfile, LaunchTarget, NewLaunchTarget},
+    launch_hook::{LaunchHook, NewLaunchHook},
+    play_day::DayBoundary,
+    play_session::{NewPlaySession, PlaySession, PlaySessionAudit, PlaySessionEdit, PlayedRange},
+    smart_collection::{NewSmartCollection, SmartCollection},
+    tracking_checkpoint::TrackingCheckpoint,
     Id,
 };
 use anyhow::Result;
@@ -49,24 +59,35 @@ pub trait CollectionRepository {
     #[allow(dead_code)]
     async fn get_brandname_and_rubies(&self) -> Result<Vec<(String, String)>>;
 
-    #[allow(dead_code)]
     async fn get_element_ids_by_is_nukige(
         &self,
         is_nukige: bool,
     ) -> Result<Vec<Id<CollectionElement>>>;
-    #[allow(dead_code)]
     async fn get_element_ids_by_install_at_not_null(&self) -> Result<Vec<Id<CollectionElement>>>;
-    #[allow(dead_code)]
     async fn get_element_ids_by_brandnames(
         &self,
         brandnames: &[String],
     ) -> Result<Vec<Id<CollectionElement>>>;
-    #[allow(dead_code)]
     async fn get_element_ids_by_sellday(
         &self,
         since: &str,
         until: &str,
     ) -> Result<Vec<Id<CollectionElement>>>;
+    async fn get_all_element_ids(&self) -> Result<Vec<Id<CollectionElement>>>;
+    async fn get_element_ids_by_play_statuses(
+        &self,
+        play_statuses: &[i32],
+    ) -> Result<Vec<Id<CollectionElement>>>;
+    async fn get_element_ids_by_total_play_time_seconds(
+        &self,
+        min_seconds: Option<i32>,
+        max_seconds: Option<i32>,
+    ) -> Result<Vec<Id<CollectionElement>>>;
+    async fn get_element_ids_by_last_play_at_since(
+        &self,
+        since: DateTime<Local>,
+    ) -> Result<Vec<Id<CollectionElement>>>;
+    async fn get_element_ids_by_like_at_not_null(&self) -> Result<Vec<Id<CollectionElement>>>;
 
     async fn update_element_last_play_at_by_id(
         &self,
@@ -101,6 +122,15 @@ pub trait CollectionRepository {
         seconds: i32,
     ) -> Result<()>;
     async fn get_daily_play_times(&self, id: &Id<CollectionElement>) -> Result<Vec<DailyPlayTime>>;
+    /// 日付の区切りを `from` から `to` に変え、プレイ日ごとの増減を同じトランザクションで反映する。0 秒未満にはしない
+    ///
+    /// 保存済みの区切りが `from` でなければ、二重に振り分けないよう何も変えずにエラーを返す
+    async fn update_day_boundary(
+        &self,
+        from: DayBoundary,
+        to: DayBoundary,
+        deltas: &[(Id<CollectionElement>, NaiveDate, i32)],
+    ) -> Result<()>;
 
     #[allow(dead_code)]
     async fn delete_element_by_id(&self, id: &Id<CollectionElement>) -> Result<()>;
@@ -122,4 +152,174 @@ pub trait CollectionRepository {
         exe_path: Option<String>,
         lnk_path: Option<String>,
     ) -> Result<()>;
+
+    async fn get_all_collections(&self) -> Result<Vec<Collection>>;
+    async fn get_collection_by_id(&self, id: &Id<Collection>) -> Result<Option<Collection>>;
+    async fn get_collection_by_name(&self, name: &str) -> Result<Option<Collection>>;
+    async fn create_collection(&self, new: &NewCollection) -> Result<Id<Collection>>;
+    async fn update_collection_name(&self, id: &Id<Collection>, name: &str) -> Result<()>;
+    /// `ids` の並び順で sort_order を振り直す
+    async fn update_collections_order(&self, ids: &[Id<Collection>]) -> Result<()>;
+    async fn delete_collection(&self, id: &Id<Collection>) -> Result<()>;
+    async fn get_element_ids_by_collection_id(
+        &self,
+        id: &Id<Collection>,
+    ) -> Result<Vec<Id<CollectionElement>>>;
+    /// すでに登録済みのエレメントは無視し、新規分は末尾に追加する
+    async fn add_collection_elements(
+        &self,
+        id: &Id<Collection>,
+        element_ids: &[Id<CollectionElement>],
+    ) -> Result<()>;
+    async fn remove_collection_elements(
+        &self,
+        id: &Id<Collection>,
+        element_ids: &[Id<CollectionElement>],
+    ) -> Result<()>;
+    /// `element_ids` の並び順で order_index を振り直す
+    async fn update_collection_elements_order(
+        &self,
+        id: &Id<Collection>,
+        element_ids: &[Id<CollectionElement>],
+    ) -> Result<()>;
+
+    async fn get_all_smart_collections(&self) -> Result<Vec<SmartCollection>>;
+    async fn get_smart_collection_by_id(
+        &self,
+        id: &Id<SmartCollection>,
+    ) -> Result<Option<SmartCollection>>;
+    async fn get_smart_collection_by_name(&self, name: &str) -> Result<Option<SmartCollection>>;
+    async fn create_smart_collection(
+        &self,
+        new: &NewSmartCollection,
+    ) -> Result<Id<SmartCollection>>;
+    async fn update_smart_collection(
+        &self,
+        id: &Id<SmartCollection>,
+        new: &NewSmartCollection,
+    ) -> Result<()>;
+    async fn delete_smart_collection(&self, id: &Id<SmartCollection>) -> Result<()>;
+
+    async fn get_launch_targets_by_element_id(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> Result<Vec<LaunchTarget>>;
+    async fn get_launch_target_by_id(&self, id: &Id<LaunchTarget>) -> Result<Option<LaunchTarget>>;
+    async fn create_launch_target(&self, new: &NewLaunchTarget) -> Result<Id<LaunchTarget>>;
+    async fn update_launch_target(
+        &self,
+        id: &Id<LaunchTarget>,
+        name: &str,
+        path: &str,
+    ) -> Result<()>;
+    async fn delete_launch_target(&self, id: &Id<LaunchTarget>) -> Result<()>;
+    /// 既定の起動対象を切り替える。None の場合はエレメント本体のパスが既定になる
+    async fn set_default_launch_target(
+        &self,
+        element_id: &Id<CollectionElement>,
+        id: Option<&Id<LaunchTarget>>,
+    ) -> Result<()>;
+
+    async fn get_launch_profile(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> Result<Option<LaunchProfile>>;
+    async fn upsert_launch_profile(
+        &self,
+        element_id: &Id<CollectionElement>,
+        profile: &LaunchProfile,
+    ) -> Result<()>;
+    async fn delete_launch_profile(&self, element_id: &Id<CollectionElement>) -> Result<()>;
+
+    /// element_id が None の場合は全体のフックだけを返す
+    async fn get_launch_hooks(
+        &self,
+        element_id: Option<&Id<CollectionElement>>,
+    ) -> Result<Vec<LaunchHook>>;
+    /// ゲームの起動時に実行する全体とエレメントのフックを返す
+    async fn get_launch_hooks_for_element(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> Result<Vec<LaunchHook>>;
+    async fn get_launch_hook_by_id(&self, id: &Id<LaunchHook>) -> Result<Option<LaunchHook>>;
+    async fn create_launch_hook(&self, new: &NewLaunchHook) -> Result<Id<LaunchHook>>;
+    async fn update_launch_hook(&self, id: &Id<LaunchHook>, new: &NewLaunchHook) -> Result<()>;
+    async fn delete_launch_hook(&self, id: &Id<LaunchHook>) -> Result<()>;
+
+    async fn create_play_session(&self, new: &NewPlaySession) -> Result<Id<PlaySession>>;
+    /// プレイ中のセッションの経過を記録する
+    async fn update_play_session_progress(
+        &self,
+        id: &Id<PlaySession>,
+        played_seconds: i32,
+        paused_seconds: i32,
+    ) -> Result<()>;
+    /// プレイしていた範囲を記録する。直前の範囲の終わりから続く場合はその範囲を延ばす
+    async fn add_play_session_played_range(
+        &self,
+        id: &Id<PlaySession>,
+        range: &PlayedRange,
+    ) -> Result<()>;
+    async fn finish_play_session(
+        &self,
+        id: &Id<PlaySession>,
+        ended_at: DateTime<Local>,
+        played_seconds: i32,
+        paused_seconds: i32,
+    ) -> Result<()>;
+    /// 新しい順
+    async fn get_play_sessions_by_element_id(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> Result<Vec<PlaySession>>;
+    /// 期間 [since, until) と重なるセッションを古い順に返す。element_id が None の場合はすべてのゲーム
+    async fn get_play_sessions_by_range(
+        &self,
+        element_id: Option<&Id<CollectionElement>>,
+        since: DateTime<Local>,
+        until: DateTime<Local>,
+    ) -> Result<Vec<PlaySession>>;
+    async fn get_finished_play_sessions(&self) -> Result<Vec<PlaySession>>;
+    /// 以前のバージョンのプレイ履歴を日ごとのプレイ時間に取り込む。取り込み済みの場合は何もせず false を返す
+    ///
+    /// 合計プレイ時間は日ごとのプレイ時間の合計を下回らないように引き上げる
+    async fn import_legacy_play_history(
+        &self,
+        id: &Id<CollectionElement>,
+        record_count: i32,
+        daily_play_times: &[(NaiveDate, i32)],
+        first_play_at: Option<DateTime<Local>>,
+        last_play_at: Option<DateTime<Local>>,
+    ) -> Result<bool>;
+    /// 同じエレメントに取り込み済みの他のランチャーやストアの記録
+    async fn get_external_play_time_imports(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> Result<Vec<ExternalPlayTimeImport>>;
+    /// 他のランチャーやストアの記録を取り込む
+    ///
+    /// 取り込み済みの記録の更新、セッションの登録、合計・日ごとのプレイ時間と最初と最後のプレイ日時の更新をまとめて反映する
+    async fn apply_external_play_time_import(
+        &self,
+        import: &ExternalPlayTimeImport,
+        imported_seconds: i64,
+        sessions: &[(DateTime<Local>, DateTime<Local>)],
+        daily_play_times: &[(NaiveDate, i32)],
+        first_play_at: Option<DateTime<Local>>,
+        last_play_at: Option<DateTime<Local>>,
+    ) -> Result<()>;
+    async fn get_play_session_by_id(&self, id: &Id<PlaySession>) -> Result<Option<PlaySession>>;
+    /// セッションの削除・登録と合計・日ごとのプレイ時間、最初と最後のプレイ日時の更新をまとめて反映し、変更を記録する
+    async fn apply_play_session_edit(&self, edit: &PlaySessionEdit)
+        -> Result<Id<PlaySessionAudit>>;
+    /// 新しい順
+    async fn get_play_session_audits(&self, limit: i32) -> Result<Vec<PlaySessionAudit>>;
+    async fn get_play_session_audit_by_id(
+        &self,
+        id: &Id<PlaySessionAudit>,
+    ) -> Result<Option<PlaySessionAudit>>;
+
+    async fn get_tracking_checkpoints(&self) -> Result<Vec<TrackingCheckpoint>>;
+    async fn upsert_tracking_checkpoint(&self, checkpoint: &TrackingCheckpoint) -> Result<()>;
+    async fn delete_tracking_checkpoint(&self, element_id: &Id<CollectionElement>) -> Result<()>;
 }
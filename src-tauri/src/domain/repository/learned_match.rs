@@ -0,0 +1,18 @@
+use crate::domain::{
+    learned_match::{LearnedGameMatch, NewLearnedGameMatch},
+    Id,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait LearnedMatchRepository {
+    async fn get_learned_game_matches(&self) -> Result<Vec<LearnedGameMatch>>;
+    /// `overwrite` が false の場合は既にある紐づけを残す。追加・更新した件数を返す
+    async fn upsert_learned_game_matches(
+        &self,
+        matches: &[NewLearnedGameMatch],
+        overwrite: bool,
+    ) -> Result<u64>;
+    async fn delete_learned_game_match(&self, id: &Id<LearnedGameMatch>) -> Result<()>;
+}
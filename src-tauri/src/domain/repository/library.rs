@@ -0,0 +1,37 @@
+use crate::domain::{
+    collection::CollectionElement,
+    library::{LibraryRoot, LibrarySuggestion, NewLibrarySuggestion, RemovedInstall},
+    Id,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait LibraryRepository {
+    async fn get_library_roots(&self) -> Result<Vec<LibraryRoot>>;
+    async fn get_library_root_by_path(&self, path: &str) -> Result<Option<LibraryRoot>>;
+    async fn add_library_root(&self, path: &str) -> Result<Id<LibraryRoot>>;
+    /// フォルダの候補も合わせて削除する
+    async fn delete_library_root(&self, id: &Id<LibraryRoot>) -> Result<()>;
+
+    /// 新しい順
+    async fn get_library_suggestions(&self) -> Result<Vec<LibrarySuggestion>>;
+    async fn get_library_suggestion_by_id(
+        &self,
+        id: &Id<LibrarySuggestion>,
+    ) -> Result<Option<LibrarySuggestion>>;
+    /// 同じファイルの候補がある場合は置き換える
+    async fn upsert_library_suggestions(&self, suggestions: &[NewLibrarySuggestion]) -> Result<()>;
+    async fn delete_library_suggestion(&self, id: &Id<LibrarySuggestion>) -> Result<()>;
+    /// 登録済みになったゲームの候補を削除する
+    async fn delete_library_suggestions_by_game_ids(&self, game_ids: &[i32]) -> Result<()>;
+
+    /// 新しい順
+    async fn get_removed_installs(&self) -> Result<Vec<RemovedInstall>>;
+    /// 記録済みのゲームは最初に見つけた日時を残す
+    async fn flag_removed_installs(
+        &self,
+        installs: &[(Id<CollectionElement>, String)],
+    ) -> Result<()>;
+    async fn clear_removed_installs(&self, element_ids: &[Id<CollectionElement>]) -> Result<()>;
+}
@@ -0,0 +1,17 @@
+use crate::domain::{
+    matching_rule::{MatchingRuleOverride, NewMatchingRuleOverride},
+    Id,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait MatchingRuleRepository {
+    /// 古い順
+    async fn get_matching_rule_overrides(&self) -> Result<Vec<MatchingRuleOverride>>;
+    async fn add_matching_rule_override(
+        &self,
+        new_override: &NewMatchingRuleOverride,
+    ) -> Result<Id<MatchingRuleOverride>>;
+    async fn delete_matching_rule_override(&self, id: &Id<MatchingRuleOverride>) -> Result<()>;
+}
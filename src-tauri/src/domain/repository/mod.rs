@@ -1,5 +1,10 @@
 pub mod all_game_cache;
 pub mod collection;
+pub mod learned_match;
+pub mod library;
+pub mod matching_rule;
+pub mod play_stats;
 pub mod explored_cache;
 pub mod repositories;
 pub mod screenshot;
+pub mod tag;
@@ -0,0 +1,49 @@
+use crate::domain::{
+    collection::CollectionElement,
+    play_session::PlaySessionSummary,
+    play_stats::{GamePlayTime, GroupPlayTime, PlayStatsPeriod, PlayStreak, PlayTimeBucket},
+    Id,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Local, NaiveDate};
+
+/// プレイ日ごとの記録とプレイセッションからの集計。期間の `from` と `to` はどちらもその日を含む
+#[async_trait]
+pub trait PlayStatsRepository {
+    async fn get_play_time_buckets(
+        &self,
+        period: PlayStatsPeriod,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PlayTimeBucket>>;
+    /// プレイ時間の長い順
+    async fn get_top_games(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: i32,
+    ) -> Result<Vec<GamePlayTime>>;
+    async fn get_brand_play_times(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<GroupPlayTime>>;
+    /// 発売年ごとの合計。key は西暦の4桁
+    async fn get_sellday_year_play_times(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<GroupPlayTime>>;
+    /// 最も長く連続してプレイした日の並び。同じ長さの場合は新しいもの
+    async fn get_longest_play_streak(
+        &self,
+        element_id: Option<&Id<CollectionElement>>,
+    ) -> Result<Option<PlayStreak>>;
+    /// `since` から `until` までに終了したセッションの集計
+    async fn get_play_session_summary(
+        &self,
+        since: DateTime<Local>,
+        until: DateTime<Local>,
+    ) -> Result<PlaySessionSummary>;
+}
@@ -1,6 +1,8 @@
 use super::{
     all_game_cache::AllGameCacheRepository, collection::CollectionRepository,
-    explored_cache::ExploredCacheRepository, screenshot::ScreenshotRepository,
+    explored_cache::ExploredCacheRepository, learned_match::LearnedMatchRepository,
+    library::LibraryRepository, matching_rule::MatchingRuleRepository,
+    play_stats::PlayStatsRepository, screenshot::ScreenshotRepository, tag::TagRepository,
 };
 
 pub trait RepositoriesExt {
@@ -8,9 +10,19 @@ pub trait RepositoriesExt {
     type ExploredCacheRepo: ExploredCacheRepository;
     type AllGameCacheRepo: AllGameCacheRepository;
     type ScreenshotRepo: ScreenshotRepository;
+    type TagRepo: TagRepository;
+    type PlayStatsRepo: PlayStatsRepository;
+    type LibraryRepo: LibraryRepository;
+    type MatchingRuleRepo: MatchingRuleRepository;
+    type LearnedMatchRepo: LearnedMatchRepository;
 
     fn collection_repository(&self) -> &Self::CollectionRepo;
     fn explored_cache_repository(&self) -> &Self::ExploredCacheRepo;
     fn all_game_cache_repository(&self) -> &Self::AllGameCacheRepo;
     fn screenshot_repository(&self) -> &Self::ScreenshotRepo;
+    fn tag_repository(&self) -> &Self::TagRepo;
+    fn play_stats_repository(&self) -> &Self::PlayStatsRepo;
+    fn library_repository(&self) -> &Self::LibraryRepo;
+    fn matching_rule_repository(&self) -> &Self::MatchingRuleRepo;
+    fn learned_match_repository(&self) -> &Self::LearnedMatchRepo;
 }
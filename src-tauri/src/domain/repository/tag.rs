@@ -0,0 +1,25 @@
+use crate::domain::{collection::CollectionElement, tag::Tag, Id};
+use anyhow::Result;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait TagRepository {
+    async fn get_all(&self) -> Result<Vec<Tag>>;
+    async fn get_by_id(&self, id: &Id<Tag>) -> Result<Option<Tag>>;
+    async fn get_by_name(&self, name: &str) -> Result<Option<Tag>>;
+    async fn get_by_element_id(&self, element_id: &Id<CollectionElement>) -> Result<Vec<Tag>>;
+    /// 同名のタグがあればその ID を返し、なければ作成する
+    async fn get_or_create(&self, name: &str) -> Result<Id<Tag>>;
+    async fn rename(&self, id: &Id<Tag>, name: &str) -> Result<()>;
+    async fn delete(&self, id: &Id<Tag>) -> Result<()>;
+    /// `source` に紐づくエレメントを `target` に付け替えて `source` を削除する
+    async fn merge(&self, source: &Id<Tag>, target: &Id<Tag>) -> Result<()>;
+    async fn add_to_element(&self, element_id: &Id<CollectionElement>, id: &Id<Tag>) -> Result<()>;
+    async fn remove_from_element(
+        &self,
+        element_id: &Id<CollectionElement>,
+        id: &Id<Tag>,
+    ) -> Result<()>;
+    /// (エレメント ID, タグ名) の組をすべて返す
+    async fn get_element_tag_names(&self) -> Result<Vec<(Id<CollectionElement>, String)>>;
+}
@@ -0,0 +1,231 @@
+//! PC 内のゲームの走査結果
+//!
+//! 調べたファイルごとに候補と類似度、結果を決めた規則を残す
+
+use derive_new::new;
+use serde::Serialize;
+
+use super::{
+    all_game_cache::AllGameCacheOne,
+    matching_rule::{MatchingRuleHit, MatchingRuleKind},
+};
+
+/// 走査でゲームと紐づけるときの類似度の閾値
+pub const SCAN_MATCH_THRESHOLD: f32 = 0.8;
+/// 走査結果に残す候補の数
+pub const SCAN_CANDIDATE_LIMIT: usize = 3;
+
+/// ファイルの結果を決めた規則
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScanRule {
+    /// 前回までに調べたファイルのため飛ばした
+    ExploredCache,
+    /// ショートカットのリンク先が存在しない
+    LnkTargetMissing,
+    /// ファイル名か親フォルダ名を取得できない
+    InvalidPath,
+    /// インストーラーやマニュアル等のファイル名
+    NotGameWord,
+    /// ユーザーが直した紐づけ
+    LearnedMatch,
+    /// フォルダ構成で決まっているゲーム
+    PathSpecific,
+    /// ファイル名で決まっているゲーム
+    EqualFilename,
+    /// 類似度が閾値を超えた
+    Similarity,
+    /// ファイル名か親フォルダ名がゲーム名に含まれる
+    PartialName,
+    /// 実行ファイルの会社名が候補のブランド名と一致した
+    BrandName,
+    /// 類似度が閾値以下
+    BelowThreshold,
+    /// 競合時に無視する単語がファイル名に含まれる
+    IgnoreWordOnConflict,
+    /// 競合時に優先する単語がファイル名に含まれる
+    PreferredWordOnConflict,
+    /// 競合時にゲーム名との類似度で決めた
+    SimilarityOnConflict,
+}
+
+impl ScanRule {
+    /// ゲームと紐づいた規則か
+    pub fn is_matched(&self) -> bool {
+        matches!(
+            self,
+            ScanRule::LearnedMatch
+                | ScanRule::PathSpecific
+                | ScanRule::EqualFilename
+                | ScanRule::Similarity
+                | ScanRule::PartialName
+                | ScanRule::BrandName
+        )
+    }
+}
+
+/// ファイルから求めたゲームの候補。類似度の高い順
+#[derive(new, Debug, Clone)]
+pub struct GameCandidateMatch {
+    pub rule: ScanRule,
+    pub candidates: Vec<(AllGameCacheOne, f32)>,
+    pub hits: Vec<MatchingRuleHit>,
+}
+
+impl GameCandidateMatch {
+    /// 紐づいたゲーム。閾値以下の候補しかない場合は None
+    pub fn most_probable(&self) -> Option<&AllGameCacheOne> {
+        if !self.rule.is_matched() {
+            return None;
+        }
+        self.candidates.first().map(|(candidate, _)| candidate)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScanOutcome {
+    /// ゲームと紐づいた
+    Matched,
+    Skipped,
+    /// 同じゲームの別のファイルが選ばれた
+    ConflictLost,
+}
+
+#[derive(new, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanCandidate {
+    pub game_id: i32,
+    pub gamename: String,
+    pub score: f32,
+}
+
+/// 調べたファイルごとの結果
+#[derive(new, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanFileResult {
+    pub file_path: String,
+    pub outcome: ScanOutcome,
+    pub rule: ScanRule,
+    pub candidates: Vec<ScanCandidate>,
+    /// 当てはまった規則ファイルの規則
+    pub rule_hits: Vec<MatchingRuleHit>,
+}
+
+/// 同じゲームに紐づいた複数のファイルからどれを選んだか
+#[derive(new, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanConflict {
+    pub game_id: i32,
+    pub gamename: String,
+    pub kept_path: String,
+    pub dropped_path: String,
+    pub rule: ScanRule,
+    /// 結果を決めた単語
+    pub word: Option<String>,
+}
+
+impl ScanConflict {
+    /// 結果を決めた規則ファイルの規則の種類
+    pub fn rule_kind(&self) -> Option<MatchingRuleKind> {
+        match self.rule {
+            ScanRule::IgnoreWordOnConflict => Some(MatchingRuleKind::IgnoreWordOnConflict),
+            ScanRule::PreferredWordOnConflict => Some(MatchingRuleKind::PreferredWordOnConflict),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanReport {
+    pub dry_run: bool,
+    /// 登録したゲーム名。試しに実行した場合は登録するもの
+    pub registered: Vec<String>,
+    pub files: Vec<ScanFileResult>,
+    pub conflicts: Vec<ScanConflict>,
+}
+
+impl ScanReport {
+    pub fn push_skipped(&mut self, file_path: String, rule: ScanRule) {
+        self.files.push(ScanFileResult::new(
+            file_path,
+            ScanOutcome::Skipped,
+            rule,
+            vec![],
+            vec![],
+        ));
+    }
+
+    /// 競合で選ばれなかったファイルの結果を書き換える
+    pub fn apply_conflicts(&mut self, conflicts: Vec<ScanConflict>) {
+        for conflict in conflicts.iter() {
+            if let Some(file) = self
+                .files
+                .iter_mut()
+                .find(|file| file.file_path == conflict.dropped_path)
+            {
+                file.outcome = ScanOutcome::ConflictLost;
+                file.rule = conflict.rule;
+                if let (Some(kind), Some(word)) = (conflict.rule_kind(), conflict.word.clone()) {
+                    file.rule_hits
+                        .push(MatchingRuleHit::new(kind, word, Some(conflict.game_id)));
+                }
+            }
+        }
+        self.conflicts.extend(conflicts);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_probable_ignores_candidates_below_threshold() {
+        let candidate = AllGameCacheOne::new(1, "サクラノ刻".to_string());
+        let matched =
+            GameCandidateMatch::new(ScanRule::Similarity, vec![(candidate.clone(), 0.9)], vec![]);
+        assert_eq!(matched.most_probable().map(|v| v.id), Some(1));
+
+        let below =
+            GameCandidateMatch::new(ScanRule::BelowThreshold, vec![(candidate, 0.5)], vec![]);
+        assert!(below.most_probable().is_none());
+    }
+
+    #[test]
+    fn test_apply_conflicts_marks_dropped_file() {
+        let mut report = ScanReport::default();
+        for path in ["games/a/a.exe", "games/a/setting.exe"] {
+            report.files.push(ScanFileResult::new(
+                path.to_string(),
+                ScanOutcome::Matched,
+                ScanRule::Similarity,
+                vec![ScanCandidate::new(1, "a".to_string(), 1.0)],
+                vec![],
+            ));
+        }
+
+        report.apply_conflicts(vec![ScanConflict::new(
+            1,
+            "a".to_string(),
+            "games/a/a.exe".to_string(),
+            "games/a/setting.exe".to_string(),
+            ScanRule::IgnoreWordOnConflict,
+            Some("setting".to_string()),
+        )]);
+
+        assert_eq!(report.files[0].outcome, ScanOutcome::Matched);
+        assert_eq!(report.files[1].outcome, ScanOutcome::ConflictLost);
+        assert_eq!(report.files[1].rule, ScanRule::IgnoreWordOnConflict);
+        assert_eq!(
+            report.files[1].rule_hits,
+            vec![MatchingRuleHit::new(
+                MatchingRuleKind::IgnoreWordOnConflict,
+                "setting".to_string(),
+                Some(1)
+            )]
+        );
+        assert_eq!(report.conflicts.len(), 1);
+    }
+}
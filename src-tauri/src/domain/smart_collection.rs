@@ -0,0 +1,229 @@
+use chrono::{DateTime, Local, NaiveDate};
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+use super::Id;
+
+/// 保存された条件で動的にエレメントを絞り込むコレクション
+#[derive(new, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartCollection {
+    pub id: Id<SmartCollection>,
+    pub name: String,
+    pub filter: SmartCollectionFilter,
+    pub created_at: DateTime<Local>,
+    pub updated_at: DateTime<Local>,
+}
+
+#[derive(new, Debug, Clone)]
+pub struct NewSmartCollection {
+    pub name: String,
+    pub filter: SmartCollectionFilter,
+}
+
+/// スマートコレクションの条件木
+///
+/// `And` / `Or` で条件を組み合わせ、葉の条件はそれぞれエレメントの集合に対応する
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SmartCollectionFilter {
+    And {
+        filters: Vec<SmartCollectionFilter>,
+    },
+    Or {
+        filters: Vec<SmartCollectionFilter>,
+    },
+    Brand {
+        brandnames: Vec<String>,
+    },
+    /// 発売日 (YYYY-MM-DD) の範囲。両端を含む
+    Sellday {
+        since: String,
+        until: String,
+    },
+    PlayStatus {
+        play_statuses: Vec<i32>,
+    },
+    TotalPlayTime {
+        min_seconds: Option<i32>,
+        max_seconds: Option<i32>,
+    },
+    /// 最後にプレイしてから `days` 日以内
+    LastPlayedWithin {
+        days: i32,
+    },
+    Liked {
+        is_liked: bool,
+    },
+    Installed {
+        is_installed: bool,
+    },
+    Nukige {
+        is_nukige: bool,
+    },
+}
+
+impl SmartCollectionFilter {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            SmartCollectionFilter::And { filters } | SmartCollectionFilter::Or { filters } => {
+                if filters.is_empty() {
+                    return Err("条件グループが空です".to_string());
+                }
+                filters.iter().try_for_each(|v| v.validate())
+            }
+            SmartCollectionFilter::Brand { brandnames } => {
+                if brandnames.is_empty() {
+                    return Err("ブランドが指定されていません".to_string());
+                }
+                Ok(())
+            }
+            SmartCollectionFilter::Sellday { since, until } => {
+                let since = parse_sellday(since)?;
+                let until = parse_sellday(until)?;
+                if since > until {
+                    return Err(format!("発売日の範囲が不正です: {} > {}", since, until));
+                }
+                Ok(())
+            }
+            SmartCollectionFilter::PlayStatus { play_statuses } => {
+                if play_statuses.is_empty() {
+                    return Err("プレイ状況が指定されていません".to_string());
+                }
+                Ok(())
+            }
+            SmartCollectionFilter::TotalPlayTime {
+                min_seconds,
+                max_seconds,
+            } => match (min_seconds, max_seconds) {
+                (None, None) => Err("プレイ時間の範囲が指定されていません".to_string()),
+                (Some(min), Some(max)) if min > max => {
+                    Err(format!("プレイ時間の範囲が不正です: {} > {}", min, max))
+                }
+                _ => Ok(()),
+            },
+            SmartCollectionFilter::LastPlayedWithin { days } => {
+                if *days < 0 {
+                    return Err(format!("日数が不正です: {}", days));
+                }
+                Ok(())
+            }
+            SmartCollectionFilter::Liked { .. }
+            | SmartCollectionFilter::Installed { .. }
+            | SmartCollectionFilter::Nukige { .. } => Ok(()),
+        }
+    }
+}
+
+fn parse_sellday(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("発売日の形式が不正です: `{}`", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smart_collection_filter_deserialize() {
+        let json = r#"{
+            "type": "and",
+            "filters": [
+                { "type": "brand", "brandnames": ["ゆずソフト"] },
+                { "type": "totalPlayTime", "minSeconds": 3600, "maxSeconds": null },
+                { "type": "or", "filters": [
+                    { "type": "liked", "isLiked": true },
+                    { "type": "lastPlayedWithin", "days": 30 }
+                ]}
+            ]
+        }"#;
+
+        let filter: SmartCollectionFilter = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            filter,
+            SmartCollectionFilter::And {
+                filters: vec![
+                    SmartCollectionFilter::Brand {
+                        brandnames: vec!["ゆずソフト".to_string()]
+                    },
+                    SmartCollectionFilter::TotalPlayTime {
+                        min_seconds: Some(3600),
+                        max_seconds: None
+                    },
+                    SmartCollectionFilter::Or {
+                        filters: vec![
+                            SmartCollectionFilter::Liked { is_liked: true },
+                            SmartCollectionFilter::LastPlayedWithin { days: 30 },
+                        ]
+                    },
+                ]
+            }
+        );
+    }
+
+    #[test]
+    fn test_smart_collection_filter_serialize_roundtrip() {
+        let filter = SmartCollectionFilter::Sellday {
+            since: "2020-01-01".to_string(),
+            until: "2020-12-31".to_string(),
+        };
+        let json = serde_json::to_string(&filter).unwrap();
+        assert!(json.contains(r#""type":"sellday""#));
+
+        let deserialized: SmartCollectionFilter = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, filter);
+    }
+
+    #[test]
+    fn test_smart_collection_filter_validate_ok() {
+        let filter = SmartCollectionFilter::Or {
+            filters: vec![
+                SmartCollectionFilter::Installed { is_installed: true },
+                SmartCollectionFilter::PlayStatus {
+                    play_statuses: vec![0, 1],
+                },
+            ],
+        };
+        assert!(filter.validate().is_ok());
+    }
+
+    #[test]
+    fn test_smart_collection_filter_validate_rejects_empty_group() {
+        let filter = SmartCollectionFilter::And {
+            filters: vec![SmartCollectionFilter::Or { filters: vec![] }],
+        };
+        assert!(filter.validate().is_err());
+    }
+
+    #[test]
+    fn test_smart_collection_filter_validate_rejects_invalid_ranges() {
+        let sellday = SmartCollectionFilter::Sellday {
+            since: "2021-01-01".to_string(),
+            until: "2020-01-01".to_string(),
+        };
+        assert!(sellday.validate().is_err());
+
+        let sellday_format = SmartCollectionFilter::Sellday {
+            since: "2020/01/01".to_string(),
+            until: "2020-12-31".to_string(),
+        };
+        assert!(sellday_format.validate().is_err());
+
+        let play_time = SmartCollectionFilter::TotalPlayTime {
+            min_seconds: Some(100),
+            max_seconds: Some(10),
+        };
+        assert!(play_time.validate().is_err());
+
+        let unbounded = SmartCollectionFilter::TotalPlayTime {
+            min_seconds: None,
+            max_seconds: None,
+        };
+        assert!(unbounded.validate().is_err());
+    }
+}
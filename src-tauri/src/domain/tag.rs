@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+
+use derive_new::new;
+use serde::{Deserialize, Serialize};
+
+use super::Id;
+
+#[derive(new, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: Id<Tag>,
+    pub name: String,
+    pub element_count: i32,
+}
+
+/// タグ検索式
+///
+/// `泣きゲー AND (club-pick OR "needs patch") AND NOT 抜きゲー` のように書く。
+/// `AND` は省略でき、`&` / `|` / `!` も演算子として使える。
+/// 空白や記号を含むタグはダブルクォートで囲む
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagExpression {
+    Tag(String),
+    Not(Box<TagExpression>),
+    And(Vec<TagExpression>),
+    Or(Vec<TagExpression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    And,
+    Or,
+    Not,
+    LeftParen,
+    RightParen,
+}
+
+impl TagExpression {
+    pub fn parse(source: &str) -> Result<TagExpression, String> {
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            return Err("検索式が空です".to_string());
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let expression = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("予期しないトークンです: {:?}", token));
+        }
+        Ok(expression)
+    }
+
+    pub fn matches(&self, tags: &HashSet<&str>) -> bool {
+        match self {
+            TagExpression::Tag(name) => tags.contains(name.as_str()),
+            TagExpression::Not(inner) => !inner.matches(tags),
+            TagExpression::And(children) => children.iter().all(|v| v.matches(tags)),
+            TagExpression::Or(children) => children.iter().any(|v| v.matches(tags)),
+        }
+    }
+}
+
+fn is_word_boundary(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '(' | ')' | '"' | '&' | '|')
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = source.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LeftParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RightParen);
+            }
+            '&' => {
+                chars.next();
+                tokens.push(Token::And);
+            }
+            '|' => {
+                chars.next();
+                tokens.push(Token::Or);
+            }
+            '!' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            '"' => {
+                chars.next();
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("ダブルクォートが閉じられていません".to_string()),
+                    }
+                }
+                tokens.push(Token::Word(word));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if is_word_boundary(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<TagExpression, String> {
+        let mut children = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            children.push(self.parse_and()?);
+        }
+        Ok(flatten(children, TagExpression::Or))
+    }
+
+    fn parse_and(&mut self) -> Result<TagExpression, String> {
+        let mut children = vec![self.parse_not()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                    children.push(self.parse_not()?);
+                }
+                // AND の省略
+                Some(Token::Word(_)) | Some(Token::Not) | Some(Token::LeftParen) => {
+                    children.push(self.parse_not()?);
+                }
+                _ => break,
+            }
+        }
+        Ok(flatten(children, TagExpression::And))
+    }
+
+    fn parse_not(&mut self) -> Result<TagExpression, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(TagExpression::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<TagExpression, String> {
+        match self.next() {
+            Some(Token::Word(word)) => Ok(TagExpression::Tag(word)),
+            Some(Token::LeftParen) => {
+                let expression = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RightParen) => Ok(expression),
+                    _ => Err("括弧が閉じられていません".to_string()),
+                }
+            }
+            Some(token) => Err(format!("予期しないトークンです: {:?}", token)),
+            None => Err("検索式が途中で終わっています".to_string()),
+        }
+    }
+}
+
+fn flatten(
+    mut children: Vec<TagExpression>,
+    f: fn(Vec<TagExpression>) -> TagExpression,
+) -> TagExpression {
+    if children.len() == 1 {
+        children.remove(0)
+    } else {
+        f(children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str) -> TagExpression {
+        TagExpression::Tag(name.to_string())
+    }
+
+    fn tags<'a>(names: &[&'a str]) -> HashSet<&'a str> {
+        names.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_parse_single_tag() {
+        assert_eq!(TagExpression::parse("泣きゲー").unwrap(), tag("泣きゲー"));
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let expression = TagExpression::parse("a OR b AND NOT c").unwrap();
+        assert_eq!(
+            expression,
+            TagExpression::Or(vec![
+                tag("a"),
+                TagExpression::And(vec![tag("b"), TagExpression::Not(Box::new(tag("c")))]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_implicit_and_quotes_and_symbols() {
+        let expression =
+            TagExpression::parse(r#"泣きゲー (club-pick | "needs patch") !抜きゲー"#).unwrap();
+        assert_eq!(
+            expression,
+            TagExpression::And(vec![
+                tag("泣きゲー"),
+                TagExpression::Or(vec![tag("club-pick"), tag("needs patch")]),
+                TagExpression::Not(Box::new(tag("抜きゲー"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_lowercase_keywords_are_tags() {
+        let expression = TagExpression::parse("and or").unwrap();
+        assert_eq!(expression, TagExpression::And(vec![tag("and"), tag("or")]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(TagExpression::parse("").is_err());
+        assert!(TagExpression::parse("   ").is_err());
+        assert!(TagExpression::parse("(a OR b").is_err());
+        assert!(TagExpression::parse("a OR").is_err());
+        assert!(TagExpression::parse("a )").is_err());
+        assert!(TagExpression::parse(r#""needs patch"#).is_err());
+    }
+
+    #[test]
+    fn test_matches() {
+        let expression =
+            TagExpression::parse(r#"泣きゲー AND (club-pick OR "needs patch") AND NOT 抜きゲー"#)
+                .unwrap();
+
+        assert!(expression.matches(&tags(&["泣きゲー", "club-pick"])));
+        assert!(expression.matches(&tags(&["泣きゲー", "needs patch", "other"])));
+        assert!(!expression.matches(&tags(&["泣きゲー"])));
+        assert!(!expression.matches(&tags(&["泣きゲー", "club-pick", "抜きゲー"])));
+        assert!(!expression.matches(&tags(&[])));
+    }
+}
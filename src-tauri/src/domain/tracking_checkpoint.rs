@@ -0,0 +1,134 @@
+use chrono::{DateTime, Local};
+use derive_new::new;
+
+use super::{collection::CollectionElement, play_session::PlaySession, Id};
+
+/// 実行中の追跡セッションの保存内容
+///
+/// 監視の tick ごとに更新し、正常に終了したら削除する。起動時に残っているものは
+/// クラッシュやアップデートで中断されたセッション
+#[allow(clippy::too_many_arguments)]
+#[derive(new, Debug, Clone)]
+pub struct TrackingCheckpoint {
+    pub collection_element_id: Id<CollectionElement>,
+    pub play_session_id: Option<Id<PlaySession>>,
+    pub pid: u32,
+    /// PID の再利用と区別するためのプロセスの開始時刻(UNIX秒)
+    pub process_started_at: Option<i64>,
+    pub game_path: String,
+    pub game_name: String,
+    pub started_at: DateTime<Local>,
+    /// 最後に監視が動いていた時刻
+    pub heartbeat_at: DateTime<Local>,
+    pub played_seconds: i32,
+    pub paused_seconds: i32,
+    /// 操作によるポーズ中か
+    pub is_paused: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckpointResolution {
+    /// ゲームが動いているので追跡を再開する
+    Reattach,
+    /// ゲームは終了しているので最後の heartbeat でセッションを閉じる
+    Close,
+}
+
+/// 起動時の開始時刻の誤差として許容する秒数
+const PROCESS_STARTED_AT_TOLERANCE_SECONDS: i64 = 2;
+
+/// 中断されたセッションを再開するか閉じるかを決める
+///
+/// `live_process_started_at` は保存した PID のプロセスが動いている場合のその開始時刻
+pub fn resolve_tracking_checkpoint(
+    checkpoint: &TrackingCheckpoint,
+    live_process_started_at: Option<i64>,
+) -> CheckpointResolution {
+    match (checkpoint.process_started_at, live_process_started_at) {
+        (_, None) => CheckpointResolution::Close,
+        (Some(saved), Some(live))
+            if (saved - live).abs() > PROCESS_STARTED_AT_TOLERANCE_SECONDS =>
+        {
+            CheckpointResolution::Close
+        }
+        _ => CheckpointResolution::Reattach,
+    }
+}
+
+/// 最後の heartbeat から再開までの空白時間
+pub fn checkpoint_resume_gap(
+    checkpoint: &TrackingCheckpoint,
+    now: DateTime<Local>,
+) -> std::time::Duration {
+    (now - checkpoint.heartbeat_at).to_std().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn checkpoint(process_started_at: Option<i64>) -> TrackingCheckpoint {
+        let started_at = Local.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        TrackingCheckpoint::new(
+            Id::new(1),
+            Some(Id::new(10)),
+            1234,
+            process_started_at,
+            "C:/Games/game.exe".to_string(),
+            "game".to_string(),
+            started_at,
+            started_at + chrono::Duration::minutes(30),
+            1700,
+            100,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_resolve_tracking_checkpoint_closes_when_process_is_gone() {
+        assert_eq!(
+            resolve_tracking_checkpoint(&checkpoint(Some(1000)), None),
+            CheckpointResolution::Close
+        );
+    }
+
+    #[test]
+    fn test_resolve_tracking_checkpoint_reattaches_to_same_process() {
+        assert_eq!(
+            resolve_tracking_checkpoint(&checkpoint(Some(1000)), Some(1001)),
+            CheckpointResolution::Reattach
+        );
+        assert_eq!(
+            resolve_tracking_checkpoint(&checkpoint(None), Some(5000)),
+            CheckpointResolution::Reattach
+        );
+    }
+
+    #[test]
+    fn test_resolve_tracking_checkpoint_closes_when_pid_is_reused() {
+        assert_eq!(
+            resolve_tracking_checkpoint(&checkpoint(Some(1000)), Some(5000)),
+            CheckpointResolution::Close
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_resume_gap() {
+        let checkpoint = checkpoint(None);
+        assert_eq!(
+            checkpoint_resume_gap(
+                &checkpoint,
+                checkpoint.heartbeat_at + chrono::Duration::seconds(15)
+            ),
+            std::time::Duration::from_secs(15)
+        );
+        assert_eq!(
+            checkpoint_resume_gap(
+                &checkpoint,
+                checkpoint.heartbeat_at - chrono::Duration::seconds(15)
+            ),
+            std::time::Duration::ZERO
+        );
+    }
+}
@@ -0,0 +1,7 @@
+use std::time::Duration;
+
+/// ユーザーの入力操作の取得元
+pub trait InputActivityWindows {
+    /// 最後にキーボード・マウスの入力があってからの経過時間
+    fn idle_duration(&self) -> anyhow::Result<Duration>;
+}
@@ -1 +1,2 @@
+pub mod input_activity;
 pub mod process;
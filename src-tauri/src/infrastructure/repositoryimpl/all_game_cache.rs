@@ -1,9 +1,10 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Local, NaiveDateTime};
-use sqlx::{query_as, QueryBuilder, Row};
+use sqlx::{query, query_as, QueryBuilder, Row};
 
 use crate::domain::{
     all_game_cache::{AllGameCache, AllGameCacheOneWithThumbnailUrl, NewAllGameCacheOne},
+    normalize::normalize,
     repository::all_game_cache::AllGameCacheRepository,
 };
 
@@ -43,9 +44,11 @@ impl AllGameCacheRepository for RepositoryImpl<AllGameCache> {
         offset: i64,
     ) -> anyhow::Result<Vec<AllGameCacheOneWithThumbnailUrl>> {
         let pool = self.pool.0.clone();
-        let trimmed = query.trim().to_string();
-        let tokens = trimmed
+        // ファイル名との紐づけと同じ正規化で、表記の揺れや記号を無視して探す
+        let normalized = normalize(&query);
+        let tokens = query
             .split_whitespace()
+            .map(normalize)
             .filter(|token| !token.is_empty())
             .collect::<Vec<_>>();
         let mut builder = QueryBuilder::<sqlx::Sqlite>::new(
@@ -56,18 +59,18 @@ impl AllGameCacheRepository for RepositoryImpl<AllGameCache> {
             builder.push(" WHERE ");
             let mut separated = builder.separated(" AND ");
             for token in tokens {
-                separated.push("gamename LIKE ");
-                separated.push_bind(format!("%{}%", token));
+                separated.push("normalized_gamename LIKE ");
+                separated.push_bind_unseparated(format!("%{}%", token));
             }
         }
 
-        if trimmed.is_empty() {
+        if normalized.is_empty() {
             builder.push(" ORDER BY id DESC");
         } else {
-            builder.push(" ORDER BY CASE WHEN gamename = ");
-            builder.push_bind(trimmed.clone());
-            builder.push(" THEN 0 WHEN gamename LIKE ");
-            builder.push_bind(format!("{}%", trimmed));
+            builder.push(" ORDER BY CASE WHEN normalized_gamename = ");
+            builder.push_bind(normalized.clone());
+            builder.push(" THEN 0 WHEN normalized_gamename LIKE ");
+            builder.push_bind(format!("{}%", normalized));
             builder.push(" THEN 1 ELSE 2 END, LENGTH(gamename) ASC, id DESC");
         }
         builder.push(" LIMIT ");
@@ -115,11 +118,13 @@ impl AllGameCacheRepository for RepositoryImpl<AllGameCache> {
         }
         for c in cache.chunks(1000) {
             // ref: https://docs.rs/sqlx-core/latest/sqlx_core/query_builder/struct.QueryBuilder.html#method.push_values
-            let mut query_builder =
-                QueryBuilder::new("INSERT INTO all_game_caches (id, gamename, thumbnail_url) ");
+            let mut query_builder = QueryBuilder::new(
+                "INSERT INTO all_game_caches (id, gamename, normalized_gamename, thumbnail_url) ",
+            );
             query_builder.push_values(c, |mut b, new| {
                 b.push_bind(new.id);
                 b.push_bind(new.gamename.clone());
+                b.push_bind(normalize(&new.gamename));
                 b.push_bind(new.thumbnail_url.clone());
             });
 
@@ -144,4 +149,104 @@ impl AllGameCacheRepository for RepositoryImpl<AllGameCache> {
         query.execute(&*pool).await?;
         Ok(())
     }
+    async fn refresh_normalized_gamenames(&self) -> anyhow::Result<u64> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<(i32, String, String)> =
+            query_as("SELECT id, gamename, normalized_gamename FROM all_game_caches")
+                .fetch_all(&*pool)
+                .await?;
+        let mut tx = pool.begin().await?;
+        let mut updated = 0;
+        for (id, gamename, normalized_gamename) in rows {
+            let normalized = normalize(&gamename);
+            if normalized == normalized_gamename {
+                continue;
+            }
+            query("UPDATE all_game_caches SET normalized_gamename = ? WHERE id = ?")
+                .bind(normalized)
+                .bind(id)
+                .execute(&mut tx)
+                .await?;
+            updated += 1;
+        }
+        tx.commit().await?;
+        Ok(updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::repositoryimpl::driver::Db;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    async fn new_repository() -> RepositoryImpl<AllGameCache> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE all_game_caches (
+                id INTEGER PRIMARY KEY,
+                gamename TEXT NOT NULL,
+                thumbnail_url TEXT NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                normalized_gamename TEXT NOT NULL DEFAULT ''
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        RepositoryImpl::new(Db(Arc::new(pool)))
+    }
+
+    fn new_cache(id: i32, gamename: &str) -> NewAllGameCacheOne {
+        NewAllGameCacheOne::new(id, gamename.to_string(), String::new())
+    }
+
+    async fn search_ids(repository: &RepositoryImpl<AllGameCache>, query: &str) -> Vec<i32> {
+        repository
+            .search(query.to_string(), 10, 0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|v| v.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_ignores_kana_width_and_symbols() {
+        let repository = new_repository().await;
+        repository
+            .update(vec![
+                new_cache(1, "サクラノ詩 -櫻の森の上を舞う-"),
+                new_cache(2, "サクラノ刻 -櫻の森の下を歩む-"),
+                new_cache(3, "恋する☆乙女"),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(search_ids(&repository, "ｻｸﾗﾉ 桜").await, vec![2, 1]);
+        assert_eq!(search_ids(&repository, "さくらの刻").await, vec![2]);
+        assert_eq!(search_ids(&repository, "恋する乙女").await, vec![3]);
+        // 記号だけの検索語は全件を返す
+        assert_eq!(search_ids(&repository, "☆").await, vec![3, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_normalized_gamenames() {
+        let repository = new_repository().await;
+        sqlx::query(
+            "INSERT INTO all_game_caches (id, gamename, thumbnail_url) VALUES (1, 'ゲームＡ', ''), (2, 'ゲームB', '')",
+        )
+        .execute(&*repository.pool.0)
+        .await
+        .unwrap();
+
+        assert_eq!(repository.refresh_normalized_gamenames().await.unwrap(), 2);
+        assert_eq!(repository.refresh_normalized_gamenames().await.unwrap(), 0);
+        assert_eq!(search_ids(&repository, "げーむa").await, vec![1]);
+    }
 }
@@ -1,11 +1,28 @@
 use async_trait::async_trait;
-use chrono::{DateTime, Local, NaiveDate};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
 use sqlx::{query, query_as, QueryBuilder, Row};
 
-use super::{models::collection::CollectionElementTable, repository::RepositoryImpl};
+use super::{
+    models::collection::{
+        CollectionElementTable, CollectionTable, ExternalPlayTimeImportTable, LaunchHookTable,
+        LaunchProfileTable, LaunchTargetTable, PlaySessionAuditTable, PlaySessionTable,
+        SmartCollectionTable, TrackingCheckpointTable,
+    },
+    repository::RepositoryImpl,
+};
 use crate::domain::{
-    collection::{CollectionElement, NewCollectionElement, NewCollectionElementDetail},
+    collection::{
+        Collection, CollectionElement, NewCollection, NewCollectionElement,
+        NewCollectionElementDetail,
+    },
+    external_play_time::ExternalPlayTimeImport,
+    launch::{LaunchProfile, LaunchTarget, NewLaunchTarget},
+    launch_hook::{LaunchHook, NewLaunchHook},
+    play_day::{DayBoundary, DAY_BOUNDARY_SETTING_KEY},
+    play_session::{NewPlaySession, PlaySession, PlaySessionAudit, PlaySessionEdit, PlayedRange},
     repository::collection::{CollectionRepository, DailyPlayTime, GameScreenshotCache},
+    smart_collection::{NewSmartCollection, SmartCollection},
+    tracking_checkpoint::TrackingCheckpoint,
     Id,
 };
 
@@ -18,7 +35,13 @@ impl CollectionRepository for RepositoryImpl<CollectionElement> {
                 c.id, c.gamename, c.exe_path, c.lnk_path, c.install_at, c.first_play_at, c.last_play_at, c.like_at,
                 c.play_status, c.total_play_time_seconds, c.thumbnail_width, c.thumbnail_height, 
                 c.created_at, c.updated_at,
-                cd.gamename_ruby, cd.sellday, cd.is_nukige, cd.brandname, cd.brandname_ruby
+                cd.gamename_ruby, cd.sellday, cd.is_nukige, cd.brandname, cd.brandname_ruby,
+                (SELECT json_group_array(name) FROM (
+                    SELECT t.name FROM collection_element_tags AS et
+                    INNER JOIN tags AS t ON t.id = et.tag_id
+                    WHERE et.collection_element_id = c.id
+                    ORDER BY t.name
+                )) AS tags_json
             FROM collection_elements as c
             LEFT JOIN collection_element_details as cd
             ON c.id = cd.collection_element_id",
@@ -37,7 +60,13 @@ impl CollectionRepository for RepositoryImpl<CollectionElement> {
                 c.id, c.gamename, c.exe_path, c.lnk_path, c.install_at, c.first_play_at, c.last_play_at, c.like_at,
                 c.play_status, c.total_play_time_seconds, c.thumbnail_width, c.thumbnail_height, 
                 c.created_at, c.updated_at,
-                cd.gamename_ruby, cd.sellday, cd.is_nukige, cd.brandname, cd.brandname_ruby
+                cd.gamename_ruby, cd.sellday, cd.is_nukige, cd.brandname, cd.brandname_ruby,
+                (SELECT json_group_array(name) FROM (
+                    SELECT t.name FROM collection_element_tags AS et
+                    INNER JOIN tags AS t ON t.id = et.tag_id
+                    WHERE et.collection_element_id = c.id
+                    ORDER BY t.name
+                )) AS tags_json
             FROM collection_elements as c
             LEFT JOIN collection_element_details as cd
             ON c.id = cd.collection_element_id
@@ -253,6 +282,72 @@ impl CollectionRepository for RepositoryImpl<CollectionElement> {
         .await?;
         Ok(ids.into_iter().map(|v| Id::new(v.0)).collect())
     }
+    async fn get_all_element_ids(&self) -> anyhow::Result<Vec<Id<CollectionElement>>> {
+        let pool = self.pool.0.clone();
+        let ids: Vec<(i32,)> = sqlx::query_as("SELECT id FROM collection_elements")
+            .fetch_all(&*pool)
+            .await?;
+        Ok(ids.into_iter().map(|v| Id::new(v.0)).collect())
+    }
+    async fn get_element_ids_by_play_statuses(
+        &self,
+        play_statuses: &[i32],
+    ) -> anyhow::Result<Vec<Id<CollectionElement>>> {
+        if play_statuses.is_empty() {
+            return Ok(vec![]);
+        }
+        let pool = self.pool.0.clone();
+        let mut builder =
+            QueryBuilder::new("SELECT id FROM collection_elements WHERE play_status IN (");
+        let mut separated = builder.separated(", ");
+        for play_status in play_statuses.iter() {
+            separated.push_bind(play_status);
+        }
+        separated.push_unseparated(")");
+        let ids: Vec<(i32,)> = builder.build_query_as().fetch_all(&*pool).await?;
+        Ok(ids.into_iter().map(|v| Id::new(v.0)).collect())
+    }
+    async fn get_element_ids_by_total_play_time_seconds(
+        &self,
+        min_seconds: Option<i32>,
+        max_seconds: Option<i32>,
+    ) -> anyhow::Result<Vec<Id<CollectionElement>>> {
+        let pool = self.pool.0.clone();
+        let ids: Vec<(i32,)> = sqlx::query_as(
+            "SELECT id FROM collection_elements
+            WHERE (? IS NULL OR total_play_time_seconds >= ?)
+            AND (? IS NULL OR total_play_time_seconds <= ?)",
+        )
+        .bind(min_seconds)
+        .bind(min_seconds)
+        .bind(max_seconds)
+        .bind(max_seconds)
+        .fetch_all(&*pool)
+        .await?;
+        Ok(ids.into_iter().map(|v| Id::new(v.0)).collect())
+    }
+    async fn get_element_ids_by_last_play_at_since(
+        &self,
+        since: DateTime<Local>,
+    ) -> anyhow::Result<Vec<Id<CollectionElement>>> {
+        let pool = self.pool.0.clone();
+        let ids: Vec<(i32,)> =
+            sqlx::query_as("SELECT id FROM collection_elements WHERE last_play_at >= ?")
+                .bind(since.naive_utc())
+                .fetch_all(&*pool)
+                .await?;
+        Ok(ids.into_iter().map(|v| Id::new(v.0)).collect())
+    }
+    async fn get_element_ids_by_like_at_not_null(
+        &self,
+    ) -> anyhow::Result<Vec<Id<CollectionElement>>> {
+        let pool = self.pool.0.clone();
+        let ids: Vec<(i32,)> =
+            sqlx::query_as("SELECT id FROM collection_elements WHERE like_at IS NOT NULL")
+                .fetch_all(&*pool)
+                .await?;
+        Ok(ids.into_iter().map(|v| Id::new(v.0)).collect())
+    }
     async fn update_element_last_play_at_by_id(
         &self,
         id: &Id<CollectionElement>,
@@ -414,6 +509,32 @@ impl CollectionRepository for RepositoryImpl<CollectionElement> {
             .collect())
     }
 
+    async fn update_day_boundary(
+        &self,
+        from: DayBoundary,
+        to: DayBoundary,
+        deltas: &[(Id<CollectionElement>, NaiveDate, i32)],
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        let mut tx = pool.begin().await?;
+        let current: Option<String> = query("SELECT value FROM app_settings WHERE key = ?")
+            .bind(DAY_BOUNDARY_SETTING_KEY)
+            .fetch_optional(&mut tx)
+            .await?
+            .and_then(|r| r.get("value"));
+        if DayBoundary::from_setting(current.as_deref()) != from {
+            anyhow::bail!("day boundary is no longer {}", from.to_setting_value());
+        }
+        apply_daily_play_time_deltas_in_tx(&mut tx, deltas).await?;
+        query("INSERT OR REPLACE INTO app_settings (key, value) VALUES (?, ?)")
+            .bind(DAY_BOUNDARY_SETTING_KEY)
+            .bind(to.to_setting_value())
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
     async fn delete_element_by_id(&self, id: &Id<CollectionElement>) -> anyhow::Result<()> {
         let pool = self.pool.0.clone();
         query("delete from collection_elements where id = ?") // collection_elements から削除
@@ -520,4 +641,1351 @@ impl CollectionRepository for RepositoryImpl<CollectionElement> {
             .await?;
         Ok(())
     }
+
+    async fn get_all_collections(&self) -> anyhow::Result<Vec<Collection>> {
+        let pool = self.pool.0.clone();
+        let records = query_as::<_, CollectionTable>(
+            "SELECT
+                c.id, c.name, c.sort_order, c.created_at, c.updated_at,
+                COUNT(m.id) AS element_count
+            FROM collections AS c
+            LEFT JOIN collection_element_maps AS m
+            ON c.id = m.collection_id
+            GROUP BY c.id
+            ORDER BY c.sort_order ASC, c.id ASC",
+        )
+        .fetch_all(&*pool)
+        .await?;
+        Ok(records.into_iter().flat_map(|v| v.try_into()).collect())
+    }
+
+    async fn get_collection_by_id(
+        &self,
+        id: &Id<Collection>,
+    ) -> anyhow::Result<Option<Collection>> {
+        let pool = self.pool.0.clone();
+        let record = query_as::<_, CollectionTable>(
+            "SELECT
+                c.id, c.name, c.sort_order, c.created_at, c.updated_at,
+                (SELECT COUNT(*) FROM collection_element_maps AS m WHERE m.collection_id = c.id) AS element_count
+            FROM collections AS c
+            WHERE c.id = ?",
+        )
+        .bind(id.value)
+        .fetch_optional(&*pool)
+        .await?;
+        Ok(record.and_then(|v| v.try_into().ok()))
+    }
+
+    async fn get_collection_by_name(&self, name: &str) -> anyhow::Result<Option<Collection>> {
+        let pool = self.pool.0.clone();
+        let record = query_as::<_, CollectionTable>(
+            "SELECT
+                c.id, c.name, c.sort_order, c.created_at, c.updated_at,
+                (SELECT COUNT(*) FROM collection_element_maps AS m WHERE m.collection_id = c.id) AS element_count
+            FROM collections AS c
+            WHERE c.name = ?",
+        )
+        .bind(name)
+        .fetch_optional(&*pool)
+        .await?;
+        Ok(record.and_then(|v| v.try_into().ok()))
+    }
+
+    async fn create_collection(&self, new: &NewCollection) -> anyhow::Result<Id<Collection>> {
+        let pool = self.pool.0.clone();
+        let result = query(
+            "INSERT INTO collections (name, sort_order)
+            VALUES (?, (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM collections))",
+        )
+        .bind(new.name.clone())
+        .execute(&*pool)
+        .await?;
+        Ok(Id::new(result.last_insert_rowid() as i32))
+    }
+
+    async fn update_collection_name(&self, id: &Id<Collection>, name: &str) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("UPDATE collections SET name = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(name)
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_collections_order(&self, ids: &[Id<Collection>]) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        // 途中で失敗しても並び順が混ざらないよう、まとめて反映する
+        let mut tx = pool.begin().await?;
+        for (sort_order, id) in ids.iter().enumerate() {
+            let updated = query("UPDATE collections SET sort_order = ? WHERE id = ?")
+                .bind(sort_order as i32)
+                .bind(id.value)
+                .execute(&mut tx)
+                .await?
+                .rows_affected();
+            if updated == 0 {
+                anyhow::bail!("collection {} does not exist", id.value);
+            }
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn delete_collection(&self, id: &Id<Collection>) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        // collection_element_maps は ON DELETE CASCADE で削除される
+        query("DELETE FROM collections WHERE id = ?")
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_element_ids_by_collection_id(
+        &self,
+        id: &Id<Collection>,
+    ) -> anyhow::Result<Vec<Id<CollectionElement>>> {
+        let pool = self.pool.0.clone();
+        let ids: Vec<(i32,)> = sqlx::query_as(
+            "SELECT collection_element_id FROM collection_element_maps
+            WHERE collection_id = ?
+            ORDER BY order_index ASC, id ASC",
+        )
+        .bind(id.value)
+        .fetch_all(&*pool)
+        .await?;
+        Ok(ids.into_iter().map(|v| Id::new(v.0)).collect())
+    }
+
+    async fn add_collection_elements(
+        &self,
+        id: &Id<Collection>,
+        element_ids: &[Id<CollectionElement>],
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        for element_id in element_ids.iter() {
+            query(
+                "INSERT OR IGNORE INTO collection_element_maps
+                    (collection_id, collection_element_id, order_index)
+                VALUES (?, ?, (
+                    SELECT COALESCE(MAX(order_index) + 1, 0)
+                    FROM collection_element_maps WHERE collection_id = ?
+                ))",
+            )
+            .bind(id.value)
+            .bind(element_id.value)
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        }
+        query("UPDATE collections SET updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_collection_elements(
+        &self,
+        id: &Id<Collection>,
+        element_ids: &[Id<CollectionElement>],
+    ) -> anyhow::Result<()> {
+        if element_ids.is_empty() {
+            return Ok(());
+        }
+        let pool = self.pool.0.clone();
+        let mut builder =
+            QueryBuilder::new("DELETE FROM collection_element_maps WHERE collection_id = ");
+        builder.push_bind(id.value);
+        builder.push(" AND collection_element_id IN (");
+        let mut separated = builder.separated(", ");
+        for element_id in element_ids.iter() {
+            separated.push_bind(element_id.value);
+        }
+        separated.push_unseparated(")");
+        builder.build().execute(&*pool).await?;
+
+        query("UPDATE collections SET updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn update_collection_elements_order(
+        &self,
+        id: &Id<Collection>,
+        element_ids: &[Id<CollectionElement>],
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        // 途中で失敗しても並び順が混ざらないよう、まとめて反映する
+        let mut tx = pool.begin().await?;
+        for (order_index, element_id) in element_ids.iter().enumerate() {
+            query(
+                "UPDATE collection_element_maps SET order_index = ?, updated_at = CURRENT_TIMESTAMP
+                WHERE collection_id = ? AND collection_element_id = ?",
+            )
+            .bind(order_index as i32)
+            .bind(id.value)
+            .bind(element_id.value)
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_all_smart_collections(&self) -> anyhow::Result<Vec<SmartCollection>> {
+        let pool = self.pool.0.clone();
+        let records = query_as::<_, SmartCollectionTable>(
+            "SELECT id, name, filter_json, created_at, updated_at
+            FROM smart_collections
+            ORDER BY id ASC",
+        )
+        .fetch_all(&*pool)
+        .await?;
+        Ok(records.into_iter().flat_map(|v| v.try_into()).collect())
+    }
+
+    async fn get_smart_collection_by_id(
+        &self,
+        id: &Id<SmartCollection>,
+    ) -> anyhow::Result<Option<SmartCollection>> {
+        let pool = self.pool.0.clone();
+        let record = query_as::<_, SmartCollectionTable>(
+            "SELECT id, name, filter_json, created_at, updated_at
+            FROM smart_collections
+            WHERE id = ?",
+        )
+        .bind(id.value)
+        .fetch_optional(&*pool)
+        .await?;
+        record.map(|v| v.try_into()).transpose()
+    }
+
+    async fn get_smart_collection_by_name(
+        &self,
+        name: &str,
+    ) -> anyhow::Result<Option<SmartCollection>> {
+        let pool = self.pool.0.clone();
+        let record = query_as::<_, SmartCollectionTable>(
+            "SELECT id, name, filter_json, created_at, updated_at
+            FROM smart_collections
+            WHERE name = ?",
+        )
+        .bind(name)
+        .fetch_optional(&*pool)
+        .await?;
+        record.map(|v| v.try_into()).transpose()
+    }
+
+    async fn create_smart_collection(
+        &self,
+        new: &NewSmartCollection,
+    ) -> anyhow::Result<Id<SmartCollection>> {
+        let pool = self.pool.0.clone();
+        let result = query("INSERT INTO smart_collections (name, filter_json) VALUES (?, ?)")
+            .bind(new.name.clone())
+            .bind(serde_json::to_string(&new.filter)?)
+            .execute(&*pool)
+            .await?;
+        Ok(Id::new(result.last_insert_rowid() as i32))
+    }
+
+    async fn update_smart_collection(
+        &self,
+        id: &Id<SmartCollection>,
+        new: &NewSmartCollection,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query(
+            "UPDATE smart_collections SET name = ?, filter_json = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?",
+        )
+        .bind(new.name.clone())
+        .bind(serde_json::to_string(&new.filter)?)
+        .bind(id.value)
+        .execute(&*pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_smart_collection(&self, id: &Id<SmartCollection>) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("DELETE FROM smart_collections WHERE id = ?")
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_launch_targets_by_element_id(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Vec<LaunchTarget>> {
+        let pool = self.pool.0.clone();
+        let records = query_as::<_, LaunchTargetTable>(
+            "SELECT id, collection_element_id, name, path, is_default, sort_order
+            FROM collection_element_launch_targets
+            WHERE collection_element_id = ?
+            ORDER BY sort_order ASC, id ASC",
+        )
+        .bind(element_id.value)
+        .fetch_all(&*pool)
+        .await?;
+        Ok(records.into_iter().flat_map(|v| v.try_into()).collect())
+    }
+
+    async fn get_launch_target_by_id(
+        &self,
+        id: &Id<LaunchTarget>,
+    ) -> anyhow::Result<Option<LaunchTarget>> {
+        let pool = self.pool.0.clone();
+        let record = query_as::<_, LaunchTargetTable>(
+            "SELECT id, collection_element_id, name, path, is_default, sort_order
+            FROM collection_element_launch_targets
+            WHERE id = ?",
+        )
+        .bind(id.value)
+        .fetch_optional(&*pool)
+        .await?;
+        Ok(record.and_then(|v| v.try_into().ok()))
+    }
+
+    async fn create_launch_target(
+        &self,
+        new: &NewLaunchTarget,
+    ) -> anyhow::Result<Id<LaunchTarget>> {
+        let pool = self.pool.0.clone();
+        let result = query(
+            "INSERT INTO collection_element_launch_targets
+                (collection_element_id, name, path, sort_order)
+            VALUES (?, ?, ?, (
+                SELECT COALESCE(MAX(sort_order) + 1, 0)
+                FROM collection_element_launch_targets WHERE collection_element_id = ?
+            ))",
+        )
+        .bind(new.collection_element_id.value)
+        .bind(new.name.clone())
+        .bind(new.path.clone())
+        .bind(new.collection_element_id.value)
+        .execute(&*pool)
+        .await?;
+        Ok(Id::new(result.last_insert_rowid() as i32))
+    }
+
+    async fn update_launch_target(
+        &self,
+        id: &Id<LaunchTarget>,
+        name: &str,
+        path: &str,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query(
+            "UPDATE collection_element_launch_targets
+            SET name = ?, path = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?",
+        )
+        .bind(name)
+        .bind(path)
+        .bind(id.value)
+        .execute(&*pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_launch_target(&self, id: &Id<LaunchTarget>) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("DELETE FROM collection_element_launch_targets WHERE id = ?")
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn set_default_launch_target(
+        &self,
+        element_id: &Id<CollectionElement>,
+        id: Option<&Id<LaunchTarget>>,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        let mut tx = pool.begin().await?;
+        query(
+            "UPDATE collection_element_launch_targets SET is_default = 0
+            WHERE collection_element_id = ? AND is_default != 0",
+        )
+        .bind(element_id.value)
+        .execute(&mut tx)
+        .await?;
+        if let Some(id) = id {
+            query(
+                "UPDATE collection_element_launch_targets SET is_default = 1
+                WHERE id = ? AND collection_element_id = ?",
+            )
+            .bind(id.value)
+            .bind(element_id.value)
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_launch_profile(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Option<LaunchProfile>> {
+        let pool = self.pool.0.clone();
+        let record = query_as::<_, LaunchProfileTable>(
+            "SELECT arguments_json, working_directory, environment_json, wrapper_command
+            FROM collection_element_launch_profiles
+            WHERE collection_element_id = ?",
+        )
+        .bind(element_id.value)
+        .fetch_optional(&*pool)
+        .await?;
+        record.map(|v| v.try_into()).transpose()
+    }
+
+    async fn upsert_launch_profile(
+        &self,
+        element_id: &Id<CollectionElement>,
+        profile: &LaunchProfile,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query(
+            "INSERT INTO collection_element_launch_profiles
+            (collection_element_id, arguments_json, working_directory, environment_json, wrapper_command)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(collection_element_id) DO UPDATE SET
+            arguments_json = excluded.arguments_json,
+            working_directory = excluded.working_directory,
+            environment_json = excluded.environment_json,
+            wrapper_command = excluded.wrapper_command,
+            updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(element_id.value)
+        .bind(serde_json::to_string(&profile.arguments)?)
+        .bind(&profile.working_directory)
+        .bind(serde_json::to_string(&profile.environment)?)
+        .bind(&profile.wrapper_command)
+        .execute(&*pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_launch_profile(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("DELETE FROM collection_element_launch_profiles WHERE collection_element_id = ?")
+            .bind(element_id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_launch_hooks(
+        &self,
+        element_id: Option<&Id<CollectionElement>>,
+    ) -> anyhow::Result<Vec<LaunchHook>> {
+        let pool = self.pool.0.clone();
+        let records = query_as::<_, LaunchHookTable>(
+            "SELECT id, collection_element_id, timing, name, command, timeout_seconds, cancel_launch_on_failure, sort_order
+            FROM launch_hooks
+            WHERE collection_element_id IS ?
+            ORDER BY sort_order, id",
+        )
+        .bind(element_id.map(|v| v.value))
+        .fetch_all(&*pool)
+        .await?;
+        records.into_iter().map(|v| v.try_into()).collect()
+    }
+
+    async fn get_launch_hooks_for_element(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Vec<LaunchHook>> {
+        let pool = self.pool.0.clone();
+        let records = query_as::<_, LaunchHookTable>(
+            "SELECT id, collection_element_id, timing, name, command, timeout_seconds, cancel_launch_on_failure, sort_order
+            FROM launch_hooks
+            WHERE collection_element_id IS NULL OR collection_element_id = ?
+            ORDER BY sort_order, id",
+        )
+        .bind(element_id.value)
+        .fetch_all(&*pool)
+        .await?;
+        records.into_iter().map(|v| v.try_into()).collect()
+    }
+
+    async fn get_launch_hook_by_id(
+        &self,
+        id: &Id<LaunchHook>,
+    ) -> anyhow::Result<Option<LaunchHook>> {
+        let pool = self.pool.0.clone();
+        let record = query_as::<_, LaunchHookTable>(
+            "SELECT id, collection_element_id, timing, name, command, timeout_seconds, cancel_launch_on_failure, sort_order
+            FROM launch_hooks
+            WHERE id = ?",
+        )
+        .bind(id.value)
+        .fetch_optional(&*pool)
+        .await?;
+        record.map(|v| v.try_into()).transpose()
+    }
+
+    async fn create_launch_hook(&self, new: &NewLaunchHook) -> anyhow::Result<Id<LaunchHook>> {
+        let pool = self.pool.0.clone();
+        let result = query(
+            "INSERT INTO launch_hooks
+            (collection_element_id, timing, name, command, timeout_seconds, cancel_launch_on_failure, sort_order)
+            SELECT ?, ?, ?, ?, ?, ?, COALESCE(MAX(sort_order) + 1, 0)
+            FROM launch_hooks WHERE collection_element_id IS ?",
+        )
+        .bind(new.collection_element_id.as_ref().map(|v| v.value))
+        .bind(new.timing.as_str())
+        .bind(&new.name)
+        .bind(&new.command)
+        .bind(new.timeout_seconds as i32)
+        .bind(new.cancel_launch_on_failure as i32)
+        .bind(new.collection_element_id.as_ref().map(|v| v.value))
+        .execute(&*pool)
+        .await?;
+        Ok(Id::new(result.last_insert_rowid() as i32))
+    }
+
+    async fn update_launch_hook(
+        &self,
+        id: &Id<LaunchHook>,
+        new: &NewLaunchHook,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query(
+            "UPDATE launch_hooks
+            SET timing = ?, name = ?, command = ?, timeout_seconds = ?, cancel_launch_on_failure = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?",
+        )
+        .bind(new.timing.as_str())
+        .bind(&new.name)
+        .bind(&new.command)
+        .bind(new.timeout_seconds as i32)
+        .bind(new.cancel_launch_on_failure as i32)
+        .bind(id.value)
+        .execute(&*pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_launch_hook(&self, id: &Id<LaunchHook>) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("DELETE FROM launch_hooks WHERE id = ?")
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn create_play_session(&self, new: &NewPlaySession) -> anyhow::Result<Id<PlaySession>> {
+        let pool = self.pool.0.clone();
+        let result = query(
+            "INSERT INTO play_sessions (collection_element_id, started_at, pid, exe_path)
+            VALUES (?, ?, ?, ?)",
+        )
+        .bind(new.collection_element_id.value)
+        .bind(new.started_at.naive_utc())
+        .bind(new.pid.map(|v| v as i64))
+        .bind(&new.exe_path)
+        .execute(&*pool)
+        .await?;
+        Ok(Id::new(result.last_insert_rowid() as i32))
+    }
+
+    async fn update_play_session_progress(
+        &self,
+        id: &Id<PlaySession>,
+        played_seconds: i32,
+        paused_seconds: i32,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query(
+            "UPDATE play_sessions
+            SET played_seconds = ?, paused_seconds = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?",
+        )
+        .bind(played_seconds)
+        .bind(paused_seconds)
+        .bind(id.value)
+        .execute(&*pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn add_play_session_played_range(
+        &self,
+        id: &Id<PlaySession>,
+        range: &PlayedRange,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        let extended = query(
+            "UPDATE play_session_played_ranges SET ended_at = ?
+            WHERE play_session_id = ? AND ended_at = ?",
+        )
+        .bind(range.1.naive_utc())
+        .bind(id.value)
+        .bind(range.0.naive_utc())
+        .execute(&*pool)
+        .await?
+        .rows_affected();
+        if extended == 0 {
+            query(
+                "INSERT INTO play_session_played_ranges (play_session_id, started_at, ended_at)
+                VALUES (?, ?, ?)",
+            )
+            .bind(id.value)
+            .bind(range.0.naive_utc())
+            .bind(range.1.naive_utc())
+            .execute(&*pool)
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn finish_play_session(
+        &self,
+        id: &Id<PlaySession>,
+        ended_at: DateTime<Local>,
+        played_seconds: i32,
+        paused_seconds: i32,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query(
+            "UPDATE play_sessions
+            SET ended_at = ?, played_seconds = ?, paused_seconds = ?, updated_at = CURRENT_TIMESTAMP
+            WHERE id = ?",
+        )
+        .bind(ended_at.naive_utc())
+        .bind(played_seconds)
+        .bind(paused_seconds)
+        .bind(id.value)
+        .execute(&*pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_play_sessions_by_element_id(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Vec<PlaySession>> {
+        let pool = self.pool.0.clone();
+        let records = query_as::<_, PlaySessionTable>(
+            "SELECT id, collection_element_id, started_at, ended_at, pid, exe_path, played_seconds, paused_seconds
+            FROM play_sessions
+            WHERE collection_element_id = ?
+            ORDER BY started_at DESC, id DESC",
+        )
+        .bind(element_id.value)
+        .fetch_all(&*pool)
+        .await?;
+        let mut sessions = records
+            .into_iter()
+            .map(|v| v.try_into())
+            .collect::<anyhow::Result<Vec<PlaySession>>>()?;
+        attach_played_ranges(&pool, &mut sessions).await?;
+        Ok(sessions)
+    }
+
+    async fn get_play_sessions_by_range(
+        &self,
+        element_id: Option<&Id<CollectionElement>>,
+        since: DateTime<Local>,
+        until: DateTime<Local>,
+    ) -> anyhow::Result<Vec<PlaySession>> {
+        let pool = self.pool.0.clone();
+        let mut builder = QueryBuilder::new(
+            "SELECT id, collection_element_id, started_at, ended_at, pid, exe_path, played_seconds, paused_seconds
+            FROM play_sessions
+            WHERE started_at < ",
+        );
+        builder.push_bind(until.naive_utc());
+        builder.push(" AND COALESCE(ended_at, started_at) >= ");
+        builder.push_bind(since.naive_utc());
+        if let Some(element_id) = element_id {
+            builder.push(" AND collection_element_id = ");
+            builder.push_bind(element_id.value);
+        }
+        builder.push(" ORDER BY started_at, id");
+        let records = builder
+            .build_query_as::<PlaySessionTable>()
+            .fetch_all(&*pool)
+            .await?;
+        let mut sessions = records
+            .into_iter()
+            .map(|v| v.try_into())
+            .collect::<anyhow::Result<Vec<PlaySession>>>()?;
+        attach_played_ranges(&pool, &mut sessions).await?;
+        Ok(sessions)
+    }
+    async fn get_finished_play_sessions(&self) -> anyhow::Result<Vec<PlaySession>> {
+        let pool = self.pool.0.clone();
+        let records = query_as::<_, PlaySessionTable>(
+            "SELECT id, collection_element_id, started_at, ended_at, pid, exe_path, played_seconds, paused_seconds
+            FROM play_sessions
+            WHERE ended_at IS NOT NULL
+            ORDER BY started_at, id",
+        )
+        .fetch_all(&*pool)
+        .await?;
+        let mut sessions = records
+            .into_iter()
+            .map(|v| v.try_into())
+            .collect::<anyhow::Result<Vec<PlaySession>>>()?;
+        attach_played_ranges(&pool, &mut sessions).await?;
+        Ok(sessions)
+    }
+
+    async fn import_legacy_play_history(
+        &self,
+        id: &Id<CollectionElement>,
+        record_count: i32,
+        daily_play_times: &[(NaiveDate, i32)],
+        first_play_at: Option<DateTime<Local>>,
+        last_play_at: Option<DateTime<Local>>,
+    ) -> anyhow::Result<bool> {
+        let pool = self.pool.0.clone();
+        let mut tx = pool.begin().await?;
+        let imported_seconds: i64 = daily_play_times.iter().map(|(_, v)| *v as i64).sum();
+        let inserted = query(
+            "INSERT INTO legacy_play_history_imports (collection_element_id, record_count, imported_seconds)
+            VALUES (?, ?, ?)
+            ON CONFLICT(collection_element_id) DO NOTHING",
+        )
+        .bind(id.value)
+        .bind(record_count)
+        .bind(imported_seconds)
+        .execute(&mut tx)
+        .await?
+        .rows_affected();
+        if inserted == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        let deltas: Vec<(Id<CollectionElement>, NaiveDate, i32)> = daily_play_times
+            .iter()
+            .map(|(play_date, seconds)| (id.clone(), *play_date, *seconds))
+            .collect();
+        apply_daily_play_time_deltas_in_tx(&mut tx, &deltas).await?;
+        // MIN / MAX は NULL を含むと NULL になるので、片方しかない場合は COALESCE で残す
+        query(
+            "UPDATE collection_elements SET
+                total_play_time_seconds = MAX(
+                    total_play_time_seconds,
+                    (SELECT COALESCE(SUM(play_time_seconds), 0)
+                    FROM collection_element_daily_play_times
+                    WHERE collection_element_id = ?1)
+                ),
+                first_play_at = COALESCE(MIN(first_play_at, ?2), first_play_at, ?2),
+                last_play_at = COALESCE(MAX(last_play_at, ?3), last_play_at, ?3)
+            WHERE id = ?1",
+        )
+        .bind(id.value)
+        .bind(first_play_at.map(|v| v.naive_utc()))
+        .bind(last_play_at.map(|v| v.naive_utc()))
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn get_external_play_time_imports(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Vec<ExternalPlayTimeImport>> {
+        let pool = self.pool.0.clone();
+        let records = query_as::<_, ExternalPlayTimeImportTable>(
+            "SELECT source, external_id, collection_element_id, imported_seconds, last_session_ended_at
+            FROM external_play_time_imports
+            WHERE collection_element_id = ?",
+        )
+        .bind(element_id.value)
+        .fetch_all(&*pool)
+        .await?;
+        records.into_iter().map(|v| v.try_into()).collect()
+    }
+
+    async fn apply_external_play_time_import(
+        &self,
+        import: &ExternalPlayTimeImport,
+        imported_seconds: i64,
+        sessions: &[(DateTime<Local>, DateTime<Local>)],
+        daily_play_times: &[(NaiveDate, i32)],
+        first_play_at: Option<DateTime<Local>>,
+        last_play_at: Option<DateTime<Local>>,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        let mut tx = pool.begin().await?;
+        query(
+            "INSERT INTO external_play_time_imports (source, external_id, collection_element_id, imported_seconds, last_session_ended_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(source, external_id) DO UPDATE SET
+                collection_element_id = excluded.collection_element_id,
+                imported_seconds = excluded.imported_seconds,
+                last_session_ended_at = excluded.last_session_ended_at,
+                imported_at = CURRENT_TIMESTAMP",
+        )
+        .bind(import.source.as_str())
+        .bind(&import.external_id)
+        .bind(import.collection_element_id.value)
+        .bind(import.imported_seconds)
+        .bind(import.last_session_ended_at.map(|v| v.naive_utc()))
+        .execute(&mut tx)
+        .await?;
+
+        for (started_at, ended_at) in sessions {
+            query(
+                "INSERT INTO play_sessions (collection_element_id, started_at, ended_at, played_seconds, paused_seconds)
+                VALUES (?, ?, ?, ?, 0)",
+            )
+            .bind(import.collection_element_id.value)
+            .bind(started_at.naive_utc())
+            .bind(ended_at.naive_utc())
+            .bind((*ended_at - *started_at).num_seconds() as i32)
+            .execute(&mut tx)
+            .await?;
+        }
+
+        let deltas: Vec<(Id<CollectionElement>, NaiveDate, i32)> = daily_play_times
+            .iter()
+            .map(|(play_date, seconds)| {
+                (import.collection_element_id.clone(), *play_date, *seconds)
+            })
+            .collect();
+        apply_daily_play_time_deltas_in_tx(&mut tx, &deltas).await?;
+        query(
+            "UPDATE collection_elements SET
+                total_play_time_seconds = total_play_time_seconds + ?2,
+                first_play_at = COALESCE(MIN(first_play_at, ?3), first_play_at, ?3),
+                last_play_at = COALESCE(MAX(last_play_at, ?4), last_play_at, ?4)
+            WHERE id = ?1",
+        )
+        .bind(import.collection_element_id.value)
+        .bind(imported_seconds)
+        .bind(first_play_at.map(|v| v.naive_utc()))
+        .bind(last_play_at.map(|v| v.naive_utc()))
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_play_session_by_id(
+        &self,
+        id: &Id<PlaySession>,
+    ) -> anyhow::Result<Option<PlaySession>> {
+        let pool = self.pool.0.clone();
+        let record = query_as::<_, PlaySessionTable>(
+            "SELECT id, collection_element_id, started_at, ended_at, pid, exe_path, played_seconds, paused_seconds
+            FROM play_sessions
+            WHERE id = ?",
+        )
+        .bind(id.value)
+        .fetch_optional(&*pool)
+        .await?;
+        let Some(record) = record else {
+            return Ok(None);
+        };
+        let mut sessions = vec![record.try_into()?];
+        attach_played_ranges(&pool, &mut sessions).await?;
+        Ok(sessions.pop())
+    }
+
+    async fn apply_play_session_edit(
+        &self,
+        edit: &PlaySessionEdit,
+    ) -> anyhow::Result<Id<PlaySessionAudit>> {
+        let pool = self.pool.0.clone();
+        let mut tx = pool.begin().await?;
+
+        for session in edit.removed.iter() {
+            query("DELETE FROM play_sessions WHERE id = ?")
+                .bind(session.id.value)
+                .execute(&mut tx)
+                .await?;
+        }
+
+        let mut added = Vec::with_capacity(edit.added.len());
+        for session in edit.added.iter() {
+            let result = query(
+                "INSERT INTO play_sessions (id, collection_element_id, started_at, ended_at, pid, exe_path, played_seconds, paused_seconds)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(session.restore_id.as_ref().map(|v| v.value))
+            .bind(session.collection_element_id.value)
+            .bind(session.started_at.naive_utc())
+            .bind(session.ended_at.naive_utc())
+            .bind(session.pid.map(|v| v as i64))
+            .bind(&session.exe_path)
+            .bind(session.played_seconds)
+            .bind(session.paused_seconds)
+            .execute(&mut tx)
+            .await?;
+            let id = Id::new(result.last_insert_rowid() as i32);
+            for (started_at, ended_at) in session.played_ranges.iter() {
+                query(
+                    "INSERT INTO play_session_played_ranges (play_session_id, started_at, ended_at)
+                    VALUES (?, ?, ?)",
+                )
+                .bind(id.value)
+                .bind(started_at.naive_utc())
+                .bind(ended_at.naive_utc())
+                .execute(&mut tx)
+                .await?;
+            }
+            added.push(session.to_play_session(id));
+        }
+
+        for (id, seconds) in edit.play_time_deltas.iter() {
+            query(
+                "UPDATE collection_elements
+                SET total_play_time_seconds = MAX(0, total_play_time_seconds + ?)
+                WHERE id = ?",
+            )
+            .bind(seconds)
+            .bind(id.value)
+            .execute(&mut tx)
+            .await?;
+        }
+        apply_daily_play_time_deltas_in_tx(&mut tx, &edit.daily_play_time_deltas).await?;
+        for (id, first_play_at, last_play_at) in edit.play_at.iter() {
+            query(
+                "UPDATE collection_elements SET first_play_at = ?, last_play_at = ? WHERE id = ?",
+            )
+            .bind(first_play_at.map(|v| v.naive_utc()))
+            .bind(last_play_at.map(|v| v.naive_utc()))
+            .bind(id.value)
+            .execute(&mut tx)
+            .await?;
+        }
+
+        let result = query(
+            "INSERT INTO play_session_audits (action, before_json, after_json, undo_of)
+            VALUES (?, ?, ?, ?)",
+        )
+        .bind(edit.action.as_str())
+        .bind(serde_json::to_string(&edit.removed)?)
+        .bind(serde_json::to_string(&added)?)
+        .bind(edit.undo_of.as_ref().map(|v| v.value))
+        .execute(&mut tx)
+        .await?;
+        if let Some(undo_of) = edit.undo_of.as_ref() {
+            query("UPDATE play_session_audits SET undone_at = CURRENT_TIMESTAMP WHERE id = ?")
+                .bind(undo_of.value)
+                .execute(&mut tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Id::new(result.last_insert_rowid() as i32))
+    }
+
+    async fn get_play_session_audits(&self, limit: i32) -> anyhow::Result<Vec<PlaySessionAudit>> {
+        let pool = self.pool.0.clone();
+        let records = query_as::<_, PlaySessionAuditTable>(
+            "SELECT id, action, before_json, after_json, undo_of, created_at, undone_at
+            FROM play_session_audits
+            ORDER BY id DESC
+            LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&*pool)
+        .await?;
+        records.into_iter().map(|v| v.try_into()).collect()
+    }
+
+    async fn get_play_session_audit_by_id(
+        &self,
+        id: &Id<PlaySessionAudit>,
+    ) -> anyhow::Result<Option<PlaySessionAudit>> {
+        let pool = self.pool.0.clone();
+        let record = query_as::<_, PlaySessionAuditTable>(
+            "SELECT id, action, before_json, after_json, undo_of, created_at, undone_at
+            FROM play_session_audits
+            WHERE id = ?",
+        )
+        .bind(id.value)
+        .fetch_optional(&*pool)
+        .await?;
+        record.map(|v| v.try_into()).transpose()
+    }
+
+    async fn get_tracking_checkpoints(&self) -> anyhow::Result<Vec<TrackingCheckpoint>> {
+        let pool = self.pool.0.clone();
+        let records = query_as::<_, TrackingCheckpointTable>(
+            "SELECT collection_element_id, play_session_id, pid, process_started_at, game_path, game_name, started_at, heartbeat_at, played_seconds, paused_seconds, is_paused
+            FROM tracking_checkpoints
+            ORDER BY started_at",
+        )
+        .fetch_all(&*pool)
+        .await?;
+        records.into_iter().map(|v| v.try_into()).collect()
+    }
+
+    async fn upsert_tracking_checkpoint(
+        &self,
+        checkpoint: &TrackingCheckpoint,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query(
+            "INSERT INTO tracking_checkpoints (collection_element_id, play_session_id, pid, process_started_at, game_path, game_name, started_at, heartbeat_at, played_seconds, paused_seconds, is_paused)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(collection_element_id) DO UPDATE SET
+                play_session_id = excluded.play_session_id,
+                pid = excluded.pid,
+                process_started_at = excluded.process_started_at,
+                game_path = excluded.game_path,
+                game_name = excluded.game_name,
+                started_at = excluded.started_at,
+                heartbeat_at = excluded.heartbeat_at,
+                played_seconds = excluded.played_seconds,
+                paused_seconds = excluded.paused_seconds,
+                is_paused = excluded.is_paused",
+        )
+        .bind(checkpoint.collection_element_id.value)
+        .bind(checkpoint.play_session_id.as_ref().map(|v| v.value))
+        .bind(checkpoint.pid as i64)
+        .bind(checkpoint.process_started_at)
+        .bind(&checkpoint.game_path)
+        .bind(&checkpoint.game_name)
+        .bind(checkpoint.started_at.naive_utc())
+        .bind(checkpoint.heartbeat_at.naive_utc())
+        .bind(checkpoint.played_seconds)
+        .bind(checkpoint.paused_seconds)
+        .bind(checkpoint.is_paused as i32)
+        .execute(&*pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_tracking_checkpoint(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("DELETE FROM tracking_checkpoints WHERE collection_element_id = ?")
+            .bind(element_id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+}
+
+/// プレイ日ごとの増減をトランザクション内で反映する
+///
+/// 0 秒未満になる日がある場合は記録と増減が食い違っているので、丸めずにエラーにする
+async fn apply_daily_play_time_deltas_in_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    deltas: &[(Id<CollectionElement>, NaiveDate, i32)],
+) -> anyhow::Result<()> {
+    for (id, play_date, seconds) in deltas {
+        let play_date = play_date.format("%Y-%m-%d").to_string();
+        query(
+            "INSERT INTO collection_element_daily_play_times
+                (collection_element_id, play_date, play_time_seconds)
+            VALUES (?, ?, ?)
+            ON CONFLICT(collection_element_id, play_date) DO UPDATE SET
+                play_time_seconds = play_time_seconds + excluded.play_time_seconds,
+                updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(id.value)
+        .bind(&play_date)
+        .bind(seconds)
+        .execute(&mut *tx)
+        .await?;
+        let play_time_seconds: i64 = query(
+            "SELECT play_time_seconds FROM collection_element_daily_play_times
+            WHERE collection_element_id = ? AND play_date = ?",
+        )
+        .bind(id.value)
+        .bind(&play_date)
+        .fetch_one(&mut *tx)
+        .await?
+        .get("play_time_seconds");
+        if play_time_seconds < 0 {
+            anyhow::bail!(
+                "daily play time of element {} on {} would be negative",
+                id.value,
+                play_date
+            );
+        }
+    }
+    Ok(())
+}
+
+/// セッションにプレイしていた範囲を付ける
+async fn attach_played_ranges(
+    pool: &sqlx::SqlitePool,
+    sessions: &mut [PlaySession],
+) -> anyhow::Result<()> {
+    // SQLite のパラメータ数の上限を超えないよう分けて取得する
+    for chunk in sessions.chunks_mut(500) {
+        let mut builder = QueryBuilder::new(
+            "SELECT play_session_id, started_at, ended_at
+            FROM play_session_played_ranges
+            WHERE play_session_id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for session in chunk.iter() {
+            separated.push_bind(session.id.value);
+        }
+        separated.push_unseparated(") ORDER BY play_session_id, started_at");
+        let rows = builder.build().fetch_all(pool).await?;
+        for row in rows {
+            let id: i32 = row.get("play_session_id");
+            let started_at: NaiveDateTime = row.get("started_at");
+            let ended_at: NaiveDateTime = row.get("ended_at");
+            if let Some(session) = chunk.iter_mut().find(|v| v.id.value == id) {
+                session.played_ranges.push((
+                    started_at.and_utc().with_timezone(&Local),
+                    ended_at.and_utc().with_timezone(&Local),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::repositoryimpl::driver::Db;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    async fn setup_repository() -> RepositoryImpl<CollectionElement> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        for sql in [
+            "CREATE TABLE app_settings (key TEXT PRIMARY KEY, value TEXT)",
+            "CREATE TABLE collections (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                sort_order INTEGER NOT NULL DEFAULT 0
+            )",
+            "INSERT INTO collections (id, name, sort_order) VALUES (1, 'A', 0), (2, 'B', 1)",
+            "CREATE TABLE collection_element_daily_play_times (
+                collection_element_id INTEGER NOT NULL,
+                play_date TEXT NOT NULL,
+                play_time_seconds INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (collection_element_id, play_date)
+            )",
+            "INSERT INTO collection_element_daily_play_times (collection_element_id, play_date, play_time_seconds)
+                VALUES (1, '2026-01-02', 3600)",
+            "CREATE TABLE play_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                collection_element_id INTEGER NOT NULL,
+                started_at DATETIME NOT NULL,
+                ended_at DATETIME,
+                pid INTEGER,
+                exe_path TEXT,
+                played_seconds INTEGER NOT NULL DEFAULT 0,
+                paused_seconds INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            "CREATE TABLE play_session_played_ranges (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                play_session_id INTEGER NOT NULL,
+                started_at DATETIME NOT NULL,
+                ended_at DATETIME NOT NULL
+            )",
+        ] {
+            query(sql).execute(&pool).await.unwrap();
+        }
+
+        RepositoryImpl::new(Db(Arc::new(pool)))
+    }
+
+    fn deltas() -> Vec<(Id<CollectionElement>, NaiveDate, i32)> {
+        vec![
+            (
+                Id::new(1),
+                NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+                3600,
+            ),
+            (
+                Id::new(1),
+                NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(),
+                -3600,
+            ),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_update_day_boundary_applies_deltas_and_setting() {
+        let repository = setup_repository().await;
+        let to = DayBoundary::parse("04:00").unwrap();
+
+        repository
+            .update_day_boundary(DayBoundary::default(), to, &deltas())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            repository
+                .get_app_setting(DAY_BOUNDARY_SETTING_KEY.to_string())
+                .await
+                .unwrap(),
+            Some("04:00".to_string())
+        );
+        let times = repository.get_daily_play_times(&Id::new(1)).await.unwrap();
+        let seconds = |date: &str| {
+            times
+                .iter()
+                .find(|v| v.play_date == date)
+                .map(|v| v.play_time_seconds)
+        };
+        assert_eq!(seconds("2026-01-01"), Some(3600));
+        assert_eq!(seconds("2026-01-02"), None);
+    }
+
+    #[tokio::test]
+    async fn test_update_day_boundary_rejects_stale_boundary() {
+        let repository = setup_repository().await;
+        let to = DayBoundary::parse("04:00").unwrap();
+        repository
+            .update_day_boundary(DayBoundary::default(), to, &deltas())
+            .await
+            .unwrap();
+
+        // 同じ変更をやり直しても二重に振り分けない
+        assert!(repository
+            .update_day_boundary(DayBoundary::default(), to, &deltas())
+            .await
+            .is_err());
+        let times = repository.get_daily_play_times(&Id::new(1)).await.unwrap();
+        assert_eq!(
+            times
+                .iter()
+                .find(|v| v.play_date == "2026-01-01")
+                .map(|v| v.play_time_seconds),
+            Some(3600)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_day_boundary_rejects_negative_daily_play_time() {
+        let repository = setup_repository().await;
+        let to = DayBoundary::parse("04:00").unwrap();
+        let deltas = vec![
+            (
+                Id::new(1),
+                NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+                7200,
+            ),
+            (
+                Id::new(1),
+                NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(),
+                -7200,
+            ),
+        ];
+
+        // 記録より多く減らす場合は 0 に丸めず、区切りも変えない
+        assert!(repository
+            .update_day_boundary(DayBoundary::default(), to, &deltas)
+            .await
+            .is_err());
+        assert_eq!(
+            repository
+                .get_app_setting(DAY_BOUNDARY_SETTING_KEY.to_string())
+                .await
+                .unwrap(),
+            None
+        );
+        let times = repository.get_daily_play_times(&Id::new(1)).await.unwrap();
+        assert_eq!(
+            times
+                .iter()
+                .map(|v| (v.play_date.as_str(), v.play_time_seconds))
+                .collect::<Vec<_>>(),
+            vec![("2026-01-02", 3600)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_play_session_played_range_extends_previous_range() {
+        use chrono::TimeZone;
+
+        let repository = setup_repository().await;
+        let at = |minute: u32| Local.with_ymd_and_hms(2026, 1, 1, 23, minute, 0).unwrap();
+        let id = repository
+            .create_play_session(&NewPlaySession::new(Id::new(1), at(0), Some(100), None))
+            .await
+            .unwrap();
+
+        for range in [(at(0), at(10)), (at(10), at(20)), (at(40), at(50))] {
+            repository
+                .add_play_session_played_range(&id, &range)
+                .await
+                .unwrap();
+        }
+
+        let session = repository
+            .get_play_session_by_id(&id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            session.played_ranges,
+            vec![(at(0), at(20)), (at(40), at(50))]
+        );
+    }
+
+    async fn collection_sort_orders(
+        repository: &RepositoryImpl<CollectionElement>,
+    ) -> Vec<(i32, i32)> {
+        sqlx::query_as("SELECT id, sort_order FROM collections ORDER BY id ASC")
+            .fetch_all(&*repository.pool.0)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_update_collections_order() {
+        let repository = setup_repository().await;
+
+        repository
+            .update_collections_order(&[Id::new(2), Id::new(1)])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            collection_sort_orders(&repository).await,
+            vec![(1, 1), (2, 0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_collections_order_rejects_unknown_id() {
+        let repository = setup_repository().await;
+
+        // 存在しない ID が含まれていれば、それより前の並び順も反映しない
+        assert!(repository
+            .update_collections_order(&[Id::new(2), Id::new(3), Id::new(1)])
+            .await
+            .is_err());
+        assert_eq!(
+            collection_sort_orders(&repository).await,
+            vec![(1, 0), (2, 1)]
+        );
+    }
 }
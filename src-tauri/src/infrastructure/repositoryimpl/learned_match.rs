@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use chrono::Local;
+use sqlx::{query, query_as, types::chrono::NaiveDateTime, QueryBuilder};
+
+use super::repository::RepositoryImpl;
+use crate::domain::{
+    learned_match::{LearnedGameMatch, LearnedGameMatchKey, NewLearnedGameMatch},
+    repository::learned_match::LearnedMatchRepository,
+    Id,
+};
+
+fn to_learned_game_match(
+    (id, parent_folder, file_stem, game_id, updated_at): (i32, String, String, i32, NaiveDateTime),
+) -> LearnedGameMatch {
+    LearnedGameMatch::new(
+        Id::new(id),
+        LearnedGameMatchKey::new(parent_folder, file_stem),
+        game_id,
+        updated_at.and_utc().with_timezone(&Local),
+    )
+}
+
+#[async_trait]
+impl LearnedMatchRepository for RepositoryImpl<LearnedGameMatch> {
+    async fn get_learned_game_matches(&self) -> anyhow::Result<Vec<LearnedGameMatch>> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<(i32, String, String, i32, NaiveDateTime)> = query_as(
+            "SELECT id, parent_folder, file_stem, game_id, updated_at
+            FROM learned_game_matches
+            ORDER BY parent_folder, file_stem",
+        )
+        .fetch_all(&*pool)
+        .await?;
+        Ok(rows.into_iter().map(to_learned_game_match).collect())
+    }
+
+    async fn upsert_learned_game_matches(
+        &self,
+        matches: &[NewLearnedGameMatch],
+        overwrite: bool,
+    ) -> anyhow::Result<u64> {
+        let pool = self.pool.0.clone();
+        let mut affected = 0;
+        for c in matches.chunks(300) {
+            let mut builder = QueryBuilder::new(
+                "INSERT INTO learned_game_matches (parent_folder, file_stem, game_id) ",
+            );
+            builder.push_values(c, |mut b, new| {
+                b.push_bind(&new.key.parent_folder)
+                    .push_bind(&new.key.file_stem)
+                    .push_bind(new.game_id);
+            });
+            if overwrite {
+                builder.push(
+                    " ON CONFLICT(parent_folder, file_stem) DO UPDATE SET
+                        game_id = excluded.game_id,
+                        updated_at = CURRENT_TIMESTAMP
+                    WHERE game_id != excluded.game_id",
+                );
+            } else {
+                builder.push(" ON CONFLICT(parent_folder, file_stem) DO NOTHING");
+            }
+            affected += builder.build().execute(&*pool).await?.rows_affected();
+        }
+        Ok(affected)
+    }
+
+    async fn delete_learned_game_match(&self, id: &Id<LearnedGameMatch>) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("DELETE FROM learned_game_matches WHERE id = ?")
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::repositoryimpl::driver::Db;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    fn new_match(parent_folder: &str, file_stem: &str, game_id: i32) -> NewLearnedGameMatch {
+        NewLearnedGameMatch::new(
+            LearnedGameMatchKey::new(parent_folder.to_string(), file_stem.to_string()),
+            game_id,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_upsert_learned_game_matches() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE learned_game_matches (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                parent_folder TEXT NOT NULL,
+                file_stem TEXT NOT NULL,
+                game_id INTEGER NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                UNIQUE (parent_folder, file_stem)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let repository: RepositoryImpl<LearnedGameMatch> = RepositoryImpl::new(Db(Arc::new(pool)));
+
+        let affected = repository
+            .upsert_learned_game_matches(
+                &[
+                    new_match("gamea", "launcher", 1),
+                    new_match("gameb", "start", 2),
+                ],
+                true,
+            )
+            .await
+            .unwrap();
+        assert_eq!(affected, 2);
+
+        // 読み込みでは手元の紐づけを残す
+        let affected = repository
+            .upsert_learned_game_matches(
+                &[
+                    new_match("gamea", "launcher", 10),
+                    new_match("gamec", "game", 3),
+                ],
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(affected, 1);
+
+        // 直した場合は上書きし、同じ紐づけは数えない
+        let affected = repository
+            .upsert_learned_game_matches(
+                &[
+                    new_match("gameb", "start", 20),
+                    new_match("gamec", "game", 3),
+                ],
+                true,
+            )
+            .await
+            .unwrap();
+        assert_eq!(affected, 1);
+
+        let matches = repository.get_learned_game_matches().await.unwrap();
+        let game_ids: Vec<_> = matches.iter().map(|v| v.game_id).collect();
+        assert_eq!(game_ids, vec![1, 20, 3]);
+
+        repository
+            .delete_learned_game_match(&matches[0].id)
+            .await
+            .unwrap();
+        assert_eq!(
+            repository.get_learned_game_matches().await.unwrap().len(),
+            2
+        );
+    }
+}
@@ -0,0 +1,368 @@
+use async_trait::async_trait;
+use chrono::Local;
+use sqlx::{query, query_as, types::chrono::NaiveDateTime, QueryBuilder};
+
+use super::repository::RepositoryImpl;
+use crate::domain::{
+    collection::CollectionElement,
+    library::{LibraryRoot, LibrarySuggestion, NewLibrarySuggestion, RemovedInstall},
+    repository::library::LibraryRepository,
+    Id,
+};
+
+type LibrarySuggestionRow = (i32, i32, String, i32, String, f64, NaiveDateTime);
+
+fn to_library_root((id, path, created_at): (i32, String, NaiveDateTime)) -> LibraryRoot {
+    LibraryRoot::new(
+        Id::new(id),
+        path,
+        created_at.and_utc().with_timezone(&Local),
+    )
+}
+
+fn to_library_suggestion(
+    (id, library_root_id, file_path, game_id, gamename, confidence, created_at): LibrarySuggestionRow,
+) -> LibrarySuggestion {
+    LibrarySuggestion::new(
+        Id::new(id),
+        Id::new(library_root_id),
+        file_path,
+        game_id,
+        gamename,
+        confidence as f32,
+        created_at.and_utc().with_timezone(&Local),
+    )
+}
+
+#[async_trait]
+impl LibraryRepository for RepositoryImpl<LibraryRoot> {
+    async fn get_library_roots(&self) -> anyhow::Result<Vec<LibraryRoot>> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<(i32, String, NaiveDateTime)> =
+            query_as("SELECT id, path, created_at FROM library_roots ORDER BY path")
+                .fetch_all(&*pool)
+                .await?;
+        Ok(rows.into_iter().map(to_library_root).collect())
+    }
+
+    async fn get_library_root_by_path(&self, path: &str) -> anyhow::Result<Option<LibraryRoot>> {
+        let pool = self.pool.0.clone();
+        let row: Option<(i32, String, NaiveDateTime)> =
+            query_as("SELECT id, path, created_at FROM library_roots WHERE path = ?")
+                .bind(path)
+                .fetch_optional(&*pool)
+                .await?;
+        Ok(row.map(to_library_root))
+    }
+
+    async fn add_library_root(&self, path: &str) -> anyhow::Result<Id<LibraryRoot>> {
+        let pool = self.pool.0.clone();
+        let result = query("INSERT INTO library_roots (path) VALUES (?)")
+            .bind(path)
+            .execute(&*pool)
+            .await?;
+        Ok(Id::new(result.last_insert_rowid() as i32))
+    }
+
+    async fn delete_library_root(&self, id: &Id<LibraryRoot>) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        let mut tx = pool.begin().await?;
+        query("DELETE FROM library_suggestions WHERE library_root_id = ?")
+            .bind(id.value)
+            .execute(&mut tx)
+            .await?;
+        query("DELETE FROM library_roots WHERE id = ?")
+            .bind(id.value)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_library_suggestions(&self) -> anyhow::Result<Vec<LibrarySuggestion>> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<LibrarySuggestionRow> = query_as(
+            "SELECT id, library_root_id, file_path, game_id, gamename, confidence, created_at
+            FROM library_suggestions
+            ORDER BY created_at DESC, id DESC",
+        )
+        .fetch_all(&*pool)
+        .await?;
+        Ok(rows.into_iter().map(to_library_suggestion).collect())
+    }
+
+    async fn get_library_suggestion_by_id(
+        &self,
+        id: &Id<LibrarySuggestion>,
+    ) -> anyhow::Result<Option<LibrarySuggestion>> {
+        let pool = self.pool.0.clone();
+        let row: Option<LibrarySuggestionRow> = query_as(
+            "SELECT id, library_root_id, file_path, game_id, gamename, confidence, created_at
+            FROM library_suggestions
+            WHERE id = ?",
+        )
+        .bind(id.value)
+        .fetch_optional(&*pool)
+        .await?;
+        Ok(row.map(to_library_suggestion))
+    }
+
+    async fn upsert_library_suggestions(
+        &self,
+        suggestions: &[NewLibrarySuggestion],
+    ) -> anyhow::Result<()> {
+        if suggestions.is_empty() {
+            return Ok(());
+        }
+        let pool = self.pool.0.clone();
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO library_suggestions (library_root_id, file_path, game_id, gamename, confidence) ",
+        );
+        builder.push_values(suggestions, |mut b, suggestion| {
+            b.push_bind(suggestion.library_root_id.value)
+                .push_bind(&suggestion.file_path)
+                .push_bind(suggestion.game_id)
+                .push_bind(&suggestion.gamename)
+                .push_bind(suggestion.confidence as f64);
+        });
+        builder.push(
+            " ON CONFLICT(file_path) DO UPDATE SET
+                library_root_id = excluded.library_root_id,
+                game_id = excluded.game_id,
+                gamename = excluded.gamename,
+                confidence = excluded.confidence",
+        );
+        builder.build().execute(&*pool).await?;
+        Ok(())
+    }
+
+    async fn delete_library_suggestion(&self, id: &Id<LibrarySuggestion>) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("DELETE FROM library_suggestions WHERE id = ?")
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_library_suggestions_by_game_ids(&self, game_ids: &[i32]) -> anyhow::Result<()> {
+        if game_ids.is_empty() {
+            return Ok(());
+        }
+        let pool = self.pool.0.clone();
+        let mut builder = QueryBuilder::new("DELETE FROM library_suggestions WHERE game_id IN (");
+        let mut separated = builder.separated(", ");
+        for game_id in game_ids {
+            separated.push_bind(*game_id);
+        }
+        separated.push_unseparated(")");
+        builder.build().execute(&*pool).await?;
+        Ok(())
+    }
+
+    async fn get_removed_installs(&self) -> anyhow::Result<Vec<RemovedInstall>> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<(i32, String, String, NaiveDateTime)> = query_as(
+            "SELECT r.collection_element_id, c.gamename, r.path, r.detected_at
+            FROM library_removed_installs AS r
+            INNER JOIN collection_elements AS c ON c.id = r.collection_element_id
+            ORDER BY r.detected_at DESC, r.collection_element_id",
+        )
+        .fetch_all(&*pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, gamename, path, detected_at)| {
+                RemovedInstall::new(
+                    Id::new(id),
+                    gamename,
+                    path,
+                    detected_at.and_utc().with_timezone(&Local),
+                )
+            })
+            .collect())
+    }
+
+    async fn flag_removed_installs(
+        &self,
+        installs: &[(Id<CollectionElement>, String)],
+    ) -> anyhow::Result<()> {
+        if installs.is_empty() {
+            return Ok(());
+        }
+        let pool = self.pool.0.clone();
+        let mut builder = QueryBuilder::new(
+            "INSERT INTO library_removed_installs (collection_element_id, path) ",
+        );
+        builder.push_values(installs, |mut b, (id, path)| {
+            b.push_bind(id.value).push_bind(path);
+        });
+        builder.push(" ON CONFLICT(collection_element_id) DO NOTHING");
+        builder.build().execute(&*pool).await?;
+        Ok(())
+    }
+
+    async fn clear_removed_installs(
+        &self,
+        element_ids: &[Id<CollectionElement>],
+    ) -> anyhow::Result<()> {
+        if element_ids.is_empty() {
+            return Ok(());
+        }
+        let pool = self.pool.0.clone();
+        let mut builder = QueryBuilder::new(
+            "DELETE FROM library_removed_installs WHERE collection_element_id IN (",
+        );
+        let mut separated = builder.separated(", ");
+        for id in element_ids {
+            separated.push_bind(id.value);
+        }
+        separated.push_unseparated(")");
+        builder.build().execute(&*pool).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::repositoryimpl::driver::Db;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    async fn setup_repository() -> RepositoryImpl<LibraryRoot> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        for sql in [
+            "CREATE TABLE collection_elements (id INTEGER PRIMARY KEY, gamename TEXT NOT NULL)",
+            "CREATE TABLE library_roots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL UNIQUE,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            "CREATE TABLE library_suggestions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                library_root_id INTEGER NOT NULL,
+                file_path TEXT NOT NULL UNIQUE,
+                game_id INTEGER NOT NULL,
+                gamename TEXT NOT NULL,
+                confidence REAL NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            "CREATE TABLE library_removed_installs (
+                collection_element_id INTEGER PRIMARY KEY,
+                path TEXT NOT NULL,
+                detected_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+            "INSERT INTO collection_elements (id, gamename) VALUES (1, 'game1'), (2, 'game2')",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        RepositoryImpl::new(Db(Arc::new(pool)))
+    }
+
+    #[tokio::test]
+    async fn test_library_suggestions_upsert_and_delete() {
+        let repository = setup_repository().await;
+        let root_id = repository.add_library_root("D:\\Games").await.unwrap();
+        let other_root_id = repository.add_library_root("E:\\Games").await.unwrap();
+
+        repository
+            .upsert_library_suggestions(&[
+                NewLibrarySuggestion::new(
+                    root_id.clone(),
+                    "D:\\Games\\a\\a.exe".to_string(),
+                    10,
+                    "game a".to_string(),
+                    0.85,
+                ),
+                NewLibrarySuggestion::new(
+                    other_root_id.clone(),
+                    "E:\\Games\\b\\b.exe".to_string(),
+                    20,
+                    "game b".to_string(),
+                    0.8,
+                ),
+            ])
+            .await
+            .unwrap();
+        // 同じファイルは新しい候補で置き換える
+        repository
+            .upsert_library_suggestions(&[NewLibrarySuggestion::new(
+                root_id.clone(),
+                "D:\\Games\\a\\a.exe".to_string(),
+                11,
+                "game a2".to_string(),
+                0.88,
+            )])
+            .await
+            .unwrap();
+
+        let suggestions = repository.get_library_suggestions().await.unwrap();
+        assert_eq!(suggestions.len(), 2);
+        let a = suggestions
+            .iter()
+            .find(|v| v.file_path == "D:\\Games\\a\\a.exe")
+            .unwrap();
+        assert_eq!(a.game_id, 11);
+        assert_eq!(a.gamename, "game a2");
+        assert!((a.confidence - 0.88).abs() < 1e-6);
+
+        repository
+            .delete_library_suggestions_by_game_ids(&[11])
+            .await
+            .unwrap();
+        // フォルダを削除するとその候補も削除する
+        repository
+            .delete_library_root(&other_root_id)
+            .await
+            .unwrap();
+        assert!(repository
+            .get_library_suggestions()
+            .await
+            .unwrap()
+            .is_empty());
+        let roots = repository.get_library_roots().await.unwrap();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].id.value, root_id.value);
+    }
+
+    #[tokio::test]
+    async fn test_removed_installs_keep_first_detection() {
+        let repository = setup_repository().await;
+
+        repository
+            .flag_removed_installs(&[
+                (Id::new(1), "D:\\Games\\a\\a.exe".to_string()),
+                (Id::new(2), "D:\\Games\\b\\b.lnk".to_string()),
+            ])
+            .await
+            .unwrap();
+        repository
+            .flag_removed_installs(&[(Id::new(1), "D:\\Games\\a\\other.exe".to_string())])
+            .await
+            .unwrap();
+
+        let removed = repository.get_removed_installs().await.unwrap();
+        assert_eq!(removed.len(), 2);
+        let a = removed
+            .iter()
+            .find(|v| v.collection_element_id.value == 1)
+            .unwrap();
+        assert_eq!(a.gamename, "game1");
+        assert_eq!(a.path, "D:\\Games\\a\\a.exe");
+
+        repository
+            .clear_removed_installs(&[Id::new(1)])
+            .await
+            .unwrap();
+        let removed = repository.get_removed_installs().await.unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].collection_element_id.value, 2);
+    }
+}
@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+use chrono::Local;
+use sqlx::{query, query_as, types::chrono::NaiveDateTime};
+
+use super::repository::RepositoryImpl;
+use crate::domain::{
+    matching_rule::{MatchingRuleOverride, MatchingRules, NewMatchingRuleOverride},
+    repository::matching_rule::MatchingRuleRepository,
+    Id,
+};
+
+type MatchingRuleOverrideRow = (
+    i32,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<i32>,
+    NaiveDateTime,
+);
+
+fn to_matching_rule_override(
+    (id, kind, action, value, grandparent, game_id, created_at): MatchingRuleOverrideRow,
+) -> anyhow::Result<MatchingRuleOverride> {
+    Ok(MatchingRuleOverride::new(
+        Id::new(id),
+        kind.parse()?,
+        action.parse()?,
+        value,
+        grandparent,
+        game_id,
+        created_at.and_utc().with_timezone(&Local),
+    ))
+}
+
+#[async_trait]
+impl MatchingRuleRepository for RepositoryImpl<MatchingRules> {
+    async fn get_matching_rule_overrides(&self) -> anyhow::Result<Vec<MatchingRuleOverride>> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<MatchingRuleOverrideRow> = query_as(
+            "SELECT id, kind, action, value, grandparent, game_id, created_at
+            FROM matching_rule_overrides
+            ORDER BY id",
+        )
+        .fetch_all(&*pool)
+        .await?;
+        rows.into_iter().map(to_matching_rule_override).collect()
+    }
+
+    async fn add_matching_rule_override(
+        &self,
+        new_override: &NewMatchingRuleOverride,
+    ) -> anyhow::Result<Id<MatchingRuleOverride>> {
+        let pool = self.pool.0.clone();
+        let result = query(
+            "INSERT INTO matching_rule_overrides (kind, action, value, grandparent, game_id)
+            VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(new_override.kind.as_str())
+        .bind(new_override.action.as_str())
+        .bind(&new_override.value)
+        .bind(&new_override.grandparent)
+        .bind(new_override.game_id)
+        .execute(&*pool)
+        .await?;
+        Ok(Id::new(result.last_insert_rowid() as i32))
+    }
+
+    async fn delete_matching_rule_override(
+        &self,
+        id: &Id<MatchingRuleOverride>,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("DELETE FROM matching_rule_overrides WHERE id = ?")
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::matching_rule::{MatchingRuleAction, MatchingRuleKind};
+    use crate::infrastructure::repositoryimpl::driver::Db;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_matching_rule_overrides_round_trip() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE matching_rule_overrides (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                action TEXT NOT NULL,
+                value TEXT NOT NULL DEFAULT '',
+                grandparent TEXT,
+                game_id INTEGER,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let repository: RepositoryImpl<MatchingRules> = RepositoryImpl::new(Db(Arc::new(pool)));
+
+        let word_id = repository
+            .add_matching_rule_override(&NewMatchingRuleOverride::new(
+                MatchingRuleKind::NotGameTerm,
+                MatchingRuleAction::Add,
+                "ツール".to_string(),
+                None,
+                None,
+            ))
+            .await
+            .unwrap();
+        repository
+            .add_matching_rule_override(&NewMatchingRuleOverride::new(
+                MatchingRuleKind::PathSpecific,
+                MatchingRuleAction::Add,
+                "すみれ".to_string(),
+                Some("nekoneko".to_string()),
+                Some(20178),
+            ))
+            .await
+            .unwrap();
+
+        let overrides = repository.get_matching_rule_overrides().await.unwrap();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0].kind, MatchingRuleKind::NotGameTerm);
+        assert_eq!(overrides[0].action, MatchingRuleAction::Add);
+        assert_eq!(overrides[1].grandparent.as_deref(), Some("nekoneko"));
+        assert_eq!(overrides[1].game_id, Some(20178));
+
+        repository
+            .delete_matching_rule_override(&word_id)
+            .await
+            .unwrap();
+        let overrides = repository.get_matching_rule_overrides().await.unwrap();
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides[0].kind, MatchingRuleKind::PathSpecific);
+    }
+}
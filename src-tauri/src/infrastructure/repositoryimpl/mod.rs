@@ -2,6 +2,11 @@ pub mod all_game_cache;
 pub mod collection;
 pub mod driver;
 pub mod explored_cache;
+pub mod learned_match;
+pub mod library;
+pub mod matching_rule;
 pub mod models;
+pub mod play_stats;
 pub mod repository;
 pub mod screenshot;
+pub mod tag;
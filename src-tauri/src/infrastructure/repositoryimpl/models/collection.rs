@@ -2,7 +2,16 @@ use chrono::Local;
 use sqlx::types::chrono::NaiveDateTime;
 use sqlx::FromRow;
 
-use crate::domain::{collection::CollectionElement, Id};
+use crate::domain::{
+    collection::{Collection, CollectionElement},
+    external_play_time::ExternalPlayTimeImport,
+    launch::{LaunchProfile, LaunchTarget},
+    launch_hook::LaunchHook,
+    play_session::{PlaySession, PlaySessionAudit},
+    smart_collection::SmartCollection,
+    tracking_checkpoint::TrackingCheckpoint,
+    Id,
+};
 
 #[derive(FromRow)]
 pub struct CollectionElementTable {
@@ -25,6 +34,7 @@ pub struct CollectionElementTable {
     pub thumbnail_height: Option<i32>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub tags_json: String,
 }
 
 impl TryFrom<CollectionElementTable> for CollectionElement {
@@ -50,6 +60,237 @@ impl TryFrom<CollectionElementTable> for CollectionElement {
             st.thumbnail_height,
             st.created_at.and_utc().with_timezone(&Local),
             st.updated_at.and_utc().with_timezone(&Local),
+            serde_json::from_str(&st.tags_json)?,
+        ))
+    }
+}
+
+#[derive(FromRow)]
+pub struct CollectionTable {
+    pub id: i32,
+    pub name: String,
+    pub sort_order: i32,
+    pub element_count: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl TryFrom<CollectionTable> for Collection {
+    type Error = anyhow::Error;
+    fn try_from(st: CollectionTable) -> Result<Self, Self::Error> {
+        Ok(Collection::new(
+            Id::new(st.id),
+            st.name,
+            st.sort_order,
+            st.element_count,
+            st.created_at.and_utc().with_timezone(&Local),
+            st.updated_at.and_utc().with_timezone(&Local),
+        ))
+    }
+}
+
+#[derive(FromRow)]
+pub struct SmartCollectionTable {
+    pub id: i32,
+    pub name: String,
+    pub filter_json: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl TryFrom<SmartCollectionTable> for SmartCollection {
+    type Error = anyhow::Error;
+    fn try_from(st: SmartCollectionTable) -> Result<Self, Self::Error> {
+        Ok(SmartCollection::new(
+            Id::new(st.id),
+            st.name,
+            serde_json::from_str(&st.filter_json)?,
+            st.created_at.and_utc().with_timezone(&Local),
+            st.updated_at.and_utc().with_timezone(&Local),
+        ))
+    }
+}
+
+#[derive(FromRow)]
+pub struct LaunchTargetTable {
+    pub id: i32,
+    pub collection_element_id: i32,
+    pub name: String,
+    pub path: String,
+    pub is_default: i32,
+    pub sort_order: i32,
+}
+
+impl TryFrom<LaunchTargetTable> for LaunchTarget {
+    type Error = anyhow::Error;
+    fn try_from(st: LaunchTargetTable) -> Result<Self, Self::Error> {
+        Ok(LaunchTarget::new(
+            Id::new(st.id),
+            Id::new(st.collection_element_id),
+            st.name,
+            st.path,
+            st.is_default != 0,
+            st.sort_order,
+        ))
+    }
+}
+
+#[derive(FromRow)]
+pub struct LaunchProfileTable {
+    pub arguments_json: String,
+    pub working_directory: Option<String>,
+    pub environment_json: String,
+    pub wrapper_command: Option<String>,
+}
+
+impl TryFrom<LaunchProfileTable> for LaunchProfile {
+    type Error = anyhow::Error;
+    fn try_from(st: LaunchProfileTable) -> Result<Self, Self::Error> {
+        Ok(LaunchProfile::new(
+            serde_json::from_str(&st.arguments_json)?,
+            st.working_directory,
+            serde_json::from_str(&st.environment_json)?,
+            st.wrapper_command,
+        ))
+    }
+}
+
+#[derive(FromRow)]
+pub struct LaunchHookTable {
+    pub id: i32,
+    pub collection_element_id: Option<i32>,
+    pub timing: String,
+    pub name: String,
+    pub command: String,
+    pub timeout_seconds: i32,
+    pub cancel_launch_on_failure: i32,
+    pub sort_order: i32,
+}
+
+#[derive(FromRow)]
+pub struct PlaySessionTable {
+    pub id: i32,
+    pub collection_element_id: i32,
+    pub started_at: NaiveDateTime,
+    pub ended_at: Option<NaiveDateTime>,
+    pub pid: Option<i64>,
+    pub exe_path: Option<String>,
+    pub played_seconds: i32,
+    pub paused_seconds: i32,
+}
+
+impl TryFrom<PlaySessionTable> for PlaySession {
+    type Error = anyhow::Error;
+    fn try_from(st: PlaySessionTable) -> Result<Self, Self::Error> {
+        Ok(PlaySession::new(
+            Id::new(st.id),
+            Id::new(st.collection_element_id),
+            st.started_at.and_utc().with_timezone(&Local),
+            st.ended_at.map(|v| v.and_utc().with_timezone(&Local)),
+            st.pid.map(|v| v as u32),
+            st.exe_path,
+            st.played_seconds,
+            st.paused_seconds,
+        ))
+    }
+}
+
+#[derive(FromRow)]
+pub struct PlaySessionAuditTable {
+    pub id: i32,
+    pub action: String,
+    pub before_json: String,
+    pub after_json: String,
+    pub undo_of: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub undone_at: Option<NaiveDateTime>,
+}
+
+impl TryFrom<PlaySessionAuditTable> for PlaySessionAudit {
+    type Error = anyhow::Error;
+    fn try_from(st: PlaySessionAuditTable) -> Result<Self, Self::Error> {
+        Ok(PlaySessionAudit::new(
+            Id::new(st.id),
+            st.action.parse()?,
+            serde_json::from_str(&st.before_json)?,
+            serde_json::from_str(&st.after_json)?,
+            st.undo_of.map(Id::new),
+            st.created_at.and_utc().with_timezone(&Local),
+            st.undone_at.map(|v| v.and_utc().with_timezone(&Local)),
+        ))
+    }
+}
+
+#[derive(FromRow)]
+pub struct ExternalPlayTimeImportTable {
+    pub source: String,
+    pub external_id: String,
+    pub collection_element_id: i32,
+    pub imported_seconds: i64,
+    pub last_session_ended_at: Option<NaiveDateTime>,
+}
+
+impl TryFrom<ExternalPlayTimeImportTable> for ExternalPlayTimeImport {
+    type Error = anyhow::Error;
+    fn try_from(st: ExternalPlayTimeImportTable) -> Result<Self, Self::Error> {
+        Ok(ExternalPlayTimeImport {
+            source: st.source.parse()?,
+            external_id: st.external_id,
+            collection_element_id: Id::new(st.collection_element_id),
+            imported_seconds: st.imported_seconds,
+            last_session_ended_at: st
+                .last_session_ended_at
+                .map(|v| v.and_utc().with_timezone(&Local)),
+        })
+    }
+}
+
+#[derive(FromRow)]
+pub struct TrackingCheckpointTable {
+    pub collection_element_id: i32,
+    pub play_session_id: Option<i32>,
+    pub pid: i64,
+    pub process_started_at: Option<i64>,
+    pub game_path: String,
+    pub game_name: String,
+    pub started_at: NaiveDateTime,
+    pub heartbeat_at: NaiveDateTime,
+    pub played_seconds: i32,
+    pub paused_seconds: i32,
+    pub is_paused: i32,
+}
+
+impl TryFrom<TrackingCheckpointTable> for TrackingCheckpoint {
+    type Error = anyhow::Error;
+    fn try_from(st: TrackingCheckpointTable) -> Result<Self, Self::Error> {
+        Ok(TrackingCheckpoint::new(
+            Id::new(st.collection_element_id),
+            st.play_session_id.map(Id::new),
+            u32::try_from(st.pid)?,
+            st.process_started_at,
+            st.game_path,
+            st.game_name,
+            st.started_at.and_utc().with_timezone(&Local),
+            st.heartbeat_at.and_utc().with_timezone(&Local),
+            st.played_seconds,
+            st.paused_seconds,
+            st.is_paused != 0,
+        ))
+    }
+}
+
+impl TryFrom<LaunchHookTable> for LaunchHook {
+    type Error = anyhow::Error;
+    fn try_from(st: LaunchHookTable) -> Result<Self, Self::Error> {
+        Ok(LaunchHook::new(
+            Id::new(st.id),
+            st.collection_element_id.map(Id::new),
+            st.timing.parse()?,
+            st.name,
+            st.command,
+            st.timeout_seconds.max(0) as u32,
+            st.cancel_launch_on_failure != 0,
+            st.sort_order,
         ))
     }
 }
@@ -88,11 +329,13 @@ mod tests {
             thumbnail_height: Some(256),
             created_at: create_base_datetime(),
             updated_at: create_base_datetime(),
+            tags_json: r#"["club-pick","泣きゲー"]"#.to_string(),
         };
 
         let domain: CollectionElement = table.try_into().unwrap();
 
         assert_eq!(domain.id.value, 123);
+        assert_eq!(domain.tags, vec!["club-pick", "泣きゲー"]);
         assert_eq!(domain.gamename, "テストゲーム");
         assert!(!domain.is_nukige);
         assert_eq!(domain.play_status, 1);
@@ -121,9 +364,322 @@ mod tests {
             thumbnail_height: None,
             created_at: create_base_datetime(),
             updated_at: create_base_datetime(),
+            tags_json: "[]".to_string(),
         };
 
         let domain: CollectionElement = table.try_into().unwrap();
         assert!(domain.is_nukige);
+        assert!(domain.tags.is_empty());
+    }
+
+    #[test]
+    fn test_collection_table_to_domain_conversion() {
+        let table = CollectionTable {
+            id: 7,
+            name: "積みゲー 2026".to_string(),
+            sort_order: 2,
+            element_count: 12,
+            created_at: create_base_datetime(),
+            updated_at: create_base_datetime(),
+        };
+
+        let domain: Collection = table.try_into().unwrap();
+
+        assert_eq!(domain.id.value, 7);
+        assert_eq!(domain.name, "積みゲー 2026");
+        assert_eq!(domain.sort_order, 2);
+        assert_eq!(domain.element_count, 12);
+    }
+
+    #[test]
+    fn test_smart_collection_table_to_domain_conversion() {
+        let table = SmartCollectionTable {
+            id: 3,
+            name: "未プレイの抜きゲー".to_string(),
+            filter_json: r#"{"type":"nukige","isNukige":true}"#.to_string(),
+            created_at: create_base_datetime(),
+            updated_at: create_base_datetime(),
+        };
+
+        let domain: SmartCollection = table.try_into().unwrap();
+
+        assert_eq!(domain.id.value, 3);
+        assert_eq!(
+            domain.filter,
+            crate::domain::smart_collection::SmartCollectionFilter::Nukige { is_nukige: true }
+        );
+    }
+
+    #[test]
+    fn test_smart_collection_table_with_broken_filter_fails() {
+        let table = SmartCollectionTable {
+            id: 4,
+            name: "broken".to_string(),
+            filter_json: "{".to_string(),
+            created_at: create_base_datetime(),
+            updated_at: create_base_datetime(),
+        };
+
+        let result: anyhow::Result<SmartCollection> = table.try_into();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_launch_target_table_to_domain_conversion() {
+        let table = LaunchTargetTable {
+            id: 5,
+            collection_element_id: 123,
+            name: "設定".to_string(),
+            path: "C:/Games/test/config.exe".to_string(),
+            is_default: 1,
+            sort_order: 2,
+        };
+
+        let domain: LaunchTarget = table.try_into().unwrap();
+
+        assert_eq!(domain.id.value, 5);
+        assert_eq!(domain.collection_element_id.value, 123);
+        assert_eq!(domain.name, "設定");
+        assert!(domain.is_default);
+        assert_eq!(domain.sort_order, 2);
+    }
+
+    #[test]
+    fn test_launch_profile_table_to_domain_conversion() {
+        let table = LaunchProfileTable {
+            arguments_json: r#"["-lang","ja"]"#.to_string(),
+            working_directory: Some("D:/save".to_string()),
+            environment_json: r#"{"__COMPAT_LAYER":"RunAsInvoker"}"#.to_string(),
+            wrapper_command: Some("LEProc.exe -run {exe}".to_string()),
+        };
+
+        let domain: LaunchProfile = table.try_into().unwrap();
+
+        assert_eq!(domain.arguments, vec!["-lang", "ja"]);
+        assert_eq!(domain.working_directory, Some("D:/save".to_string()));
+        assert_eq!(
+            domain.environment.get("__COMPAT_LAYER"),
+            Some(&"RunAsInvoker".to_string())
+        );
+        assert_eq!(
+            domain.wrapper_command,
+            Some("LEProc.exe -run {exe}".to_string())
+        );
+    }
+
+    #[test]
+    fn test_launch_hook_table_to_domain_conversion() {
+        let table = LaunchHookTable {
+            id: 6,
+            collection_element_id: None,
+            timing: "post_exit".to_string(),
+            name: "backup".to_string(),
+            command: "backup.bat {dir}".to_string(),
+            timeout_seconds: 120,
+            cancel_launch_on_failure: 0,
+            sort_order: 1,
+        };
+
+        let domain: LaunchHook = table.try_into().unwrap();
+
+        assert_eq!(domain.id.value, 6);
+        assert!(domain.collection_element_id.is_none());
+        assert_eq!(
+            domain.timing,
+            crate::domain::launch_hook::LaunchHookTiming::PostExit
+        );
+        assert_eq!(domain.timeout_seconds, 120);
+        assert!(!domain.cancel_launch_on_failure);
+    }
+
+    #[test]
+    fn test_launch_hook_table_with_unknown_timing_fails() {
+        let table = LaunchHookTable {
+            id: 7,
+            collection_element_id: Some(1),
+            timing: "unknown".to_string(),
+            name: "x".to_string(),
+            command: "x".to_string(),
+            timeout_seconds: 1,
+            cancel_launch_on_failure: 1,
+            sort_order: 0,
+        };
+
+        let result: anyhow::Result<LaunchHook> = table.try_into();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_play_session_table_to_domain_conversion() {
+        let table = PlaySessionTable {
+            id: 8,
+            collection_element_id: 123,
+            started_at: create_base_datetime(),
+            ended_at: None,
+            pid: Some(4321),
+            exe_path: Some("C:/Games/test/game.exe".to_string()),
+            played_seconds: 3600,
+            paused_seconds: 60,
+        };
+
+        let domain: PlaySession = table.try_into().unwrap();
+
+        assert_eq!(domain.id.value, 8);
+        assert_eq!(domain.collection_element_id.value, 123);
+        assert_eq!(
+            domain.started_at,
+            create_base_datetime().and_utc().with_timezone(&Local)
+        );
+        assert!(domain.ended_at.is_none());
+        assert_eq!(domain.pid, Some(4321));
+        assert_eq!(domain.played_seconds, 3600);
+        assert_eq!(domain.paused_seconds, 60);
+    }
+
+    #[test]
+    fn test_tracking_checkpoint_table_to_domain_conversion() {
+        let table = TrackingCheckpointTable {
+            collection_element_id: 5,
+            play_session_id: Some(9),
+            pid: 4321,
+            process_started_at: Some(1_700_000_000),
+            game_path: "C:/Games/test/game.exe".to_string(),
+            game_name: "test".to_string(),
+            started_at: create_base_datetime(),
+            heartbeat_at: create_base_datetime() + chrono::Duration::minutes(5),
+            played_seconds: 290,
+            paused_seconds: 10,
+            is_paused: 1,
+        };
+
+        let domain: TrackingCheckpoint = table.try_into().unwrap();
+
+        assert_eq!(domain.collection_element_id.value, 5);
+        assert_eq!(domain.play_session_id.map(|v| v.value), Some(9));
+        assert_eq!(domain.pid, 4321);
+        assert_eq!(domain.process_started_at, Some(1_700_000_000));
+        assert_eq!(
+            domain.heartbeat_at,
+            (create_base_datetime() + chrono::Duration::minutes(5))
+                .and_utc()
+                .with_timezone(&Local)
+        );
+        assert_eq!(domain.played_seconds, 290);
+        assert!(domain.is_paused);
+    }
+
+    #[test]
+    fn test_tracking_checkpoint_table_rejects_invalid_pid() {
+        let table = TrackingCheckpointTable {
+            collection_element_id: 5,
+            play_session_id: None,
+            pid: -1,
+            process_started_at: None,
+            game_path: String::new(),
+            game_name: String::new(),
+            started_at: create_base_datetime(),
+            heartbeat_at: create_base_datetime(),
+            played_seconds: 0,
+            paused_seconds: 0,
+            is_paused: 0,
+        };
+
+        let result: anyhow::Result<TrackingCheckpoint> = table.try_into();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_play_session_audit_table_to_domain_conversion() {
+        let before = vec![PlaySession::new(
+            Id::new(3),
+            Id::new(7),
+            create_base_datetime().and_utc().with_timezone(&Local),
+            Some(
+                (create_base_datetime() + chrono::Duration::hours(1))
+                    .and_utc()
+                    .with_timezone(&Local),
+            ),
+            None,
+            None,
+            3600,
+            0,
+        )];
+        let table = PlaySessionAuditTable {
+            id: 2,
+            action: "delete".to_string(),
+            before_json: serde_json::to_string(&before).unwrap(),
+            after_json: "[]".to_string(),
+            undo_of: None,
+            created_at: create_base_datetime(),
+            undone_at: None,
+        };
+
+        let domain: PlaySessionAudit = table.try_into().unwrap();
+
+        assert_eq!(domain.id.value, 2);
+        assert_eq!(
+            domain.action,
+            crate::domain::play_session::PlaySessionEditAction::Delete
+        );
+        assert_eq!(domain.before.len(), 1);
+        assert_eq!(domain.before[0].id.value, 3);
+        assert_eq!(domain.before[0].played_seconds, 3600);
+        assert!(domain.after.is_empty());
+        assert!(domain.undone_at.is_none());
+    }
+
+    #[test]
+    fn test_play_session_audit_table_rejects_unknown_action() {
+        let table = PlaySessionAuditTable {
+            id: 2,
+            action: "rename".to_string(),
+            before_json: "[]".to_string(),
+            after_json: "[]".to_string(),
+            undo_of: None,
+            created_at: create_base_datetime(),
+            undone_at: None,
+        };
+
+        let result: anyhow::Result<PlaySessionAudit> = table.try_into();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_external_play_time_import_table_to_domain_conversion() {
+        let table = ExternalPlayTimeImportTable {
+            source: "vnite".to_string(),
+            external_id: "abc".to_string(),
+            collection_element_id: 7,
+            imported_seconds: 5400,
+            last_session_ended_at: Some(create_base_datetime()),
+        };
+
+        let domain: ExternalPlayTimeImport = table.try_into().unwrap();
+
+        assert_eq!(
+            domain.source,
+            crate::domain::external_play_time::ExternalPlayTimeSource::Vnite
+        );
+        assert_eq!(domain.external_id, "abc");
+        assert_eq!(domain.collection_element_id.value, 7);
+        assert_eq!(domain.imported_seconds, 5400);
+        assert_eq!(
+            domain.last_session_ended_at,
+            Some(create_base_datetime().and_utc().with_timezone(&Local))
+        );
+    }
+
+    #[test]
+    fn test_external_play_time_import_table_rejects_unknown_source() {
+        let table = ExternalPlayTimeImportTable {
+            source: "gog".to_string(),
+            external_id: "abc".to_string(),
+            collection_element_id: 7,
+            imported_seconds: 0,
+            last_session_ended_at: None,
+        };
+
+        let result: anyhow::Result<ExternalPlayTimeImport> = table.try_into();
+        assert!(result.is_err());
     }
 }
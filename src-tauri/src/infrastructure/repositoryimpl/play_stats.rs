@@ -0,0 +1,412 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Local, NaiveDate};
+use sqlx::{query_as, QueryBuilder};
+
+use super::repository::RepositoryImpl;
+use crate::domain::{
+    collection::CollectionElement,
+    play_session::PlaySessionSummary,
+    play_stats::{
+        GamePlayTime, GroupPlayTime, PlayStats, PlayStatsPeriod, PlayStreak, PlayTimeBucket,
+    },
+    repository::play_stats::PlayStatsRepository,
+    Id,
+};
+
+fn parse_play_date(value: &str) -> anyhow::Result<NaiveDate> {
+    Ok(NaiveDate::parse_from_str(value, "%Y-%m-%d")?)
+}
+
+fn to_date_string(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// 集計単位の最初の日を求める SQL の式
+fn period_start_expression(period: PlayStatsPeriod) -> &'static str {
+    match period {
+        PlayStatsPeriod::Day => "play_date",
+        // 次の日曜日(当日を含む)の6日前が月曜日
+        PlayStatsPeriod::Week => "date(play_date, 'weekday 0', '-6 days')",
+        PlayStatsPeriod::Month => "date(play_date, 'start of month')",
+        PlayStatsPeriod::Year => "date(play_date, 'start of year')",
+    }
+}
+
+#[async_trait]
+impl PlayStatsRepository for RepositoryImpl<PlayStats> {
+    async fn get_play_time_buckets(
+        &self,
+        period: PlayStatsPeriod,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<Vec<PlayTimeBucket>> {
+        let pool = self.pool.0.clone();
+        let mut builder = QueryBuilder::new("SELECT ");
+        builder.push(period_start_expression(period));
+        builder.push(
+            " AS period_start, SUM(play_time_seconds), COUNT(DISTINCT collection_element_id)
+            FROM collection_element_daily_play_times
+            WHERE play_time_seconds > 0 AND play_date BETWEEN ",
+        );
+        builder.push_bind(to_date_string(from));
+        builder.push(" AND ");
+        builder.push_bind(to_date_string(to));
+        builder.push(" GROUP BY period_start ORDER BY period_start");
+        let rows: Vec<(String, i64, i32)> = builder.build_query_as().fetch_all(&*pool).await?;
+        rows.into_iter()
+            .map(|(period_start, seconds, game_count)| {
+                Ok(PlayTimeBucket::new(
+                    parse_play_date(&period_start)?,
+                    seconds,
+                    game_count,
+                ))
+            })
+            .collect()
+    }
+
+    async fn get_top_games(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: i32,
+    ) -> anyhow::Result<Vec<GamePlayTime>> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<(i32, String, i64, i32)> = query_as(
+            "SELECT e.id, e.gamename, SUM(d.play_time_seconds) AS total, COUNT(*)
+            FROM collection_element_daily_play_times AS d
+            INNER JOIN collection_elements AS e ON e.id = d.collection_element_id
+            WHERE d.play_time_seconds > 0 AND d.play_date BETWEEN ? AND ?
+            GROUP BY e.id
+            ORDER BY total DESC, e.id ASC
+            LIMIT ?",
+        )
+        .bind(to_date_string(from))
+        .bind(to_date_string(to))
+        .bind(limit)
+        .fetch_all(&*pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(id, gamename, seconds, play_days)| {
+                GamePlayTime::new(id, gamename, seconds, play_days)
+            })
+            .collect())
+    }
+
+    async fn get_brand_play_times(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<Vec<GroupPlayTime>> {
+        let pool = self.pool.0.clone();
+        // 詳細が重複して登録されていても二重に数えないようにエレメントごとに1件にする
+        let rows: Vec<(String, i64, i32)> = query_as(
+            "SELECT cd.brandname, SUM(d.play_time_seconds) AS total, COUNT(DISTINCT d.collection_element_id)
+            FROM collection_element_daily_play_times AS d
+            INNER JOIN (
+                SELECT collection_element_id, MAX(brandname) AS brandname
+                FROM collection_element_details
+                GROUP BY collection_element_id
+            ) AS cd ON cd.collection_element_id = d.collection_element_id
+            WHERE d.play_time_seconds > 0 AND d.play_date BETWEEN ? AND ? AND cd.brandname != ''
+            GROUP BY cd.brandname
+            ORDER BY total DESC, cd.brandname ASC",
+        )
+        .bind(to_date_string(from))
+        .bind(to_date_string(to))
+        .fetch_all(&*pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(key, seconds, game_count)| GroupPlayTime::new(key, seconds, game_count))
+            .collect())
+    }
+
+    async fn get_sellday_year_play_times(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<Vec<GroupPlayTime>> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<(String, i64, i32)> = query_as(
+            "SELECT cd.sellday_year, SUM(d.play_time_seconds), COUNT(DISTINCT d.collection_element_id)
+            FROM collection_element_daily_play_times AS d
+            INNER JOIN (
+                SELECT collection_element_id, substr(MAX(sellday), 1, 4) AS sellday_year
+                FROM collection_element_details
+                GROUP BY collection_element_id
+            ) AS cd ON cd.collection_element_id = d.collection_element_id
+            WHERE d.play_time_seconds > 0 AND d.play_date BETWEEN ? AND ?
+                AND cd.sellday_year GLOB '[0-9][0-9][0-9][0-9]'
+            GROUP BY cd.sellday_year
+            ORDER BY cd.sellday_year ASC",
+        )
+        .bind(to_date_string(from))
+        .bind(to_date_string(to))
+        .fetch_all(&*pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(key, seconds, game_count)| GroupPlayTime::new(key, seconds, game_count))
+            .collect())
+    }
+
+    async fn get_longest_play_streak(
+        &self,
+        element_id: Option<&Id<CollectionElement>>,
+    ) -> anyhow::Result<Option<PlayStreak>> {
+        let pool = self.pool.0.clone();
+        // 連続した日付は (日付 - 順位) が同じ値になる
+        let row: Option<(String, String, i32)> = query_as(
+            "WITH play_days AS (
+                SELECT DISTINCT play_date
+                FROM collection_element_daily_play_times
+                WHERE play_time_seconds > 0 AND (?1 IS NULL OR collection_element_id = ?1)
+            ),
+            grouped AS (
+                SELECT play_date, julianday(play_date) - ROW_NUMBER() OVER (ORDER BY play_date) AS grp
+                FROM play_days
+            )
+            SELECT MIN(play_date) AS start_date, MAX(play_date), COUNT(*) AS days
+            FROM grouped
+            GROUP BY grp
+            ORDER BY days DESC, start_date DESC
+            LIMIT 1",
+        )
+        .bind(element_id.map(|v| v.value))
+        .fetch_optional(&*pool)
+        .await?;
+        row.map(|(start_date, end_date, days)| {
+            Ok(PlayStreak::new(
+                days,
+                parse_play_date(&start_date)?,
+                parse_play_date(&end_date)?,
+            ))
+        })
+        .transpose()
+    }
+
+    async fn get_play_session_summary(
+        &self,
+        since: DateTime<Local>,
+        until: DateTime<Local>,
+    ) -> anyhow::Result<PlaySessionSummary> {
+        let pool = self.pool.0.clone();
+        let (session_count, total_played_seconds, longest_played_seconds, average_played_seconds): (
+            i32,
+            i64,
+            i32,
+            i32,
+        ) = query_as(
+            "SELECT COUNT(*), COALESCE(SUM(played_seconds), 0), COALESCE(MAX(played_seconds), 0),
+                COALESCE(CAST(AVG(played_seconds) AS INTEGER), 0)
+            FROM play_sessions
+            WHERE ended_at IS NOT NULL AND ended_at >= ? AND ended_at < ?",
+        )
+        .bind(since.naive_utc())
+        .bind(until.naive_utc())
+        .fetch_one(&*pool)
+        .await?;
+        Ok(PlaySessionSummary {
+            session_count,
+            total_played_seconds,
+            longest_played_seconds,
+            average_played_seconds,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::repositoryimpl::driver::Db;
+    use chrono::TimeZone;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    async fn setup_repository() -> RepositoryImpl<PlayStats> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        for sql in [
+            "CREATE TABLE collection_elements (id INTEGER PRIMARY KEY, gamename TEXT NOT NULL)",
+            "CREATE TABLE collection_element_details (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                collection_element_id INTEGER NOT NULL,
+                sellday TEXT NOT NULL,
+                brandname TEXT NOT NULL
+            )",
+            "CREATE TABLE collection_element_daily_play_times (
+                collection_element_id INTEGER NOT NULL,
+                play_date TEXT NOT NULL,
+                play_time_seconds INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (collection_element_id, play_date)
+            )",
+            "CREATE TABLE play_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                collection_element_id INTEGER NOT NULL,
+                started_at DATETIME NOT NULL,
+                ended_at DATETIME,
+                played_seconds INTEGER NOT NULL DEFAULT 0
+            )",
+            "INSERT INTO collection_elements (id, gamename) VALUES (1, 'game1'), (2, 'game2'), (3, 'game3')",
+            "INSERT INTO collection_element_details (collection_element_id, sellday, brandname)
+                VALUES (1, '2019-04-26', 'brandA'), (2, '2019-10-25', 'brandA'), (3, '2021-01-29', 'brandB')",
+            // 2026-01-04 は日曜日、2026-01-05 は月曜日
+            "INSERT INTO collection_element_daily_play_times (collection_element_id, play_date, play_time_seconds)
+                VALUES
+                (1, '2026-01-03', 600),
+                (1, '2026-01-04', 1200),
+                (1, '2026-01-05', 1800),
+                (2, '2026-01-05', 300),
+                (2, '2026-02-01', 3600),
+                (3, '2026-01-10', 0),
+                (3, '2025-12-31', 900)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        RepositoryImpl::new(Db(Arc::new(pool)))
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_play_time_buckets_by_week_and_month() {
+        let repository = setup_repository().await;
+
+        let weeks = repository
+            .get_play_time_buckets(PlayStatsPeriod::Week, date(2026, 1, 1), date(2026, 1, 31))
+            .await
+            .unwrap();
+        assert_eq!(
+            weeks,
+            vec![
+                PlayTimeBucket::new(date(2025, 12, 29), 1800, 1),
+                PlayTimeBucket::new(date(2026, 1, 5), 2100, 2),
+            ]
+        );
+
+        let months = repository
+            .get_play_time_buckets(PlayStatsPeriod::Month, date(2025, 1, 1), date(2026, 12, 31))
+            .await
+            .unwrap();
+        assert_eq!(
+            months,
+            vec![
+                PlayTimeBucket::new(date(2025, 12, 1), 900, 1),
+                PlayTimeBucket::new(date(2026, 1, 1), 3900, 2),
+                PlayTimeBucket::new(date(2026, 2, 1), 3600, 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_top_games() {
+        let repository = setup_repository().await;
+
+        let games = repository
+            .get_top_games(date(2026, 1, 1), date(2026, 12, 31), 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            games,
+            vec![GamePlayTime::new(2, "game2".to_string(), 3900, 2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_brand_and_sellday_year_play_times() {
+        let repository = setup_repository().await;
+        let (from, to) = (date(2025, 1, 1), date(2026, 12, 31));
+
+        assert_eq!(
+            repository.get_brand_play_times(from, to).await.unwrap(),
+            vec![
+                GroupPlayTime::new("brandA".to_string(), 7500, 2),
+                GroupPlayTime::new("brandB".to_string(), 900, 1),
+            ]
+        );
+        assert_eq!(
+            repository
+                .get_sellday_year_play_times(from, to)
+                .await
+                .unwrap(),
+            vec![
+                GroupPlayTime::new("2019".to_string(), 7500, 2),
+                GroupPlayTime::new("2021".to_string(), 900, 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_longest_play_streak() {
+        let repository = setup_repository().await;
+
+        assert_eq!(
+            repository.get_longest_play_streak(None).await.unwrap(),
+            Some(PlayStreak::new(3, date(2026, 1, 3), date(2026, 1, 5)))
+        );
+        assert_eq!(
+            repository
+                .get_longest_play_streak(Some(&Id::new(2)))
+                .await
+                .unwrap(),
+            Some(PlayStreak::new(1, date(2026, 2, 1), date(2026, 2, 1)))
+        );
+        assert_eq!(
+            repository
+                .get_longest_play_streak(Some(&Id::new(9)))
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_play_session_summary() {
+        let repository = setup_repository().await;
+        let at = |hour: u32| Local.with_ymd_and_hms(2026, 1, 5, hour, 0, 0).unwrap();
+        for (started_at, ended_at, played_seconds) in [
+            (at(10), Some(at(11)), 3000),
+            (at(12), Some(at(14)), 6000),
+            (at(20), None, 0),
+        ] {
+            sqlx::query(
+                "INSERT INTO play_sessions (collection_element_id, started_at, ended_at, played_seconds)
+                VALUES (1, ?, ?, ?)",
+            )
+            .bind(started_at.naive_utc())
+            .bind(ended_at.map(|v| v.naive_utc()))
+            .bind(played_seconds)
+            .execute(&*repository.pool.0)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            repository
+                .get_play_session_summary(at(0), at(23))
+                .await
+                .unwrap(),
+            PlaySessionSummary {
+                session_count: 2,
+                total_played_seconds: 9000,
+                longest_played_seconds: 6000,
+                average_played_seconds: 4500,
+            }
+        );
+        assert_eq!(
+            repository
+                .get_play_session_summary(at(12), at(13))
+                .await
+                .unwrap(),
+            PlaySessionSummary::default()
+        );
+    }
+}
@@ -4,6 +4,8 @@ use std::sync::Arc;
 
 use crate::domain::{
     all_game_cache::AllGameCache, collection::CollectionElement, explored_cache::ExploredCache,
+    learned_match::LearnedGameMatch, library::LibraryRoot, matching_rule::MatchingRules,
+    play_stats::PlayStats, tag::Tag,
 };
 
 use super::driver::Db;
@@ -21,6 +23,11 @@ pub struct Repositories {
     explored_cache_repository: RepositoryImpl<ExploredCache>,
     all_game_cache_repository: RepositoryImpl<AllGameCache>,
     screenshot_repository: ScreenshotRepositoryImpl,
+    tag_repository: RepositoryImpl<Tag>,
+    play_stats_repository: RepositoryImpl<PlayStats>,
+    library_repository: RepositoryImpl<LibraryRoot>,
+    matching_rule_repository: RepositoryImpl<MatchingRules>,
+    learned_match_repository: RepositoryImpl<LearnedGameMatch>,
 }
 use crate::domain::repository::repositories::RepositoriesExt;
 
@@ -29,6 +36,11 @@ impl RepositoriesExt for Repositories {
     type ExploredCacheRepo = RepositoryImpl<ExploredCache>;
     type AllGameCacheRepo = RepositoryImpl<AllGameCache>;
     type ScreenshotRepo = ScreenshotRepositoryImpl;
+    type TagRepo = RepositoryImpl<Tag>;
+    type PlayStatsRepo = RepositoryImpl<PlayStats>;
+    type LibraryRepo = RepositoryImpl<LibraryRoot>;
+    type MatchingRuleRepo = RepositoryImpl<MatchingRules>;
+    type LearnedMatchRepo = RepositoryImpl<LearnedGameMatch>;
 
     fn collection_repository(&self) -> &Self::CollectionRepo {
         &self.collection_repository
@@ -42,6 +54,21 @@ impl RepositoriesExt for Repositories {
     fn screenshot_repository(&self) -> &Self::ScreenshotRepo {
         &self.screenshot_repository
     }
+    fn tag_repository(&self) -> &Self::TagRepo {
+        &self.tag_repository
+    }
+    fn play_stats_repository(&self) -> &Self::PlayStatsRepo {
+        &self.play_stats_repository
+    }
+    fn library_repository(&self) -> &Self::LibraryRepo {
+        &self.library_repository
+    }
+    fn matching_rule_repository(&self) -> &Self::MatchingRuleRepo {
+        &self.matching_rule_repository
+    }
+    fn learned_match_repository(&self) -> &Self::LearnedMatchRepo {
+        &self.learned_match_repository
+    }
 }
 
 impl Repositories {
@@ -50,12 +77,22 @@ impl Repositories {
         let explored_cache_repository = RepositoryImpl::new(db.clone());
         let all_game_cache_repository = RepositoryImpl::new(db.clone());
         let screenshot_repository = ScreenshotRepositoryImpl::new(Arc::new(db.clone()));
+        let tag_repository = RepositoryImpl::new(db.clone());
+        let play_stats_repository = RepositoryImpl::new(db.clone());
+        let library_repository = RepositoryImpl::new(db.clone());
+        let matching_rule_repository = RepositoryImpl::new(db.clone());
+        let learned_match_repository = RepositoryImpl::new(db.clone());
 
         Self {
             collection_repository,
             explored_cache_repository,
             all_game_cache_repository,
             screenshot_repository,
+            tag_repository,
+            play_stats_repository,
+            library_repository,
+            matching_rule_repository,
+            learned_match_repository,
         }
     }
 }
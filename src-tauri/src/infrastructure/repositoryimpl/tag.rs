@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use sqlx::{query, query_as};
+
+use super::repository::RepositoryImpl;
+use crate::domain::{collection::CollectionElement, repository::tag::TagRepository, tag::Tag, Id};
+
+fn to_tag((id, name, element_count): (i32, String, i32)) -> Tag {
+    Tag::new(Id::new(id), name, element_count)
+}
+
+#[async_trait]
+impl TagRepository for RepositoryImpl<Tag> {
+    async fn get_all(&self) -> anyhow::Result<Vec<Tag>> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<(i32, String, i32)> = query_as(
+            "SELECT t.id, t.name, COUNT(et.collection_element_id)
+            FROM tags AS t
+            LEFT JOIN collection_element_tags AS et
+            ON t.id = et.tag_id
+            GROUP BY t.id
+            ORDER BY t.name ASC",
+        )
+        .fetch_all(&*pool)
+        .await?;
+        Ok(rows.into_iter().map(to_tag).collect())
+    }
+
+    async fn get_by_id(&self, id: &Id<Tag>) -> anyhow::Result<Option<Tag>> {
+        let pool = self.pool.0.clone();
+        let row: Option<(i32, String, i32)> = query_as(
+            "SELECT t.id, t.name,
+                (SELECT COUNT(*) FROM collection_element_tags AS et WHERE et.tag_id = t.id)
+            FROM tags AS t
+            WHERE t.id = ?",
+        )
+        .bind(id.value)
+        .fetch_optional(&*pool)
+        .await?;
+        Ok(row.map(to_tag))
+    }
+
+    async fn get_by_name(&self, name: &str) -> anyhow::Result<Option<Tag>> {
+        let pool = self.pool.0.clone();
+        let row: Option<(i32, String, i32)> = query_as(
+            "SELECT t.id, t.name,
+                (SELECT COUNT(*) FROM collection_element_tags AS et WHERE et.tag_id = t.id)
+            FROM tags AS t
+            WHERE t.name = ?",
+        )
+        .bind(name)
+        .fetch_optional(&*pool)
+        .await?;
+        Ok(row.map(to_tag))
+    }
+
+    async fn get_by_element_id(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Vec<Tag>> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<(i32, String, i32)> = query_as(
+            "SELECT t.id, t.name,
+                (SELECT COUNT(*) FROM collection_element_tags AS c WHERE c.tag_id = t.id)
+            FROM tags AS t
+            INNER JOIN collection_element_tags AS et
+            ON t.id = et.tag_id
+            WHERE et.collection_element_id = ?
+            ORDER BY t.name ASC",
+        )
+        .bind(element_id.value)
+        .fetch_all(&*pool)
+        .await?;
+        Ok(rows.into_iter().map(to_tag).collect())
+    }
+
+    async fn get_or_create(&self, name: &str) -> anyhow::Result<Id<Tag>> {
+        let pool = self.pool.0.clone();
+        query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+            .bind(name)
+            .execute(&*pool)
+            .await?;
+        let (id,): (i32,) = query_as("SELECT id FROM tags WHERE name = ?")
+            .bind(name)
+            .fetch_one(&*pool)
+            .await?;
+        Ok(Id::new(id))
+    }
+
+    async fn rename(&self, id: &Id<Tag>, name: &str) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("UPDATE tags SET name = ? WHERE id = ?")
+            .bind(name)
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &Id<Tag>) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        // collection_element_tags は ON DELETE CASCADE で削除される
+        query("DELETE FROM tags WHERE id = ?")
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn merge(&self, source: &Id<Tag>, target: &Id<Tag>) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        let mut tx = pool.begin().await?;
+        query(
+            "INSERT OR IGNORE INTO collection_element_tags (collection_element_id, tag_id)
+            SELECT collection_element_id, ? FROM collection_element_tags WHERE tag_id = ?",
+        )
+        .bind(target.value)
+        .bind(source.value)
+        .execute(&mut tx)
+        .await?;
+        query("DELETE FROM tags WHERE id = ?")
+            .bind(source.value)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn add_to_element(
+        &self,
+        element_id: &Id<CollectionElement>,
+        id: &Id<Tag>,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("INSERT OR IGNORE INTO collection_element_tags (collection_element_id, tag_id) VALUES (?, ?)")
+            .bind(element_id.value)
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_from_element(
+        &self,
+        element_id: &Id<CollectionElement>,
+        id: &Id<Tag>,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.0.clone();
+        query("DELETE FROM collection_element_tags WHERE collection_element_id = ? AND tag_id = ?")
+            .bind(element_id.value)
+            .bind(id.value)
+            .execute(&*pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_element_tag_names(&self) -> anyhow::Result<Vec<(Id<CollectionElement>, String)>> {
+        let pool = self.pool.0.clone();
+        let rows: Vec<(i32, String)> = query_as(
+            "SELECT et.collection_element_id, t.name
+            FROM collection_element_tags AS et
+            INNER JOIN tags AS t
+            ON t.id = et.tag_id",
+        )
+        .fetch_all(&*pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(element_id, name)| (Id::new(element_id), name))
+            .collect())
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use windows::Win32::{
+    System::SystemInformation::GetTickCount,
+    UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
+};
+
+use super::windows::WindowsImpl;
+use crate::domain::{input_activity::InputActivity, windows::input_activity::InputActivityWindows};
+
+impl InputActivityWindows for WindowsImpl<InputActivity> {
+    fn idle_duration(&self) -> anyhow::Result<Duration> {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+            return Err(anyhow::anyhow!("cannot get last input info"));
+        }
+        // どちらも起動からのミリ秒で約49日で一周するため wrapping_sub で差を取る
+        let now = unsafe { GetTickCount() };
+        Ok(Duration::from_millis(now.wrapping_sub(info.dwTime) as u64))
+    }
+}
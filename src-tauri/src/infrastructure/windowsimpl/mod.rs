@@ -1,3 +1,4 @@
+pub mod input_activity;
 pub mod process;
 mod screenshot;
 pub mod windows;
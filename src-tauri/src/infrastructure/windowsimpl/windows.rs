@@ -1,7 +1,11 @@
 use derive_new::new;
 use std::marker::PhantomData;
 
-use crate::domain::{process::Process, windows::process::ProcessWindows};
+use crate::domain::{
+    input_activity::InputActivity,
+    process::Process,
+    windows::{input_activity::InputActivityWindows, process::ProcessWindows},
+};
 
 #[derive(new)]
 pub struct WindowsImpl<T> {
@@ -10,25 +14,36 @@ pub struct WindowsImpl<T> {
 
 pub struct Windows {
     process: WindowsImpl<Process>,
+    input_activity: WindowsImpl<InputActivity>,
 }
 pub trait WindowsExt {
     type ProcessWindows: ProcessWindows;
+    type InputActivityWindows: InputActivityWindows;
 
     fn process(&self) -> &Self::ProcessWindows;
+    fn input_activity(&self) -> &Self::InputActivityWindows;
 }
 
 impl WindowsExt for Windows {
     type ProcessWindows = WindowsImpl<Process>;
+    type InputActivityWindows = WindowsImpl<InputActivity>;
 
     fn process(&self) -> &Self::ProcessWindows {
         &self.process
     }
+    fn input_activity(&self) -> &Self::InputActivityWindows {
+        &self.input_activity
+    }
 }
 
 impl Windows {
     pub fn new() -> Self {
         let process = WindowsImpl::new();
+        let input_activity = WindowsImpl::new();
 
-        Self { process }
+        Self {
+            process,
+            input_activity,
+        }
     }
 }
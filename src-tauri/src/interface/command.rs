@@ -2,7 +2,14 @@ use super::{
     error::CommandError,
     models::{
         all_game_cache::AllGameCacheOne,
-        collection::{CollectionElement, ProgressLivePayload, ProgressPayload},
+        collection::{
+            Collection, CollectionElement, LaunchHook, LaunchTarget, PlaySession, PlaySessionAudit,
+            ProcessCandidate, ProgressLivePayload, ProgressPayload, SmartCollection,
+        },
+        learned_match::LearnedGameMatch,
+        library::{LibraryRoot, LibrarySuggestion, RemovedInstall},
+        matching_rule::MatchingRuleOverride,
+        tag::Tag,
     },
     module::{Modules, ModulesExt},
 };
@@ -10,18 +17,31 @@ use crate::{
     domain::{
         collection::NewCollectionElement,
         distance::find_nearest,
+        external_play_time::ExternalPlayTimeSource,
         file::{
             get_exe_path_from_lnk, get_file_created_at_sync, get_icon_path, get_lnk_metadatas,
-            get_thumbnail_candidate_urls, get_thumbnail_path, normalize,
+            get_thumbnail_candidate_urls, get_thumbnail_path,
         },
+        launch::LaunchProfile,
+        launch_hook::{LaunchHookTiming, NewLaunchHook},
+        matching_rule::{MatchingRules, NewMatchingRuleOverride},
+        normalize::normalize,
+        play_session::PlaySessionSummary,
+        play_stats::{GamePlayTime, GroupPlayTime, PlayStatsPeriod, PlayStreak, PlayTimeBucket},
         repository::collection::{
             DailyPlayTime as DomainDailyPlayTime, GameScreenshotCache as DomainGameScreenshotCache,
         },
+        scan::{ScanReport, ScanRule},
+        smart_collection::SmartCollectionFilter,
         Id,
     },
     usecase::error::UseCaseError,
+    usecase::external_play_time::ExternalImportReport,
+    usecase::learned_match::LearnedGameMatchImportReport,
     usecase::models::collection::CreateCollectionElementDetail,
+    usecase::pause_manager::{PauseReason, TrackingSession},
 };
+use chrono::{DateTime, Local, NaiveDate};
 use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter, Listener, Manager, State, WebviewUrl, WebviewWindowBuilder};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
@@ -46,9 +66,32 @@ pub struct WindowScreenshot {
 #[serde(rename_all = "camelCase")]
 pub struct TrackingState {
     pub is_tracking: bool,
+    /// active_* と is_paused はフォーカス中のセッションの状態
     pub is_paused: bool,
+    pub pause_reason: Option<PauseReason>,
     pub active_game_id: Option<i32>,
     pub active_process_id: Option<u32>,
+    pub sessions: Vec<TrackingSessionState>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackingSessionState {
+    pub game_id: i32,
+    pub process_id: Option<u32>,
+    pub is_paused: bool,
+    pub pause_reason: Option<PauseReason>,
+}
+
+impl From<TrackingSession> for TrackingSessionState {
+    fn from(value: TrackingSession) -> Self {
+        Self {
+            game_id: value.game_id,
+            process_id: value.process_id,
+            is_paused: value.is_paused,
+            pause_reason: value.pause_reason,
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -580,7 +623,7 @@ pub async fn launch_shortcut_game(
         if let Ok(game_id) = game_id_str.parse::<i32>() {
             modules
                 .collection_use_case()
-                .play_game_and_track(handle.into(), game_id)
+                .play_game_and_track(handle.into(), game_id, None)
                 .await?;
         }
     }
@@ -594,6 +637,29 @@ pub async fn create_elements_in_pc(
     explore_dir_paths: Vec<String>,
     use_cache: bool,
 ) -> Result<Vec<String>, CommandError> {
+    let report = scan_elements(&modules, handle, explore_dir_paths, use_cache, false).await?;
+    Ok(report.registered)
+}
+
+/// 調べたファイルごとの候補と結果を返す。`dry_run` の場合は何も登録しない
+#[tauri::command]
+pub async fn scan_elements_in_pc(
+    modules: State<'_, Arc<Modules>>,
+    handle: AppHandle,
+    explore_dir_paths: Vec<String>,
+    use_cache: bool,
+    dry_run: bool,
+) -> Result<ScanReport, CommandError> {
+    scan_elements(&modules, handle, explore_dir_paths, use_cache, dry_run).await
+}
+
+async fn scan_elements(
+    modules: &Modules,
+    handle: AppHandle,
+    explore_dir_paths: Vec<String>,
+    use_cache: bool,
+    dry_run: bool,
+) -> Result<ScanReport, CommandError> {
     for path_str in &explore_dir_paths {
         if !std::path::Path::new(path_str).is_dir() {
             return Err(CommandError::Anyhow(anyhow::anyhow!(
@@ -619,16 +685,12 @@ pub async fn create_elements_in_pc(
     }));
 
     let explored_caches = modules.explored_cache_use_case().get_cache().await?;
-    let explore_files: Vec<String> = modules
+    let (cached_files, explore_files): (Vec<String>, Vec<String>) = modules
         .file_use_case()
         .concurrency_get_file_paths(explore_dir_paths)
         .await?
         .into_iter()
-        .filter_map(|v| match use_cache && explored_caches.contains(&v) {
-            true => None,
-            false => Some(v),
-        })
-        .collect();
+        .partition(|v| use_cache && explored_caches.contains(v));
 
     emit_progress(format!(
         "指定したフォルダの .lnk .exe ファイルを取得しました。ファイル数: {}",
@@ -645,62 +707,237 @@ pub async fn create_elements_in_pc(
         .all_game_cache_use_case()
         .get_all_game_cache()
         .await?;
+    let brandnames = modules.collection_use_case().get_brandnames().await?;
 
-    let new_elements = modules
+    let (new_elements, mut report) = modules
         .file_use_case()
-        .filter_files_to_collection_elements(
+        .scan_files_to_collection_elements(
             &handle,
+            modules.matching_rule_use_case().current(),
+            modules.learned_match_use_case().current(),
             explore_files.clone(),
             all_game_cache,
+            brandnames,
             emit_progress,
             process_each_game_file_callback,
+            dry_run,
         )
         .await?;
+    for file in cached_files {
+        report.push_skipped(file, ScanRule::ExploredCache);
+    }
+    if dry_run {
+        return Ok(report);
+    }
 
-    let new_elements_game_caches = modules
-        .all_game_cache_use_case()
-        .get_by_ids(new_elements.iter().map(|v| v.id.value).collect())
-        .await?;
-    modules
-        .collection_use_case()
-        .concurrency_save_thumbnails_from_candidates(
-            &handle,
-            new_elements_game_caches
-                .into_iter()
-                .map(|v| {
-                    let urls = new_elements
-                        .iter()
-                        .find(|element| element.id.value == v.id)
-                        .map(|element| {
-                            get_thumbnail_candidate_urls(element, v.thumbnail_url.clone())
-                        })
-                        .unwrap_or_else(|| vec![v.thumbnail_url.clone()]);
-                    (Id::new(v.id), urls)
-                })
-                .collect(),
-        )
-        .await?;
+    super::logic::register_new_elements(&handle, modules, &new_elements).await?;
 
     modules
-        .collection_use_case()
-        .upsert_collection_elements(&new_elements)
+        .explored_cache_use_case()
+        .add_cache(explore_files)
         .await?;
 
-    let new_element_ids = new_elements
-        .iter()
-        .map(|v| v.id.clone())
-        .collect::<Vec<Id<_>>>();
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn get_library_roots(
+    modules: State<'_, Arc<Modules>>,
+) -> Result<Vec<LibraryRoot>, CommandError> {
+    Ok(modules
+        .library_use_case()
+        .get_roots()
+        .await?
+        .into_iter()
+        .map(|v| v.into())
+        .collect())
+}
+
+#[tauri::command]
+pub async fn add_library_root(
+    modules: State<'_, Arc<Modules>>,
+    path: String,
+) -> Result<i32, CommandError> {
+    let id = modules.library_use_case().add_root(&path).await?;
+    let roots = modules.library_use_case().get_roots().await?;
+    modules.library_watcher().watch(&roots)?;
+    Ok(id.value)
+}
+
+#[tauri::command]
+pub async fn remove_library_root(
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+) -> Result<(), CommandError> {
+    modules.library_use_case().remove_root(&Id::new(id)).await?;
+    let roots = modules.library_use_case().get_roots().await?;
+    modules.library_watcher().watch(&roots)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_library_suggestions(
+    modules: State<'_, Arc<Modules>>,
+) -> Result<Vec<LibrarySuggestion>, CommandError> {
+    Ok(modules
+        .library_use_case()
+        .get_suggestions()
+        .await?
+        .into_iter()
+        .map(|v| v.into())
+        .collect())
+}
+
+/// 候補のゲームを登録し、その名前を返す
+#[tauri::command]
+pub async fn accept_library_suggestion(
+    handle: AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+) -> Result<String, CommandError> {
+    let suggestion = modules
+        .library_use_case()
+        .get_suggestion(&Id::new(id))
+        .await?;
+    let is_lnk = suggestion.file_path.to_lowercase().ends_with(".lnk");
+    let new_element = NewCollectionElement::new(
+        Id::new(suggestion.game_id),
+        suggestion.gamename,
+        (!is_lnk).then(|| suggestion.file_path.clone()),
+        is_lnk.then(|| suggestion.file_path.clone()),
+        get_file_created_at_sync(&suggestion.file_path),
+    );
+    let handle = Arc::new(handle);
     modules
         .collection_use_case()
-        .concurrency_upsert_collection_element_thumbnail_size(&handle, new_element_ids)
+        .save_element_icon(&handle, &new_element)
         .await?;
-
-    modules
-        .explored_cache_use_case()
-        .add_cache(explore_files)
+    super::logic::register_new_elements(&handle, &modules, std::slice::from_ref(&new_element))
         .await?;
+    Ok(new_element.gamename)
+}
+
+#[tauri::command]
+pub async fn dismiss_library_suggestion(
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .library_use_case()
+        .dismiss_suggestion(&Id::new(id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_removed_installs(
+    modules: State<'_, Arc<Modules>>,
+) -> Result<Vec<RemovedInstall>, CommandError> {
+    Ok(modules
+        .library_use_case()
+        .get_removed_installs()
+        .await?
+        .into_iter()
+        .map(|v| v.into())
+        .collect())
+}
+
+#[tauri::command]
+pub async fn dismiss_removed_install(
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .library_use_case()
+        .dismiss_removed_install(&Id::new(id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_matching_rules(
+    modules: State<'_, Arc<Modules>>,
+) -> Result<MatchingRules, CommandError> {
+    Ok((*modules.matching_rule_use_case().current()).clone())
+}
+
+#[tauri::command]
+pub async fn get_matching_rule_overrides(
+    modules: State<'_, Arc<Modules>>,
+) -> Result<Vec<MatchingRuleOverride>, CommandError> {
+    Ok(modules
+        .matching_rule_use_case()
+        .get_overrides()
+        .await?
+        .into_iter()
+        .map(|v| v.into())
+        .collect())
+}
+
+#[tauri::command]
+pub async fn add_matching_rule_override(
+    modules: State<'_, Arc<Modules>>,
+    new_override: NewMatchingRuleOverride,
+) -> Result<MatchingRules, CommandError> {
+    Ok(modules
+        .matching_rule_use_case()
+        .add_override(&new_override)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn delete_matching_rule_override(
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+) -> Result<MatchingRules, CommandError> {
+    Ok(modules
+        .matching_rule_use_case()
+        .delete_override(&Id::new(id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_learned_game_matches(
+    modules: State<'_, Arc<Modules>>,
+) -> Result<Vec<LearnedGameMatch>, CommandError> {
+    Ok(modules
+        .learned_match_use_case()
+        .get_learned_game_matches()
+        .await?
+        .into_iter()
+        .map(|v| v.into())
+        .collect())
+}
+
+#[tauri::command]
+pub async fn delete_learned_game_match(
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .learned_match_use_case()
+        .delete_learned_game_match(&Id::new(id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn export_learned_game_matches(
+    modules: State<'_, Arc<Modules>>,
+    path: String,
+) -> Result<usize, CommandError> {
+    Ok(modules
+        .learned_match_use_case()
+        .export_learned_game_matches(&path)
+        .await?)
+}
 
-    Ok(new_elements.into_iter().map(|v| v.gamename).collect())
+#[tauri::command]
+pub async fn import_learned_game_matches(
+    modules: State<'_, Arc<Modules>>,
+    path: String,
+) -> Result<LearnedGameMatchImportReport, CommandError> {
+    Ok(modules
+        .learned_match_use_case()
+        .import_learned_game_matches(&path)
+        .await?)
 }
 
 #[tauri::command]
@@ -795,6 +1032,13 @@ pub async fn upsert_collection_element(
         .collection_use_case()
         .upsert_collection_element(&new_element)
         .await?;
+    if let Some(path) = new_element
+        .exe_path
+        .as_ref()
+        .or(new_element.lnk_path.as_ref())
+    {
+        learn_game_match(&modules, path, new_element.id.value).await;
+    }
     modules
         .collection_use_case()
         .save_element_icon(&handle, &new_element)
@@ -846,139 +1090,355 @@ pub async fn play_game(
     modules: State<'_, Arc<Modules>>,
     element_id: i32,
     _is_admin: Option<bool>,
+    launch_target_id: Option<i32>,
 ) -> Result<(), CommandError> {
     Ok(modules
         .collection_use_case()
-        .play_game_and_track(handle.into(), element_id)
+        .play_game_and_track(handle.into(), element_id, launch_target_id)
         .await?)
 }
 
 #[tauri::command]
-pub async fn get_app_setting(
-    modules: State<'_, Arc<Modules>>,
-    key: String,
-) -> Result<Option<String>, CommandError> {
-    Ok(modules.collection_use_case().get_app_setting(key).await?)
-}
-
-#[tauri::command]
-pub async fn get_game_screenshot_cache(
+pub async fn get_process_candidates(
     modules: State<'_, Arc<Modules>>,
     collection_element_id: i32,
-) -> Result<Option<GameScreenshotCache>, CommandError> {
+) -> Result<Vec<ProcessCandidate>, CommandError> {
     Ok(modules
         .collection_use_case()
-        .get_game_screenshot_cache(collection_element_id)
+        .get_process_candidates(&Id::new(collection_element_id))
         .await?
-        .map(Into::into))
+        .into_iter()
+        .map(Into::into)
+        .collect())
 }
 
 #[tauri::command]
-pub async fn upsert_game_screenshot_cache(
+pub async fn attach_game_process(
+    handle: AppHandle,
     modules: State<'_, Arc<Modules>>,
-    cache: GameScreenshotCache,
+    collection_element_id: i32,
+    pid: u32,
 ) -> Result<(), CommandError> {
     Ok(modules
         .collection_use_case()
-        .upsert_game_screenshot_cache(cache.into())
+        .attach_game_process(handle.into(), collection_element_id, pid)
         .await?)
 }
 
 #[tauri::command]
-pub async fn set_app_setting(
-    app_handle: AppHandle,
+pub async fn stop_tracking_session(
     modules: State<'_, Arc<Modules>>,
-    key: String,
-    value: Option<String>,
+    game_id: Option<i32>,
 ) -> Result<(), CommandError> {
-    modules
-        .collection_use_case()
-        .set_app_setting(key.clone(), value)
-        .await?;
-
-    if key == "shortcut_game_id" {
-        app_handle
-            .emit("shortcut-game-changed", ())
-            .map_err(anyhow::Error::from)?;
-    }
-
-    Ok(())
+    Ok(modules.collection_use_case().stop_tracking(game_id)?)
 }
 
 #[tauri::command]
-pub async fn get_play_time_minutes(
-    handle: AppHandle,
+pub async fn get_launch_targets(
     modules: State<'_, Arc<Modules>>,
     collection_element_id: i32,
-) -> Result<f32, CommandError> {
+) -> Result<Vec<LaunchTarget>, CommandError> {
     Ok(modules
-        .file_use_case()
-        .get_play_time_minutes(&Arc::new(handle), &Id::new(collection_element_id))?)
+        .collection_use_case()
+        .get_launch_targets(&Id::new(collection_element_id))
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
 }
 
 #[tauri::command]
-pub async fn get_collection_element(
-    handle: AppHandle,
+pub async fn add_launch_target(
     modules: State<'_, Arc<Modules>>,
     collection_element_id: i32,
-) -> Result<CollectionElement, CommandError> {
-    match modules
+    name: String,
+    path: String,
+    is_default: bool,
+) -> Result<LaunchTarget, CommandError> {
+    Ok(modules
         .collection_use_case()
-        .get_element_by_element_id(&Id::new(collection_element_id))
-        .await
-        .map(|v| CollectionElement::from_domain(&Arc::new(handle), v))
-    {
-        Ok(v) => Ok(v),
-        Err(e) => {
-            if let Some(UseCaseError::CollectionElementIsNotFound) =
-                e.downcast_ref::<UseCaseError>()
-            {
-                return Err(CommandError::NotFound);
-            }
-            Err(CommandError::Anyhow(e))
-        }
-    }
+        .add_launch_target(&Id::new(collection_element_id), name, path, is_default)
+        .await?
+        .into())
 }
 
 #[tauri::command]
-pub async fn delete_collection_element(
-    handle: tauri::AppHandle,
+pub async fn update_launch_target(
     modules: State<'_, Arc<Modules>>,
-    collection_element_id: i32,
-) -> Result<(), CommandError> {
+    launch_target_id: i32,
+    name: String,
+    path: String,
+) -> Result<LaunchTarget, CommandError> {
     Ok(modules
         .collection_use_case()
-        .delete_collection_element_by_id(&Arc::new(handle), &Id::new(collection_element_id))
-        .await?)
+        .update_launch_target(&Id::new(launch_target_id), name, path)
+        .await?
+        .into())
 }
 
 #[tauri::command]
-pub async fn get_not_registered_detail_element_ids(
+pub async fn delete_launch_target(
     modules: State<'_, Arc<Modules>>,
-) -> Result<Vec<i32>, CommandError> {
+    launch_target_id: i32,
+) -> Result<(), CommandError> {
     Ok(modules
         .collection_use_case()
-        .get_not_registered_detail_element_ids()
-        .await?
-        .into_iter()
-        .map(|v| v.value)
-        .collect())
+        .delete_launch_target(&Id::new(launch_target_id))
+        .await?)
 }
 
 #[tauri::command]
-pub async fn create_element_details(
+pub async fn set_default_launch_target(
     modules: State<'_, Arc<Modules>>,
-    details: Vec<CreateCollectionElementDetail>,
+    collection_element_id: i32,
+    launch_target_id: Option<i32>,
 ) -> Result<(), CommandError> {
     Ok(modules
         .collection_use_case()
-        .create_element_details(details.into_iter().map(|v| v.into()).collect())
+        .set_default_launch_target(
+            &Id::new(collection_element_id),
+            launch_target_id.map(Id::new),
+        )
         .await?)
 }
 
 #[tauri::command]
-pub async fn get_all_elements(
-    handle: AppHandle,
+pub async fn get_launch_profile(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+) -> Result<Option<LaunchProfile>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_launch_profile(&Id::new(collection_element_id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn save_launch_profile(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+    profile: LaunchProfile,
+) -> Result<LaunchProfile, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .save_launch_profile(&Id::new(collection_element_id), profile)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn delete_launch_profile(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .delete_launch_profile(&Id::new(collection_element_id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_launch_hooks(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: Option<i32>,
+) -> Result<Vec<LaunchHook>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_launch_hooks(collection_element_id.map(Id::new).as_ref())
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn add_launch_hook(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: Option<i32>,
+    timing: LaunchHookTiming,
+    name: String,
+    command: String,
+    timeout_seconds: u32,
+    cancel_launch_on_failure: bool,
+) -> Result<LaunchHook, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .add_launch_hook(NewLaunchHook::new(
+            collection_element_id.map(Id::new),
+            timing,
+            name,
+            command,
+            timeout_seconds,
+            cancel_launch_on_failure,
+        ))
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn update_launch_hook(
+    modules: State<'_, Arc<Modules>>,
+    launch_hook_id: i32,
+    timing: LaunchHookTiming,
+    name: String,
+    command: String,
+    timeout_seconds: u32,
+    cancel_launch_on_failure: bool,
+) -> Result<LaunchHook, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .update_launch_hook(
+            &Id::new(launch_hook_id),
+            NewLaunchHook::new(
+                None,
+                timing,
+                name,
+                command,
+                timeout_seconds,
+                cancel_launch_on_failure,
+            ),
+        )
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn delete_launch_hook(
+    modules: State<'_, Arc<Modules>>,
+    launch_hook_id: i32,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .delete_launch_hook(&Id::new(launch_hook_id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_app_setting(
+    modules: State<'_, Arc<Modules>>,
+    key: String,
+) -> Result<Option<String>, CommandError> {
+    Ok(modules.collection_use_case().get_app_setting(key).await?)
+}
+
+#[tauri::command]
+pub async fn get_game_screenshot_cache(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+) -> Result<Option<GameScreenshotCache>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_game_screenshot_cache(collection_element_id)
+        .await?
+        .map(Into::into))
+}
+
+#[tauri::command]
+pub async fn upsert_game_screenshot_cache(
+    modules: State<'_, Arc<Modules>>,
+    cache: GameScreenshotCache,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .upsert_game_screenshot_cache(cache.into())
+        .await?)
+}
+
+#[tauri::command]
+pub async fn set_app_setting(
+    app_handle: AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    key: String,
+    value: Option<String>,
+) -> Result<(), CommandError> {
+    modules
+        .collection_use_case()
+        .set_app_setting(key.clone(), value)
+        .await?;
+
+    if key == "shortcut_game_id" {
+        app_handle
+            .emit("shortcut-game-changed", ())
+            .map_err(anyhow::Error::from)?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_play_time_minutes(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+) -> Result<f32, CommandError> {
+    let element = modules
+        .collection_use_case()
+        .get_element_by_element_id(&Id::new(collection_element_id))
+        .await?;
+    Ok(element.total_play_time_seconds as f32 / 60.0)
+}
+
+#[tauri::command]
+pub async fn get_collection_element(
+    handle: AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+) -> Result<CollectionElement, CommandError> {
+    match modules
+        .collection_use_case()
+        .get_element_by_element_id(&Id::new(collection_element_id))
+        .await
+        .map(|v| CollectionElement::from_domain(&Arc::new(handle), v))
+    {
+        Ok(v) => Ok(v),
+        Err(e) => {
+            if let Some(UseCaseError::CollectionElementIsNotFound) =
+                e.downcast_ref::<UseCaseError>()
+            {
+                return Err(CommandError::NotFound);
+            }
+            Err(CommandError::Anyhow(e))
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn delete_collection_element(
+    handle: tauri::AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .delete_collection_element_by_id(&Arc::new(handle), &Id::new(collection_element_id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_not_registered_detail_element_ids(
+    modules: State<'_, Arc<Modules>>,
+) -> Result<Vec<i32>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_not_registered_detail_element_ids()
+        .await?
+        .into_iter()
+        .map(|v| v.value)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn create_element_details(
+    modules: State<'_, Arc<Modules>>,
+    details: Vec<CreateCollectionElementDetail>,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .create_element_details(details.into_iter().map(|v| v.into()).collect())
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_all_elements(
+    handle: AppHandle,
     modules: State<'_, Arc<Modules>>,
 ) -> Result<Vec<CollectionElement>, CommandError> {
     let handle = &Arc::new(handle);
@@ -998,48 +1458,311 @@ pub async fn update_element_like(
     is_like: bool,
 ) -> Result<(), CommandError> {
     Ok(modules
-        .collection_use_case()
-        .update_element_like_at(&Id::new(id), is_like)
+        .collection_use_case()
+        .update_element_like_at(&Id::new(id), is_like)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn update_element_play_status(
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+    play_status: i32,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .update_element_play_status(&Id::new(id), play_status)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn adjust_untracked_play_time_seconds(
+    handle: AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+    seconds: i32,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .adjust_untracked_play_time_seconds(&Arc::new(handle), &Id::new(id), seconds)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_day_boundary(modules: State<'_, Arc<Modules>>) -> Result<String, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_day_boundary()
+        .await?
+        .to_setting_value())
+}
+
+#[tauri::command]
+pub async fn update_day_boundary(
+    modules: State<'_, Arc<Modules>>,
+    value: String,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .update_day_boundary(&value)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_collection_element_daily_play_times(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+) -> Result<Vec<CollectionElementDailyPlayTime>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_collection_element_daily_play_times(&Id::new(collection_element_id))
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_play_sessions(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+) -> Result<Vec<PlaySession>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_play_sessions(&Id::new(collection_element_id))
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_play_sessions_by_range(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: Option<i32>,
+    since: DateTime<Local>,
+    until: DateTime<Local>,
+) -> Result<Vec<PlaySession>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_play_sessions_by_range(collection_element_id.map(Id::new).as_ref(), since, until)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_play_session_summary(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: Option<i32>,
+    since: DateTime<Local>,
+    until: DateTime<Local>,
+) -> Result<PlaySessionSummary, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_play_session_summary(collection_element_id.map(Id::new).as_ref(), since, until)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn add_play_session(
+    handle: AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+    started_at: DateTime<Local>,
+    ended_at: DateTime<Local>,
+    played_seconds: Option<i32>,
+) -> Result<PlaySessionAudit, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .add_play_session(
+            &Arc::new(handle),
+            &Id::new(collection_element_id),
+            started_at,
+            ended_at,
+            played_seconds,
+        )
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn update_play_session(
+    handle: AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+    collection_element_id: i32,
+    started_at: DateTime<Local>,
+    ended_at: DateTime<Local>,
+    played_seconds: Option<i32>,
+) -> Result<PlaySessionAudit, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .update_play_session(
+            &Arc::new(handle),
+            &Id::new(id),
+            &Id::new(collection_element_id),
+            started_at,
+            ended_at,
+            played_seconds,
+        )
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn delete_play_session(
+    handle: AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+) -> Result<PlaySessionAudit, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .delete_play_session(&Arc::new(handle), &Id::new(id))
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn split_play_session(
+    handle: AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+    split_at: DateTime<Local>,
+    moved_collection_element_id: i32,
+) -> Result<PlaySessionAudit, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .split_play_session(
+            &Arc::new(handle),
+            &Id::new(id),
+            split_at,
+            &Id::new(moved_collection_element_id),
+        )
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn get_play_session_audits(
+    modules: State<'_, Arc<Modules>>,
+    limit: i32,
+) -> Result<Vec<PlaySessionAudit>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_play_session_audits(limit)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn undo_play_session_audit(
+    handle: AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    id: i32,
+) -> Result<PlaySessionAudit, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .undo_play_session_audit(&Arc::new(handle), &Id::new(id))
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn import_external_play_times(
+    handle: AppHandle,
+    modules: State<'_, Arc<Modules>>,
+    source: ExternalPlayTimeSource,
+    path: String,
+    dry_run: bool,
+) -> Result<ExternalImportReport, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .import_external_play_times(
+            &Arc::new(handle),
+            &modules.matching_rule_use_case().current(),
+            &modules.learned_match_use_case().current(),
+            source,
+            &path,
+            dry_run,
+        )
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_play_time_buckets(
+    modules: State<'_, Arc<Modules>>,
+    period: PlayStatsPeriod,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<PlayTimeBucket>, CommandError> {
+    Ok(modules
+        .play_stats_use_case()
+        .get_play_time_buckets(period, from, to)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_top_played_games(
+    modules: State<'_, Arc<Modules>>,
+    from: NaiveDate,
+    to: NaiveDate,
+    limit: i32,
+) -> Result<Vec<GamePlayTime>, CommandError> {
+    Ok(modules
+        .play_stats_use_case()
+        .get_top_games(from, to, limit)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_brand_play_times(
+    modules: State<'_, Arc<Modules>>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<GroupPlayTime>, CommandError> {
+    Ok(modules
+        .play_stats_use_case()
+        .get_brand_play_times(from, to)
         .await?)
 }
 
 #[tauri::command]
-pub async fn update_element_play_status(
+pub async fn get_sellday_year_play_times(
     modules: State<'_, Arc<Modules>>,
-    id: i32,
-    play_status: i32,
-) -> Result<(), CommandError> {
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<GroupPlayTime>, CommandError> {
     Ok(modules
-        .collection_use_case()
-        .update_element_play_status(&Id::new(id), play_status)
+        .play_stats_use_case()
+        .get_sellday_year_play_times(from, to)
         .await?)
 }
 
 #[tauri::command]
-pub async fn adjust_untracked_play_time_seconds(
-    handle: AppHandle,
+pub async fn get_longest_play_streak(
     modules: State<'_, Arc<Modules>>,
-    id: i32,
-    seconds: i32,
-) -> Result<(), CommandError> {
+    collection_element_id: Option<i32>,
+) -> Result<Option<PlayStreak>, CommandError> {
     Ok(modules
-        .collection_use_case()
-        .adjust_untracked_play_time_seconds(&Arc::new(handle), &Id::new(id), seconds)
+        .play_stats_use_case()
+        .get_longest_play_streak(collection_element_id.map(Id::new).as_ref())
         .await?)
 }
 
 #[tauri::command]
-pub async fn get_collection_element_daily_play_times(
+pub async fn get_play_session_stats(
     modules: State<'_, Arc<Modules>>,
-    collection_element_id: i32,
-) -> Result<Vec<CollectionElementDailyPlayTime>, CommandError> {
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<PlaySessionSummary, CommandError> {
     Ok(modules
-        .collection_use_case()
-        .get_collection_element_daily_play_times(&Id::new(collection_element_id))
-        .await?
-        .into_iter()
-        .map(Into::into)
-        .collect())
+        .play_stats_use_case()
+        .get_play_session_summary(from, to)
+        .await?)
 }
 
 #[tauri::command]
@@ -1094,10 +1817,17 @@ pub async fn get_game_candidates(
         .all_game_cache_use_case()
         .get_all_game_cache()
         .await?;
+    let brandnames = modules.collection_use_case().get_brandnames().await?;
 
     Ok(modules
         .file_use_case()
-        .get_game_candidates(all_game_cache, filepath)
+        .get_game_candidates(
+            &modules.matching_rule_use_case().current(),
+            &modules.learned_match_use_case().current(),
+            all_game_cache,
+            brandnames,
+            filepath,
+        )
         .await?
         .into_iter()
         .map(|c| (c.id, c.gamename))
@@ -1162,8 +1892,7 @@ pub async fn save_fullscreen_screenshot(
     modules: State<'_, Arc<Modules>>,
     work_id: i32,
 ) -> Result<String, CommandError> {
-    let tracking_session = modules.pause_manager().tracking_session();
-    if tracking_session.as_ref().map(|session| session.game_id) != Some(work_id) {
+    if !modules.pause_manager().is_tracking_game(work_id) {
         return Err(anyhow::anyhow!(
             "対象ゲームがLauncherg-Modで起動中として記録されていません"
         )
@@ -1271,27 +2000,56 @@ pub async fn update_game_image(
 pub async fn toggle_pause_tracking(
     handle: AppHandle,
     modules: State<'_, Arc<Modules>>,
+    game_id: Option<i32>,
 ) -> Result<bool, CommandError> {
-    super::logic::toggle_pause_and_notify(&handle, &modules).map_err(CommandError::Anyhow)
+    super::logic::toggle_pause_and_notify(&handle, &modules, game_id).map_err(CommandError::Anyhow)
 }
 
 #[tauri::command]
-pub async fn get_pause_state(modules: State<'_, Arc<Modules>>) -> Result<bool, CommandError> {
-    Ok(modules.pause_manager().is_paused())
+pub async fn get_pause_state(
+    modules: State<'_, Arc<Modules>>,
+    game_id: Option<i32>,
+) -> Result<bool, CommandError> {
+    let game_id = game_id.or_else(|| {
+        modules
+            .pause_manager()
+            .focused_session()
+            .map(|session| session.game_id)
+    });
+    Ok(game_id.is_some_and(|game_id| modules.pause_manager().is_paused(game_id)))
 }
 
 #[tauri::command]
 pub async fn get_tracking_state(
     modules: State<'_, Arc<Modules>>,
 ) -> Result<TrackingState, CommandError> {
-    let session = modules.pause_manager().tracking_session();
+    let session = modules.pause_manager().focused_session();
     Ok(TrackingState {
         is_tracking: modules.pause_manager().is_tracking(),
-        is_paused: modules.pause_manager().is_paused(),
+        is_paused: session.as_ref().is_some_and(|session| session.is_paused),
+        pause_reason: session.as_ref().and_then(|session| session.pause_reason),
         active_game_id: session.as_ref().map(|session| session.game_id),
-        active_process_id: session.as_ref().map(|session| session.process_id),
+        active_process_id: session.as_ref().and_then(|session| session.process_id),
+        sessions: modules
+            .pause_manager()
+            .sessions()
+            .into_iter()
+            .map(Into::into)
+            .collect(),
     })
 }
+
+#[tauri::command]
+pub async fn set_focused_tracking_session(
+    modules: State<'_, Arc<Modules>>,
+    game_id: i32,
+) -> Result<(), CommandError> {
+    modules
+        .pause_manager()
+        .set_focused_session(game_id)
+        .map_err(|_| anyhow::Error::from(UseCaseError::NoActiveTrackingSession))?;
+    Ok(())
+}
 use crate::domain::repository::screenshot::Screenshot;
 
 #[tauri::command]
@@ -1368,10 +2126,33 @@ pub async fn update_collection_element_path(
     id: i32,
     path: String,
 ) -> Result<(), CommandError> {
-    Ok(modules
+    modules
         .collection_use_case()
-        .update_collection_element_path(&Id::new(id), path)
-        .await?)
+        .update_collection_element_path(&Id::new(id), path.clone())
+        .await?;
+    learn_game_match(&modules, &path, id).await;
+    Ok(())
+}
+
+/// 手で紐づけたファイルを覚え、次の走査から使う。覚えられなくても操作は失敗にしない
+async fn learn_game_match(modules: &Modules, path: &str, game_id: i32) {
+    let exe_path = if path.to_lowercase().ends_with(".lnk") {
+        get_lnk_metadatas(vec![path])
+            .ok()
+            .and_then(|metadatas| metadatas.get(path).map(|v| v.path.clone()))
+    } else {
+        Some(path.to_string())
+    };
+    let Some(exe_path) = exe_path else {
+        return;
+    };
+    if let Err(e) = modules
+        .learned_match_use_case()
+        .learn(&exe_path, game_id)
+        .await
+    {
+        eprintln!("Failed to learn game match for {}: {}", exe_path, e);
+    }
 }
 
 #[tauri::command]
@@ -1385,6 +2166,311 @@ pub async fn delete_collection_element_logical(
         .await?)
 }
 
+#[tauri::command]
+pub async fn get_all_collections(
+    modules: State<'_, Arc<Modules>>,
+) -> Result<Vec<Collection>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_all_collections()
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn create_collection(
+    modules: State<'_, Arc<Modules>>,
+    name: String,
+) -> Result<Collection, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .create_collection(name)
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn rename_collection(
+    modules: State<'_, Arc<Modules>>,
+    collection_id: i32,
+    name: String,
+) -> Result<Collection, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .rename_collection(&Id::new(collection_id), name)
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn delete_collection(
+    modules: State<'_, Arc<Modules>>,
+    collection_id: i32,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .delete_collection(&Id::new(collection_id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn update_collections_order(
+    modules: State<'_, Arc<Modules>>,
+    collection_ids: Vec<i32>,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .update_collections_order(collection_ids.into_iter().map(Id::new).collect())
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_collection_element_ids(
+    modules: State<'_, Arc<Modules>>,
+    collection_id: i32,
+) -> Result<Vec<i32>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_collection_element_ids(&Id::new(collection_id))
+        .await?
+        .into_iter()
+        .map(|v| v.value)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn add_collection_elements(
+    modules: State<'_, Arc<Modules>>,
+    collection_id: i32,
+    element_ids: Vec<i32>,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .add_collection_elements(
+            &Id::new(collection_id),
+            element_ids.into_iter().map(Id::new).collect(),
+        )
+        .await?)
+}
+
+#[tauri::command]
+pub async fn remove_collection_elements(
+    modules: State<'_, Arc<Modules>>,
+    collection_id: i32,
+    element_ids: Vec<i32>,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .remove_collection_elements(
+            &Id::new(collection_id),
+            element_ids.into_iter().map(Id::new).collect(),
+        )
+        .await?)
+}
+
+#[tauri::command]
+pub async fn update_collection_elements_order(
+    modules: State<'_, Arc<Modules>>,
+    collection_id: i32,
+    element_ids: Vec<i32>,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .update_collection_elements_order(
+            &Id::new(collection_id),
+            element_ids.into_iter().map(Id::new).collect(),
+        )
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_all_smart_collections(
+    modules: State<'_, Arc<Modules>>,
+) -> Result<Vec<SmartCollection>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_all_smart_collections()
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn create_smart_collection(
+    modules: State<'_, Arc<Modules>>,
+    name: String,
+    filter: SmartCollectionFilter,
+) -> Result<SmartCollection, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .create_smart_collection(name, filter)
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn update_smart_collection(
+    modules: State<'_, Arc<Modules>>,
+    smart_collection_id: i32,
+    name: String,
+    filter: SmartCollectionFilter,
+) -> Result<SmartCollection, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .update_smart_collection(&Id::new(smart_collection_id), name, filter)
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn delete_smart_collection(
+    modules: State<'_, Arc<Modules>>,
+    smart_collection_id: i32,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .delete_smart_collection(&Id::new(smart_collection_id))
+        .await?)
+}
+
+#[tauri::command]
+pub async fn get_smart_collection_element_ids(
+    modules: State<'_, Arc<Modules>>,
+    smart_collection_id: i32,
+) -> Result<Vec<i32>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .get_smart_collection_element_ids(&Id::new(smart_collection_id))
+        .await?
+        .into_iter()
+        .map(|v| v.value)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn evaluate_smart_collection_filter(
+    modules: State<'_, Arc<Modules>>,
+    filter: SmartCollectionFilter,
+) -> Result<Vec<i32>, CommandError> {
+    Ok(modules
+        .collection_use_case()
+        .evaluate_smart_collection_filter(&filter)
+        .await?
+        .into_iter()
+        .map(|v| v.value)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_all_tags(modules: State<'_, Arc<Modules>>) -> Result<Vec<Tag>, CommandError> {
+    Ok(modules
+        .tag_use_case()
+        .get_all_tags()
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_element_tags(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+) -> Result<Vec<Tag>, CommandError> {
+    Ok(modules
+        .tag_use_case()
+        .get_element_tags(&Id::new(collection_element_id))
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+}
+
+#[tauri::command]
+pub async fn add_element_tags(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+    names: Vec<String>,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .tag_use_case()
+        .add_element_tags(&Id::new(collection_element_id), names)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn remove_element_tag(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+    name: String,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .tag_use_case()
+        .remove_element_tag(&Id::new(collection_element_id), name)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn rename_element_tag(
+    modules: State<'_, Arc<Modules>>,
+    collection_element_id: i32,
+    from: String,
+    to: String,
+) -> Result<(), CommandError> {
+    Ok(modules
+        .tag_use_case()
+        .rename_element_tag(&Id::new(collection_element_id), from, to)
+        .await?)
+}
+
+#[tauri::command]
+pub async fn rename_tag(
+    modules: State<'_, Arc<Modules>>,
+    tag_id: i32,
+    name: String,
+) -> Result<Tag, CommandError> {
+    Ok(modules
+        .tag_use_case()
+        .rename_tag(&Id::new(tag_id), name)
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn merge_tags(
+    modules: State<'_, Arc<Modules>>,
+    source_tag_id: i32,
+    target_tag_id: i32,
+) -> Result<Tag, CommandError> {
+    Ok(modules
+        .tag_use_case()
+        .merge_tags(&Id::new(source_tag_id), &Id::new(target_tag_id))
+        .await?
+        .into())
+}
+
+#[tauri::command]
+pub async fn delete_tag(modules: State<'_, Arc<Modules>>, tag_id: i32) -> Result<(), CommandError> {
+    Ok(modules.tag_use_case().delete_tag(&Id::new(tag_id)).await?)
+}
+
+#[tauri::command]
+pub async fn get_element_ids_by_tag_expression(
+    modules: State<'_, Arc<Modules>>,
+    expression: String,
+) -> Result<Vec<i32>, CommandError> {
+    Ok(modules
+        .tag_use_case()
+        .get_element_ids_by_tag_expression(expression)
+        .await?
+        .into_iter()
+        .map(|v| v.value)
+        .collect())
+}
+
 #[tauri::command]
 pub fn show_main_window(handle: AppHandle) -> Result<(), CommandError> {
     if let Some(window) = handle.get_webview_window("main") {
@@ -1,4 +1,6 @@
-use std::sync::{Mutex, OnceLock};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 
 #[cfg(target_os = "windows")]
@@ -7,7 +9,20 @@ use windows::Win32::{
     UI::WindowsAndMessaging::{GetForegroundWindow, SetForegroundWindow},
 };
 
+use super::models::library::LibraryUpdatedPayload;
 use super::module::{Modules, ModulesExt};
+use crate::domain::{
+    collection::NewCollectionElement,
+    file::get_thumbnail_candidate_urls,
+    library::{is_library_target_file, LibraryEventDebouncer},
+    Id,
+};
+use crate::usecase::idle_detector::{parse_idle_pause_threshold, IDLE_PAUSE_THRESHOLD_SETTING_KEY};
+
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// インストーラーの書き込みが落ち着くまで待つ時間
+const LIBRARY_QUIET_PERIOD: Duration = Duration::from_secs(10);
+const LIBRARY_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 static PAUSE_FOREGROUND_WINDOW: OnceLock<Mutex<Option<isize>>> = OnceLock::new();
 
@@ -45,12 +60,23 @@ fn restore_foreground_window() {
 #[cfg(not(target_os = "windows"))]
 fn restore_foreground_window() {}
 
-pub fn toggle_pause_and_notify(app: &AppHandle, modules: &Modules) -> anyhow::Result<bool> {
-    let is_paused = modules
+/// ポーズを切り替える。game_id が None の場合はフォーカス中のセッション
+pub fn toggle_pause_and_notify(
+    app: &AppHandle,
+    modules: &Modules,
+    game_id: Option<i32>,
+) -> anyhow::Result<bool> {
+    let (_, is_paused) = modules
         .pause_manager()
-        .toggle()
+        .toggle(game_id)
         .map_err(|e| anyhow::anyhow!(e))?;
 
+    notify_pause_state(app, is_paused)?;
+    Ok(is_paused)
+}
+
+/// ポーズ状態に合わせてオーバーレイを切り替え、pause-toggled を発火する
+fn notify_pause_state(app: &AppHandle, is_paused: bool) -> anyhow::Result<()> {
     if let Some(window) = app.get_webview_window("overlay") {
         if is_paused {
             remember_foreground_window();
@@ -63,5 +89,267 @@ pub fn toggle_pause_and_notify(app: &AppHandle, modules: &Modules) -> anyhow::Re
     }
 
     app.emit("pause-toggled", is_paused)?;
-    Ok(is_paused)
+    Ok(())
+}
+
+/// 追跡中に一定時間入力がなければ自動でポーズし、入力が戻れば再開する
+///
+/// 自動ポーズまでの時間は idle_pause_threshold_minutes の設定で、未設定の場合は何もしない
+pub fn spawn_idle_pause_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(IDLE_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let modules = app.state::<Arc<Modules>>().inner().clone();
+            if !modules.pause_manager().is_tracking() {
+                continue;
+            }
+            let setting = modules
+                .collection_use_case()
+                .get_app_setting(IDLE_PAUSE_THRESHOLD_SETTING_KEY.to_string())
+                .await
+                .ok()
+                .flatten();
+            let Some(threshold) = parse_idle_pause_threshold(setting.as_deref()) else {
+                continue;
+            };
+
+            match modules.idle_detector_use_case().poll(threshold) {
+                Ok(changes) if !changes.is_empty() => {
+                    let is_paused = modules
+                        .pause_manager()
+                        .focused_session()
+                        .is_some_and(|session| session.is_paused);
+                    if let Err(e) = notify_pause_state(&app, is_paused) {
+                        eprintln!("Error notifying idle pause: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("Error detecting idle state: {}", e),
+            }
+        }
+    });
+}
+
+/// 前回の起動で中断された追跡セッションを再開するか閉じる
+pub fn spawn_tracking_checkpoint_reconciliation(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let modules = app.state::<Arc<Modules>>().inner().clone();
+        if let Err(e) = modules
+            .collection_use_case()
+            .reconcile_tracking_checkpoints(Arc::new(app.clone()))
+            .await
+        {
+            eprintln!("Error reconciling tracking checkpoints: {}", e);
+        }
+    });
+}
+
+/// 以前のバージョンのプレイ履歴が残っていれば取り込む
+pub fn spawn_legacy_play_history_import(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let modules = app.state::<Arc<Modules>>().inner().clone();
+        match modules
+            .collection_use_case()
+            .import_legacy_play_histories(&Arc::new(app.clone()))
+            .await
+        {
+            Ok(report) if report.imported_files > 0 => {
+                log::info!(
+                    "Imported {} legacy play history files ({} records, {} seconds, {} skipped)",
+                    report.imported_files,
+                    report.imported_records,
+                    report.imported_seconds,
+                    report.skipped_files
+                );
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Error importing legacy play histories: {}", e),
+        }
+    });
+}
+
+/// 見つけたゲームのサムネイルを取得して登録する
+pub async fn register_new_elements(
+    handle: &Arc<AppHandle>,
+    modules: &Modules,
+    new_elements: &[NewCollectionElement],
+) -> anyhow::Result<()> {
+    let new_elements_game_caches = modules
+        .all_game_cache_use_case()
+        .get_by_ids(new_elements.iter().map(|v| v.id.value).collect())
+        .await?;
+    modules
+        .collection_use_case()
+        .concurrency_save_thumbnails_from_candidates(
+            handle,
+            new_elements_game_caches
+                .into_iter()
+                .map(|v| {
+                    let urls = new_elements
+                        .iter()
+                        .find(|element| element.id.value == v.id)
+                        .map(|element| {
+                            get_thumbnail_candidate_urls(element, v.thumbnail_url.clone())
+                        })
+                        .unwrap_or_else(|| vec![v.thumbnail_url.clone()]);
+                    (Id::new(v.id), urls)
+                })
+                .collect(),
+        )
+        .await?;
+
+    modules
+        .collection_use_case()
+        .upsert_collection_elements(new_elements)
+        .await?;
+
+    let new_element_ids = new_elements
+        .iter()
+        .map(|v| v.id.clone())
+        .collect::<Vec<Id<_>>>();
+    modules
+        .collection_use_case()
+        .concurrency_upsert_collection_element_thumbnail_size(handle, new_element_ids)
+        .await?;
+    modules
+        .library_use_case()
+        .mark_registered(&new_elements.iter().map(|v| v.id.value).collect::<Vec<_>>())
+        .await
+}
+
+/// ライブラリのフォルダを監視し、追加されたゲームを登録する
+///
+/// 変更は一定時間落ち着いてからまとめて処理する。
+/// 一致の確からしさが library_auto_register_threshold 以上のものは登録し、それ以外は候補に残す
+pub fn spawn_library_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let modules = app.state::<Arc<Modules>>().inner().clone();
+        let Some(mut receiver) = modules.library_watcher().take_receiver() else {
+            return;
+        };
+        match modules.library_use_case().get_roots().await {
+            Ok(roots) => {
+                if let Err(e) = modules.library_watcher().watch(&roots) {
+                    eprintln!("Error watching library roots: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Error loading library roots: {}", e),
+        }
+        if let Err(e) = modules.library_use_case().check_removed_installs().await {
+            eprintln!("Error checking removed installs: {}", e);
+        }
+
+        let handle = Arc::new(app.clone());
+        let mut debouncer = LibraryEventDebouncer::new(LIBRARY_QUIET_PERIOD);
+        let mut interval = tokio::time::interval(LIBRARY_POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                paths = receiver.recv() => {
+                    let Some(paths) = paths else {
+                        break;
+                    };
+                    for path in paths {
+                        debouncer.push(path, Instant::now());
+                    }
+                }
+                _ = interval.tick() => {
+                    if debouncer.is_empty() {
+                        continue;
+                    }
+                    let paths = debouncer.take_ready(Instant::now());
+                    if paths.is_empty() {
+                        continue;
+                    }
+                    match process_library_changes(&handle, &modules, paths).await {
+                        Ok(payload) if !payload.is_empty() => {
+                            let _ = handle.emit("library-updated", payload);
+                        }
+                        Ok(_) => {}
+                        Err(e) => eprintln!("Error processing library changes: {}", e),
+                    }
+                }
+            }
+        }
+    });
+}
+
+async fn process_library_changes(
+    handle: &Arc<AppHandle>,
+    modules: &Modules,
+    paths: Vec<PathBuf>,
+) -> anyhow::Result<LibraryUpdatedPayload> {
+    let (existing, removed): (Vec<PathBuf>, Vec<PathBuf>) =
+        paths.into_iter().partition(|path| path.exists());
+    let removed_count = modules
+        .library_use_case()
+        .flag_removed_installs(&removed)
+        .await?;
+
+    // フォルダごとコピーされた場合はフォルダの中のファイルをまとめて探す
+    let (dirs, files): (Vec<PathBuf>, Vec<PathBuf>) =
+        existing.into_iter().partition(|path| path.is_dir());
+    let mut files: Vec<String> = files
+        .into_iter()
+        .filter(|path| is_library_target_file(path))
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    if !dirs.is_empty() {
+        files.extend(
+            modules
+                .file_use_case()
+                .concurrency_get_file_paths(
+                    dirs.into_iter()
+                        .map(|path| path.to_string_lossy().to_string())
+                        .collect(),
+                )
+                .await?,
+        );
+    }
+    files.sort();
+    files.dedup();
+    let files = modules
+        .library_use_case()
+        .filter_unregistered_files(files)
+        .await?;
+    if files.is_empty() {
+        return Ok(LibraryUpdatedPayload::new(vec![], 0, removed_count as i32));
+    }
+
+    let all_game_cache = modules
+        .all_game_cache_use_case()
+        .get_all_game_cache()
+        .await?;
+    let brandnames = modules.collection_use_case().get_brandnames().await?;
+    let new_elements = modules
+        .file_use_case()
+        .filter_files_to_collection_elements(
+            handle,
+            modules.matching_rule_use_case().current(),
+            modules.learned_match_use_case().current(),
+            files.clone(),
+            all_game_cache,
+            brandnames,
+            Arc::new(|_| Ok(())),
+            Arc::new(Mutex::new(|| Ok(()))),
+        )
+        .await?;
+    let (registered, suggestions) = modules
+        .library_use_case()
+        .classify_new_elements(new_elements)
+        .await?;
+    if !registered.is_empty() {
+        register_new_elements(handle, modules, &registered).await?;
+    }
+    modules
+        .library_use_case()
+        .save_suggestions(&suggestions)
+        .await?;
+    modules.explored_cache_use_case().add_cache(files).await?;
+
+    Ok(LibraryUpdatedPayload::new(
+        registered.into_iter().map(|v| v.gamename).collect(),
+        suggestions.len() as i32,
+        removed_count as i32,
+    ))
 }
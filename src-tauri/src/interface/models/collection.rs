@@ -7,6 +7,7 @@ use tauri::AppHandle;
 use crate::domain::{
     self,
     file::{get_icon_path, get_thumbnail_path},
+    smart_collection::SmartCollectionFilter,
 };
 
 #[allow(clippy::too_many_arguments)]
@@ -33,6 +34,7 @@ pub struct CollectionElement {
     pub registered_at: String,
     pub thumbnail_width: Option<i32>,
     pub thumbnail_height: Option<i32>,
+    pub tags: Vec<String>,
 }
 
 impl CollectionElement {
@@ -61,10 +63,180 @@ impl CollectionElement {
             st.updated_at.to_rfc3339(),
             st.thumbnail_width,
             st.thumbnail_height,
+            st.tags,
         )
     }
 }
 
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub id: i32,
+    pub name: String,
+    pub sort_order: i32,
+    pub element_count: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<domain::collection::Collection> for Collection {
+    fn from(st: domain::collection::Collection) -> Self {
+        Collection::new(
+            st.id.value,
+            st.name,
+            st.sort_order,
+            st.element_count,
+            st.created_at.to_rfc3339(),
+            st.updated_at.to_rfc3339(),
+        )
+    }
+}
+
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartCollection {
+    pub id: i32,
+    pub name: String,
+    pub filter: SmartCollectionFilter,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<domain::smart_collection::SmartCollection> for SmartCollection {
+    fn from(st: domain::smart_collection::SmartCollection) -> Self {
+        SmartCollection::new(
+            st.id.value,
+            st.name,
+            st.filter,
+            st.created_at.to_rfc3339(),
+            st.updated_at.to_rfc3339(),
+        )
+    }
+}
+
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchTarget {
+    pub id: i32,
+    pub collection_element_id: i32,
+    pub name: String,
+    pub path: String,
+    pub is_default: bool,
+    pub sort_order: i32,
+}
+
+impl From<domain::launch::LaunchTarget> for LaunchTarget {
+    fn from(st: domain::launch::LaunchTarget) -> Self {
+        LaunchTarget::new(
+            st.id.value,
+            st.collection_element_id.value,
+            st.name,
+            st.path,
+            st.is_default,
+            st.sort_order,
+        )
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchHook {
+    pub id: i32,
+    pub collection_element_id: Option<i32>,
+    pub timing: domain::launch_hook::LaunchHookTiming,
+    pub name: String,
+    pub command: String,
+    pub timeout_seconds: u32,
+    pub cancel_launch_on_failure: bool,
+    pub sort_order: i32,
+}
+
+impl From<domain::launch_hook::LaunchHook> for LaunchHook {
+    fn from(st: domain::launch_hook::LaunchHook) -> Self {
+        LaunchHook::new(
+            st.id.value,
+            st.collection_element_id.map(|v| v.value),
+            st.timing,
+            st.name,
+            st.command,
+            st.timeout_seconds,
+            st.cancel_launch_on_failure,
+            st.sort_order,
+        )
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaySession {
+    pub id: i32,
+    pub collection_element_id: i32,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub pid: Option<u32>,
+    pub exe_path: Option<String>,
+    pub played_seconds: i32,
+    pub paused_seconds: i32,
+}
+
+impl From<domain::play_session::PlaySession> for PlaySession {
+    fn from(st: domain::play_session::PlaySession) -> Self {
+        PlaySession::new(
+            st.id.value,
+            st.collection_element_id.value,
+            st.started_at.to_rfc3339(),
+            st.ended_at.map(|v| v.to_rfc3339()),
+            st.pid,
+            st.exe_path,
+            st.played_seconds,
+            st.paused_seconds,
+        )
+    }
+}
+
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaySessionAudit {
+    pub id: i32,
+    pub action: String,
+    pub before: Vec<PlaySession>,
+    pub after: Vec<PlaySession>,
+    pub undo_of: Option<i32>,
+    pub created_at: String,
+    pub undone_at: Option<String>,
+}
+
+impl From<domain::play_session::PlaySessionAudit> for PlaySessionAudit {
+    fn from(st: domain::play_session::PlaySessionAudit) -> Self {
+        PlaySessionAudit::new(
+            st.id.value,
+            st.action.as_str().to_string(),
+            st.before.into_iter().map(Into::into).collect(),
+            st.after.into_iter().map(Into::into).collect(),
+            st.undo_of.map(|v| v.value),
+            st.created_at.to_rfc3339(),
+            st.undone_at.map(|v| v.to_rfc3339()),
+        )
+    }
+}
+
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessCandidate {
+    pub pid: u32,
+    pub name: String,
+    pub exe_path: String,
+    pub score: i32,
+}
+
+impl From<crate::usecase::game_tracker::ProcessCandidate> for ProcessCandidate {
+    fn from(st: crate::usecase::game_tracker::ProcessCandidate) -> Self {
+        ProcessCandidate::new(st.pid, st.name, st.exe_path, st.score)
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct CalculateDistanceKV {
     pub key: String,
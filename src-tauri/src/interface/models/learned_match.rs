@@ -0,0 +1,26 @@
+use derive_new::new;
+use serde::Serialize;
+
+use crate::domain;
+
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LearnedGameMatch {
+    pub id: i32,
+    pub parent_folder: String,
+    pub file_stem: String,
+    pub game_id: i32,
+    pub updated_at: String,
+}
+
+impl From<domain::learned_match::LearnedGameMatch> for LearnedGameMatch {
+    fn from(st: domain::learned_match::LearnedGameMatch) -> Self {
+        LearnedGameMatch::new(
+            st.id.value,
+            st.key.parent_folder,
+            st.key.file_stem,
+            st.game_id,
+            st.updated_at.to_rfc3339(),
+        )
+    }
+}
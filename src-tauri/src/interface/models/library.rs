@@ -0,0 +1,80 @@
+use derive_new::new;
+use serde::Serialize;
+
+use crate::domain;
+
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryRoot {
+    pub id: i32,
+    pub path: String,
+    pub created_at: String,
+}
+
+impl From<domain::library::LibraryRoot> for LibraryRoot {
+    fn from(st: domain::library::LibraryRoot) -> Self {
+        LibraryRoot::new(st.id.value, st.path, st.created_at.to_rfc3339())
+    }
+}
+
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibrarySuggestion {
+    pub id: i32,
+    pub library_root_id: i32,
+    pub file_path: String,
+    pub game_id: i32,
+    pub gamename: String,
+    pub confidence: f32,
+    pub created_at: String,
+}
+
+impl From<domain::library::LibrarySuggestion> for LibrarySuggestion {
+    fn from(st: domain::library::LibrarySuggestion) -> Self {
+        LibrarySuggestion::new(
+            st.id.value,
+            st.library_root_id.value,
+            st.file_path,
+            st.game_id,
+            st.gamename,
+            st.confidence,
+            st.created_at.to_rfc3339(),
+        )
+    }
+}
+
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovedInstall {
+    pub collection_element_id: i32,
+    pub gamename: String,
+    pub path: String,
+    pub detected_at: String,
+}
+
+impl From<domain::library::RemovedInstall> for RemovedInstall {
+    fn from(st: domain::library::RemovedInstall) -> Self {
+        RemovedInstall::new(
+            st.collection_element_id.value,
+            st.gamename,
+            st.path,
+            st.detected_at.to_rfc3339(),
+        )
+    }
+}
+
+/// ライブラリのフォルダの変更を処理した結果
+#[derive(new, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryUpdatedPayload {
+    /// 自動で登録したゲームの名前
+    pub registered: Vec<String>,
+    pub suggestion_count: i32,
+    pub removed_count: i32,
+}
+
+impl LibraryUpdatedPayload {
+    pub fn is_empty(&self) -> bool {
+        self.registered.is_empty() && self.suggestion_count == 0 && self.removed_count == 0
+    }
+}
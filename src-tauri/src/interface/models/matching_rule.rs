@@ -0,0 +1,33 @@
+use derive_new::new;
+use serde::Serialize;
+
+use crate::domain::{
+    self,
+    matching_rule::{MatchingRuleAction, MatchingRuleKind},
+};
+
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchingRuleOverride {
+    pub id: i32,
+    pub kind: MatchingRuleKind,
+    pub action: MatchingRuleAction,
+    pub value: String,
+    pub grandparent: Option<String>,
+    pub game_id: Option<i32>,
+    pub created_at: String,
+}
+
+impl From<domain::matching_rule::MatchingRuleOverride> for MatchingRuleOverride {
+    fn from(st: domain::matching_rule::MatchingRuleOverride) -> Self {
+        MatchingRuleOverride::new(
+            st.id.value,
+            st.kind,
+            st.action,
+            st.value,
+            st.grandparent,
+            st.game_id,
+            st.created_at.to_rfc3339(),
+        )
+    }
+}
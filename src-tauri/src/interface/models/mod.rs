@@ -1,2 +1,6 @@
 pub mod all_game_cache;
 pub mod collection;
+pub mod learned_match;
+pub mod library;
+pub mod matching_rule;
+pub mod tag;
@@ -0,0 +1,18 @@
+use derive_new::new;
+use serde::Serialize;
+
+use crate::domain;
+
+#[derive(new, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tag {
+    pub id: i32,
+    pub name: String,
+    pub element_count: i32,
+}
+
+impl From<domain::tag::Tag> for Tag {
+    fn from(st: domain::tag::Tag) -> Self {
+        Tag::new(st.id.value, st.name, st.element_count)
+    }
+}
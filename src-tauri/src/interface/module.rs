@@ -12,8 +12,11 @@ use crate::{
     },
     usecase::{
         all_game_cache::AllGameCacheUseCase, collection::CollectionUseCase,
-        explored_cache::ExploredCacheUseCase, file::FileUseCase, pause_manager::PauseManager,
-        process::ProcessUseCase,
+        explored_cache::ExploredCacheUseCase, file::FileUseCase,
+        idle_detector::IdleDetectorUseCase, learned_match::LearnedMatchUseCase,
+        library::LibraryUseCase, library_watcher::LibraryWatcher,
+        matching_rule::MatchingRuleUseCase, pause_manager::PauseManager,
+        play_stats::PlayStatsUseCase, process::ProcessUseCase, tag::TagUseCase,
     },
 };
 
@@ -24,6 +27,13 @@ pub struct Modules {
     file_use_case: FileUseCase<Explorers>,
     all_game_cache_use_case: AllGameCacheUseCase<Repositories>,
     process_use_case: ProcessUseCase<Windows>,
+    idle_detector_use_case: IdleDetectorUseCase<Windows>,
+    tag_use_case: TagUseCase<Repositories>,
+    play_stats_use_case: PlayStatsUseCase<Repositories>,
+    library_use_case: LibraryUseCase<Repositories>,
+    library_watcher: LibraryWatcher,
+    matching_rule_use_case: MatchingRuleUseCase<Repositories>,
+    learned_match_use_case: LearnedMatchUseCase<Repositories>,
     pause_manager: PauseManager,
 }
 pub trait ModulesExt {
@@ -37,6 +47,13 @@ pub trait ModulesExt {
 
     fn file_use_case(&self) -> &FileUseCase<Self::Explorers>;
     fn process_use_case(&self) -> &ProcessUseCase<Self::Windows>;
+    fn idle_detector_use_case(&self) -> &IdleDetectorUseCase<Self::Windows>;
+    fn tag_use_case(&self) -> &TagUseCase<Self::Repositories>;
+    fn play_stats_use_case(&self) -> &PlayStatsUseCase<Self::Repositories>;
+    fn library_use_case(&self) -> &LibraryUseCase<Self::Repositories>;
+    fn library_watcher(&self) -> &LibraryWatcher;
+    fn matching_rule_use_case(&self) -> &MatchingRuleUseCase<Self::Repositories>;
+    fn learned_match_use_case(&self) -> &LearnedMatchUseCase<Self::Repositories>;
     fn pause_manager(&self) -> &PauseManager;
 }
 
@@ -61,6 +78,27 @@ impl ModulesExt for Modules {
     fn process_use_case(&self) -> &ProcessUseCase<Self::Windows> {
         &self.process_use_case
     }
+    fn idle_detector_use_case(&self) -> &IdleDetectorUseCase<Self::Windows> {
+        &self.idle_detector_use_case
+    }
+    fn tag_use_case(&self) -> &TagUseCase<Self::Repositories> {
+        &self.tag_use_case
+    }
+    fn play_stats_use_case(&self) -> &PlayStatsUseCase<Self::Repositories> {
+        &self.play_stats_use_case
+    }
+    fn library_use_case(&self) -> &LibraryUseCase<Self::Repositories> {
+        &self.library_use_case
+    }
+    fn library_watcher(&self) -> &LibraryWatcher {
+        &self.library_watcher
+    }
+    fn matching_rule_use_case(&self) -> &MatchingRuleUseCase<Self::Repositories> {
+        &self.matching_rule_use_case
+    }
+    fn learned_match_use_case(&self) -> &LearnedMatchUseCase<Self::Repositories> {
+        &self.learned_match_use_case
+    }
     fn pause_manager(&self) -> &PauseManager {
         &self.pause_manager
     }
@@ -77,14 +115,37 @@ impl Modules {
         let explored_cache_use_case = ExploredCacheUseCase::new(repositories.clone());
         let all_game_cache_use_case: AllGameCacheUseCase<Repositories> =
             AllGameCacheUseCase::new(repositories.clone());
+        if let Err(e) = all_game_cache_use_case.refresh_normalized_gamenames().await {
+            eprintln!("Failed to normalize game names: {}", e);
+        }
 
         let file_use_case: FileUseCase<Explorers> = FileUseCase::new(explorers.clone());
 
         let process_use_case: ProcessUseCase<Windows> = ProcessUseCase::new(windows.clone());
+        let tag_use_case: TagUseCase<Repositories> = TagUseCase::new(repositories.clone());
+        let play_stats_use_case: PlayStatsUseCase<Repositories> =
+            PlayStatsUseCase::new(repositories.clone());
+        let library_use_case: LibraryUseCase<Repositories> =
+            LibraryUseCase::new(repositories.clone());
+        let matching_rule_use_case: MatchingRuleUseCase<Repositories> =
+            MatchingRuleUseCase::new(repositories.clone())?;
+        // 走査の前にユーザーによる規則の上書きを読み込む
+        if let Err(e) = matching_rule_use_case.reload().await {
+            eprintln!("Failed to load matching rule overrides: {}", e);
+        }
+        let learned_match_use_case: LearnedMatchUseCase<Repositories> =
+            LearnedMatchUseCase::new(repositories.clone());
+        if let Err(e) = learned_match_use_case.reload().await {
+            eprintln!("Failed to load learned game matches: {}", e);
+        }
         let pause_manager = PauseManager::new();
+        let idle_detector_use_case: IdleDetectorUseCase<Windows> =
+            IdleDetectorUseCase::new(windows.clone(), Arc::new(pause_manager.clone()));
         let screenshot_watcher = crate::usecase::screenshot_watcher::ScreenshotWatcher::new(
             repositories.clone(),
+            Arc::new(pause_manager.clone()),
             Arc::new(std::sync::Mutex::new(None)),
+            Arc::new(std::sync::Mutex::new(vec![])),
         );
 
         let collection_use_case = CollectionUseCase::new(
@@ -101,6 +162,13 @@ impl Modules {
 
             file_use_case,
             process_use_case,
+            idle_detector_use_case,
+            tag_use_case,
+            play_stats_use_case,
+            library_use_case,
+            library_watcher: LibraryWatcher::new(),
+            matching_rule_use_case,
+            learned_match_use_case,
             pause_manager,
         })
     }
@@ -23,7 +23,7 @@ pub async fn handle_shortcut(app_handle: AppHandle, shortcut: Shortcut) {
                     if let Ok(game_id) = game_id_str.parse::<i32>() {
                         if let Err(e) = modules
                             .collection_use_case()
-                            .play_game_and_track(app_handle.clone().into(), game_id)
+                            .play_game_and_track(app_handle.clone().into(), game_id, None)
                             .await
                         {
                             eprintln!("Error playing game: {}", e);
@@ -43,7 +43,7 @@ pub async fn handle_shortcut(app_handle: AppHandle, shortcut: Shortcut) {
         if let Ok(pause_shortcut) = pause_shortcut_key.parse::<Shortcut>() {
             if shortcut == pause_shortcut {
                 // Check if tracking before allowing pause
-                if let Err(e) = super::logic::toggle_pause_and_notify(&app_handle, &modules) {
+                if let Err(e) = super::logic::toggle_pause_and_notify(&app_handle, &modules, None) {
                     eprintln!("Error toggling pause: {}", e);
                 }
             }
@@ -64,7 +64,7 @@ pub async fn handle_shortcut(app_handle: AppHandle, shortcut: Shortcut) {
 
     if let Ok(screenshot_shortcut) = screenshot_shortcut_str.parse::<Shortcut>() {
         if shortcut == screenshot_shortcut {
-            if let Some(session) = modules.pause_manager().tracking_session() {
+            if let Some(session) = modules.pause_manager().focused_session() {
                 let work_id = session.game_id;
                 let app_handle_arc = Arc::new(app_handle.clone());
 
@@ -174,6 +174,10 @@ fn main() {
                 // Modulesの初期化を先に行う
                 let modules = Arc::new(tauri::async_runtime::block_on(Modules::new(app.handle()))?);
                 app.manage(modules);
+                interface::logic::spawn_idle_pause_monitor(app.handle().clone());
+                interface::logic::spawn_tracking_checkpoint_reconciliation(app.handle().clone());
+                interface::logic::spawn_legacy_play_history_import(app.handle().clone());
+                interface::logic::spawn_library_watcher(app.handle().clone());
 
                 app.manage(TrayLeftClickMenuToken::new());
 
@@ -326,12 +330,44 @@ fn main() {
         )
         .invoke_handler(tauri::generate_handler![
             command::create_elements_in_pc,
+            command::scan_elements_in_pc,
+            command::get_library_roots,
+            command::add_library_root,
+            command::remove_library_root,
+            command::get_library_suggestions,
+            command::accept_library_suggestion,
+            command::dismiss_library_suggestion,
+            command::get_removed_installs,
+            command::dismiss_removed_install,
+            command::get_matching_rules,
+            command::get_matching_rule_overrides,
+            command::add_matching_rule_override,
+            command::delete_matching_rule_override,
+            command::get_learned_game_matches,
+            command::delete_learned_game_match,
+            command::export_learned_game_matches,
+            command::import_learned_game_matches,
             command::get_nearest_key_and_distance,
             command::upload_image,
             command::upsert_collection_element,
             command::update_collection_element_icon,
             command::get_default_import_dirs,
             command::play_game,
+            command::get_process_candidates,
+            command::attach_game_process,
+            command::stop_tracking_session,
+            command::get_launch_targets,
+            command::add_launch_target,
+            command::update_launch_target,
+            command::delete_launch_target,
+            command::set_default_launch_target,
+            command::get_launch_profile,
+            command::save_launch_profile,
+            command::delete_launch_profile,
+            command::get_launch_hooks,
+            command::add_launch_hook,
+            command::update_launch_hook,
+            command::delete_launch_hook,
             command::get_play_time_minutes,
             command::get_collection_element,
             command::delete_collection_element,
@@ -342,6 +378,24 @@ fn main() {
             command::update_element_play_status, // 追加
             command::adjust_untracked_play_time_seconds,
             command::get_collection_element_daily_play_times,
+            command::get_day_boundary,
+            command::update_day_boundary,
+            command::get_play_sessions,
+            command::get_play_sessions_by_range,
+            command::get_play_session_summary,
+            command::add_play_session,
+            command::update_play_session,
+            command::delete_play_session,
+            command::split_play_session,
+            command::get_play_session_audits,
+            command::undo_play_session_audit,
+            command::import_external_play_times,
+            command::get_play_time_buckets,
+            command::get_top_played_games,
+            command::get_brand_play_times,
+            command::get_sellday_year_play_times,
+            command::get_longest_play_streak,
+            command::get_play_session_stats,
             command::open_folder,
             command::get_all_game_cache_last_updated,
             command::update_all_game_cache,
@@ -365,6 +419,7 @@ fn main() {
             command::restore_foreground_window,
             command::get_pause_state,
             command::get_tracking_state,
+            command::set_focused_tracking_session,
             command::get_game_screenshots,
             command::get_all_screenshots,
             command::open_screenshot_window,
@@ -374,6 +429,30 @@ fn main() {
             command::update_screenshots_order,
             command::update_collection_element_path,
             command::delete_collection_element_logical,
+            command::get_all_collections,
+            command::create_collection,
+            command::rename_collection,
+            command::delete_collection,
+            command::update_collections_order,
+            command::get_collection_element_ids,
+            command::add_collection_elements,
+            command::remove_collection_elements,
+            command::update_collection_elements_order,
+            command::get_all_smart_collections,
+            command::create_smart_collection,
+            command::update_smart_collection,
+            command::delete_smart_collection,
+            command::get_smart_collection_element_ids,
+            command::evaluate_smart_collection_filter,
+            command::get_all_tags,
+            command::get_element_tags,
+            command::add_element_tags,
+            command::remove_element_tag,
+            command::rename_element_tag,
+            command::rename_tag,
+            command::merge_tags,
+            command::delete_tag,
+            command::get_element_ids_by_tag_expression,
             command::show_main_window,
             command::save_main_window_state,
             command::hide_tray_menu,
@@ -0,0 +1,25 @@
+-- Add stable ordering to user-defined collections and their elements
+
+ALTER TABLE collections ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE collection_element_maps ADD COLUMN order_index INTEGER NOT NULL DEFAULT 0;
+
+-- Keep only the first map for each (collection, element) pair
+DELETE FROM collection_element_maps WHERE id NOT IN (
+    SELECT MIN(id) FROM collection_element_maps
+    GROUP BY collection_id, collection_element_id
+);
+
+-- Set order based on insertion order for existing records
+UPDATE collections SET sort_order = (
+    SELECT COUNT(*) FROM collections AS c2
+    WHERE c2.id < collections.id
+);
+
+UPDATE collection_element_maps SET order_index = (
+    SELECT COUNT(*) FROM collection_element_maps AS m2
+    WHERE m2.collection_id = collection_element_maps.collection_id
+    AND m2.id < collection_element_maps.id
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_collection_element_maps_collection_id_element_id
+ON collection_element_maps(collection_id, collection_element_id);
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS collection_element_launch_targets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    collection_element_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL,
+    is_default INTEGER NOT NULL DEFAULT 0,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (collection_element_id) REFERENCES collection_elements(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_collection_element_launch_targets_element_id
+ON collection_element_launch_targets(collection_element_id);
+
+-- 既定の起動対象はエレメントごとに1つまで
+CREATE UNIQUE INDEX IF NOT EXISTS idx_collection_element_launch_targets_default
+ON collection_element_launch_targets(collection_element_id) WHERE is_default = 1;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS collection_element_launch_profiles (
+    collection_element_id INTEGER PRIMARY KEY,
+    arguments_json TEXT NOT NULL DEFAULT '[]',
+    working_directory TEXT,
+    environment_json TEXT NOT NULL DEFAULT '{}',
+    wrapper_command TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (collection_element_id) REFERENCES collection_elements(id) ON DELETE CASCADE
+);
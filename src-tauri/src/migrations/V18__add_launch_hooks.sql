@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS launch_hooks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- NULL means the hook runs for every game
+    collection_element_id INTEGER,
+    timing TEXT NOT NULL,
+    name TEXT NOT NULL,
+    command TEXT NOT NULL,
+    timeout_seconds INTEGER NOT NULL DEFAULT 60,
+    cancel_launch_on_failure INTEGER NOT NULL DEFAULT 0,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (collection_element_id) REFERENCES collection_elements(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_launch_hooks_collection_element_id ON launch_hooks(collection_element_id);
@@ -0,0 +1,17 @@
+-- One row per tracked play session. ended_at stays NULL while the session is running
+CREATE TABLE IF NOT EXISTS play_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    collection_element_id INTEGER NOT NULL,
+    started_at DATETIME NOT NULL,
+    ended_at DATETIME,
+    pid INTEGER,
+    exe_path TEXT,
+    played_seconds INTEGER NOT NULL DEFAULT 0,
+    paused_seconds INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (collection_element_id) REFERENCES collection_elements(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_play_sessions_element_started_at ON play_sessions(collection_element_id, started_at);
+CREATE INDEX IF NOT EXISTS idx_play_sessions_started_at ON play_sessions(started_at);
@@ -0,0 +1,19 @@
+-- Checkpoint of a running tracking session, refreshed on every monitor tick.
+-- The row is removed when the session ends normally, so rows left at startup belong to
+-- sessions interrupted by a crash or an update and are reconciled then.
+CREATE TABLE IF NOT EXISTS tracking_checkpoints (
+    collection_element_id INTEGER PRIMARY KEY,
+    play_session_id INTEGER,
+    pid INTEGER NOT NULL,
+    -- process start time (unix seconds) to tell a reused pid from the game process
+    process_started_at INTEGER,
+    game_path TEXT NOT NULL,
+    game_name TEXT NOT NULL,
+    started_at DATETIME NOT NULL,
+    heartbeat_at DATETIME NOT NULL,
+    played_seconds INTEGER NOT NULL DEFAULT 0,
+    paused_seconds INTEGER NOT NULL DEFAULT 0,
+    is_paused INTEGER NOT NULL DEFAULT 0,
+    FOREIGN KEY (collection_element_id) REFERENCES collection_elements(id) ON DELETE CASCADE,
+    FOREIGN KEY (play_session_id) REFERENCES play_sessions(id) ON DELETE SET NULL
+);
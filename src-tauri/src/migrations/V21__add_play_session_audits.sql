@@ -0,0 +1,15 @@
+-- Audit trail of manual play session changes (add / edit / delete / split / undo).
+-- before_json and after_json hold the sessions removed and inserted by the change,
+-- which is enough to undo it.
+CREATE TABLE IF NOT EXISTS play_session_audits (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    action TEXT NOT NULL,
+    before_json TEXT NOT NULL DEFAULT '[]',
+    after_json TEXT NOT NULL DEFAULT '[]',
+    undo_of INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    undone_at DATETIME,
+    FOREIGN KEY (undo_of) REFERENCES play_session_audits(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_play_session_audits_created_at ON play_session_audits(created_at);
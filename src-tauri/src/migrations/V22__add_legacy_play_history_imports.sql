@@ -0,0 +1,10 @@
+-- Legacy <id>.jsonl play histories that have been folded into the daily play times.
+-- The row is written in the same transaction as the import, so a file is never
+-- counted twice even if renaming it afterwards fails.
+CREATE TABLE IF NOT EXISTS legacy_play_history_imports (
+    collection_element_id INTEGER PRIMARY KEY,
+    record_count INTEGER NOT NULL DEFAULT 0,
+    imported_seconds INTEGER NOT NULL DEFAULT 0,
+    imported_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (collection_element_id) REFERENCES collection_elements(id) ON DELETE CASCADE
+);
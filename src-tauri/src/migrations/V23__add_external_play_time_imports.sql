@@ -0,0 +1,16 @@
+-- Play time imported from other launchers and stores, one row per game in each source.
+-- imported_seconds is the source's total that has already been credited, so importing
+-- the same file again only adds what was played since, and the largest total across
+-- sources is credited once for each collection element.
+CREATE TABLE IF NOT EXISTS external_play_time_imports (
+    source TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    collection_element_id INTEGER NOT NULL,
+    imported_seconds INTEGER NOT NULL DEFAULT 0,
+    last_session_ended_at DATETIME,
+    imported_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (source, external_id),
+    FOREIGN KEY (collection_element_id) REFERENCES collection_elements(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_external_play_time_imports_element_id ON external_play_time_imports(collection_element_id);
@@ -0,0 +1,27 @@
+-- Folders watched for newly installed games
+CREATE TABLE IF NOT EXISTS library_roots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    path TEXT NOT NULL UNIQUE,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Files found in a watched folder whose match was below the auto-register threshold.
+-- game_id is the ErogameScape id of the candidate, which is not registered yet
+CREATE TABLE IF NOT EXISTS library_suggestions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    library_root_id INTEGER NOT NULL,
+    file_path TEXT NOT NULL UNIQUE,
+    game_id INTEGER NOT NULL,
+    gamename TEXT NOT NULL,
+    confidence REAL NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (library_root_id) REFERENCES library_roots(id) ON DELETE CASCADE
+);
+
+-- Registered games whose install folder was removed from a watched folder
+CREATE TABLE IF NOT EXISTS library_removed_installs (
+    collection_element_id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL,
+    detected_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (collection_element_id) REFERENCES collection_elements(id) ON DELETE CASCADE
+);
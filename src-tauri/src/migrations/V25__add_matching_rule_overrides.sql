@@ -0,0 +1,11 @@
+-- User overrides merged on top of the matching rules file bundled with the app.
+-- value holds the word, the file name or the parent folder name depending on kind
+CREATE TABLE IF NOT EXISTS matching_rule_overrides (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    action TEXT NOT NULL,
+    value TEXT NOT NULL DEFAULT '',
+    grandparent TEXT,
+    game_id INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,11 @@
+-- Matches the user corrected by hand, keyed by the normalized parent folder name and file stem.
+-- game_id is the ErogameScape id
+CREATE TABLE IF NOT EXISTS learned_game_matches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    parent_folder TEXT NOT NULL,
+    file_stem TEXT NOT NULL,
+    game_id INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (parent_folder, file_stem)
+);
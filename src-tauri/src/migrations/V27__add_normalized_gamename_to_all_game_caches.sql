@@ -0,0 +1,3 @@
+-- Game names normalized the same way as file and folder names, used for search.
+-- Filled in on startup for rows cached before this column existed
+ALTER TABLE all_game_caches ADD COLUMN normalized_gamename TEXT NOT NULL DEFAULT '';
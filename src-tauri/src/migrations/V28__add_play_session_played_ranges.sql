@@ -0,0 +1,11 @@
+-- Ranges of a play session that were actually played, excluding pauses and gaps.
+-- Used to split the session by play date again when the day boundary changes or the session is edited
+CREATE TABLE IF NOT EXISTS play_session_played_ranges (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    play_session_id INTEGER NOT NULL,
+    started_at DATETIME NOT NULL,
+    ended_at DATETIME NOT NULL,
+    FOREIGN KEY (play_session_id) REFERENCES play_sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_play_session_played_ranges_session ON play_session_played_ranges(play_session_id, started_at);
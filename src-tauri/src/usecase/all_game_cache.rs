@@ -73,4 +73,11 @@ impl<R: RepositoriesExt> AllGameCacheUseCase<R> {
             .update(cache)
             .await
     }
+    /// 正規化の仕方が変わったときのため、検索用のゲーム名をそろえ直す
+    pub async fn refresh_normalized_gamenames(&self) -> anyhow::Result<u64> {
+        self.repositories
+            .all_game_cache_repository()
+            .refresh_normalized_gamenames()
+            .await
+    }
 }
@@ -1,30 +1,70 @@
-use std::{fs, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use chrono::Local;
+use chrono::{DateTime, Local};
 use derive_new::new;
-use sysinfo::PidExt;
+use futures::future::BoxFuture;
+use sysinfo::{PidExt, ProcessExt, System, SystemExt};
 use tauri::{AppHandle, Emitter};
-use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
 use tokio::time::{Duration, Instant};
 
 use super::error::UseCaseError;
+use super::external_play_time::{
+    match_external_record, plan_external_import, ExternalImportEntry, ExternalImportReport,
+    ExternalImportStatus,
+};
 use super::game_tracker::{
-    is_path_related_error, launch_game, split_play_time_by_local_date, GameProcessMonitor,
-    ProcessSearchConfig,
+    is_path_related_error, launch_game, list_process_candidates, process_started_at,
+    GameProcessMonitor, ProcessCandidate, ProcessSearchConfig, TrackingTarget,
 };
+use super::launch_hook::{run_launch_hooks, skip_launch_hooks};
+use super::legacy_play_history::{fold_play_histories, LegacyPlayHistoryImportReport};
 use super::pause_manager::PauseManager;
+use super::play_session_editor::{build_play_session_edit, ElementPlayState};
+use super::play_time::SystemClock;
 use crate::{
     domain::repository::repositories::RepositoriesExt,
     domain::{
-        collection::{CollectionElement, NewCollectionElement, NewCollectionElementDetail},
+        all_game_cache::AllGameCacheOne,
+        candidate_index::GameCandidateIndex,
+        collection::{
+            Collection, CollectionElement, NewCollection, NewCollectionElement,
+            NewCollectionElementDetail,
+        },
+        external_play_time::{
+            parse_external_play_records, ExternalPlayTimeImport, ExternalPlayTimeSource,
+        },
         file::{
-            ensure_screenshot_thumbnail, get_icon_path, get_lnk_metadatas,
-            get_screenshot_thumbnail_path, get_thumbnail_path, save_icon_to_png,
-            save_thumbnail_from_candidates,
+            ensure_screenshot_thumbnail, find_legacy_play_history_files, get_icon_path,
+            get_imported_play_history_path, get_legacy_play_history_paths, get_lnk_metadatas,
+            get_screenshot_thumbnail_path, get_steam_app_metadata_by_path, get_thumbnail_path,
+            parse_play_histories, save_icon_to_png, save_thumbnail_from_candidates,
+        },
+        launch::{resolve_launch_path, LaunchProfile, LaunchTarget, NewLaunchTarget},
+        launch_hook::{LaunchHook, LaunchHookContext, LaunchHookTiming, NewLaunchHook},
+        learned_match::LearnedGameMatches,
+        matching_rule::MatchingRules,
+        normalize::normalize,
+        play_day::{
+            compute_daily_play_time_rebucket, split_play_time_by_local_date, DayBoundary,
+            DAY_BOUNDARY_SETTING_KEY,
+        },
+        play_session::{
+            split_play_session, summarize_play_sessions, ManualPlaySession, PlaySession,
+            PlaySessionAudit, PlaySessionEditAction, PlaySessionSummary,
         },
         repository::collection::{CollectionRepository, DailyPlayTime, GameScreenshotCache},
         repository::screenshot::{Screenshot, ScreenshotRepository},
+        smart_collection::{NewSmartCollection, SmartCollection, SmartCollectionFilter},
+        tracking_checkpoint::{
+            checkpoint_resume_gap, resolve_tracking_checkpoint, CheckpointResolution,
+            TrackingCheckpoint,
+        },
         Id,
     },
 };
@@ -45,12 +85,60 @@ impl<R: RepositoriesExt + Send + Sync + 'static> CollectionUseCase<R> {
     /// 2. ゲームプロセスの検索
     /// 3. プレイ時間の記録
     /// 4. スクリーンショットの監視
+    ///
+    /// `launch_target_id` を省略した場合は既定の起動対象を使う。
+    /// どの起動対象から起動してもプレイ時間は同じエレメントに記録される。
+    /// 起動前フックは起動の直前に、終了後フックは監視の後片付けが終わった後に実行する。
+    /// 同じゲームを二重に起動しないよう、フックや起動の前にセッションを予約する
     pub async fn play_game_and_track(
         &self,
         handle: Arc<AppHandle>,
         element_id: i32,
+        launch_target_id: Option<i32>,
+    ) -> anyhow::Result<()> {
+        self.pause_manager
+            .reserve_session(element_id)
+            .map_err(|_| UseCaseError::GameIsAlreadyTracked)?;
+        let result = self
+            .launch_and_track(handle, element_id, launch_target_id)
+            .await;
+        if result.is_err() {
+            self.pause_manager.release_reservation(element_id);
+        }
+        result
+    }
+
+    async fn launch_and_track(
+        &self,
+        handle: Arc<AppHandle>,
+        element_id: i32,
+        launch_target_id: Option<i32>,
     ) -> anyhow::Result<()> {
         let element = self.get_element_by_element_id(&Id::new(element_id)).await?;
+        let targets = self
+            .repositories
+            .collection_repository()
+            .get_launch_targets_by_element_id(&element.id)
+            .await?;
+        let resolved = resolve_launch_path(&element, &targets, launch_target_id)?;
+        let profile = self
+            .repositories
+            .collection_repository()
+            .get_launch_profile(&element.id)
+            .await?;
+        let hooks = self
+            .repositories
+            .collection_repository()
+            .get_launch_hooks_for_element(&element.id)
+            .await?;
+        let hook_context = LaunchHookContext::new(element_id, resolved.path.clone());
+
+        // 起動前フック
+        if let Err(hook_name) =
+            run_launch_hooks(&hooks, LaunchHookTiming::PreLaunch, &hook_context).await
+        {
+            return Err(UseCaseError::PreLaunchHookFailed(hook_name).into());
+        }
 
         self.update_element_last_play_at(&Id::new(element_id))
             .await?;
@@ -58,12 +146,12 @@ impl<R: RepositoriesExt + Send + Sync + 'static> CollectionUseCase<R> {
         let tracking_started_at = Instant::now();
 
         // ゲームを起動
-        let launch_result = match launch_game(&element) {
+        let launch_result = match launch_game(&resolved.path, profile.as_ref()) {
             Ok(result) => result,
             Err(e) => {
-                // パス関連のエラーの場合、未インストール状態にする
+                // エレメント本体のパスでパス関連のエラーの場合、未インストール状態にする
                 if let Some(io_error) = e.downcast_ref::<std::io::Error>() {
-                    if is_path_related_error(io_error) {
+                    if resolved.launch_target_id.is_none() && is_path_related_error(io_error) {
                         self.delete_collection_element_logical(&Id::new(element_id))
                             .await?;
                     }
@@ -72,23 +160,250 @@ impl<R: RepositoriesExt + Send + Sync + 'static> CollectionUseCase<R> {
             }
         };
 
-        let game_name = element.gamename.clone();
-        let path_str = launch_result.path_str.clone();
-        let spawned_pid = launch_result.spawned_pid;
+        self.spawn_tracking(
+            handle,
+            TrackingTarget {
+                element_id,
+                game_name: element.gamename.clone(),
+                path_str: launch_result.path_str.clone(),
+                spawned_pid: launch_result.spawned_pid,
+                started_at: tracking_started_at,
+                resume_from: None,
+            },
+            hooks,
+            hook_context,
+        )
+    }
+
+    /// 起動済みのプロセスにアタッチしてプレイ時間の追跡を始める
+    ///
+    /// アタッチ前の時間は記録しない。起動前フックを実行していないため終了後フックも実行しない
+    pub async fn attach_game_process(
+        &self,
+        handle: Arc<AppHandle>,
+        element_id: i32,
+        pid: u32,
+    ) -> anyhow::Result<()> {
+        self.pause_manager
+            .reserve_session(element_id)
+            .map_err(|_| UseCaseError::GameIsAlreadyTracked)?;
+        let result = self.attach_and_track(handle, element_id, pid).await;
+        if result.is_err() {
+            self.pause_manager.release_reservation(element_id);
+        }
+        result
+    }
+
+    async fn attach_and_track(
+        &self,
+        handle: Arc<AppHandle>,
+        element_id: i32,
+        pid: u32,
+    ) -> anyhow::Result<()> {
+        let element = self.get_element_by_element_id(&Id::new(element_id)).await?;
+        let mut system = System::new();
+        let sys_pid = sysinfo::Pid::from_u32(pid);
+        if !system.refresh_process(sys_pid) {
+            return Err(UseCaseError::ProcessIsNotFound(pid).into());
+        }
+        let path_str = match self.get_element_launch_path(&element).await {
+            Some(path) => path,
+            None => system
+                .process(sys_pid)
+                .map(|process| process.exe().to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+
+        self.update_element_last_play_at(&Id::new(element_id))
+            .await?;
+        let hook_context = LaunchHookContext::new(element_id, path_str.clone());
+        self.spawn_tracking(
+            handle,
+            TrackingTarget {
+                element_id,
+                game_name: element.gamename.clone(),
+                path_str,
+                spawned_pid: Some(pid),
+                started_at: Instant::now(),
+                resume_from: None,
+            },
+            vec![],
+            hook_context,
+        )
+    }
+
+    /// 手動でアタッチするプロセスの候補をスコアの高い順に返す
+    pub async fn get_process_candidates(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Vec<ProcessCandidate>> {
+        let element = self.get_element_by_element_id(element_id).await?;
+        let path_str = self
+            .get_element_launch_path(&element)
+            .await
+            .unwrap_or_default();
+        Ok(list_process_candidates(
+            &path_str,
+            &element.gamename,
+            &ProcessSearchConfig::default(),
+        )
+        .await)
+    }
+
+    /// 追跡中のセッションを終了する。ゲームは終了させず、それまでのプレイ時間は記録する
+    ///
+    /// element_id が None の場合はフォーカス中のセッションを終了する
+    pub fn stop_tracking(&self, element_id: Option<i32>) -> anyhow::Result<()> {
+        self.pause_manager
+            .request_stop(element_id)
+            .map_err(|_| UseCaseError::NoActiveTrackingSession.into())
+    }
+
+    /// 前回の起動で中断された追跡セッションを整理する
+    ///
+    /// ゲームがまだ動いていれば追跡を再開し、終了していれば最後の heartbeat でセッションを閉じる。
+    /// 再開したセッションでは終了後フックを実行しない
+    pub async fn reconcile_tracking_checkpoints(
+        &self,
+        handle: Arc<AppHandle>,
+    ) -> anyhow::Result<()> {
+        let checkpoints = self
+            .repositories
+            .collection_repository()
+            .get_tracking_checkpoints()
+            .await?;
+        if checkpoints.is_empty() {
+            return Ok(());
+        }
+        let mut system = System::new();
+        system.refresh_processes();
+
+        for checkpoint in checkpoints {
+            let element_id = checkpoint.collection_element_id.value;
+            let live_process_started_at = process_started_at(&system, checkpoint.pid);
+            let resolution = resolve_tracking_checkpoint(&checkpoint, live_process_started_at);
+            if resolution == CheckpointResolution::Reattach {
+                match self.reattach_tracking(handle.clone(), checkpoint.clone()) {
+                    Ok(()) => {
+                        println!(
+                            "Reattached tracking session (element: {}, PID: {})",
+                            element_id, checkpoint.pid
+                        );
+                        continue;
+                    }
+                    Err(e) => eprintln!("Failed to reattach tracking session: {}", e),
+                }
+            }
+            self.close_tracking_checkpoint(&checkpoint).await?;
+            let _ = handle.emit("collection-element-updated", element_id);
+        }
+        Ok(())
+    }
+
+    fn reattach_tracking(
+        &self,
+        handle: Arc<AppHandle>,
+        checkpoint: TrackingCheckpoint,
+    ) -> anyhow::Result<()> {
+        let element_id = checkpoint.collection_element_id.value;
+        let is_paused = checkpoint.is_paused;
+        let gap = checkpoint_resume_gap(&checkpoint, Local::now());
+        let started_at = Instant::now().checked_sub(gap).unwrap_or_else(Instant::now);
+        let hook_context = LaunchHookContext::new(element_id, checkpoint.game_path.clone());
+        self.pause_manager
+            .reserve_session(element_id)
+            .map_err(|_| UseCaseError::GameIsAlreadyTracked)?;
+        if let Err(e) = self.spawn_tracking(
+            handle,
+            TrackingTarget {
+                element_id,
+                game_name: checkpoint.game_name.clone(),
+                path_str: checkpoint.game_path.clone(),
+                spawned_pid: Some(checkpoint.pid),
+                started_at,
+                resume_from: Some(checkpoint),
+            },
+            vec![],
+            hook_context,
+        ) {
+            self.pause_manager.release_reservation(element_id);
+            return Err(e);
+        }
+        if is_paused {
+            let _ = self.pause_manager.set_paused(element_id, true);
+        }
+        Ok(())
+    }
+
+    /// 中断されたセッションを最後の heartbeat の時刻で終了する
+    ///
+    /// プレイ時間は tick ごとに記録済みのため、セッションと最終プレイ日時だけを更新する
+    async fn close_tracking_checkpoint(
+        &self,
+        checkpoint: &TrackingCheckpoint,
+    ) -> anyhow::Result<()> {
+        if let Some(session_id) = checkpoint.play_session_id.as_ref() {
+            self.repositories
+                .collection_repository()
+                .finish_play_session(
+                    session_id,
+                    checkpoint.heartbeat_at,
+                    checkpoint.played_seconds,
+                    checkpoint.paused_seconds,
+                )
+                .await?;
+        }
+        self.repositories
+            .collection_repository()
+            .update_element_last_play_at_by_id(
+                &checkpoint.collection_element_id,
+                checkpoint.heartbeat_at,
+            )
+            .await?;
+        self.repositories
+            .collection_repository()
+            .delete_tracking_checkpoint(&checkpoint.collection_element_id)
+            .await
+    }
+
+    async fn get_element_launch_path(&self, element: &CollectionElement) -> Option<String> {
+        let targets = self
+            .repositories
+            .collection_repository()
+            .get_launch_targets_by_element_id(&element.id)
+            .await
+            .ok()?;
+        resolve_launch_path(element, &targets, None)
+            .ok()
+            .map(|v| v.path)
+    }
+
+    /// `target` のゲームは予約済みであること
+    fn spawn_tracking(
+        &self,
+        handle: Arc<AppHandle>,
+        target: TrackingTarget,
+        hooks: Vec<LaunchHook>,
+        hook_context: LaunchHookContext,
+    ) -> anyhow::Result<()> {
+        // 予約を引き継いで追跡状態を開始
+        self.pause_manager
+            .begin_session(target.element_id)
+            .map_err(|_| UseCaseError::GameIsAlreadyTracked)?;
+
+        let element_id = target.element_id;
         let repositories = self.repositories.clone();
         let pause_manager = self.pause_manager.clone();
         let screenshot_watcher = self.screenshot_watcher.clone();
 
         tauri::async_runtime::spawn(async move {
-            // 追跡状態を開始
-            pause_manager.set_tracking(true);
-
             let monitor = GameProcessMonitor::new(
                 handle.clone(),
                 repositories,
                 pause_manager.clone(),
                 screenshot_watcher,
                 element_id,
+                Arc::new(SystemClock),
             );
 
             // ポーズショートカットを登録
@@ -100,22 +415,33 @@ impl<R: RepositoriesExt + Send + Sync + 'static> CollectionUseCase<R> {
             // ゲームプロセスを検索
             let config = ProcessSearchConfig::default();
             if let Some(pid) = monitor
-                .find_game_process(&path_str, spawned_pid, &game_name, &config)
+                .find_game_process(
+                    &target.path_str,
+                    target.spawned_pid,
+                    &target.game_name,
+                    &config,
+                )
                 .await
             {
-                pause_manager.set_tracking_session(element_id, pid.as_u32());
+                pause_manager.set_session_process(element_id, pid.as_u32());
                 // プロセスを監視
                 monitor
-                    .monitor_process(pid, registered_shortcut, tracking_started_at)
+                    .monitor_process(pid, registered_shortcut, &target, &config)
                     .await;
+
+                // 終了後フック
+                let _ = run_launch_hooks(&hooks, LaunchHookTiming::PostExit, &hook_context).await;
             } else {
-                pause_manager.set_tracking(false);
-                if let Some(shortcut) = registered_shortcut {
-                    let _ = handle.global_shortcut().unregister(shortcut);
-                }
+                pause_manager.end_session(element_id);
+                monitor.unregister_pause_shortcut(registered_shortcut);
+                // ゲームがまだ起動中の可能性があるため、終了後フックは実行しない
+                skip_launch_hooks(
+                    &hooks,
+                    LaunchHookTiming::PostExit,
+                    "game process was not found",
+                );
             }
         });
-
         Ok(())
     }
 
@@ -296,9 +622,14 @@ impl<R: RepositoriesExt + Send + Sync + 'static> CollectionUseCase<R> {
         }
 
         // Delete play history
-        let play_history_path = crate::domain::file::get_play_history_path(handle, id);
-        if std::path::Path::new(&play_history_path).exists() {
-            let _ = std::fs::remove_file(play_history_path);
+        for play_history_path in get_legacy_play_history_paths(Path::new(&self.save_root_dir), id)
+        {
+            let imported_path = get_imported_play_history_path(&play_history_path);
+            for path in [play_history_path, imported_path] {
+                if path.exists() {
+                    let _ = std::fs::remove_file(path);
+                }
+            }
         }
 
         // Delete screenshots directory
@@ -417,7 +748,10 @@ impl<R: RepositoriesExt + Send + Sync + 'static> CollectionUseCase<R> {
                 .update_element_last_play_at_by_id(id, now)
                 .await?;
 
-            for (play_date, daily_seconds) in split_play_time_by_local_date(now, bounded_seconds) {
+            let day_boundary = self.get_day_boundary().await?;
+            for (play_date, daily_seconds) in
+                split_play_time_by_local_date(now, bounded_seconds, day_boundary)
+            {
                 self.repositories
                     .collection_repository()
                     .add_daily_play_time_seconds(id, play_date, daily_seconds)
@@ -454,6 +788,19 @@ impl<R: RepositoriesExt + Send + Sync + 'static> CollectionUseCase<R> {
             .await
     }
 
+    /// 詳細を取得済みのゲームのブランド名。走査で実行ファイルの会社名と照らし合わせる
+    pub async fn get_brandnames(&self) -> anyhow::Result<HashMap<i32, String>> {
+        Ok(self
+            .repositories
+            .collection_repository()
+            .get_all_elements()
+            .await?
+            .into_iter()
+            .filter(|v| !v.brandname.is_empty())
+            .map(|v| (v.id.value, v.brandname))
+            .collect())
+    }
+
     pub async fn get_collection_element_daily_play_times(
         &self,
         id: &Id<CollectionElement>,
@@ -464,97 +811,663 @@ impl<R: RepositoriesExt + Send + Sync + 'static> CollectionUseCase<R> {
             .await
     }
 
-    pub async fn touch_element(&self, id: &Id<CollectionElement>) -> anyhow::Result<()> {
-        self.repositories.collection_repository().touch(id).await
+    pub async fn get_day_boundary(&self) -> anyhow::Result<DayBoundary> {
+        let value = self
+            .repositories
+            .collection_repository()
+            .get_app_setting(DAY_BOUNDARY_SETTING_KEY.to_string())
+            .await?;
+        Ok(DayBoundary::from_setting(value.as_deref()))
     }
 
-    pub async fn get_app_setting(&self, key: String) -> anyhow::Result<Option<String>> {
+    /// 日付の区切りを変更し、記録済みのプレイ日ごとの時間を新しい区切りで振り分け直す
+    ///
+    /// 振り分け直すのはプレイセッションが残っている時間のみで、それ以前の記録はそのまま残す
+    pub async fn update_day_boundary(&self, value: &str) -> anyhow::Result<()> {
+        let new_boundary = DayBoundary::parse(value).map_err(UseCaseError::InvalidDayBoundary)?;
+        if self.pause_manager.is_tracking() {
+            return Err(UseCaseError::DayBoundaryCannotChangeWhileTracking.into());
+        }
+        let current_boundary = self.get_day_boundary().await?;
+        if current_boundary == new_boundary {
+            return Ok(());
+        }
+
+        let sessions = self
+            .repositories
+            .collection_repository()
+            .get_finished_play_sessions()
+            .await?;
+        let deltas: Vec<(Id<CollectionElement>, chrono::NaiveDate, i32)> =
+            compute_daily_play_time_rebucket(&sessions, current_boundary, new_boundary)
+                .into_iter()
+                .map(|(element_id, play_date, seconds)| (Id::new(element_id), play_date, seconds))
+                .collect();
         self.repositories
             .collection_repository()
-            .get_app_setting(key)
+            .update_day_boundary(current_boundary, new_boundary, &deltas)
             .await
     }
 
-    pub async fn set_app_setting(&self, key: String, value: Option<String>) -> anyhow::Result<()> {
-        self.repositories
+    /// 以前のバージョンのプレイ履歴(`<id>.jsonl`)を日ごとのプレイ時間に取り込み、ファイルを取り込み済みにする
+    ///
+    /// 取り込みはエレメントごとに一度だけ記録するため、何度実行しても同じ時間を二重に数えない
+    pub async fn import_legacy_play_histories(
+        &self,
+        handle: &Arc<AppHandle>,
+    ) -> anyhow::Result<LegacyPlayHistoryImportReport> {
+        let mut report = LegacyPlayHistoryImportReport::default();
+        let files = find_legacy_play_history_files(Path::new(&self.save_root_dir));
+        if files.is_empty() {
+            return Ok(report);
+        }
+        let day_boundary = self.get_day_boundary().await?;
+
+        // 両方の置き場所に同じエレメントの履歴がある場合はまとめて取り込む
+        let mut grouped: BTreeMap<i32, Vec<PathBuf>> = BTreeMap::new();
+        for (element_id, path) in files {
+            grouped.entry(element_id).or_default().push(path);
+        }
+        for (element_id, paths) in grouped {
+            let id = Id::new(element_id);
+            let file_count = paths.len() as i32;
+            let element = self
+                .repositories
+                .collection_repository()
+                .get_element_by_element_id(&id)
+                .await?;
+            if element.is_none() {
+                report.skipped_files += file_count;
+                continue;
+            }
+
+            let mut histories = vec![];
+            let mut invalid_count = 0;
+            let mut read_error = None;
+            for path in paths.iter() {
+                match fs::read_to_string(path) {
+                    Ok(content) => {
+                        let (parsed, invalid) = parse_play_histories(&content);
+                        histories.extend(parsed);
+                        invalid_count += invalid as i32;
+                    }
+                    Err(e) => {
+                        read_error = Some((path, e));
+                        break;
+                    }
+                }
+            }
+            if let Some((path, e)) = read_error {
+                eprintln!(
+                    "[import_legacy_play_histories] failed to read {}: {}",
+                    path.display(),
+                    e
+                );
+                report.skipped_files += file_count;
+                continue;
+            }
+
+            let folded = fold_play_histories(&histories, day_boundary);
+            let imported = self
+                .repositories
+                .collection_repository()
+                .import_legacy_play_history(
+                    &id,
+                    folded.record_count,
+                    &folded.daily_play_times,
+                    folded.first_played_at,
+                    folded.last_played_at,
+                )
+                .await?;
+            if imported {
+                report.imported_files += file_count;
+                report.imported_records += folded.record_count;
+                report.imported_seconds += folded.total_seconds as i64;
+                report.skipped_records += folded.skipped_count + invalid_count;
+                let _ = handle.emit("collection-element-updated", element_id);
+            } else {
+                report.already_imported_files += file_count;
+            }
+
+            for path in paths {
+                if let Err(e) = fs::rename(&path, get_imported_play_history_path(&path)) {
+                    eprintln!(
+                        "[import_legacy_play_histories] failed to mark {} as imported: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// 他のランチャーやストアが記録したプレイ時間を取り込む
+    ///
+    /// 記録は Steam の App ID、実行ファイルのパス、タイトルの順でエレメントと対応付ける。
+    /// パスとタイトルは `rules` と `learned` で比べる。
+    /// `dry_run` の場合は何も書き込まずに取り込む内容だけを返す
+    pub async fn import_external_play_times(
+        &self,
+        handle: &Arc<AppHandle>,
+        rules: &MatchingRules,
+        learned: &LearnedGameMatches,
+        source: ExternalPlayTimeSource,
+        path: &str,
+        dry_run: bool,
+    ) -> anyhow::Result<ExternalImportReport> {
+        let contents = fs::read_to_string(path)
+            .map_err(|_| UseCaseError::ExternalPlayTimeFileIsNotReadable(path.to_string()))?;
+        let records = parse_external_play_records(source, &contents)?;
+        let day_boundary = self.get_day_boundary().await?;
+        let now = Local::now();
+
+        let elements = self
+            .repositories
             .collection_repository()
-            .set_app_setting(key, value)
-            .await
+            .get_all_elements()
+            .await?;
+        let mut steam_app_ids = HashMap::new();
+        for element in elements.iter() {
+            let app_id = [element.exe_path.as_deref(), element.lnk_path.as_deref()]
+                .into_iter()
+                .flatten()
+                .find_map(get_steam_app_metadata_by_path)
+                .map(|v| v.app_id);
+            if let Some(app_id) = app_id {
+                steam_app_ids.entry(app_id).or_insert(element.id.value);
+            }
+        }
+        let index = GameCandidateIndex::new(
+            elements
+                .iter()
+                .map(|element| AllGameCacheOne::new(element.id.value, normalize(&element.gamename)))
+                .collect(),
+        )
+        .with_brandnames(
+            elements
+                .iter()
+                .map(|element| (element.id.value, element.brandname.clone()))
+                .collect(),
+        );
+
+        let mut report = ExternalImportReport {
+            source,
+            dry_run,
+            imported_seconds: 0,
+            imported_sessions: 0,
+            entries: vec![],
+        };
+        let mut planned: Vec<ExternalPlayTimeImport> = vec![];
+        for record in records {
+            let mut entry = ExternalImportEntry {
+                external_id: record.external_id.clone(),
+                title: record.title.clone(),
+                collection_element_id: None,
+                gamename: None,
+                matched_by: None,
+                total_seconds: record.total_seconds,
+                imported_seconds: 0,
+                session_count: 0,
+                status: ExternalImportStatus::Unmatched,
+            };
+            let Some((element_id, matched_by)) =
+                match_external_record(rules, learned, &record, &steam_app_ids, &index)
+            else {
+                report.entries.push(entry);
+                continue;
+            };
+            entry.collection_element_id = Some(element_id);
+            entry.gamename = elements
+                .iter()
+                .find(|v| v.id.value == element_id)
+                .map(|v| v.gamename.clone());
+            entry.matched_by = Some(matched_by);
+            if self.pause_manager.is_tracking_game(element_id) {
+                entry.status = ExternalImportStatus::Tracking;
+                report.entries.push(entry);
+                continue;
+            }
+
+            let id = Id::new(element_id);
+            let repository = self.repositories.collection_repository();
+            // 試しに実行する場合も、同じファイルの先の記録は取り込んだものとして数える
+            let mut imports: Vec<_> = planned
+                .iter()
+                .filter(|v| v.collection_element_id.value == element_id)
+                .cloned()
+                .collect();
+            imports.extend(repository.get_external_play_time_imports(&id).await?);
+            let sessions = repository.get_play_sessions_by_element_id(&id).await?;
+            let plan =
+                plan_external_import(source, &record, &imports, &sessions, day_boundary, now);
+            entry.imported_seconds = plan.imported_seconds;
+            entry.session_count = plan.sessions.len() as i32;
+            if plan.is_empty() {
+                entry.status = ExternalImportStatus::AlreadyImported;
+                report.entries.push(entry);
+                continue;
+            }
+
+            let import = ExternalPlayTimeImport {
+                source,
+                external_id: record.external_id.clone(),
+                collection_element_id: id,
+                imported_seconds: plan.credited_seconds,
+                last_session_ended_at: plan.last_session_ended_at,
+            };
+            if dry_run {
+                entry.status = ExternalImportStatus::WouldImport;
+                planned.push(import);
+            } else {
+                repository
+                    .apply_external_play_time_import(
+                        &import,
+                        plan.imported_seconds,
+                        &plan.sessions,
+                        &plan.daily_play_times,
+                        plan.first_played_at,
+                        plan.last_played_at,
+                    )
+                    .await?;
+                let _ = handle.emit("collection-element-updated", element_id);
+                entry.status = ExternalImportStatus::Imported;
+            }
+            report.imported_seconds += plan.imported_seconds;
+            report.imported_sessions += entry.session_count;
+            report.entries.push(entry);
+        }
+        Ok(report)
     }
 
-    pub async fn get_game_screenshot_cache(
+    pub async fn get_play_sessions(
         &self,
-        collection_element_id: i32,
-    ) -> anyhow::Result<Option<GameScreenshotCache>> {
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Vec<PlaySession>> {
         self.repositories
             .collection_repository()
-            .get_game_screenshot_cache(collection_element_id)
+            .get_play_sessions_by_element_id(element_id)
             .await
     }
 
-    pub async fn upsert_game_screenshot_cache(
+    pub async fn get_play_sessions_by_range(
         &self,
-        cache: GameScreenshotCache,
-    ) -> anyhow::Result<()> {
+        element_id: Option<&Id<CollectionElement>>,
+        since: DateTime<Local>,
+        until: DateTime<Local>,
+    ) -> anyhow::Result<Vec<PlaySession>> {
+        if since >= until {
+            return Ok(vec![]);
+        }
         self.repositories
             .collection_repository()
-            .upsert_game_screenshot_cache(cache)
+            .get_play_sessions_by_range(element_id, since, until)
             .await
     }
 
-    pub async fn get_game_screenshots(
+    pub async fn get_play_session_summary(
         &self,
-        _handle: &Arc<AppHandle>,
-        game_id: i32,
-    ) -> anyhow::Result<Vec<Screenshot>> {
-        let screenshots = self
-            .repositories
-            .screenshot_repository()
-            .get_by_game_id(&Id::new(game_id))
+        element_id: Option<&Id<CollectionElement>>,
+        since: DateTime<Local>,
+        until: DateTime<Local>,
+    ) -> anyhow::Result<PlaySessionSummary> {
+        let sessions = self
+            .get_play_sessions_by_range(element_id, since, until)
             .await?;
+        Ok(summarize_play_sessions(&sessions))
+    }
 
-        let root_dir = self.save_root_dir.clone();
-        let game_dir = std::path::Path::new(&root_dir)
-            .join("game-memos")
-            .join(game_id.to_string());
-        Ok(screenshots
-            .into_iter()
-            .map(|mut s| {
-                let relative_filename = s.filename.clone();
-                s.filename = game_dir
-                    .join(&relative_filename)
-                    .to_string_lossy()
-                    .to_string();
-                s.thumbnail_filename =
-                    match ensure_screenshot_thumbnail(&root_dir, s.game_id, &relative_filename) {
-                        Ok(path) => path,
-                        Err(e) => {
-                            eprintln!(
-                                "[get_game_screenshots] ensure_screenshot_thumbnail failed: {}",
-                                e
-                            );
-                            None
-                        }
-                    };
-                s
-            })
-            .collect())
+    /// 過去のセッションを手動で登録する。プレイ時間を省略した場合は開始から終了までをプレイ時間にする
+    pub async fn add_play_session(
+        &self,
+        handle: &Arc<AppHandle>,
+        element_id: &Id<CollectionElement>,
+        started_at: DateTime<Local>,
+        ended_at: DateTime<Local>,
+        played_seconds: Option<i32>,
+    ) -> anyhow::Result<PlaySessionAudit> {
+        let played_seconds =
+            played_seconds.unwrap_or_else(|| (ended_at - started_at).num_seconds() as i32);
+        let added = ManualPlaySession::new(
+            None,
+            element_id.clone(),
+            started_at,
+            ended_at,
+            None,
+            None,
+            played_seconds,
+            0,
+        );
+        self.commit_play_session_edit(
+            handle,
+            PlaySessionEditAction::Add,
+            vec![],
+            vec![added],
+            None,
+        )
+        .await
     }
 
-    pub async fn get_all_screenshots(
+    /// 終了済みのセッションの日時・プレイ時間・ゲームを変更する
+    ///
+    /// プレイ時間を省略した場合はポーズしていた時間を除いた開始から終了までをプレイ時間にする
+    pub async fn update_play_session(
         &self,
-        _handle: &Arc<AppHandle>,
-    ) -> anyhow::Result<Vec<Screenshot>> {
-        let screenshots = self.repositories.screenshot_repository().get_all().await?;
+        handle: &Arc<AppHandle>,
+        id: &Id<PlaySession>,
+        element_id: &Id<CollectionElement>,
+        started_at: DateTime<Local>,
+        ended_at: DateTime<Local>,
+        played_seconds: Option<i32>,
+    ) -> anyhow::Result<PlaySessionAudit> {
+        let session = self.get_finished_play_session(id).await?;
+        let original = ManualPlaySession::try_from(&session)
+            .map_err(UseCaseError::InvalidPlaySession)?;
+        let paused_seconds = original
+            .paused_seconds
+            .min((ended_at - started_at).num_seconds().max(0) as i32);
+        let played_seconds = played_seconds.unwrap_or_else(|| {
+            ((ended_at - started_at).num_seconds() as i32 - paused_seconds).max(0)
+        });
+        let mut updated = ManualPlaySession {
+            collection_element_id: element_id.clone(),
+            started_at,
+            ended_at,
+            played_seconds,
+            paused_seconds,
+            ..original
+        };
+        updated.trim_played_ranges();
+        self.commit_play_session_edit(
+            handle,
+            PlaySessionEditAction::Edit,
+            vec![session],
+            vec![updated],
+            None,
+        )
+        .await
+    }
 
-        let root_dir = self.save_root_dir.clone();
+    pub async fn delete_play_session(
+        &self,
+        handle: &Arc<AppHandle>,
+        id: &Id<PlaySession>,
+    ) -> anyhow::Result<PlaySessionAudit> {
+        let session = self.get_finished_play_session(id).await?;
+        self.commit_play_session_edit(
+            handle,
+            PlaySessionEditAction::Delete,
+            vec![session],
+            vec![],
+            None,
+        )
+        .await
+    }
 
-        Ok(screenshots
-            .into_iter()
-            .map(|mut s| {
-                let relative_filename = s.filename.clone();
-                let game_dir = std::path::Path::new(&root_dir)
-                    .join("game-memos")
+    /// セッションを `split_at` で分け、後半を別のゲームのプレイとして記録し直す
+    pub async fn split_play_session(
+        &self,
+        handle: &Arc<AppHandle>,
+        id: &Id<PlaySession>,
+        split_at: DateTime<Local>,
+        moved_element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<PlaySessionAudit> {
+        let session = self.get_finished_play_session(id).await?;
+        let (first, second) = split_play_session(&session, split_at, moved_element_id.clone())
+            .map_err(UseCaseError::InvalidPlaySession)?;
+        self.commit_play_session_edit(
+            handle,
+            PlaySessionEditAction::Split,
+            vec![session],
+            vec![first, second],
+            None,
+        )
+        .await
+    }
+
+    pub async fn get_play_session_audits(
+        &self,
+        limit: i32,
+    ) -> anyhow::Result<Vec<PlaySessionAudit>> {
+        self.repositories
+            .collection_repository()
+            .get_play_session_audits(limit)
+            .await
+    }
+
+    /// 変更を取り消し、変更前のセッションを元の ID で復元する
+    ///
+    /// 変更後のセッションがその後さらに編集されている場合は取り消せない
+    pub async fn undo_play_session_audit(
+        &self,
+        handle: &Arc<AppHandle>,
+        id: &Id<PlaySessionAudit>,
+    ) -> anyhow::Result<PlaySessionAudit> {
+        let audit = self
+            .repositories
+            .collection_repository()
+            .get_play_session_audit_by_id(id)
+            .await?
+            .ok_or(UseCaseError::PlaySessionAuditIsNotFound)?;
+        if audit.undone_at.is_some() {
+            return Err(UseCaseError::PlaySessionAuditIsAlreadyUndone.into());
+        }
+
+        let mut removed = Vec::with_capacity(audit.after.len());
+        for after in audit.after.iter() {
+            let current = self
+                .repositories
+                .collection_repository()
+                .get_play_session_by_id(&after.id)
+                .await?;
+            match current {
+                Some(current) if current.is_same_record(after) => removed.push(current),
+                _ => return Err(UseCaseError::PlaySessionAuditCannotBeUndone.into()),
+            }
+        }
+        let added = audit
+            .before
+            .iter()
+            .map(ManualPlaySession::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(UseCaseError::InvalidPlaySession)?;
+        self.commit_play_session_edit(
+            handle,
+            PlaySessionEditAction::Undo,
+            removed,
+            added,
+            Some(audit.id),
+        )
+        .await
+    }
+
+    async fn get_finished_play_session(&self, id: &Id<PlaySession>) -> anyhow::Result<PlaySession> {
+        let session = self
+            .repositories
+            .collection_repository()
+            .get_play_session_by_id(id)
+            .await?
+            .ok_or(UseCaseError::PlaySessionIsNotFound)?;
+        if session.ended_at.is_none() {
+            return Err(UseCaseError::PlaySessionCannotEditWhileTracking.into());
+        }
+        Ok(session)
+    }
+
+    /// セッションの手動変更を検証し、プレイ時間の集計と合わせて反映して変更履歴に残す
+    async fn commit_play_session_edit(
+        &self,
+        handle: &Arc<AppHandle>,
+        action: PlaySessionEditAction,
+        removed: Vec<PlaySession>,
+        added: Vec<ManualPlaySession>,
+        undo_of: Option<Id<PlaySessionAudit>>,
+    ) -> anyhow::Result<PlaySessionAudit> {
+        let now = Local::now();
+        for session in added.iter() {
+            session
+                .validate(now)
+                .map_err(UseCaseError::InvalidPlaySession)?;
+        }
+
+        let mut element_ids: Vec<i32> = removed
+            .iter()
+            .map(|v| v.collection_element_id.value)
+            .chain(added.iter().map(|v| v.collection_element_id.value))
+            .collect();
+        element_ids.sort_unstable();
+        element_ids.dedup();
+
+        let mut elements = Vec::with_capacity(element_ids.len());
+        for element_id in element_ids.iter() {
+            if self.pause_manager.is_tracking_game(*element_id) {
+                return Err(UseCaseError::PlaySessionCannotEditWhileTracking.into());
+            }
+            let id = Id::new(*element_id);
+            let element = self
+                .repositories
+                .collection_repository()
+                .get_element_by_element_id(&id)
+                .await?
+                .ok_or(UseCaseError::CollectionElementIsNotFound)?;
+            let daily_play_times = self
+                .repositories
+                .collection_repository()
+                .get_daily_play_times(&id)
+                .await?
+                .into_iter()
+                .filter_map(|v| {
+                    chrono::NaiveDate::parse_from_str(&v.play_date, "%Y-%m-%d")
+                        .ok()
+                        .map(|play_date| (play_date, v.play_time_seconds))
+                })
+                .collect();
+            let sessions = self
+                .repositories
+                .collection_repository()
+                .get_play_sessions_by_element_id(&id)
+                .await?;
+            elements.push(ElementPlayState {
+                element_id: id,
+                first_play_at: element.first_play_at,
+                last_play_at: element.last_play_at,
+                daily_play_times,
+                sessions,
+            });
+        }
+
+        let day_boundary = self.get_day_boundary().await?;
+        let edit =
+            build_play_session_edit(action, removed, added, &elements, day_boundary, undo_of);
+        let audit_id = self
+            .repositories
+            .collection_repository()
+            .apply_play_session_edit(&edit)
+            .await?;
+
+        for element_id in element_ids {
+            let _ = handle.emit("collection-element-updated", element_id);
+        }
+        let _ = handle.emit("recent-games-changed", ());
+
+        self.repositories
+            .collection_repository()
+            .get_play_session_audit_by_id(&audit_id)
+            .await?
+            .ok_or_else(|| UseCaseError::PlaySessionAuditIsNotFound.into())
+    }
+
+    pub async fn touch_element(&self, id: &Id<CollectionElement>) -> anyhow::Result<()> {
+        self.repositories.collection_repository().touch(id).await
+    }
+
+    pub async fn get_app_setting(&self, key: String) -> anyhow::Result<Option<String>> {
+        self.repositories
+            .collection_repository()
+            .get_app_setting(key)
+            .await
+    }
+
+    pub async fn set_app_setting(&self, key: String, value: Option<String>) -> anyhow::Result<()> {
+        self.repositories
+            .collection_repository()
+            .set_app_setting(key, value)
+            .await
+    }
+
+    pub async fn get_game_screenshot_cache(
+        &self,
+        collection_element_id: i32,
+    ) -> anyhow::Result<Option<GameScreenshotCache>> {
+        self.repositories
+            .collection_repository()
+            .get_game_screenshot_cache(collection_element_id)
+            .await
+    }
+
+    pub async fn upsert_game_screenshot_cache(
+        &self,
+        cache: GameScreenshotCache,
+    ) -> anyhow::Result<()> {
+        self.repositories
+            .collection_repository()
+            .upsert_game_screenshot_cache(cache)
+            .await
+    }
+
+    pub async fn get_game_screenshots(
+        &self,
+        _handle: &Arc<AppHandle>,
+        game_id: i32,
+    ) -> anyhow::Result<Vec<Screenshot>> {
+        let screenshots = self
+            .repositories
+            .screenshot_repository()
+            .get_by_game_id(&Id::new(game_id))
+            .await?;
+
+        let root_dir = self.save_root_dir.clone();
+        let game_dir = std::path::Path::new(&root_dir)
+            .join("game-memos")
+            .join(game_id.to_string());
+        Ok(screenshots
+            .into_iter()
+            .map(|mut s| {
+                let relative_filename = s.filename.clone();
+                s.filename = game_dir
+                    .join(&relative_filename)
+                    .to_string_lossy()
+                    .to_string();
+                s.thumbnail_filename =
+                    match ensure_screenshot_thumbnail(&root_dir, s.game_id, &relative_filename) {
+                        Ok(path) => path,
+                        Err(e) => {
+                            eprintln!(
+                                "[get_game_screenshots] ensure_screenshot_thumbnail failed: {}",
+                                e
+                            );
+                            None
+                        }
+                    };
+                s
+            })
+            .collect())
+    }
+
+    pub async fn get_all_screenshots(
+        &self,
+        _handle: &Arc<AppHandle>,
+    ) -> anyhow::Result<Vec<Screenshot>> {
+        let screenshots = self.repositories.screenshot_repository().get_all().await?;
+
+        let root_dir = self.save_root_dir.clone();
+
+        Ok(screenshots
+            .into_iter()
+            .map(|mut s| {
+                let relative_filename = s.filename.clone();
+                let game_dir = std::path::Path::new(&root_dir)
+                    .join("game-memos")
                     .join(s.game_id.to_string());
                 s.filename = game_dir
                     .join(&relative_filename)
@@ -726,4 +1639,694 @@ impl<R: RepositoriesExt + Send + Sync + 'static> CollectionUseCase<R> {
             .update_collection_element_path(id, None, None)
             .await
     }
+
+    pub async fn get_all_collections(&self) -> anyhow::Result<Vec<Collection>> {
+        self.repositories
+            .collection_repository()
+            .get_all_collections()
+            .await
+    }
+
+    pub async fn get_collection_by_id(&self, id: &Id<Collection>) -> anyhow::Result<Collection> {
+        Ok(self
+            .repositories
+            .collection_repository()
+            .get_collection_by_id(id)
+            .await?
+            .ok_or(UseCaseError::CollectionIsNotFound)?)
+    }
+
+    pub async fn create_collection(&self, name: String) -> anyhow::Result<Collection> {
+        let name = validate_collection_name(&name)?;
+        let repository = self.repositories.collection_repository();
+        if repository.get_collection_by_name(&name).await?.is_some() {
+            return Err(UseCaseError::CollectionIsAlreadyExist.into());
+        }
+        let id = repository
+            .create_collection(&NewCollection::new(name))
+            .await?;
+        self.get_collection_by_id(&id).await
+    }
+
+    pub async fn rename_collection(
+        &self,
+        id: &Id<Collection>,
+        name: String,
+    ) -> anyhow::Result<Collection> {
+        let name = validate_collection_name(&name)?;
+        let repository = self.repositories.collection_repository();
+        let current = self.get_collection_by_id(id).await?;
+        if let Some(existed) = repository.get_collection_by_name(&name).await? {
+            if existed.id.value != current.id.value {
+                return Err(UseCaseError::CollectionIsAlreadyExist.into());
+            }
+        }
+        repository.update_collection_name(id, &name).await?;
+        self.get_collection_by_id(id).await
+    }
+
+    pub async fn delete_collection(&self, id: &Id<Collection>) -> anyhow::Result<()> {
+        self.get_collection_by_id(id).await?;
+        self.repositories
+            .collection_repository()
+            .delete_collection(id)
+            .await
+    }
+
+    pub async fn update_collections_order(&self, ids: Vec<Id<Collection>>) -> anyhow::Result<()> {
+        self.repositories
+            .collection_repository()
+            .update_collections_order(&ids)
+            .await
+    }
+
+    pub async fn get_collection_element_ids(
+        &self,
+        id: &Id<Collection>,
+    ) -> anyhow::Result<Vec<Id<CollectionElement>>> {
+        self.get_collection_by_id(id).await?;
+        self.repositories
+            .collection_repository()
+            .get_element_ids_by_collection_id(id)
+            .await
+    }
+
+    pub async fn add_collection_elements(
+        &self,
+        id: &Id<Collection>,
+        element_ids: Vec<Id<CollectionElement>>,
+    ) -> anyhow::Result<()> {
+        self.get_collection_by_id(id).await?;
+        self.repositories
+            .collection_repository()
+            .add_collection_elements(id, &element_ids)
+            .await
+    }
+
+    pub async fn remove_collection_elements(
+        &self,
+        id: &Id<Collection>,
+        element_ids: Vec<Id<CollectionElement>>,
+    ) -> anyhow::Result<()> {
+        self.get_collection_by_id(id).await?;
+        self.repositories
+            .collection_repository()
+            .remove_collection_elements(id, &element_ids)
+            .await
+    }
+
+    /// 並び替え後のエレメント ID 一覧を受け取り、その順序で保存する
+    ///
+    /// コレクションに含まれないエレメントは無視し、並び替え後の一覧に含まれなかった
+    /// エレメントは既存の順序を保ったまま末尾に回す
+    pub async fn update_collection_elements_order(
+        &self,
+        id: &Id<Collection>,
+        element_ids: Vec<Id<CollectionElement>>,
+    ) -> anyhow::Result<()> {
+        let current = self.get_collection_element_ids(id).await?;
+        let ordered = merge_collection_element_order(&current, &element_ids);
+        self.repositories
+            .collection_repository()
+            .update_collection_elements_order(id, &ordered)
+            .await
+    }
+
+    pub async fn get_all_smart_collections(&self) -> anyhow::Result<Vec<SmartCollection>> {
+        self.repositories
+            .collection_repository()
+            .get_all_smart_collections()
+            .await
+    }
+
+    pub async fn get_smart_collection_by_id(
+        &self,
+        id: &Id<SmartCollection>,
+    ) -> anyhow::Result<SmartCollection> {
+        Ok(self
+            .repositories
+            .collection_repository()
+            .get_smart_collection_by_id(id)
+            .await?
+            .ok_or(UseCaseError::CollectionIsNotFound)?)
+    }
+
+    pub async fn create_smart_collection(
+        &self,
+        name: String,
+        filter: SmartCollectionFilter,
+    ) -> anyhow::Result<SmartCollection> {
+        let name = validate_collection_name(&name)?;
+        filter
+            .validate()
+            .map_err(UseCaseError::InvalidSmartCollectionFilter)?;
+        let repository = self.repositories.collection_repository();
+        if repository
+            .get_smart_collection_by_name(&name)
+            .await?
+            .is_some()
+        {
+            return Err(UseCaseError::CollectionIsAlreadyExist.into());
+        }
+        let id = repository
+            .create_smart_collection(&NewSmartCollection::new(name, filter))
+            .await?;
+        self.get_smart_collection_by_id(&id).await
+    }
+
+    pub async fn update_smart_collection(
+        &self,
+        id: &Id<SmartCollection>,
+        name: String,
+        filter: SmartCollectionFilter,
+    ) -> anyhow::Result<SmartCollection> {
+        let name = validate_collection_name(&name)?;
+        filter
+            .validate()
+            .map_err(UseCaseError::InvalidSmartCollectionFilter)?;
+        let repository = self.repositories.collection_repository();
+        let current = self.get_smart_collection_by_id(id).await?;
+        if let Some(existed) = repository.get_smart_collection_by_name(&name).await? {
+            if existed.id.value != current.id.value {
+                return Err(UseCaseError::CollectionIsAlreadyExist.into());
+            }
+        }
+        repository
+            .update_smart_collection(id, &NewSmartCollection::new(name, filter))
+            .await?;
+        self.get_smart_collection_by_id(id).await
+    }
+
+    pub async fn delete_smart_collection(&self, id: &Id<SmartCollection>) -> anyhow::Result<()> {
+        self.get_smart_collection_by_id(id).await?;
+        self.repositories
+            .collection_repository()
+            .delete_smart_collection(id)
+            .await
+    }
+
+    /// スマートコレクションに該当するエレメント ID を返す
+    ///
+    /// 結果はキャッシュせず呼び出しのたびに評価するため、エレメントの変更は次回の呼び出しで反映される
+    pub async fn get_smart_collection_element_ids(
+        &self,
+        id: &Id<SmartCollection>,
+    ) -> anyhow::Result<Vec<Id<CollectionElement>>> {
+        let smart_collection = self.get_smart_collection_by_id(id).await?;
+        self.evaluate_smart_collection_filter(&smart_collection.filter)
+            .await
+    }
+
+    /// 保存前の条件を評価する。編集中のプレビューに使う
+    pub async fn evaluate_smart_collection_filter(
+        &self,
+        filter: &SmartCollectionFilter,
+    ) -> anyhow::Result<Vec<Id<CollectionElement>>> {
+        filter
+            .validate()
+            .map_err(UseCaseError::InvalidSmartCollectionFilter)?;
+        let mut ids: Vec<i32> = self
+            .evaluate_filter_node(filter, Local::now())
+            .await?
+            .into_iter()
+            .collect();
+        ids.sort_unstable();
+        Ok(ids.into_iter().map(Id::new).collect())
+    }
+
+    fn evaluate_filter_node<'a>(
+        &'a self,
+        filter: &'a SmartCollectionFilter,
+        now: chrono::DateTime<Local>,
+    ) -> BoxFuture<'a, anyhow::Result<HashSet<i32>>> {
+        Box::pin(async move {
+            let ids = match filter {
+                SmartCollectionFilter::And { filters } => {
+                    let mut sets = Vec::with_capacity(filters.len());
+                    for v in filters.iter() {
+                        sets.push(self.evaluate_filter_node(v, now).await?);
+                    }
+                    return Ok(intersect_all(sets));
+                }
+                SmartCollectionFilter::Or { filters } => {
+                    let mut sets = Vec::with_capacity(filters.len());
+                    for v in filters.iter() {
+                        sets.push(self.evaluate_filter_node(v, now).await?);
+                    }
+                    return Ok(union_all(sets));
+                }
+                SmartCollectionFilter::Brand { brandnames } => {
+                    self.repositories
+                        .collection_repository()
+                        .get_element_ids_by_brandnames(brandnames)
+                        .await?
+                }
+                SmartCollectionFilter::Sellday { since, until } => {
+                    self.repositories
+                        .collection_repository()
+                        .get_element_ids_by_sellday(since, until)
+                        .await?
+                }
+                SmartCollectionFilter::PlayStatus { play_statuses } => {
+                    self.repositories
+                        .collection_repository()
+                        .get_element_ids_by_play_statuses(play_statuses)
+                        .await?
+                }
+                SmartCollectionFilter::TotalPlayTime {
+                    min_seconds,
+                    max_seconds,
+                } => {
+                    self.repositories
+                        .collection_repository()
+                        .get_element_ids_by_total_play_time_seconds(*min_seconds, *max_seconds)
+                        .await?
+                }
+                SmartCollectionFilter::LastPlayedWithin { days } => {
+                    let since = now - chrono::Duration::days(*days as i64);
+                    self.repositories
+                        .collection_repository()
+                        .get_element_ids_by_last_play_at_since(since)
+                        .await?
+                }
+                SmartCollectionFilter::Liked { is_liked } => {
+                    let liked = self
+                        .repositories
+                        .collection_repository()
+                        .get_element_ids_by_like_at_not_null()
+                        .await?;
+                    if *is_liked {
+                        liked
+                    } else {
+                        let all = self
+                            .repositories
+                            .collection_repository()
+                            .get_all_element_ids()
+                            .await?;
+                        return Ok(difference(to_id_set(all), &to_id_set(liked)));
+                    }
+                }
+                SmartCollectionFilter::Installed { is_installed } => {
+                    let installed = self
+                        .repositories
+                        .collection_repository()
+                        .get_element_ids_by_install_at_not_null()
+                        .await?;
+                    if *is_installed {
+                        installed
+                    } else {
+                        let all = self
+                            .repositories
+                            .collection_repository()
+                            .get_all_element_ids()
+                            .await?;
+                        return Ok(difference(to_id_set(all), &to_id_set(installed)));
+                    }
+                }
+                SmartCollectionFilter::Nukige { is_nukige } => {
+                    self.repositories
+                        .collection_repository()
+                        .get_element_ids_by_is_nukige(*is_nukige)
+                        .await?
+                }
+            };
+            Ok(to_id_set(ids))
+        })
+    }
+
+    pub async fn get_launch_targets(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Vec<LaunchTarget>> {
+        self.repositories
+            .collection_repository()
+            .get_launch_targets_by_element_id(element_id)
+            .await
+    }
+
+    pub async fn add_launch_target(
+        &self,
+        element_id: &Id<CollectionElement>,
+        name: String,
+        path: String,
+        is_default: bool,
+    ) -> anyhow::Result<LaunchTarget> {
+        self.get_element_by_element_id(element_id).await?;
+        let (name, path) = validate_launch_target(&name, &path)?;
+        let id = self
+            .repositories
+            .collection_repository()
+            .create_launch_target(&NewLaunchTarget::new(element_id.clone(), name, path))
+            .await?;
+        if is_default {
+            self.repositories
+                .collection_repository()
+                .set_default_launch_target(element_id, Some(&id))
+                .await?;
+        }
+        self.get_launch_target_by_id(&id).await
+    }
+
+    pub async fn update_launch_target(
+        &self,
+        id: &Id<LaunchTarget>,
+        name: String,
+        path: String,
+    ) -> anyhow::Result<LaunchTarget> {
+        self.get_launch_target_by_id(id).await?;
+        let (name, path) = validate_launch_target(&name, &path)?;
+        self.repositories
+            .collection_repository()
+            .update_launch_target(id, &name, &path)
+            .await?;
+        self.get_launch_target_by_id(id).await
+    }
+
+    pub async fn delete_launch_target(&self, id: &Id<LaunchTarget>) -> anyhow::Result<()> {
+        self.get_launch_target_by_id(id).await?;
+        self.repositories
+            .collection_repository()
+            .delete_launch_target(id)
+            .await
+    }
+
+    /// 既定の起動対象を設定する。None を渡すとエレメント本体のパスが既定に戻る
+    pub async fn set_default_launch_target(
+        &self,
+        element_id: &Id<CollectionElement>,
+        id: Option<Id<LaunchTarget>>,
+    ) -> anyhow::Result<()> {
+        if let Some(id) = id.as_ref() {
+            let target = self.get_launch_target_by_id(id).await?;
+            if target.collection_element_id.value != element_id.value {
+                return Err(UseCaseError::LaunchTargetIsNotFound.into());
+            }
+        }
+        self.repositories
+            .collection_repository()
+            .set_default_launch_target(element_id, id.as_ref())
+            .await
+    }
+
+    async fn get_launch_target_by_id(&self, id: &Id<LaunchTarget>) -> anyhow::Result<LaunchTarget> {
+        Ok(self
+            .repositories
+            .collection_repository()
+            .get_launch_target_by_id(id)
+            .await?
+            .ok_or(UseCaseError::LaunchTargetIsNotFound)?)
+    }
+
+    pub async fn get_launch_profile(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Option<LaunchProfile>> {
+        self.repositories
+            .collection_repository()
+            .get_launch_profile(element_id)
+            .await
+    }
+
+    pub async fn save_launch_profile(
+        &self,
+        element_id: &Id<CollectionElement>,
+        profile: LaunchProfile,
+    ) -> anyhow::Result<LaunchProfile> {
+        self.get_element_by_element_id(element_id).await?;
+        let profile = normalize_launch_profile(profile)?;
+        self.repositories
+            .collection_repository()
+            .upsert_launch_profile(element_id, &profile)
+            .await?;
+        Ok(profile)
+    }
+
+    pub async fn get_launch_hooks(
+        &self,
+        element_id: Option<&Id<CollectionElement>>,
+    ) -> anyhow::Result<Vec<LaunchHook>> {
+        self.repositories
+            .collection_repository()
+            .get_launch_hooks(element_id)
+            .await
+    }
+
+    pub async fn add_launch_hook(&self, new: NewLaunchHook) -> anyhow::Result<LaunchHook> {
+        if let Some(element_id) = new.collection_element_id.as_ref() {
+            self.get_element_by_element_id(element_id).await?;
+        }
+        let new = normalize_launch_hook(new)?;
+        let id = self
+            .repositories
+            .collection_repository()
+            .create_launch_hook(&new)
+            .await?;
+        self.get_launch_hook_by_id(&id).await
+    }
+
+    /// フックの対象 (全体かエレメントか) は変更できない
+    pub async fn update_launch_hook(
+        &self,
+        id: &Id<LaunchHook>,
+        new: NewLaunchHook,
+    ) -> anyhow::Result<LaunchHook> {
+        self.get_launch_hook_by_id(id).await?;
+        let new = normalize_launch_hook(new)?;
+        self.repositories
+            .collection_repository()
+            .update_launch_hook(id, &new)
+            .await?;
+        self.get_launch_hook_by_id(id).await
+    }
+
+    pub async fn delete_launch_hook(&self, id: &Id<LaunchHook>) -> anyhow::Result<()> {
+        self.get_launch_hook_by_id(id).await?;
+        self.repositories
+            .collection_repository()
+            .delete_launch_hook(id)
+            .await
+    }
+
+    async fn get_launch_hook_by_id(&self, id: &Id<LaunchHook>) -> anyhow::Result<LaunchHook> {
+        Ok(self
+            .repositories
+            .collection_repository()
+            .get_launch_hook_by_id(id)
+            .await?
+            .ok_or(UseCaseError::LaunchHookIsNotFound)?)
+    }
+
+    pub async fn delete_launch_profile(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<()> {
+        self.repositories
+            .collection_repository()
+            .delete_launch_profile(element_id)
+            .await
+    }
+}
+
+fn validate_collection_name(name: &str) -> anyhow::Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(UseCaseError::CollectionNameIsEmpty.into());
+    }
+    Ok(name.to_string())
+}
+
+/// 空白だけの作業ディレクトリやラッパーは未設定として扱う
+fn normalize_launch_profile(profile: LaunchProfile) -> anyhow::Result<LaunchProfile> {
+    let non_empty = |v: Option<String>| v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let profile = LaunchProfile::new(
+        profile.arguments,
+        non_empty(profile.working_directory),
+        profile
+            .environment
+            .into_iter()
+            .map(|(k, v)| (k.trim().to_string(), v))
+            .collect(),
+        non_empty(profile.wrapper_command),
+    );
+    profile
+        .validate()
+        .map_err(UseCaseError::InvalidLaunchProfile)?;
+    Ok(profile)
+}
+
+fn normalize_launch_hook(new: NewLaunchHook) -> anyhow::Result<NewLaunchHook> {
+    let new = NewLaunchHook {
+        name: new.name.trim().to_string(),
+        command: new.command.trim().to_string(),
+        ..new
+    };
+    new.validate().map_err(UseCaseError::InvalidLaunchHook)?;
+    Ok(new)
+}
+
+fn validate_launch_target(name: &str, path: &str) -> anyhow::Result<(String, String)> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(UseCaseError::LaunchTargetNameIsEmpty.into());
+    }
+    let path = path.trim();
+    if !std::path::Path::new(path).is_file() {
+        return Err(UseCaseError::IsNotValidPath(path.to_string()).into());
+    }
+    Ok((name.to_string(), path.to_string()))
+}
+
+fn merge_collection_element_order(
+    current: &[Id<CollectionElement>],
+    requested: &[Id<CollectionElement>],
+) -> Vec<Id<CollectionElement>> {
+    let current_ids: std::collections::HashSet<i32> = current.iter().map(|v| v.value).collect();
+    let mut seen = std::collections::HashSet::new();
+    let mut ordered: Vec<Id<CollectionElement>> = requested
+        .iter()
+        .filter(|v| current_ids.contains(&v.value) && seen.insert(v.value))
+        .cloned()
+        .collect();
+    ordered.extend(current.iter().filter(|v| !seen.contains(&v.value)).cloned());
+    ordered
+}
+
+fn to_id_set(ids: Vec<Id<CollectionElement>>) -> HashSet<i32> {
+    ids.into_iter().map(|v| v.value).collect()
+}
+
+fn intersect_all(sets: Vec<HashSet<i32>>) -> HashSet<i32> {
+    let mut iter = sets.into_iter();
+    let Some(first) = iter.next() else {
+        return HashSet::new();
+    };
+    iter.fold(first, |acc, v| acc.intersection(&v).copied().collect())
+}
+
+fn union_all(sets: Vec<HashSet<i32>>) -> HashSet<i32> {
+    sets.into_iter().flatten().collect()
+}
+
+fn difference(all: HashSet<i32>, excluded: &HashSet<i32>) -> HashSet<i32> {
+    all.into_iter().filter(|v| !excluded.contains(v)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(values: &[i32]) -> Vec<Id<CollectionElement>> {
+        values.iter().map(|v| Id::new(*v)).collect()
+    }
+
+    fn values(ids: &[Id<CollectionElement>]) -> Vec<i32> {
+        ids.iter().map(|v| v.value).collect()
+    }
+
+    #[test]
+    fn test_validate_collection_name_trims() {
+        assert_eq!(
+            validate_collection_name("  積みゲー 2026 ").unwrap(),
+            "積みゲー 2026"
+        );
+    }
+
+    #[test]
+    fn test_validate_collection_name_rejects_empty() {
+        let err = validate_collection_name("   ").unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::CollectionNameIsEmpty)
+        ));
+    }
+
+    #[test]
+    fn test_merge_collection_element_order_reorders() {
+        let ordered = merge_collection_element_order(&ids(&[1, 2, 3]), &ids(&[3, 1, 2]));
+        assert_eq!(values(&ordered), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn test_merge_collection_element_order_appends_missing_and_ignores_unknown() {
+        let ordered = merge_collection_element_order(&ids(&[1, 2, 3, 4]), &ids(&[4, 9, 2, 4]));
+        assert_eq!(values(&ordered), vec![4, 2, 1, 3]);
+    }
+
+    fn set(values: &[i32]) -> HashSet<i32> {
+        values.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_intersect_all() {
+        let result = intersect_all(vec![set(&[1, 2, 3]), set(&[2, 3, 4]), set(&[3, 2, 9])]);
+        assert_eq!(result, set(&[2, 3]));
+        assert!(intersect_all(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_union_all() {
+        let result = union_all(vec![set(&[1, 2]), set(&[2, 5]), set(&[])]);
+        assert_eq!(result, set(&[1, 2, 5]));
+    }
+
+    #[test]
+    fn test_difference() {
+        let result = difference(set(&[1, 2, 3, 4]), &set(&[2, 4, 6]));
+        assert_eq!(result, set(&[1, 3]));
+    }
+
+    #[test]
+    fn test_validate_launch_target() {
+        let file = std::env::temp_dir().join("launcherg_validate_launch_target.exe");
+        std::fs::write(&file, b"").unwrap();
+        let path = file.to_string_lossy().to_string();
+
+        let (name, validated_path) =
+            validate_launch_target(" 設定 ", &format!(" {} ", path)).unwrap();
+        assert_eq!(name, "設定");
+        assert_eq!(validated_path, path);
+
+        let err = validate_launch_target("  ", &path).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::LaunchTargetNameIsEmpty)
+        ));
+
+        let missing = file.with_file_name("launcherg_missing_launch_target.exe");
+        let err = validate_launch_target("設定", &missing.to_string_lossy()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::IsNotValidPath(_))
+        ));
+
+        std::fs::remove_file(file).unwrap();
+    }
+
+    #[test]
+    fn test_normalize_launch_profile() {
+        let mut environment = std::collections::BTreeMap::new();
+        environment.insert(" LANG ".to_string(), "ja_JP".to_string());
+        let profile = LaunchProfile::new(
+            vec!["-window".to_string()],
+            Some("  ".to_string()),
+            environment,
+            Some(" LEProc.exe -run {exe} ".to_string()),
+        );
+
+        let normalized = normalize_launch_profile(profile).unwrap();
+
+        assert_eq!(normalized.working_directory, None);
+        assert_eq!(
+            normalized.wrapper_command,
+            Some("LEProc.exe -run {exe}".to_string())
+        );
+        assert_eq!(
+            normalized.environment.get("LANG"),
+            Some(&"ja_JP".to_string())
+        );
+
+        let invalid =
+            LaunchProfile::new(vec![], None, Default::default(), Some("LEProc.exe".into()));
+        assert!(normalize_launch_profile(invalid).is_err());
+    }
 }
@@ -2,18 +2,77 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum UseCaseError {
-    #[allow(dead_code)]
     #[error("コレクションが存在しません")]
     CollectionIsNotFound,
     #[allow(dead_code)]
     #[error("このコレクションは削除できません")]
     CollectionNotPermittedToDelete,
-    #[allow(dead_code)]
     #[error("コレクションはすでに存在しています")]
     CollectionIsAlreadyExist,
+    #[error("コレクション名が空です")]
+    CollectionNameIsEmpty,
+    #[error("スマートコレクションの条件が不正です: {0}")]
+    InvalidSmartCollectionFilter(String),
+    #[error("タグが存在しません")]
+    TagIsNotFound,
+    #[error("タグはすでに存在しています")]
+    TagIsAlreadyExist,
+    #[error("タグ名が空です")]
+    TagNameIsEmpty,
+    #[error("タグの検索式が不正です: {0}")]
+    InvalidTagExpression(String),
+    #[error("起動対象が存在しません")]
+    LaunchTargetIsNotFound,
+    #[error("起動対象の名前が空です")]
+    LaunchTargetNameIsEmpty,
+    #[error("起動設定が不正です: {0}")]
+    InvalidLaunchProfile(String),
+    #[error("フックが存在しません")]
+    LaunchHookIsNotFound,
+    #[error("フックの設定が不正です: {0}")]
+    InvalidLaunchHook(String),
+    #[error("起動前フック`{0}`が失敗したため起動を中止しました")]
+    PreLaunchHookFailed(String),
+    #[error("このゲームはすでに追跡しています")]
+    GameIsAlreadyTracked,
+    #[error("追跡中のゲームがありません")]
+    NoActiveTrackingSession,
+    #[error("プロセスが見つかりません: {0}")]
+    ProcessIsNotFound(u32),
+    #[error("日付の区切りが不正です: {0}")]
+    InvalidDayBoundary(String),
+    #[error("ゲームの追跡中は日付の区切りを変更できません")]
+    DayBoundaryCannotChangeWhileTracking,
+    #[error("プレイセッションが存在しません")]
+    PlaySessionIsNotFound,
+    #[error("プレイセッションが不正です: {0}")]
+    InvalidPlaySession(String),
+    #[error("追跡中のゲームのプレイセッションは編集できません")]
+    PlaySessionCannotEditWhileTracking,
+    #[error("プレイセッションの変更履歴が存在しません")]
+    PlaySessionAuditIsNotFound,
+    #[error("この変更はすでに取り消されています")]
+    PlaySessionAuditIsAlreadyUndone,
+    #[error("変更後にプレイセッションが編集されているため取り消せません")]
+    PlaySessionAuditCannotBeUndone,
+    #[error("集計の期間が不正です")]
+    InvalidPlayStatsRange,
+    #[error("`{0}`を読み込めません")]
+    ExternalPlayTimeFileIsNotReadable(String),
+    #[error("`{0}`はフォルダではありません")]
+    LibraryRootIsNotDirectory(String),
+    #[error("このフォルダはすでに監視しています")]
+    LibraryRootIsAlreadyExist,
+    #[error("ゲームの候補が存在しません")]
+    LibrarySuggestionIsNotFound,
+    #[error("ゲームとの紐づけの規則が不正です: {0}")]
+    MatchingRuleIsInvalid(String),
+    #[error("`{0}`を読み込めません")]
+    LearnedGameMatchesFileIsNotReadable(String),
+    #[error("ゲームとの紐づけが不正です: {0}")]
+    LearnedGameMatchIsInvalid(String),
     #[error("コレクションエレメントが存在しません")]
     CollectionElementIsNotFound,
-    #[allow(dead_code)]
     #[error("`{0}`に有効な実行ファイルが存在しません")]
     IsNotValidPath(String),
 }
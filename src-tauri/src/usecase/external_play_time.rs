@@ -0,0 +1,556 @@
+//! 他のランチャーやストアのプレイ時間の取り込み
+//!
+//! 同じゲームのプレイ時間を複数の取り込み元が持つ場合は、そのうち最も長いものだけを数える。
+//! 同じ記録を再び取り込んだ場合は前回から増えた分だけを加える
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::Serialize;
+
+use crate::domain::{
+    candidate_index::GameCandidateIndex,
+    external_play_time::{ExternalPlayRecord, ExternalPlayTimeImport, ExternalPlayTimeSource},
+    file::get_game_candidates_by_exe_path,
+    learned_match::LearnedGameMatches,
+    matching_rule::MatchingRules,
+    play_day::{split_play_time_by_local_date, DayBoundary},
+    play_session::PlaySession,
+};
+
+/// 取り込み元の記録をどのようにエレメントと対応付けたか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExternalMatchedBy {
+    SteamAppId,
+    ExePath,
+    Title,
+}
+
+/// 記録を登録済みのエレメントと対応付ける
+///
+/// `steam_app_ids` は Steam の App ID からエレメントの ID、`index` はエレメントの ID と正規化したゲーム名の索引。
+/// パスとタイトルは走査と同じ規則と、ユーザーが直した紐づけで比べる
+pub fn match_external_record(
+    rules: &MatchingRules,
+    learned: &LearnedGameMatches,
+    record: &ExternalPlayRecord,
+    steam_app_ids: &HashMap<u32, i32>,
+    index: &GameCandidateIndex,
+) -> Option<(i32, ExternalMatchedBy)> {
+    if let Some(element_id) = record.steam_app_id.and_then(|v| steam_app_ids.get(&v)) {
+        return Some((*element_id, ExternalMatchedBy::SteamAppId));
+    }
+    let find = |path: &str| {
+        get_game_candidates_by_exe_path(rules, learned, index, path, 0.8, 1)
+            .ok()
+            .and_then(|v| v.first().map(|candidate| candidate.id))
+    };
+    if let Some(element_id) = record.exe_path.as_deref().and_then(find) {
+        return Some((element_id, ExternalMatchedBy::ExePath));
+    }
+    // タイトルをフォルダ名とファイル名に見立てて比べる
+    let title = record
+        .title
+        .as_deref()?
+        .replace(['/', '\\'], " ")
+        .trim()
+        .to_string();
+    if title.is_empty() {
+        return None;
+    }
+    let path = PathBuf::from(&title).join(format!("{}.exe", title));
+    find(&path.to_string_lossy()).map(|element_id| (element_id, ExternalMatchedBy::Title))
+}
+
+/// 1つの記録の取り込みで反映する内容
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExternalImportPlan {
+    /// エレメントの合計プレイ時間に加える時間
+    pub imported_seconds: i64,
+    /// 取り込み後にこの記録で反映済みとする取り込み元の合計プレイ時間
+    pub credited_seconds: i64,
+    /// 新しく登録するセッション
+    pub sessions: Vec<(DateTime<Local>, DateTime<Local>)>,
+    pub daily_play_times: Vec<(NaiveDate, i32)>,
+    pub first_played_at: Option<DateTime<Local>>,
+    pub last_played_at: Option<DateTime<Local>>,
+    pub last_session_ended_at: Option<DateTime<Local>>,
+}
+
+impl ExternalImportPlan {
+    pub fn is_empty(&self) -> bool {
+        self.imported_seconds == 0 && self.sessions.is_empty()
+    }
+}
+
+fn session_range(session: &PlaySession) -> (DateTime<Local>, DateTime<Local>) {
+    let ended_at = session.ended_at.unwrap_or_else(|| {
+        session.started_at + chrono::Duration::seconds(session.played_seconds as i64)
+    });
+    (session.started_at, ended_at)
+}
+
+fn overlaps(
+    a: &(DateTime<Local>, DateTime<Local>),
+    b: &(DateTime<Local>, DateTime<Local>),
+) -> bool {
+    a.0 < b.1 && b.0 < a.1
+}
+
+/// `range` のうち `covered` のどれとも重ならない範囲
+fn uncovered_ranges(
+    range: (DateTime<Local>, DateTime<Local>),
+    covered: &[(DateTime<Local>, DateTime<Local>)],
+) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+    let mut clipped: Vec<_> = covered
+        .iter()
+        .filter(|v| overlaps(v, &range))
+        .map(|v| (v.0.max(range.0), v.1.min(range.1)))
+        .collect();
+    clipped.sort();
+    let mut ranges = vec![];
+    let mut cursor = range.0;
+    for (start, end) in clipped {
+        if cursor < start {
+            ranges.push((cursor, start));
+        }
+        cursor = cursor.max(end);
+    }
+    if cursor < range.1 {
+        ranges.push((cursor, range.1));
+    }
+    ranges
+}
+
+/// 記録から取り込む内容を求める
+///
+/// `imports` は同じエレメントの取り込み済みの記録 (取り込み元を問わない)、`sessions` は同じエレメントの登録済みのセッション。
+/// 登録済みのセッションと重なる時間は追跡で数えているため、セッションを登録せず加える時間からも除く。
+/// セッションを持たない記録は追跡した時間も合計に含むため、最後にプレイした日時までに始めた
+/// 追跡済みのセッションのうち前回の取り込み以降に終わったものの時間を除く。
+/// 加える時間はセッション (重なるものは重ならない範囲) の新しいものから順に、そのプレイ日へ振り分ける。
+/// セッションで振り分けきれない分は最後にプレイした日 (分からない場合は `now`) にまとめる
+pub fn plan_external_import(
+    source: ExternalPlayTimeSource,
+    record: &ExternalPlayRecord,
+    imports: &[ExternalPlayTimeImport],
+    sessions: &[PlaySession],
+    day_boundary: DayBoundary,
+    now: DateTime<Local>,
+) -> ExternalImportPlan {
+    let previous = imports
+        .iter()
+        .find(|v| v.source == source && v.external_id == record.external_id);
+    let credited_for_element = imports
+        .iter()
+        .map(|v| v.imported_seconds)
+        .max()
+        .unwrap_or_default();
+
+    let tracked: Vec<_> = sessions.iter().map(session_range).collect();
+    let mut existing = tracked.clone();
+    let mut new_sessions = vec![];
+    // 時間を振り分ける範囲
+    let mut played_ranges = vec![];
+    let mut tracked_seconds = 0;
+    let mut last_session_ended_at = previous.and_then(|v| v.last_session_ended_at);
+    for session in record.sessions.iter() {
+        let is_imported = previous
+            .and_then(|v| v.last_session_ended_at)
+            .is_some_and(|v| session.1 <= v);
+        if is_imported {
+            continue;
+        }
+        last_session_ended_at = last_session_ended_at.max(Some(session.1));
+        if tracked.iter().any(|v| overlaps(v, session)) {
+            let uncovered = uncovered_ranges(*session, &tracked);
+            let uncovered_seconds: i64 = uncovered
+                .iter()
+                .map(|(start, end)| (*end - *start).num_seconds())
+                .sum();
+            tracked_seconds += (session.1 - session.0).num_seconds() - uncovered_seconds;
+            played_ranges.extend(uncovered);
+            continue;
+        }
+        if existing.iter().any(|v| overlaps(v, session)) {
+            continue;
+        }
+        existing.push(*session);
+        new_sessions.push(*session);
+        played_ranges.push(*session);
+    }
+    if record.sessions.is_empty() {
+        let previous_ended_at = previous.and_then(|v| v.last_session_ended_at);
+        for session in sessions.iter().filter(|v| v.is_tracked()) {
+            let Some(ended_at) = session.ended_at else {
+                continue;
+            };
+            let is_counted = previous_ended_at.is_some_and(|v| ended_at <= v);
+            let is_after_record = record
+                .last_played_at
+                .is_some_and(|v| session.started_at > v);
+            if is_counted || is_after_record {
+                continue;
+            }
+            tracked_seconds += session.played_seconds as i64;
+            last_session_ended_at = last_session_ended_at.max(Some(ended_at));
+        }
+    }
+    new_sessions.sort();
+    played_ranges.sort();
+
+    let session_seconds: i64 = record
+        .sessions
+        .iter()
+        .map(|(start, end)| (*end - *start).num_seconds())
+        .sum();
+    let total_seconds = record.total_seconds.max(session_seconds);
+    let imported_seconds = (total_seconds - credited_for_element - tracked_seconds).max(0);
+
+    let mut daily: BTreeMap<NaiveDate, i32> = BTreeMap::new();
+    let mut remaining = imported_seconds;
+    for (started_at, ended_at) in played_ranges.iter().rev() {
+        if remaining <= 0 {
+            break;
+        }
+        let seconds = (*ended_at - *started_at).num_seconds().min(remaining);
+        for (play_date, daily_seconds) in
+            split_play_time_by_local_date(*ended_at, seconds as i32, day_boundary)
+        {
+            *daily.entry(play_date).or_default() += daily_seconds;
+        }
+        remaining -= seconds;
+    }
+    let mut first_played_at = played_ranges.first().map(|v| v.0);
+    let mut last_played_at = played_ranges.last().map(|v| v.1);
+    if remaining > 0 {
+        let played_at = record.last_played_at.unwrap_or(now);
+        *daily.entry(day_boundary.play_date(played_at)).or_default() += remaining as i32;
+        first_played_at = Some(first_played_at.map_or(played_at, |v| v.min(played_at)));
+    }
+    if imported_seconds > 0 {
+        if let Some(played_at) = record.last_played_at {
+            last_played_at = Some(last_played_at.map_or(played_at, |v| v.max(played_at)));
+        }
+    }
+
+    ExternalImportPlan {
+        imported_seconds,
+        credited_seconds: total_seconds
+            .max(previous.map(|v| v.imported_seconds).unwrap_or_default()),
+        last_session_ended_at,
+        sessions: new_sessions,
+        daily_play_times: daily.into_iter().collect(),
+        first_played_at,
+        last_played_at,
+    }
+}
+
+/// 取り込みの結果の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExternalImportStatus {
+    Imported,
+    /// 試しに実行した場合に取り込むもの
+    WouldImport,
+    AlreadyImported,
+    Unmatched,
+    /// プレイ中のため取り込まなかったもの
+    Tracking,
+}
+
+/// 取り込み元の記録ごとの結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportEntry {
+    pub external_id: String,
+    pub title: Option<String>,
+    pub collection_element_id: Option<i32>,
+    pub gamename: Option<String>,
+    pub matched_by: Option<ExternalMatchedBy>,
+    pub total_seconds: i64,
+    pub imported_seconds: i64,
+    pub session_count: i32,
+    pub status: ExternalImportStatus,
+}
+
+/// 取り込みの結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportReport {
+    pub source: ExternalPlayTimeSource,
+    pub dry_run: bool,
+    pub imported_seconds: i64,
+    pub imported_sessions: i32,
+    pub entries: Vec<ExternalImportEntry>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        all_game_cache::AllGameCacheOne,
+        learned_match::{LearnedGameMatch, LearnedGameMatchKey},
+        normalize::normalize,
+        Id,
+    };
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2023, 1, day, hour, 0, 0).unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 1, day).unwrap()
+    }
+
+    fn record(
+        total_seconds: i64,
+        sessions: Vec<(DateTime<Local>, DateTime<Local>)>,
+    ) -> ExternalPlayRecord {
+        ExternalPlayRecord {
+            external_id: "abc".to_string(),
+            title: Some("サクラノ刻".to_string()),
+            steam_app_id: None,
+            exe_path: None,
+            total_seconds,
+            last_played_at: Some(at(10, 12)),
+            sessions,
+        }
+    }
+
+    fn import(
+        source: ExternalPlayTimeSource,
+        external_id: &str,
+        imported_seconds: i64,
+        last_session_ended_at: Option<DateTime<Local>>,
+    ) -> ExternalPlayTimeImport {
+        ExternalPlayTimeImport {
+            source,
+            external_id: external_id.to_string(),
+            collection_element_id: Id::new(1),
+            imported_seconds,
+            last_session_ended_at,
+        }
+    }
+
+    fn session(started_at: DateTime<Local>, ended_at: DateTime<Local>) -> PlaySession {
+        PlaySession {
+            id: Id::new(1),
+            collection_element_id: Id::new(1),
+            started_at,
+            ended_at: Some(ended_at),
+            pid: None,
+            exe_path: None,
+            played_seconds: (ended_at - started_at).num_seconds() as i32,
+            paused_seconds: 0,
+            played_ranges: vec![],
+        }
+    }
+
+    #[test]
+    fn test_match_external_record() {
+        let index = GameCandidateIndex::new(vec![
+            AllGameCacheOne::new(10, normalize("サクラノ刻")),
+            AllGameCacheOne::new(20, normalize("ぬきたし")),
+        ]);
+        let steam_app_ids = HashMap::from([(1238020, 20)]);
+        let rules = MatchingRules::bundled().unwrap();
+        let learned = LearnedGameMatches::default();
+
+        let mut by_title = record(60, vec![]);
+        assert_eq!(
+            match_external_record(&rules, &learned, &by_title, &steam_app_ids, &index),
+            Some((10, ExternalMatchedBy::Title))
+        );
+
+        by_title.steam_app_id = Some(1238020);
+        assert_eq!(
+            match_external_record(&rules, &learned, &by_title, &steam_app_ids, &index),
+            Some((20, ExternalMatchedBy::SteamAppId))
+        );
+
+        let unknown = ExternalPlayRecord {
+            title: Some("Unknown Game".to_string()),
+            ..record(60, vec![])
+        };
+        assert_eq!(
+            match_external_record(&rules, &learned, &unknown, &steam_app_ids, &index),
+            None
+        );
+
+        // ユーザーが直した紐づけのパスは名前が似ていなくても対応付ける
+        let by_learned_path = ExternalPlayRecord {
+            exe_path: Some("D:/Games/Other/launcher.exe".to_string()),
+            ..unknown
+        };
+        let learned = LearnedGameMatches::new(&[LearnedGameMatch::new(
+            Id::new(1),
+            LearnedGameMatchKey::new(normalize("Other"), normalize("launcher")),
+            20,
+            Local::now(),
+        )]);
+        assert_eq!(
+            match_external_record(&rules, &learned, &by_learned_path, &steam_app_ids, &index),
+            Some((20, ExternalMatchedBy::ExePath))
+        );
+    }
+
+    #[test]
+    fn test_plan_external_import_sessions() {
+        let record = record(
+            5400,
+            vec![
+                (at(2, 10), at(2, 11)),
+                (at(3, 10), at(3, 10) + chrono::Duration::minutes(30)),
+            ],
+        );
+
+        let plan = plan_external_import(
+            ExternalPlayTimeSource::Vnite,
+            &record,
+            &[],
+            &[],
+            DayBoundary::default(),
+            at(20, 0),
+        );
+
+        assert_eq!(plan.imported_seconds, 5400);
+        assert_eq!(plan.credited_seconds, 5400);
+        assert_eq!(plan.sessions.len(), 2);
+        assert_eq!(
+            plan.daily_play_times,
+            vec![(date(2), 3600), (date(3), 1800)]
+        );
+        assert_eq!(plan.first_played_at, Some(at(2, 10)));
+        assert_eq!(plan.last_played_at, Some(at(10, 12)));
+        assert_eq!(
+            plan.last_session_ended_at,
+            Some(at(3, 10) + chrono::Duration::minutes(30))
+        );
+    }
+
+    #[test]
+    fn test_plan_external_import_does_not_double_count() {
+        let record = record(7200, vec![(at(2, 10), at(2, 11)), (at(5, 10), at(5, 11))]);
+        let imports = vec![
+            // 前回は 1 月 2 日のセッションまでを取り込み済み
+            import(ExternalPlayTimeSource::Vnite, "abc", 3600, Some(at(2, 11))),
+            // 別の取り込み元が同じ時間を反映済み
+            import(ExternalPlayTimeSource::Steam, "1238020", 3600, None),
+        ];
+        // 1 月 5 日 10:00-11:00 のうち 10:30 までは追跡で記録済み
+        let sessions = vec![session(at(5, 9), at(5, 10) + chrono::Duration::minutes(30))];
+
+        let plan = plan_external_import(
+            ExternalPlayTimeSource::Vnite,
+            &record,
+            &imports,
+            &sessions,
+            DayBoundary::default(),
+            at(20, 0),
+        );
+
+        // 重なる 30 分は加えず、重ならない 30 分だけをそのプレイ日に加える
+        assert_eq!(plan.imported_seconds, 1800);
+        assert_eq!(plan.credited_seconds, 7200);
+        assert!(plan.sessions.is_empty());
+        assert_eq!(plan.daily_play_times, vec![(date(5), 1800)]);
+        assert_eq!(plan.last_session_ended_at, Some(at(5, 11)));
+
+        // 別の取り込み元でより長い時間を反映済みなら、重なる時間を除いて残る分はない
+        let imports = vec![
+            import(ExternalPlayTimeSource::Vnite, "abc", 3600, Some(at(2, 11))),
+            import(ExternalPlayTimeSource::Steam, "1238020", 5400, None),
+        ];
+        let plan = plan_external_import(
+            ExternalPlayTimeSource::Vnite,
+            &record,
+            &imports,
+            &sessions,
+            DayBoundary::default(),
+            at(20, 0),
+        );
+        assert_eq!(plan.imported_seconds, 0);
+        assert!(plan.daily_play_times.is_empty());
+        assert_eq!(plan.last_session_ended_at, Some(at(5, 11)));
+
+        // 反映済みの時間を超えていなければ何もしない
+        let steam = ExternalPlayRecord {
+            external_id: "1238020".to_string(),
+            ..record.clone()
+        };
+        let imports = vec![import(
+            ExternalPlayTimeSource::Steam,
+            "1238020",
+            7200,
+            Some(at(5, 11)),
+        )];
+        let plan = plan_external_import(
+            ExternalPlayTimeSource::Steam,
+            &steam,
+            &imports,
+            &[],
+            DayBoundary::default(),
+            at(20, 0),
+        );
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn test_plan_external_import_excludes_tracked_time_from_total() {
+        let tracked = |start, end| PlaySession {
+            pid: Some(100),
+            ..session(start, end)
+        };
+        // 合計だけの記録は、Steam から起動して追跡した時間も含む
+        let record = record(7200, vec![]);
+        let sessions = vec![
+            tracked(at(9, 10), at(9, 11)),
+            // 手入力のセッションは取り込み元の合計に含まない
+            session(at(8, 10), at(8, 11)),
+            // 取り込み元が最後にプレイした日時より後のセッション
+            tracked(at(11, 10), at(11, 11)),
+        ];
+
+        let plan = plan_external_import(
+            ExternalPlayTimeSource::Steam,
+            &record,
+            &[],
+            &sessions,
+            DayBoundary::default(),
+            at(20, 0),
+        );
+
+        assert_eq!(plan.imported_seconds, 3600);
+        assert_eq!(plan.credited_seconds, 7200);
+        assert_eq!(plan.daily_play_times, vec![(date(10), 3600)]);
+        assert_eq!(plan.last_session_ended_at, Some(at(9, 11)));
+
+        // 除いたセッションは次の取り込みで再び除かない
+        let record = ExternalPlayRecord {
+            total_seconds: 9000,
+            ..record
+        };
+        let imports = vec![import(
+            ExternalPlayTimeSource::Steam,
+            &record.external_id,
+            7200,
+            Some(at(9, 11)),
+        )];
+        let plan = plan_external_import(
+            ExternalPlayTimeSource::Steam,
+            &record,
+            &imports,
+            &sessions,
+            DayBoundary::default(),
+            at(20, 0),
+        );
+        assert_eq!(plan.imported_seconds, 1800);
+        assert_eq!(plan.last_session_ended_at, Some(at(9, 11)));
+    }
+}
@@ -1,4 +1,3 @@
-use std::io::prelude::*;
 use std::sync::Mutex;
 use std::{collections::HashMap, sync::Arc, time::Instant};
 
@@ -8,16 +7,24 @@ use tauri::AppHandle;
 use crate::domain::all_game_cache::{AllGameCache, AllGameCacheOne};
 use crate::domain::file::{
     get_file_created_at_sync, get_file_name_without_extension, get_game_candidates_by_exe_path,
-    PlayHistory,
 };
 use crate::{
     domain::{
-        collection::{CollectionElement, NewCollectionElement},
+        candidate_index::GameCandidateIndex,
+        collection::NewCollectionElement,
         distance::get_comparable_distance,
         explorer::file::FileExplorer,
         file::{
-            get_file_paths_by_exts, get_lnk_metadatas, get_most_probable_game_candidate,
-            get_play_history_path, normalize, save_icon_to_png,
+            get_file_paths_by_exts, get_lnk_metadatas, get_most_probable_game_match,
+            save_icon_to_png,
+        },
+        learned_match::LearnedGameMatches,
+        matching_rule::MatchingRules,
+        normalize::normalize,
+        pe_version::get_exe_version_info,
+        scan::{
+            GameCandidateMatch, ScanCandidate, ScanConflict, ScanFileResult, ScanOutcome,
+            ScanReport, ScanRule,
         },
         Id,
     },
@@ -32,40 +39,6 @@ pub struct FileUseCase<R: ExplorersExt> {
 type FilePathString = String;
 type ErogamescapeID = i32;
 
-const IGNORE_WORD_WHEN_CONFLICT: [&str; 29] = [
-    "設定",
-    "チェック",
-    "インスト",
-    "削除",
-    "ファイル",
-    "ください",
-    "下さい",
-    "マニュアル",
-    "アップデート",
-    "システム",
-    "check",
-    "setting",
-    "config",
-    "update",
-    "inst",
-    "tool",
-    "support",
-    "setup",
-    "unins",
-    "define",
-    "bhvc",
-    "bootstrap",
-    "file",
-    "exhibit",
-    "ihs",
-    "launcher",
-    "syscfg",
-    "updchk",
-    "acmp",
-];
-
-const SHOULD_UPDATE_WORD_WHEN_CONFLICT: [&str; 6] = ["adv", "64", "cmvs", "bgi", "実行", "起動"];
-
 fn emit_progress_with_time(
     f: Arc<impl Fn(String) -> anyhow::Result<()>>,
     start: Instant,
@@ -100,76 +73,128 @@ impl<R: ExplorersExt> FileUseCase<R> {
     // 各ゲームとして一番の候補の配列から重複したものを踏まえて各ゲームに対して最高1つのファイルパスにする
     pub fn get_map_of_one_filepath_per_game(
         &self,
+        rules: &MatchingRules,
+        learned: &LearnedGameMatches,
         cache_path_pairs: Vec<(AllGameCacheOne, String)>,
-    ) -> HashMap<ErogamescapeID, FilePathString> {
+    ) -> (HashMap<ErogamescapeID, FilePathString>, Vec<ScanConflict>) {
         let mut res: HashMap<ErogamescapeID, FilePathString> = HashMap::new();
+        let mut conflicts = vec![];
         for (cache, filepath_unnormalized) in cache_path_pairs {
-            match res.get(&cache.id) {
-                Some(current_filepath) => {
+            match res.get(&cache.id).cloned() {
+                Some(current_filepath_unnormalized) => {
+                    // 記号を取り除く前にファイル名を取り出す
                     let current_filepath =
-                        get_file_name_without_extension(&normalize(current_filepath))
-                            .unwrap_or_default();
-                    let filepath =
-                        get_file_name_without_extension(&normalize(&filepath_unnormalized))
+                        get_file_name_without_extension(&current_filepath_unnormalized)
+                            .map(|v| normalize(&v))
                             .unwrap_or_default();
+                    let filepath = get_file_name_without_extension(&filepath_unnormalized)
+                        .map(|v| normalize(&v))
+                        .unwrap_or_default();
 
-                    let mut must_update = false;
-                    let mut not_must_update = false;
+                    let mut must_update = None;
+                    let mut not_must_update = None;
                     // 競合時に無視する単語をチェックする
-                    for ignore_word in IGNORE_WORD_WHEN_CONFLICT {
-                        if current_filepath.contains(ignore_word) {
-                            must_update = true;
+                    for ignore_word in rules.ignore_words_on_conflict.iter() {
+                        if current_filepath.contains(ignore_word.as_str()) {
+                            must_update = Some((ScanRule::IgnoreWordOnConflict, ignore_word));
                             break;
                         }
-                        if filepath.contains(ignore_word) {
-                            not_must_update = true;
+                        if filepath.contains(ignore_word.as_str()) {
+                            not_must_update = Some((ScanRule::IgnoreWordOnConflict, ignore_word));
                             break;
                         }
                     }
                     // 競合時に更新する単語をチェックする
-                    for update_word in SHOULD_UPDATE_WORD_WHEN_CONFLICT {
-                        if current_filepath.contains(update_word) {
-                            not_must_update = true;
+                    for update_word in rules.preferred_words_on_conflict.iter() {
+                        if current_filepath.contains(update_word.as_str()) {
+                            not_must_update = not_must_update
+                                .or(Some((ScanRule::PreferredWordOnConflict, update_word)));
                             break;
                         }
-                        if filepath.contains(update_word) {
-                            must_update = true;
+                        if filepath.contains(update_word.as_str()) {
+                            must_update = must_update
+                                .or(Some((ScanRule::PreferredWordOnConflict, update_word)));
                             break;
                         }
                     }
-                    if must_update && !not_must_update {
-                        res.insert(cache.id, filepath_unnormalized);
-                    } else if !not_must_update {
-                        let gamename = &cache.gamename;
-                        let current_distance = get_comparable_distance(&current_filepath, gamename);
-                        let distance = get_comparable_distance(&filepath, gamename);
-                        if current_distance < distance {
-                            res.insert(cache.id, filepath_unnormalized);
-                        }
-                    }
+                    // ユーザーが直した紐づけのファイルを単語より優先する
+                    let is_learned = |path: &str| {
+                        learned
+                            .find(path)
+                            .is_some_and(|(_, game_id)| game_id == cache.id)
+                    };
+                    let is_learned_filepath = is_learned(&filepath_unnormalized);
+                    let (is_update, rule, word) =
+                        if is_learned(&current_filepath_unnormalized) != is_learned_filepath {
+                            (is_learned_filepath, ScanRule::LearnedMatch, None)
+                        } else {
+                            match (must_update, not_must_update) {
+                                (_, Some((rule, word))) => (false, rule, Some(word)),
+                                (Some((rule, word)), None) => (true, rule, Some(word)),
+                                (None, None) => {
+                                    let gamename = &cache.gamename;
+                                    let current_distance =
+                                        get_comparable_distance(&current_filepath, gamename);
+                                    let distance = get_comparable_distance(&filepath, gamename);
+                                    (
+                                        current_distance < distance,
+                                        ScanRule::SimilarityOnConflict,
+                                        None,
+                                    )
+                                }
+                            }
+                        };
+                    let (kept_path, dropped_path) = match is_update {
+                        true => (filepath_unnormalized, current_filepath_unnormalized),
+                        false => (current_filepath_unnormalized, filepath_unnormalized),
+                    };
+                    conflicts.push(ScanConflict::new(
+                        cache.id,
+                        cache.gamename,
+                        kept_path.clone(),
+                        dropped_path,
+                        rule,
+                        word.cloned(),
+                    ));
+                    res.insert(cache.id, kept_path);
                 }
                 None => {
                     res.insert(cache.id, filepath_unnormalized);
                 }
             }
         }
-        res
+        (res, conflicts)
     }
+    /// `lnk_targets` はショートカットのリンク先。バージョン情報はリンク先の実行ファイルから読む
     pub async fn concurrency_get_path_game_map<F: Fn() -> anyhow::Result<()> + Send + 'static>(
         &self,
-        normalized_all_games: Arc<AllGameCache>,
+        rules: Arc<MatchingRules>,
+        learned: Arc<LearnedGameMatches>,
+        index: Arc<GameCandidateIndex>,
         files: Vec<String>,
+        lnk_targets: HashMap<String, String>,
         callback: Arc<Mutex<F>>,
-    ) -> anyhow::Result<HashMap<ErogamescapeID, FilePathString>> {
+    ) -> anyhow::Result<Vec<(FilePathString, anyhow::Result<GameCandidateMatch>)>> {
+        let lnk_targets = Arc::new(lnk_targets);
         let get_game_id_tasks = files.into_iter().map(|path| {
-            let all = normalized_all_games.clone();
+            let index = index.clone();
+            let rules = rules.clone();
+            let learned = learned.clone();
+            let lnk_targets = lnk_targets.clone();
             let mutex_cb = Arc::clone(&callback);
             tauri::async_runtime::spawn(async move {
-                let res = get_most_probable_game_candidate(&all, path)?;
+                let version_info = get_exe_version_info(lnk_targets.get(&path).unwrap_or(&path));
+                let res = get_most_probable_game_match(
+                    &rules,
+                    &learned,
+                    &index,
+                    &path,
+                    version_info.as_ref(),
+                );
                 match mutex_cb.lock() {
                     Ok(cb) => {
                         cb()?;
-                        Ok(res)
+                        Ok((path, res))
                     }
                     Err(e) => {
                         Err(anyhow::anyhow!(e.to_string()))
@@ -178,18 +203,18 @@ impl<R: ExplorersExt> FileUseCase<R> {
             })
         });
 
-        let most_probable_game_filepath_pairs: Vec<(AllGameCacheOne, FilePathString)> =
-            futures::future::try_join_all(get_game_id_tasks)
-                .await?
-                .into_iter()
-                .filter_map(|v| v.ok().and_then(|res| res))
-                .collect();
-
-        Ok(self.get_map_of_one_filepath_per_game(most_probable_game_filepath_pairs))
+        Ok(futures::future::try_join_all(get_game_id_tasks)
+            .await?
+            .into_iter()
+            .filter_map(|v: anyhow::Result<_>| v.ok())
+            .collect())
     }
     pub async fn get_game_candidates(
         &self,
+        rules: &MatchingRules,
+        learned: &LearnedGameMatches,
         all_game_cache: AllGameCache,
+        brandnames: HashMap<i32, String>,
         file: String,
     ) -> anyhow::Result<AllGameCache> {
         let normalized_all_games = all_game_cache
@@ -199,28 +224,82 @@ impl<R: ExplorersExt> FileUseCase<R> {
                 gamename: normalize(&pair.gamename),
             })
             .collect::<AllGameCache>();
-        get_game_candidates_by_exe_path(&normalized_all_games, &file, 0.2, 5)
+        get_game_candidates_by_exe_path(
+            rules,
+            learned,
+            &GameCandidateIndex::new(normalized_all_games).with_brandnames(brandnames),
+            &file,
+            0.2,
+            5,
+        )
     }
+    #[allow(clippy::too_many_arguments)]
     pub async fn filter_files_to_collection_elements<
         F: Fn() -> anyhow::Result<()> + Send + 'static,
     >(
         &self,
         handle: &Arc<AppHandle>,
+        rules: Arc<MatchingRules>,
+        learned: Arc<LearnedGameMatches>,
         files: Vec<String>,
         all_game_cache: AllGameCache,
+        brandnames: HashMap<i32, String>,
         emit_progress: Arc<impl Fn(String) -> anyhow::Result<()>>,
         process_each_game_file_callback: Arc<Mutex<F>>,
     ) -> anyhow::Result<Vec<NewCollectionElement>> {
+        let (collection_elements, _) = self
+            .scan_files_to_collection_elements(
+                handle,
+                rules,
+                learned,
+                files,
+                all_game_cache,
+                brandnames,
+                emit_progress,
+                process_each_game_file_callback,
+                false,
+            )
+            .await?;
+        Ok(collection_elements)
+    }
+    /// ファイルをゲームと紐づけ、調べたファイルごとの結果も返す
+    ///
+    /// `rules` と `learned` は紐づけに使う規則とユーザーが直した紐づけ。
+    /// `brandnames` は詳細を取得済みのゲームのブランド名で、実行ファイルの会社名と照らし合わせる。
+    /// `dry_run` の場合は icon を保存しない
+    #[allow(clippy::too_many_arguments)]
+    pub async fn scan_files_to_collection_elements<
+        F: Fn() -> anyhow::Result<()> + Send + 'static,
+    >(
+        &self,
+        handle: &Arc<AppHandle>,
+        rules: Arc<MatchingRules>,
+        learned: Arc<LearnedGameMatches>,
+        files: Vec<String>,
+        all_game_cache: AllGameCache,
+        brandnames: HashMap<i32, String>,
+        emit_progress: Arc<impl Fn(String) -> anyhow::Result<()>>,
+        process_each_game_file_callback: Arc<Mutex<F>>,
+        dry_run: bool,
+    ) -> anyhow::Result<(Vec<NewCollectionElement>, ScanReport)> {
         let start = Instant::now();
+        let mut report = ScanReport {
+            dry_run,
+            ..Default::default()
+        };
 
-        let normalized_all_games = Arc::new(
-            all_game_cache
-                .iter()
-                .map(|pair| AllGameCacheOne {
-                    id: pair.id,
-                    gamename: normalize(&pair.gamename),
-                })
-                .collect::<AllGameCache>(),
+        // 索引は走査するファイルの間で使い回す
+        let index = Arc::new(
+            GameCandidateIndex::new(
+                all_game_cache
+                    .iter()
+                    .map(|pair| AllGameCacheOne {
+                        id: pair.id,
+                        gamename: normalize(&pair.gamename),
+                    })
+                    .collect::<AllGameCache>(),
+            )
+            .with_brandnames(brandnames),
         );
         let all_erogamescape_game_map: HashMap<i32, String> = all_game_cache
             .into_iter()
@@ -252,6 +331,7 @@ impl<R: ExplorersExt> FileUseCase<R> {
                     .is_some_and(|meta| std::path::Path::new(&meta.path).exists());
                 if !is_valid {
                     skipped_count += 1;
+                    report.push_skipped(lnk_path.clone(), ScanRule::LnkTargetMissing);
                 }
                 is_valid
             }))
@@ -266,14 +346,77 @@ impl<R: ExplorersExt> FileUseCase<R> {
             }
         }
 
-        type IdPathPairs = Vec<(i32, String)>;
-        let (exe_id_path_vec, lnk_id_path_vec): (IdPathPairs, IdPathPairs) = self
+        let path_matches = self
             .concurrency_get_path_game_map(
-                normalized_all_games,
+                rules.clone(),
+                learned.clone(),
+                index,
                 filtered_files,
+                lnk_metadatas
+                    .iter()
+                    .map(|(lnk_path, meta)| (lnk_path.clone(), meta.path.clone()))
+                    .collect(),
                 process_each_game_file_callback,
             )
-            .await?
+            .await?;
+
+        let to_scan_candidates = |matched: &GameCandidateMatch| {
+            matched
+                .candidates
+                .iter()
+                .map(|(candidate, score)| {
+                    let gamename = all_erogamescape_game_map
+                        .get(&candidate.id)
+                        .cloned()
+                        .unwrap_or(candidate.gamename.clone());
+                    ScanCandidate::new(candidate.id, gamename, *score)
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut most_probable_game_filepath_pairs = vec![];
+        for (path, matched) in path_matches {
+            let matched = match matched {
+                Ok(matched) => matched,
+                Err(_) => {
+                    report.push_skipped(path, ScanRule::InvalidPath);
+                    continue;
+                }
+            };
+            let outcome = match matched.most_probable() {
+                Some(candidate) => {
+                    most_probable_game_filepath_pairs.push((candidate.clone(), path.clone()));
+                    ScanOutcome::Matched
+                }
+                None => ScanOutcome::Skipped,
+            };
+            report.files.push(ScanFileResult::new(
+                path,
+                outcome,
+                matched.rule,
+                to_scan_candidates(&matched),
+                matched.hits,
+            ));
+        }
+
+        let (id_path_map, conflicts) = self.get_map_of_one_filepath_per_game(
+            &rules,
+            &learned,
+            most_probable_game_filepath_pairs,
+        );
+        report.apply_conflicts(
+            conflicts
+                .into_iter()
+                .map(|mut conflict| {
+                    if let Some(gamename) = all_erogamescape_game_map.get(&conflict.game_id) {
+                        conflict.gamename = gamename.clone();
+                    }
+                    conflict
+                })
+                .collect(),
+        );
+
+        type IdPathPairs = Vec<(i32, String)>;
+        let (exe_id_path_vec, lnk_id_path_vec): (IdPathPairs, IdPathPairs) = id_path_map
             .into_iter()
             .partition(|(_id, path)| path.to_lowercase().ends_with("exe"));
 
@@ -287,8 +430,10 @@ impl<R: ExplorersExt> FileUseCase<R> {
         let mut save_icon_tasks = vec![];
         for (id, exe_path) in exe_id_path_vec.into_iter() {
             // icon
-            let task = save_icon_to_png(handle, &exe_path, &Id::new(id))?;
-            save_icon_tasks.push(task);
+            if !dry_run {
+                let task = save_icon_to_png(handle, &exe_path, &Id::new(id))?;
+                save_icon_tasks.push(task);
+            }
 
             // new collection element
             if let Some(gamename) = all_erogamescape_game_map.get(&id) {
@@ -307,13 +452,15 @@ impl<R: ExplorersExt> FileUseCase<R> {
             let install_at;
             // icon (lnk_metadatas は既に事前解決済み)
             if let Some(metadata) = lnk_metadatas.get(lnk_path.as_str()) {
-                let icon_path = if !metadata.icon.is_empty() {
-                    &metadata.icon
-                } else {
-                    &metadata.path
-                };
-                let task = save_icon_to_png(handle, icon_path, &id)?;
-                save_icon_tasks.push(task);
+                if !dry_run {
+                    let icon_path = if !metadata.icon.is_empty() {
+                        &metadata.icon
+                    } else {
+                        &metadata.path
+                    };
+                    let task = save_icon_to_png(handle, icon_path, &id)?;
+                    save_icon_tasks.push(task);
+                }
 
                 install_at = get_file_created_at_sync(&metadata.path);
             } else {
@@ -335,9 +482,15 @@ impl<R: ExplorersExt> FileUseCase<R> {
             .into_iter()
             .collect::<anyhow::Result<()>>()?;
 
-        emit_progress_with_time(emit_progress.clone(), start, "icon の保存が完了しました。")?;
+        if !dry_run {
+            emit_progress_with_time(emit_progress.clone(), start, "icon の保存が完了しました。")?;
+        }
 
-        Ok(collection_elements)
+        report.registered = collection_elements
+            .iter()
+            .map(|v| v.gamename.clone())
+            .collect();
+        Ok((collection_elements, report))
     }
     pub fn get_new_upload_image_path(
         &self,
@@ -363,29 +516,4 @@ impl<R: ExplorersExt> FileUseCase<R> {
             .save_base64_image(&path, base64_image)?;
         Ok(path)
     }
-
-    pub fn get_play_time_minutes(
-        &self,
-        handle: &Arc<AppHandle>,
-        collection_element_id: &Id<CollectionElement>,
-    ) -> anyhow::Result<f32> {
-        let path = get_play_history_path(handle, collection_element_id);
-
-        let exist = std::path::Path::new(&path).exists();
-        if !exist {
-            return Ok(0.0);
-        }
-
-        let file = std::fs::File::open(&path)?;
-        let reader = std::io::BufReader::new(file);
-
-        let mut histories = vec![];
-        for line in reader.lines().map_while(Result::ok) {
-            if let Ok(history) = serde_json::from_str::<PlayHistory>(&line) {
-                histories.push(history)
-            }
-        }
-
-        Ok(histories.into_iter().map(|v| v.minutes).sum())
-    }
 }
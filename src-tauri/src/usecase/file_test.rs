@@ -1,5 +1,13 @@
 #[cfg(test)]
 mod tests {
+    use crate::domain::{
+        all_game_cache::AllGameCacheOne,
+        learned_match::{LearnedGameMatch, LearnedGameMatchKey, LearnedGameMatches},
+        matching_rule::MatchingRules,
+        normalize::normalize,
+        scan::ScanRule,
+        Id,
+    };
     use crate::usecase::file::FileUseCase;
     use std::sync::Arc;
 
@@ -50,4 +58,62 @@ mod tests {
         // コンパイルが通ることの確認用
         let _ = FileUseCase::new(Arc::new(DummyExplorers));
     }
+
+    #[test]
+    fn test_get_map_of_one_filepath_per_game_reports_conflict_words() {
+        let use_case = FileUseCase::new(Arc::new(DummyExplorers));
+        let game = AllGameCacheOne::new(1, normalize("ゲームA"));
+
+        let (map, conflicts) = use_case.get_map_of_one_filepath_per_game(
+            &MatchingRules::bundled().unwrap(),
+            &LearnedGameMatches::default(),
+            vec![
+                (game.clone(), "C:/Games/ゲームA/setting.exe".to_string()),
+                (game.clone(), "C:/Games/ゲームA/ゲームA.exe".to_string()),
+                (game, "C:/Games/ゲームA/ゲームA_adv.exe".to_string()),
+            ],
+        );
+
+        assert_eq!(
+            map.get(&1).map(|v| v.as_str()),
+            Some("C:/Games/ゲームA/ゲームA_adv.exe")
+        );
+        assert_eq!(conflicts.len(), 2);
+        assert_eq!(conflicts[0].rule, ScanRule::IgnoreWordOnConflict);
+        assert_eq!(conflicts[0].word.as_deref(), Some("setting"));
+        assert_eq!(conflicts[0].dropped_path, "C:/Games/ゲームA/setting.exe");
+        assert_eq!(conflicts[1].rule, ScanRule::PreferredWordOnConflict);
+        assert_eq!(conflicts[1].word.as_deref(), Some("adv"));
+        assert_eq!(conflicts[1].dropped_path, "C:/Games/ゲームA/ゲームA.exe");
+    }
+
+    #[test]
+    fn test_get_map_of_one_filepath_per_game_prefers_learned_match() {
+        let learned = LearnedGameMatches::new(&[LearnedGameMatch::new(
+            Id::new(1),
+            LearnedGameMatchKey::new(normalize("ゲームB"), "config".to_string()),
+            2,
+            chrono::Local::now(),
+        )]);
+        let use_case = FileUseCase::new(Arc::new(DummyExplorers));
+        let game = AllGameCacheOne::new(2, normalize("ゲームB"));
+
+        let (map, conflicts) = use_case.get_map_of_one_filepath_per_game(
+            &MatchingRules::bundled().unwrap(),
+            &learned,
+            vec![
+                (game.clone(), "C:/Games/ゲームB/ゲームB_adv.exe".to_string()),
+                (game, "C:/Games/ゲームB/config.exe".to_string()),
+            ],
+        );
+
+        // 優先する単語より、ユーザーが直した紐づけのファイルを残す
+        assert_eq!(
+            map.get(&2).map(|v| v.as_str()),
+            Some("C:/Games/ゲームB/config.exe")
+        );
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].rule, ScanRule::LearnedMatch);
+        assert_eq!(conflicts[0].word, None);
+    }
 }
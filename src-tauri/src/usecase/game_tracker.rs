@@ -3,67 +3,103 @@
 //! このモジュールはゲームプロセスの起動、監視、プレイ時間の追跡を担当します。
 //! テスト可能な小さなユニットに分割されています。
 
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
-use chrono::{DateTime, Local, NaiveDate, TimeZone};
-use sysinfo::{ProcessExt, System, SystemExt};
+use chrono::{DateTime, Local};
+use sysinfo::{PidExt, ProcessExt, System, SystemExt};
 use tauri::{AppHandle, Emitter};
 use tokio::time::{interval, Duration, Instant};
 
 use crate::{
     domain::repository::repositories::RepositoriesExt,
     domain::{
-        collection::CollectionElement, file::get_exe_path_from_lnk,
-        repository::collection::CollectionRepository, Id,
+        file::get_exe_path_from_lnk,
+        launch::{build_launch_command, LaunchProfile},
+        play_day::{split_play_time_by_local_date, DayBoundary, DAY_BOUNDARY_SETTING_KEY},
+        play_session::{next_played_range, NewPlaySession, PlaySession, PlayedRange},
+        repository::collection::CollectionRepository,
+        tracking_checkpoint::TrackingCheckpoint,
+        Id,
     },
 };
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
 
-use super::pause_manager::PauseManager;
+use super::pause_manager::{PauseManager, PauseReason};
+use super::play_time::{
+    Clock, PlayTimeAccumulator, SuspendGapPolicy, TickAccounting, SUSPEND_GAP_POLICY_SETTING_KEY,
+};
 use super::screenshot_watcher::ScreenshotWatcher;
 
-pub(crate) fn split_play_time_by_local_date(
-    end_time: DateTime<Local>,
-    seconds: i32,
-) -> Vec<(NaiveDate, i32)> {
-    if seconds <= 0 {
-        return Vec::new();
-    }
-
-    let mut remaining_seconds = seconds as i64;
-    let mut cursor = end_time - chrono::Duration::seconds(remaining_seconds);
-    let mut results: Vec<(NaiveDate, i32)> = Vec::new();
-
-    while remaining_seconds > 0 {
-        let play_date = cursor.date_naive();
-        let seconds_for_date = play_date
-            .succ_opt()
-            .and_then(|next_date| next_date.and_hms_opt(0, 0, 0))
-            .and_then(|next_midnight| Local.from_local_datetime(&next_midnight).earliest())
-            .map(|next_midnight| {
-                let seconds_until_next_date = (next_midnight - cursor).num_seconds().max(1);
-                remaining_seconds.min(seconds_until_next_date)
-            })
-            .unwrap_or(remaining_seconds);
+/// ゲームプロセスの起動結果
+pub struct LaunchResult {
+    pub spawned_pid: Option<u32>,
+    pub path_str: String,
+}
 
-        if let Some((_, existing_seconds)) = results.iter_mut().find(|(date, _)| *date == play_date)
-        {
-            *existing_seconds += seconds_for_date as i32;
-        } else {
-            results.push((play_date, seconds_for_date as i32));
-        }
+/// プロセスの開始時刻(UNIX秒)。プロセスが見つからない場合は None
+pub fn process_started_at(system: &System, pid: u32) -> Option<i64> {
+    system
+        .process(sysinfo::Pid::from_u32(pid))
+        .map(|process| process.start_time() as i64)
+}
 
-        cursor += chrono::Duration::seconds(seconds_for_date);
-        remaining_seconds -= seconds_for_date;
-    }
+/// 追跡を始めるゲームの情報
+pub struct TrackingTarget {
+    pub element_id: i32,
+    pub game_name: String,
+    pub path_str: String,
+    /// 起動したプロセスやアタッチしたプロセスのPID。None の場合はプロセスを探す
+    pub spawned_pid: Option<u32>,
+    pub started_at: Instant,
+    /// 中断されたセッションを再開する場合の保存内容
+    pub resume_from: Option<TrackingCheckpoint>,
+}
 
-    results
+/// 手動でアタッチするプロセスの候補
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessCandidate {
+    pub pid: u32,
+    pub name: String,
+    pub exe_path: String,
+    pub score: i32,
 }
 
-/// ゲームプロセスの起動結果
-pub struct LaunchResult {
-    pub spawned_pid: Option<u32>,
-    pub path_str: String,
+/// スコアの高い順、同じスコアなら名前順に並べる
+pub fn sort_process_candidates(candidates: &mut [ProcessCandidate]) {
+    candidates.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            .then_with(|| a.pid.cmp(&b.pid))
+    });
+}
+
+/// 動いているプロセスをスコア付きで列挙する。システムフォルダのプロセスは含まない
+pub async fn list_process_candidates(
+    path_str: &str,
+    game_name: &str,
+    config: &ProcessSearchConfig,
+) -> Vec<ProcessCandidate> {
+    let final_exe_path_str = resolve_final_exe_path(path_str).await;
+    let final_exe_path = std::path::Path::new(&final_exe_path_str);
+
+    let mut system = System::new();
+    system.refresh_processes();
+    let mut candidates: Vec<ProcessCandidate> = system
+        .processes()
+        .values()
+        .filter_map(|process| {
+            let score = score_process_candidate(process, final_exe_path, game_name, config);
+            (score > 0).then(|| ProcessCandidate {
+                pid: process.pid().as_u32(),
+                name: process.name().to_string(),
+                exe_path: process.exe().to_string_lossy().to_string(),
+                score,
+            })
+        })
+        .collect();
+    sort_process_candidates(&mut candidates);
+    candidates
 }
 
 /// プロセス検索の設定
@@ -72,6 +108,10 @@ pub struct ProcessSearchConfig {
     pub search_interval: Duration,
     pub system_folders: Vec<&'static str>,
     pub game_folders: Vec<&'static str>,
+    /// 追跡中のプロセスがすべて終了してからセッション終了とみなすまでの猶予
+    pub exit_grace_period: Duration,
+    /// 子孫プロセス以外でもこのスコア以上のプロセスが動いていれば追跡を続ける
+    pub keep_alive_min_score: i32,
 }
 
 impl Default for ProcessSearchConfig {
@@ -81,6 +121,8 @@ impl Default for ProcessSearchConfig {
             search_interval: Duration::from_secs(2),
             system_folders: vec!["c:\\windows"],
             game_folders: vec!["VisualNovel", "steamapps", "dmmgameplayer"],
+            exit_grace_period: Duration::from_secs(30),
+            keep_alive_min_score: 50,
         }
     }
 }
@@ -88,59 +130,47 @@ impl Default for ProcessSearchConfig {
 /// ゲームを起動する
 ///
 /// # Arguments
-/// * `element` - 起動するゲームのCollectionElement
+/// * `path_str` - 起動する実行ファイルまたはショートカットのパス
+/// * `profile` - 引数や作業ディレクトリなどの起動設定
 ///
 /// # Returns
 /// * `Ok(LaunchResult)` - 起動成功時
 /// * `Err` - 起動失敗時
-pub fn launch_game(element: &CollectionElement) -> anyhow::Result<LaunchResult> {
-    let path_str = match (&element.exe_path, &element.lnk_path) {
-        (Some(p), _) => p.clone(),
-        (None, Some(p)) => p.clone(),
-        (None, None) => {
-            return Err(anyhow::anyhow!(
-                "実行ファイルまたはショートカットが見つかりません"
-            ))
-        }
-    };
-
-    let path = std::path::Path::new(&path_str);
-    let is_lnk = path_str.to_lowercase().ends_with(".lnk");
-
-    let mut spawned_pid: Option<u32> = None;
-
-    let spawn_result = if is_lnk {
-        // .lnkファイルの場合、cmd /c startを使用
-        std::process::Command::new("cmd")
-            .args(["/c", "start", "", &path_str])
-            .spawn()
-    } else {
-        // .exeファイルの場合、直接起動
-        if let Some(parent_dir) = path.parent() {
-            match std::process::Command::new(path)
-                .current_dir(parent_dir)
-                .spawn()
-            {
-                Ok(child) => {
-                    spawned_pid = Some(child.id());
-                    Ok(child)
-                }
-                Err(e) => Err(e),
-            }
-        } else {
-            return Err(anyhow::anyhow!("親ディレクトリが見つかりません"));
-        }
-    };
+pub fn launch_game(
+    path_str: &str,
+    profile: Option<&LaunchProfile>,
+) -> anyhow::Result<LaunchResult> {
+    let command = build_launch_command(path_str, profile).map_err(|e| anyhow::anyhow!(e))?;
+
+    let mut process = std::process::Command::new(&command.program);
+    process
+        .args(&command.args)
+        .envs(command.envs.iter().cloned());
+    if let Some(current_dir) = command.current_dir.as_ref() {
+        process.current_dir(current_dir);
+    }
 
-    match spawn_result {
-        Ok(_) => Ok(LaunchResult {
-            spawned_pid,
-            path_str,
+    match process.spawn() {
+        // ショートカットやラッパー経由の場合、起動したプロセスはゲーム本体ではない
+        Ok(child) => Ok(LaunchResult {
+            spawned_pid: command.is_direct.then(|| child.id()),
+            path_str: path_str.to_string(),
         }),
         Err(e) => Err(anyhow::anyhow!("Failed to launch game: {}", e)),
     }
 }
 
+/// LNKの場合はリンク先の exe のパスを返す
+async fn resolve_final_exe_path(path_str: &str) -> String {
+    if path_str.to_lowercase().ends_with(".lnk") {
+        get_exe_path_from_lnk(path_str)
+            .await
+            .unwrap_or(path_str.to_string())
+    } else {
+        path_str.to_string()
+    }
+}
+
 /// 起動エラーがパス関連かどうかを判定
 ///
 /// # Arguments
@@ -241,6 +271,84 @@ pub fn select_best_process(
         .map(|(pid, _)| *pid)
 }
 
+/// 起動したプロセスとその子孫プロセスを追跡する
+///
+/// 起動した exe がランチャーで、ゲーム本体を起動してすぐ終了する場合でも追跡を続けるため、
+/// 一度でも追跡対象の子になったプロセスは親が終了した後も追跡対象に残す
+pub struct ProcessTreeTracker {
+    tracked_pids: HashSet<u32>,
+    last_alive_at: Instant,
+    grace_period: Duration,
+}
+
+impl ProcessTreeTracker {
+    pub fn new(root_pid: u32, now: Instant, grace_period: Duration) -> Self {
+        Self {
+            tracked_pids: HashSet::from([root_pid]),
+            last_alive_at: now,
+            grace_period,
+        }
+    }
+
+    /// 現在のプロセス一覧 `(pid, 親pid)` を反映し、セッションが続いているかを返す
+    ///
+    /// 追跡中のプロセスか `has_matching_process` のプロセスが動いていれば生存とみなし、
+    /// どちらもない状態が猶予期間を超えたら false を返す
+    pub fn observe(
+        &mut self,
+        processes: &[(u32, Option<u32>)],
+        has_matching_process: bool,
+        now: Instant,
+    ) -> bool {
+        loop {
+            let before = self.tracked_pids.len();
+            for (pid, parent) in processes {
+                if parent.is_some_and(|parent| self.tracked_pids.contains(&parent)) {
+                    self.tracked_pids.insert(*pid);
+                }
+            }
+            if self.tracked_pids.len() == before {
+                break;
+            }
+        }
+
+        if has_matching_process || !self.alive_pids(processes).is_empty() {
+            self.last_alive_at = now;
+        }
+        now.duration_since(self.last_alive_at) <= self.grace_period
+    }
+
+    /// 追跡中のプロセスのうち動いているもの
+    pub fn alive_pids(&self, processes: &[(u32, Option<u32>)]) -> Vec<u32> {
+        processes
+            .iter()
+            .map(|(pid, _)| *pid)
+            .filter(|pid| self.tracked_pids.contains(pid))
+            .collect()
+    }
+
+    /// 追跡中のプロセスかゲームらしいプロセスが最後に動いていた時刻
+    pub fn last_alive_at(&self) -> Instant {
+        self.last_alive_at
+    }
+}
+
+/// 監視の tick 1回分のプロセス一覧を反映し、セッションが続いているかと記録する時間を返す
+///
+/// プロセスが見つからなかった tick は最後に動いていた時刻までを記録し、終了を待つ猶予期間は記録しない
+pub fn account_monitor_tick(
+    tracker: &mut ProcessTreeTracker,
+    accumulator: &mut PlayTimeAccumulator,
+    processes: &[(u32, Option<u32>)],
+    has_matching_process: bool,
+    now: Instant,
+    is_paused: bool,
+) -> (bool, TickAccounting) {
+    let process_alive = tracker.observe(processes, has_matching_process, now);
+    let accounting = accumulator.advance_until(tracker.last_alive_at(), now, is_paused);
+    (process_alive, accounting)
+}
+
 /// ゲームプロセスモニター
 ///
 /// ゲームプロセスを監視し、プレイ時間を記録する責務を持つ構造体
@@ -250,6 +358,7 @@ pub struct GameProcessMonitor<R: RepositoriesExt + Send + Sync + 'static> {
     pause_manager: Arc<PauseManager>,
     screenshot_watcher: Arc<ScreenshotWatcher<R>>,
     element_id: i32,
+    clock: Arc<dyn Clock>,
 }
 
 impl<R: RepositoriesExt + Send + Sync + 'static> GameProcessMonitor<R> {
@@ -259,6 +368,7 @@ impl<R: RepositoriesExt + Send + Sync + 'static> GameProcessMonitor<R> {
         pause_manager: Arc<PauseManager>,
         screenshot_watcher: Arc<ScreenshotWatcher<R>>,
         element_id: i32,
+        clock: Arc<dyn Clock>,
     ) -> Self {
         Self {
             handle,
@@ -266,9 +376,34 @@ impl<R: RepositoriesExt + Send + Sync + 'static> GameProcessMonitor<R> {
             pause_manager,
             screenshot_watcher,
             element_id,
+            clock,
         }
     }
 
+    /// スリープとみなした時間の扱いを設定から読み込む
+    async fn load_suspend_gap_policy(&self) -> SuspendGapPolicy {
+        let value = self
+            .repositories
+            .collection_repository()
+            .get_app_setting(SUSPEND_GAP_POLICY_SETTING_KEY.to_string())
+            .await
+            .ok()
+            .flatten();
+        SuspendGapPolicy::from_setting(value.as_deref())
+    }
+
+    /// 日ごとのプレイ時間を区切る時刻を設定から読み込む
+    async fn load_day_boundary(&self) -> DayBoundary {
+        let value = self
+            .repositories
+            .collection_repository()
+            .get_app_setting(DAY_BOUNDARY_SETTING_KEY.to_string())
+            .await
+            .ok()
+            .flatten();
+        DayBoundary::from_setting(value.as_deref())
+    }
+
     /// ポーズショートカットを登録
     pub async fn register_pause_shortcut(&self) -> Option<Shortcut> {
         if let Ok(Some(pause_shortcut_key)) = self
@@ -279,14 +414,13 @@ impl<R: RepositoriesExt + Send + Sync + 'static> GameProcessMonitor<R> {
         {
             if !pause_shortcut_key.is_empty() {
                 if let Ok(shortcut) = pause_shortcut_key.parse::<Shortcut>() {
-                    if !self
-                        .handle
-                        .global_shortcut()
-                        .is_registered(shortcut) && self
-                            .handle
-                            .global_shortcut()
-                            .register(shortcut)
-                            .is_ok() {
+                    let global_shortcut = self.handle.global_shortcut();
+                    if global_shortcut.is_registered(shortcut) {
+                        // ほかのゲームの追跡で登録済みの場合は共有する
+                        if self.pause_manager.sessions().len() > 1 {
+                            return Some(shortcut);
+                        }
+                    } else if global_shortcut.register(shortcut).is_ok() {
                         return Some(shortcut);
                     }
                 }
@@ -295,6 +429,16 @@ impl<R: RepositoriesExt + Send + Sync + 'static> GameProcessMonitor<R> {
         None
     }
 
+    /// 追跡中のゲームがなくなった場合にポーズショートカットを解除
+    pub fn unregister_pause_shortcut(&self, registered_shortcut: Option<Shortcut>) {
+        if self.pause_manager.is_tracking() {
+            return;
+        }
+        if let Some(shortcut) = registered_shortcut {
+            let _ = self.handle.global_shortcut().unregister(shortcut);
+        }
+    }
+
     /// ゲームプロセスを検索
     pub async fn find_game_process(
         &self,
@@ -310,21 +454,18 @@ impl<R: RepositoriesExt + Send + Sync + 'static> GameProcessMonitor<R> {
 
         let search_start_time = Instant::now();
 
-        // LNKの解決
-        let final_exe_path_str = if path_str.to_lowercase().ends_with(".lnk") {
-            get_exe_path_from_lnk(path_str)
-                .await
-                .unwrap_or(path_str.to_string())
-        } else {
-            path_str.to_string()
-        };
+        let final_exe_path_str = resolve_final_exe_path(path_str).await;
         let final_exe_path = std::path::Path::new(&final_exe_path_str);
 
+        let stop_token = self.pause_manager.stop_token(self.element_id);
         loop {
             if search_start_time.elapsed() > config.search_timeout {
                 println!("[WARN] Game process search timed out. Play time may not be recorded.");
                 return None;
             }
+            if stop_token.as_ref().is_some_and(|v| v.is_cancelled()) {
+                return None;
+            }
 
             tokio::time::sleep(config.search_interval).await;
 
@@ -353,9 +494,20 @@ impl<R: RepositoriesExt + Send + Sync + 'static> GameProcessMonitor<R> {
         }
     }
 
-    async fn commit_play_time(&self, seconds: i32, end_time: DateTime<Local>) {
+    /// `end_time` で終わる `seconds` 秒のプレイを記録し、セッションのプレイしていた範囲を返す
+    ///
+    /// 日付の区切りの変更や編集で振り分け直せるよう、日ごとの時間と同じ範囲をセッションにも残す
+    async fn commit_play_time(
+        &self,
+        session_id: Option<&Id<PlaySession>>,
+        previous_range: Option<&PlayedRange>,
+        seconds: i32,
+        end_time: DateTime<Local>,
+        day_boundary: DayBoundary,
+    ) -> PlayedRange {
+        let range = next_played_range(previous_range, end_time, seconds);
         if seconds <= 0 {
-            return;
+            return range;
         }
 
         let id = Id::new(self.element_id);
@@ -370,25 +522,45 @@ impl<R: RepositoriesExt + Send + Sync + 'static> GameProcessMonitor<R> {
             .update_element_first_play_at_if_null_by_id(&id, end_time)
             .await;
 
-        for (play_date, daily_seconds) in split_play_time_by_local_date(end_time, seconds) {
+        for (play_date, daily_seconds) in
+            split_play_time_by_local_date(range.1, seconds, day_boundary)
+        {
             let _ = self
                 .repositories
                 .collection_repository()
                 .add_daily_play_time_seconds(&id, play_date, daily_seconds)
                 .await;
         }
+        if let Some(session_id) = session_id {
+            if let Err(e) = self
+                .repositories
+                .collection_repository()
+                .add_play_session_played_range(session_id, &range)
+                .await
+            {
+                log::error!(
+                    "Failed to record played range (element: {}): {}",
+                    self.element_id,
+                    e
+                );
+            }
+        }
 
         let _ = self
             .handle
             .emit("collection-element-updated", self.element_id);
+        range
     }
 
     /// プロセスを監視してプレイ時間を記録
+    ///
+    /// `pid` の子孫プロセスと、ゲームらしいプロセスのいずれかが動いている間は追跡を続ける
     pub async fn monitor_process(
         &self,
         pid: sysinfo::Pid,
         registered_shortcut: Option<Shortcut>,
-        tracking_started_at: Instant,
+        target: &TrackingTarget,
+        config: &ProcessSearchConfig,
     ) {
         println!("Start monitoring process (PID: {}) for game", pid);
 
@@ -400,39 +572,283 @@ impl<R: RepositoriesExt + Send + Sync + 'static> GameProcessMonitor<R> {
             eprintln!("Failed to start screenshot watcher: {}", e);
         }
 
-        let mut interval = interval(Duration::from_secs(10));
+        let tick_interval = Duration::from_secs(10);
+        let mut interval = interval(tick_interval);
         let mut system = System::new();
-        let mut accounted_until = tracking_started_at;
+        system.refresh_processes();
+        // 再開した場合、最後の heartbeat からの空白時間はスリープと同じ扱いになる
+        let mut accumulator = PlayTimeAccumulator::new(
+            target.started_at,
+            tick_interval,
+            self.load_suspend_gap_policy().await,
+        );
+        let day_boundary = self.load_day_boundary().await;
+
+        let (started_at, session_id, mut played_seconds, mut paused_seconds) =
+            match target.resume_from.as_ref() {
+                Some(checkpoint) => (
+                    checkpoint.started_at,
+                    checkpoint.play_session_id.clone(),
+                    checkpoint.played_seconds,
+                    checkpoint.paused_seconds,
+                ),
+                None => {
+                    let started_at = Local::now()
+                        - chrono::Duration::from_std(target.started_at.elapsed())
+                            .unwrap_or_default();
+                    let exe_path = system
+                        .process(pid)
+                        .map(|process| process.exe().to_string_lossy().to_string())
+                        .filter(|v| !v.is_empty());
+                    let session_id = self
+                        .start_play_session(started_at, pid.as_u32(), exe_path)
+                        .await;
+                    (started_at, session_id, 0, 0)
+                }
+            };
+        let mut checkpoint = TrackingCheckpoint::new(
+            Id::new(self.element_id),
+            session_id.clone(),
+            pid.as_u32(),
+            process_started_at(&system, pid.as_u32()),
+            target.path_str.clone(),
+            target.game_name.clone(),
+            started_at,
+            Local::now(),
+            played_seconds,
+            paused_seconds,
+            self.pause_manager.pause_reason(self.element_id) == Some(PauseReason::Manual),
+        );
+        self.save_checkpoint(&checkpoint).await;
+        let mut played_range: Option<PlayedRange> = None;
+
+        // 起動した場合は、ランチャーの待ち時間やプロセスの検索にかかった時間もプレイ時間に含める
+        if target.resume_from.is_none() {
+            let accounting = accumulator.advance_launch(
+                self.clock.now(),
+                self.pause_manager.is_paused(self.element_id),
+            );
+            if accounting.played_seconds > 0 {
+                played_range = Some(
+                    self.commit_play_time(
+                        session_id.as_ref(),
+                        played_range.as_ref(),
+                        accounting.played_seconds,
+                        Local::now(),
+                        day_boundary,
+                    )
+                    .await,
+                );
+                played_seconds += accounting.played_seconds;
+            }
+            paused_seconds += accounting.paused_seconds;
+        }
+
+        let game_name = target.game_name.as_str();
+        let final_exe_path_str = resolve_final_exe_path(&target.path_str).await;
+        let final_exe_path = std::path::Path::new(&final_exe_path_str);
+        let mut tracker =
+            ProcessTreeTracker::new(pid.as_u32(), self.clock.now(), config.exit_grace_period);
+        let mut current_pid = pid.as_u32();
+        let stop_token = self
+            .pause_manager
+            .stop_token(self.element_id)
+            .unwrap_or_default();
 
         loop {
-            interval.tick().await;
+            // 追跡の終了が要求された場合はそこまでのプレイ時間を記録して終了する
+            let stop_requested = tokio::select! {
+                _ = interval.tick() => false,
+                _ = stop_token.cancelled() => true,
+            };
             system.refresh_processes();
-            let process_alive = system.process(pid).is_some();
-
-            if !self.pause_manager.is_paused() {
-                let now = Instant::now();
-                let duration = now.duration_since(accounted_until).as_secs() as i32;
-
-                if duration > 0 {
-                    self.commit_play_time(duration, Local::now()).await;
+            let processes: Vec<(u32, Option<u32>)> = system
+                .processes()
+                .values()
+                .map(|process| (process.pid().as_u32(), process.parent().map(|v| v.as_u32())))
+                .collect();
+            let candidates: Vec<(sysinfo::Pid, i32)> = system
+                .processes()
+                .values()
+                .map(|process| {
+                    (
+                        process.pid(),
+                        score_process_candidate(process, final_exe_path, game_name, config),
+                    )
+                })
+                .filter(|(_, score)| *score >= config.keep_alive_min_score)
+                .collect();
+            let now = self.clock.now();
+            let (process_alive, accounting) = account_monitor_tick(
+                &mut tracker,
+                &mut accumulator,
+                &processes,
+                !candidates.is_empty(),
+                now,
+                self.pause_manager.is_paused(self.element_id),
+            );
+            // プロセスが終了した場合は、最後に動いていた時刻でセッションを終える
+            let accounted_at = Local::now()
+                - chrono::Duration::from_std(
+                    now.saturating_duration_since(tracker.last_alive_at()),
+                )
+                .unwrap_or_default();
+
+            // 監視中のプロセスが終了した場合、ポーズやスクリーンショットの対象を生きているプロセスに切り替える
+            if system
+                .process(sysinfo::Pid::from_u32(current_pid))
+                .is_none()
+            {
+                let next_pid = tracker
+                    .alive_pids(&processes)
+                    .into_iter()
+                    .max_by_key(|v| {
+                        candidates
+                            .iter()
+                            .find(|(pid, _)| pid.as_u32() == *v)
+                            .map(|(_, score)| *score)
+                            .unwrap_or(0)
+                    })
+                    .or_else(|| {
+                        candidates
+                            .iter()
+                            .max_by_key(|(_, score)| *score)
+                            .map(|(pid, _)| pid.as_u32())
+                    });
+                if let Some(next_pid) = next_pid {
+                    println!(
+                        "Switch monitoring process (PID: {} -> {})",
+                        current_pid, next_pid
+                    );
+                    current_pid = next_pid;
+                    self.pause_manager
+                        .set_session_process(self.element_id, current_pid);
                 }
-                accounted_until = now;
-            } else {
-                accounted_until = Instant::now();
             }
 
+            if accounting.suspended_seconds > 0 {
+                log::info!(
+                    "Excluded {} seconds of system suspend from play time (element: {})",
+                    accounting.suspended_seconds,
+                    self.element_id
+                );
+            }
+            if accounting.played_seconds > 0 {
+                played_range = Some(
+                    self.commit_play_time(
+                        session_id.as_ref(),
+                        played_range.as_ref(),
+                        accounting.played_seconds,
+                        accounted_at,
+                        day_boundary,
+                    )
+                    .await,
+                );
+                played_seconds += accounting.played_seconds;
+            }
+            paused_seconds += accounting.paused_seconds;
+
             // プロセスが終了したか確認
-            if !process_alive {
+            if !process_alive || stop_requested {
+                if let Some(session_id) = session_id.as_ref() {
+                    if let Err(e) = self
+                        .repositories
+                        .collection_repository()
+                        .finish_play_session(
+                            session_id,
+                            accounted_at,
+                            played_seconds,
+                            paused_seconds,
+                        )
+                        .await
+                    {
+                        // 起動時にセッションを閉じ直せるよう、終了時点の状態で checkpoint を残す
+                        log::error!(
+                            "Failed to finish play session (element: {}): {}",
+                            self.element_id,
+                            e
+                        );
+                        checkpoint.heartbeat_at = accounted_at;
+                        checkpoint.played_seconds = played_seconds;
+                        checkpoint.paused_seconds = paused_seconds;
+                        self.save_checkpoint(&checkpoint).await;
+                        self.cleanup(registered_shortcut).await;
+                        break;
+                    }
+                }
+                if let Err(e) = self
+                    .repositories
+                    .collection_repository()
+                    .delete_tracking_checkpoint(&Id::new(self.element_id))
+                    .await
+                {
+                    eprintln!("Failed to delete tracking checkpoint: {}", e);
+                }
                 self.cleanup(registered_shortcut).await;
                 break;
             }
+
+            if let Some(session_id) = session_id.as_ref() {
+                let _ = self
+                    .repositories
+                    .collection_repository()
+                    .update_play_session_progress(session_id, played_seconds, paused_seconds)
+                    .await;
+            }
+
+            checkpoint.pid = current_pid;
+            checkpoint.process_started_at = process_started_at(&system, current_pid);
+            checkpoint.heartbeat_at = Local::now();
+            checkpoint.played_seconds = played_seconds;
+            checkpoint.paused_seconds = paused_seconds;
+            checkpoint.is_paused =
+                self.pause_manager.pause_reason(self.element_id) == Some(PauseReason::Manual);
+            self.save_checkpoint(&checkpoint).await;
+        }
+    }
+
+    /// ランチャーが中断した場合に追跡を再開できるよう状態を保存する
+    async fn save_checkpoint(&self, checkpoint: &TrackingCheckpoint) {
+        if let Err(e) = self
+            .repositories
+            .collection_repository()
+            .upsert_tracking_checkpoint(checkpoint)
+            .await
+        {
+            eprintln!("Failed to save tracking checkpoint: {}", e);
+        }
+    }
+
+    /// プレイセッションの記録を開始する。記録に失敗してもプレイ時間の追跡は続ける
+    async fn start_play_session(
+        &self,
+        started_at: DateTime<Local>,
+        pid: u32,
+        exe_path: Option<String>,
+    ) -> Option<Id<PlaySession>> {
+        match self
+            .repositories
+            .collection_repository()
+            .create_play_session(&NewPlaySession::new(
+                Id::new(self.element_id),
+                started_at,
+                Some(pid),
+                exe_path,
+            ))
+            .await
+        {
+            Ok(id) => Some(id),
+            Err(e) => {
+                eprintln!("Failed to start play session: {}", e);
+                None
+            }
         }
     }
 
     /// 監視終了時のクリーンアップ
     async fn cleanup(&self, registered_shortcut: Option<Shortcut>) {
         // スクリーンショットウォッチャーを停止
-        self.screenshot_watcher.stop_watching();
+        self.screenshot_watcher.stop_watching(self.element_id);
 
         // 最終プレイ日時を更新
         let _ = self
@@ -441,13 +857,11 @@ impl<R: RepositoriesExt + Send + Sync + 'static> GameProcessMonitor<R> {
             .update_element_last_play_at_by_id(&Id::new(self.element_id), Local::now())
             .await;
 
-        // 追跡状態をfalseに
-        self.pause_manager.set_tracking(false);
+        // このゲームのセッションを終了
+        self.pause_manager.end_session(self.element_id);
 
-        // ポーズショートカットを解除
-        if let Some(shortcut) = registered_shortcut {
-            let _ = self.handle.global_shortcut().unregister(shortcut);
-        }
+        // ポーズショートカットを解除。ほかのゲームを追跡中の場合は残す
+        self.unregister_pause_shortcut(registered_shortcut);
 
         // トレイメニューの「最近プレイしたゲーム」を更新
         let _ = self.handle.emit("recent-games-changed", ());
@@ -467,33 +881,153 @@ mod tests {
     }
 
     #[test]
-    fn test_split_play_time_by_local_date_same_day() {
-        let end_time = Local
-            .with_ymd_and_hms(2026, 1, 2, 12, 0, 10)
-            .earliest()
-            .unwrap();
+    fn test_process_search_config_default_keep_alive() {
+        let config = ProcessSearchConfig::default();
+        assert_eq!(config.exit_grace_period, Duration::from_secs(30));
+        assert_eq!(config.keep_alive_min_score, 50);
+    }
+
+    fn candidate(pid: u32, name: &str, score: i32) -> ProcessCandidate {
+        ProcessCandidate {
+            pid,
+            name: name.to_string(),
+            exe_path: format!("C:/Games/{}", name),
+            score,
+        }
+    }
 
-        let result = split_play_time_by_local_date(end_time, 10);
+    #[test]
+    fn test_sort_process_candidates() {
+        let mut candidates = vec![
+            candidate(1, "b.exe", 10),
+            candidate(2, "game.exe", 150),
+            candidate(3, "A.exe", 10),
+            candidate(4, "a.exe", 10),
+            candidate(5, "helper.exe", 1),
+        ];
 
-        assert_eq!(result.len(), 1);
-        assert_eq!(
-            result[0],
-            (NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), 10)
-        );
+        sort_process_candidates(&mut candidates);
+
+        let pids: Vec<u32> = candidates.iter().map(|v| v.pid).collect();
+        assert_eq!(pids, vec![2, 3, 4, 1, 5]);
+    }
+
+    #[test]
+    fn test_process_tree_tracker_follows_descendants_after_launcher_exits() {
+        let start = Instant::now();
+        let mut tracker = ProcessTreeTracker::new(100, start, Duration::from_secs(30));
+
+        // ランチャー(100)がゲーム本体(200)を起動し、200がさらに子(300)を起動
+        let processes = vec![
+            (100, Some(1)),
+            (200, Some(100)),
+            (300, Some(200)),
+            (400, Some(1)),
+        ];
+        assert!(tracker.observe(&processes, false, start + Duration::from_secs(10)));
+        assert_eq!(tracker.alive_pids(&processes).len(), 3);
+
+        // ランチャーが終了しても子孫が生きていれば継続
+        let processes = vec![(200, Some(100)), (300, Some(200)), (400, Some(1))];
+        assert!(tracker.observe(&processes, false, start + Duration::from_secs(120)));
+        assert_eq!(tracker.alive_pids(&processes), vec![200, 300]);
+    }
+
+    #[test]
+    fn test_process_tree_tracker_waits_grace_period() {
+        let start = Instant::now();
+        let mut tracker = ProcessTreeTracker::new(100, start, Duration::from_secs(30));
+        let processes = vec![(400, Some(1))];
+
+        assert!(tracker.observe(&processes, false, start + Duration::from_secs(20)));
+        assert!(tracker.observe(&processes, false, start + Duration::from_secs(30)));
+        assert!(!tracker.observe(&processes, false, start + Duration::from_secs(31)));
+    }
+
+    #[test]
+    fn test_process_tree_tracker_keeps_alive_with_matching_process() {
+        let start = Instant::now();
+        let mut tracker = ProcessTreeTracker::new(100, start, Duration::from_secs(30));
+        let processes = vec![(500, None)];
+
+        assert!(tracker.observe(&processes, true, start + Duration::from_secs(60)));
+        assert!(tracker.observe(&processes, false, start + Duration::from_secs(90)));
+        assert!(!tracker.observe(&processes, false, start + Duration::from_secs(91)));
     }
 
     #[test]
-    fn test_split_play_time_by_local_date_across_midnight() {
-        let end_time = Local
-            .with_ymd_and_hms(2026, 1, 2, 0, 0, 5)
-            .earliest()
-            .unwrap();
+    fn test_account_monitor_tick_stops_crediting_after_process_exit() {
+        let start = Instant::now();
+        let mut tracker = ProcessTreeTracker::new(100, start, Duration::from_secs(30));
+        let mut accumulator =
+            PlayTimeAccumulator::new(start, Duration::from_secs(10), SuspendGapPolicy::Discard);
+        let alive = vec![(100, Some(1))];
+        let exited = vec![(400, Some(1))];
+
+        let mut played_seconds = 0;
+        let (process_alive, accounting) = account_monitor_tick(
+            &mut tracker,
+            &mut accumulator,
+            &alive,
+            false,
+            start + Duration::from_secs(10),
+            false,
+        );
+        assert!(process_alive);
+        played_seconds += accounting.played_seconds;
+
+        // 15 秒でプロセスが終了し、猶予期間を過ぎて監視が終わるまで tick が続く
+        let mut ticks = 0;
+        let final_accounting = loop {
+            ticks += 1;
+            let (process_alive, accounting) = account_monitor_tick(
+                &mut tracker,
+                &mut accumulator,
+                &exited,
+                false,
+                start + Duration::from_secs(10 + ticks * 10),
+                false,
+            );
+            played_seconds += accounting.played_seconds;
+            if !process_alive {
+                break accounting;
+            }
+        };
 
-        let result = split_play_time_by_local_date(end_time, 10);
+        assert_eq!(ticks, 4);
+        assert_eq!(final_accounting, TickAccounting::default());
+        assert_eq!(played_seconds, 10);
+        assert_eq!(tracker.last_alive_at(), start + Duration::from_secs(10));
+    }
 
-        assert_eq!(result.len(), 2);
-        assert_eq!(result[0], (NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(), 5));
-        assert_eq!(result[1], (NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), 5));
+    #[test]
+    fn test_account_monitor_tick_skips_gap_when_process_reappears() {
+        let start = Instant::now();
+        let mut tracker = ProcessTreeTracker::new(100, start, Duration::from_secs(30));
+        let mut accumulator =
+            PlayTimeAccumulator::new(start, Duration::from_secs(10), SuspendGapPolicy::Discard);
+
+        let (_, accounting) = account_monitor_tick(
+            &mut tracker,
+            &mut accumulator,
+            &[],
+            false,
+            start + Duration::from_secs(10),
+            false,
+        );
+        assert_eq!(accounting.played_seconds, 0);
+
+        // 猶予期間内にゲームらしいプロセスが見つかった場合は、見つかってからの時間だけを記録する
+        let (process_alive, accounting) = account_monitor_tick(
+            &mut tracker,
+            &mut accumulator,
+            &[],
+            true,
+            start + Duration::from_secs(20),
+            false,
+        );
+        assert!(process_alive);
+        assert_eq!(accounting.played_seconds, 10);
     }
 
     #[test]
@@ -0,0 +1,221 @@
+//! 放置検出モジュール
+//!
+//! 一定時間入力がなければ追跡中のセッションを自動でポーズし、入力が戻れば再開する。
+//! 操作によるポーズは自動で再開しない
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use derive_new::new;
+
+use crate::{
+    domain::windows::input_activity::InputActivityWindows,
+    infrastructure::windowsimpl::windows::WindowsExt,
+};
+
+use super::pause_manager::{PauseManager, PauseReason};
+
+pub const IDLE_PAUSE_THRESHOLD_SETTING_KEY: &str = "idle_pause_threshold_minutes";
+
+/// 設定値(分)から自動ポーズまでの時間を読み込む。未設定・0・不正な値の場合は無効
+pub fn parse_idle_pause_threshold(value: Option<&str>) -> Option<Duration> {
+    value
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|minutes| *minutes > 0)
+        .map(|minutes| Duration::from_secs(minutes * 60))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleAction {
+    Pause,
+    Resume,
+}
+
+/// 入力がない時間と現在のポーズの理由から自動ポーズの操作を決める
+pub fn decide_idle_action(
+    idle: Duration,
+    threshold: Duration,
+    pause_reason: Option<PauseReason>,
+) -> Option<IdleAction> {
+    match pause_reason {
+        None if idle >= threshold => Some(IdleAction::Pause),
+        Some(PauseReason::Idle) if idle < threshold => Some(IdleAction::Resume),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdlePauseChange {
+    pub game_id: i32,
+    pub is_paused: bool,
+}
+
+/// 追跡中のすべてのセッションに自動ポーズを適用し、ポーズ状態が変わったセッションを返す
+pub fn apply_idle_pause<A: InputActivityWindows + ?Sized>(
+    source: &A,
+    pause_manager: &PauseManager,
+    threshold: Duration,
+) -> anyhow::Result<Vec<IdlePauseChange>> {
+    let sessions = pause_manager.sessions();
+    if sessions.is_empty() {
+        return Ok(vec![]);
+    }
+    let idle = source.idle_duration()?;
+
+    let mut changes = vec![];
+    for session in sessions {
+        let Some(action) = decide_idle_action(idle, threshold, session.pause_reason) else {
+            continue;
+        };
+        let (current, new) = match action {
+            IdleAction::Pause => (None, Some(PauseReason::Idle)),
+            IdleAction::Resume => (Some(PauseReason::Idle), None),
+        };
+        if pause_manager.replace_pause_reason(session.game_id, current, new) {
+            changes.push(IdlePauseChange {
+                game_id: session.game_id,
+                is_paused: new.is_some(),
+            });
+        }
+    }
+    Ok(changes)
+}
+
+#[derive(new)]
+pub struct IdleDetectorUseCase<R: WindowsExt> {
+    windows: Arc<R>,
+    pause_manager: Arc<PauseManager>,
+}
+
+impl<R: WindowsExt> IdleDetectorUseCase<R> {
+    pub fn poll(&self, threshold: Duration) -> anyhow::Result<Vec<IdlePauseChange>> {
+        apply_idle_pause(
+            self.windows.input_activity(),
+            &self.pause_manager,
+            threshold,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct FakeInputActivity {
+        idle: Mutex<Duration>,
+    }
+
+    impl FakeInputActivity {
+        fn new(idle: Duration) -> Self {
+            Self {
+                idle: Mutex::new(idle),
+            }
+        }
+
+        fn set_idle(&self, idle: Duration) {
+            *self.idle.lock().unwrap() = idle;
+        }
+    }
+
+    impl InputActivityWindows for FakeInputActivity {
+        fn idle_duration(&self) -> anyhow::Result<Duration> {
+            Ok(*self.idle.lock().unwrap())
+        }
+    }
+
+    const THRESHOLD: Duration = Duration::from_secs(600);
+
+    #[test]
+    fn test_parse_idle_pause_threshold() {
+        assert_eq!(
+            parse_idle_pause_threshold(Some("15")),
+            Some(Duration::from_secs(900))
+        );
+        assert_eq!(parse_idle_pause_threshold(Some("0")), None);
+        assert_eq!(parse_idle_pause_threshold(Some("abc")), None);
+        assert_eq!(parse_idle_pause_threshold(None), None);
+    }
+
+    #[test]
+    fn test_decide_idle_action() {
+        let long = Duration::from_secs(601);
+        let short = Duration::from_secs(5);
+
+        assert_eq!(
+            decide_idle_action(long, THRESHOLD, None),
+            Some(IdleAction::Pause)
+        );
+        assert_eq!(decide_idle_action(short, THRESHOLD, None), None);
+        assert_eq!(
+            decide_idle_action(short, THRESHOLD, Some(PauseReason::Idle)),
+            Some(IdleAction::Resume)
+        );
+        assert_eq!(
+            decide_idle_action(long, THRESHOLD, Some(PauseReason::Idle)),
+            None
+        );
+        assert_eq!(
+            decide_idle_action(short, THRESHOLD, Some(PauseReason::Manual)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_apply_idle_pause_pauses_and_resumes_sessions() {
+        let manager = PauseManager::new();
+        manager.reserve_session(1).unwrap();
+        manager.begin_session(1).unwrap();
+        manager.reserve_session(2).unwrap();
+        manager.begin_session(2).unwrap();
+        let source = FakeInputActivity::new(Duration::from_secs(60));
+
+        assert!(apply_idle_pause(&source, &manager, THRESHOLD)
+            .unwrap()
+            .is_empty());
+
+        source.set_idle(Duration::from_secs(700));
+        assert_eq!(
+            apply_idle_pause(&source, &manager, THRESHOLD).unwrap(),
+            vec![
+                IdlePauseChange {
+                    game_id: 1,
+                    is_paused: true
+                },
+                IdlePauseChange {
+                    game_id: 2,
+                    is_paused: true
+                },
+            ]
+        );
+        assert_eq!(manager.pause_reason(1), Some(PauseReason::Idle));
+
+        source.set_idle(Duration::from_secs(1));
+        assert_eq!(
+            apply_idle_pause(&source, &manager, THRESHOLD)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(!manager.is_paused(1));
+        assert!(!manager.is_paused(2));
+    }
+
+    #[test]
+    fn test_apply_idle_pause_keeps_manual_pause() {
+        let manager = PauseManager::new();
+        manager.reserve_session(1).unwrap();
+        manager.begin_session(1).unwrap();
+        manager.set_paused(1, true).unwrap();
+        let source = FakeInputActivity::new(Duration::from_secs(700));
+
+        assert!(apply_idle_pause(&source, &manager, THRESHOLD)
+            .unwrap()
+            .is_empty());
+        source.set_idle(Duration::ZERO);
+        assert!(apply_idle_pause(&source, &manager, THRESHOLD)
+            .unwrap()
+            .is_empty());
+        assert_eq!(manager.pause_reason(1), Some(PauseReason::Manual));
+    }
+}
@@ -0,0 +1,142 @@
+//! ゲームの起動前・終了後フックの実行モジュール
+
+use std::process::Stdio;
+
+use tokio::time::{timeout, Duration};
+
+use crate::domain::launch_hook::{
+    build_launch_hook_command, format_launch_hook_outcome, format_skipped_launch_hook,
+    select_launch_hooks, should_cancel_launch, LaunchHook, LaunchHookContext, LaunchHookOutcome,
+    LaunchHookTiming,
+};
+
+/// フックを1つ実行する。タイムアウトした場合はプロセスを終了させる
+pub async fn run_launch_hook(hook: &LaunchHook, context: &LaunchHookContext) -> LaunchHookOutcome {
+    let command = match build_launch_hook_command(hook, context) {
+        Ok(command) => command,
+        Err(e) => {
+            return LaunchHookOutcome {
+                spawn_error: Some(e),
+                ..Default::default()
+            }
+        }
+    };
+
+    let mut process = tokio::process::Command::new(&command.program);
+    process
+        .args(&command.args)
+        .envs(command.envs.iter().cloned())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let child = match process.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return LaunchHookOutcome {
+                spawn_error: Some(e.to_string()),
+                ..Default::default()
+            }
+        }
+    };
+
+    let wait = child.wait_with_output();
+    match timeout(Duration::from_secs(hook.timeout_seconds as u64), wait).await {
+        Ok(Ok(output)) => LaunchHookOutcome {
+            exit_code: output.status.code(),
+            timed_out: false,
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            spawn_error: None,
+        },
+        Ok(Err(e)) => LaunchHookOutcome {
+            spawn_error: Some(e.to_string()),
+            ..Default::default()
+        },
+        // wait_with_output の future が drop されるので kill_on_drop によりプロセスは終了する
+        Err(_) => LaunchHookOutcome {
+            timed_out: true,
+            ..Default::default()
+        },
+    }
+}
+
+/// 指定したタイミングのフックを順に実行する
+///
+/// 起動を中止すべき起動前フックが失敗した場合、そのフックの名前を Err で返し以降のフックは実行しない
+pub async fn run_launch_hooks(
+    hooks: &[LaunchHook],
+    timing: LaunchHookTiming,
+    context: &LaunchHookContext,
+) -> Result<(), String> {
+    for hook in select_launch_hooks(hooks, timing) {
+        let outcome = run_launch_hook(hook, context).await;
+        let message = format_launch_hook_outcome(hook, &outcome);
+        if outcome.is_success() {
+            log::info!("{}", message);
+        } else {
+            log::warn!("{}", message);
+        }
+        if should_cancel_launch(hook, &outcome) {
+            return Err(hook.name.clone());
+        }
+    }
+    Ok(())
+}
+
+/// 実行しないフックをログに残す
+pub fn skip_launch_hooks(hooks: &[LaunchHook], timing: LaunchHookTiming, reason: &str) {
+    for hook in select_launch_hooks(hooks, timing) {
+        log::warn!("{}", format_skipped_launch_hook(hook, reason));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Id;
+
+    fn hook(id: i32, timing: LaunchHookTiming, cancel_launch_on_failure: bool) -> LaunchHook {
+        LaunchHook::new(
+            Id::new(id),
+            None,
+            timing,
+            format!("hook{}", id),
+            "launcherg-hook-command-that-does-not-exist".to_string(),
+            5,
+            cancel_launch_on_failure,
+            id,
+        )
+    }
+
+    fn context() -> LaunchHookContext {
+        LaunchHookContext::new(1, "C:/Games/test/game.exe".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_run_launch_hook_reports_spawn_error() {
+        let outcome =
+            run_launch_hook(&hook(1, LaunchHookTiming::PreLaunch, true), &context()).await;
+
+        assert!(outcome.spawn_error.is_some());
+        assert!(!outcome.is_success());
+    }
+
+    #[tokio::test]
+    async fn test_run_launch_hooks_cancels_only_when_configured() {
+        let hooks = vec![
+            hook(1, LaunchHookTiming::PreLaunch, false),
+            hook(2, LaunchHookTiming::PreLaunch, true),
+        ];
+
+        let result = run_launch_hooks(&hooks, LaunchHookTiming::PreLaunch, &context()).await;
+        assert_eq!(result, Err("hook2".to_string()));
+
+        let result = run_launch_hooks(&hooks[..1], LaunchHookTiming::PreLaunch, &context()).await;
+        assert_eq!(result, Ok(()));
+
+        let post_hooks = vec![hook(3, LaunchHookTiming::PostExit, true)];
+        let result = run_launch_hooks(&post_hooks, LaunchHookTiming::PostExit, &context()).await;
+        assert_eq!(result, Ok(()));
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    fs,
+    sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+
+use super::error::UseCaseError;
+use crate::domain::{
+    learned_match::{
+        LearnedGameMatch, LearnedGameMatchKey, LearnedGameMatches, LearnedGameMatchesFile,
+        NewLearnedGameMatch, SUPPORTED_LEARNED_GAME_MATCHES_VERSION,
+    },
+    repository::{learned_match::LearnedMatchRepository, repositories::RepositoriesExt},
+    Id,
+};
+
+/// 紐づけの読み込み結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LearnedGameMatchImportReport {
+    pub imported: usize,
+    /// 手元に同じファイルの紐づけがあるため読み込まなかった件数
+    pub skipped: usize,
+}
+
+/// ユーザーが手で直したファイルとゲームの紐づけの管理
+pub struct LearnedMatchUseCase<R: RepositoriesExt> {
+    repositories: Arc<R>,
+    /// 走査で使う紐づけ。DB から読み込むまでは空
+    current: Mutex<Arc<LearnedGameMatches>>,
+}
+
+impl<R: RepositoriesExt> LearnedMatchUseCase<R> {
+    pub fn new(repositories: Arc<R>) -> Self {
+        Self {
+            repositories,
+            current: Mutex::default(),
+        }
+    }
+
+    /// 走査で使っている紐づけ
+    pub fn current(&self) -> Arc<LearnedGameMatches> {
+        self.current
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub async fn get_learned_game_matches(&self) -> anyhow::Result<Vec<LearnedGameMatch>> {
+        self.repositories
+            .learned_match_repository()
+            .get_learned_game_matches()
+            .await
+    }
+
+    /// 紐づけを読み込み直し、走査で使う紐づけを置き換える
+    pub async fn reload(&self) -> anyhow::Result<()> {
+        let matches = self.get_learned_game_matches().await?;
+        *self
+            .current
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            Arc::new(LearnedGameMatches::new(&matches));
+        Ok(())
+    }
+
+    /// 手で登録・変更したファイルとゲームの組を覚える
+    ///
+    /// 親フォルダ名かファイル名を取得できないパスは覚えない
+    pub async fn learn(&self, exe_path: &str, game_id: i32) -> anyhow::Result<()> {
+        let Some(key) = LearnedGameMatchKey::from_path(exe_path) else {
+            return Ok(());
+        };
+        let affected = self
+            .repositories
+            .learned_match_repository()
+            .upsert_learned_game_matches(&[NewLearnedGameMatch::new(key, game_id)], true)
+            .await?;
+        if affected > 0 {
+            self.reload().await?;
+        }
+        Ok(())
+    }
+
+    pub async fn delete_learned_game_match(&self, id: &Id<LearnedGameMatch>) -> anyhow::Result<()> {
+        self.repositories
+            .learned_match_repository()
+            .delete_learned_game_match(id)
+            .await?;
+        self.reload().await
+    }
+
+    /// 紐づけを共有できるファイルに書き出し、書き出した件数を返す
+    pub async fn export_learned_game_matches(&self, path: &str) -> anyhow::Result<usize> {
+        let matches = self
+            .get_learned_game_matches()
+            .await?
+            .into_iter()
+            .map(|v| NewLearnedGameMatch::new(v.key, v.game_id))
+            .collect::<Vec<_>>();
+        let count = matches.len();
+        let file = LearnedGameMatchesFile::new(SUPPORTED_LEARNED_GAME_MATCHES_VERSION, matches);
+        fs::write(path, serde_json::to_string_pretty(&file)?)?;
+        Ok(count)
+    }
+
+    /// 書き出したファイルから紐づけを読み込む。手元の紐づけは上書きしない
+    pub async fn import_learned_game_matches(
+        &self,
+        path: &str,
+    ) -> anyhow::Result<LearnedGameMatchImportReport> {
+        let contents = fs::read_to_string(path)
+            .map_err(|_| UseCaseError::LearnedGameMatchesFileIsNotReadable(path.to_string()))?;
+        let file = LearnedGameMatchesFile::parse(&contents)
+            .map_err(|e| UseCaseError::LearnedGameMatchIsInvalid(e.to_string()))?;
+        for new_match in file.matches.iter() {
+            new_match
+                .validate()
+                .map_err(|e| UseCaseError::LearnedGameMatchIsInvalid(e.to_string()))?;
+        }
+
+        let imported = self
+            .repositories
+            .learned_match_repository()
+            .upsert_learned_game_matches(&file.matches, false)
+            .await? as usize;
+        self.reload().await?;
+        Ok(LearnedGameMatchImportReport {
+            imported,
+            skipped: file.matches.len() - imported,
+        })
+    }
+}
@@ -0,0 +1,140 @@
+//! 以前のバージョンのプレイ履歴の取り込み
+//!
+//! `<id>.jsonl` の `PlayHistory` を日ごとのプレイ時間にまとめる
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::Serialize;
+
+use crate::domain::{
+    file::PlayHistory,
+    play_day::{split_play_time_by_local_date, DayBoundary},
+};
+
+/// 1つのエレメントのプレイ履歴をまとめた結果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FoldedPlayHistory {
+    pub record_count: i32,
+    /// 日時が読めない・時間が 0 以下で取り込まなかった記録の数
+    pub skipped_count: i32,
+    pub total_seconds: i32,
+    pub daily_play_times: Vec<(NaiveDate, i32)>,
+    pub first_played_at: Option<DateTime<Local>>,
+    pub last_played_at: Option<DateTime<Local>>,
+}
+
+/// 各記録を開始日時から分数だけプレイしたものとして、プレイ日ごとに振り分ける
+pub fn fold_play_histories(
+    histories: &[PlayHistory],
+    day_boundary: DayBoundary,
+) -> FoldedPlayHistory {
+    let mut folded = FoldedPlayHistory::default();
+    let mut daily: BTreeMap<NaiveDate, i32> = BTreeMap::new();
+    for history in histories {
+        let seconds = (history.minutes as f64 * 60.0).round() as i32;
+        let Some(started_at) = history.started_at().filter(|_| seconds > 0) else {
+            folded.skipped_count += 1;
+            continue;
+        };
+        let ended_at = started_at + chrono::Duration::seconds(seconds as i64);
+        for (play_date, daily_seconds) in
+            split_play_time_by_local_date(ended_at, seconds, day_boundary)
+        {
+            *daily.entry(play_date).or_default() += daily_seconds;
+        }
+        folded.record_count += 1;
+        folded.total_seconds += seconds;
+        folded.first_played_at = Some(
+            folded
+                .first_played_at
+                .map_or(started_at, |v| v.min(started_at)),
+        );
+        folded.last_played_at = Some(folded.last_played_at.map_or(ended_at, |v| v.max(ended_at)));
+    }
+    folded.daily_play_times = daily.into_iter().collect();
+    folded
+}
+
+/// 取り込みの結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyPlayHistoryImportReport {
+    pub imported_files: i32,
+    pub imported_records: i32,
+    pub imported_seconds: i64,
+    /// 以前に取り込み済みで印だけ付け直したファイル
+    pub already_imported_files: i32,
+    /// エレメントが存在しないなどで取り込めなかったファイル
+    pub skipped_files: i32,
+    pub skipped_records: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn history(minutes: f32, start_date: &str) -> PlayHistory {
+        PlayHistory {
+            minutes,
+            start_date: start_date.to_string(),
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 1, day).unwrap()
+    }
+
+    #[test]
+    fn test_fold_play_histories_by_start_date() {
+        let histories = vec![
+            history(30.0, "2023-01-02T10:00:00"),
+            history(15.5, "2023-01-02T20:00:00"),
+            // 日付をまたぐ記録は開始日と翌日に分ける
+            history(120.0, "2023-01-03T23:00:00"),
+        ];
+
+        let folded = fold_play_histories(&histories, DayBoundary::default());
+
+        assert_eq!(folded.record_count, 3);
+        assert_eq!(folded.skipped_count, 0);
+        assert_eq!(folded.total_seconds, 1800 + 930 + 7200);
+        assert_eq!(
+            folded.daily_play_times,
+            vec![(date(2), 2730), (date(3), 3600), (date(4), 3600)]
+        );
+        assert_eq!(
+            folded.first_played_at,
+            Some(Local.with_ymd_and_hms(2023, 1, 2, 10, 0, 0).unwrap())
+        );
+        assert_eq!(
+            folded.last_played_at,
+            Some(Local.with_ymd_and_hms(2023, 1, 4, 1, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_fold_play_histories_skips_unusable_records() {
+        let histories = vec![
+            history(10.0, "not a date"),
+            history(0.0, "2023-01-02"),
+            history(10.0, "2023-01-02T03:00:00"),
+        ];
+
+        let folded = fold_play_histories(&histories, DayBoundary::parse("04:00").unwrap());
+
+        assert_eq!(folded.record_count, 1);
+        assert_eq!(folded.skipped_count, 2);
+        // 日付の区切りより前のプレイは前日に数える
+        assert_eq!(folded.daily_play_times, vec![(date(1), 600)]);
+    }
+
+    #[test]
+    fn test_fold_play_histories_empty() {
+        assert_eq!(
+            fold_play_histories(&[], DayBoundary::default()),
+            FoldedPlayHistory::default()
+        );
+    }
+}
@@ -0,0 +1,358 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use derive_new::new;
+
+use super::error::UseCaseError;
+use crate::domain::{
+    collection::{CollectionElement, NewCollectionElement},
+    library::{
+        find_library_root, get_match_confidence, is_install_removed,
+        parse_library_auto_register_threshold, LibraryRoot, LibrarySuggestion,
+        NewLibrarySuggestion, RemovedInstall, LIBRARY_AUTO_REGISTER_THRESHOLD_SETTING_KEY,
+    },
+    repository::{
+        collection::CollectionRepository, library::LibraryRepository, repositories::RepositoriesExt,
+    },
+    Id,
+};
+
+/// 新しく見つけたゲームを、一致の確からしさが閾値以上のものとそれ以外の候補に分ける
+///
+/// 監視中のどのフォルダにも含まれないものは除く
+pub fn classify_new_elements(
+    new_elements: Vec<NewCollectionElement>,
+    roots: &[LibraryRoot],
+    threshold: f32,
+) -> (Vec<NewCollectionElement>, Vec<NewLibrarySuggestion>) {
+    let mut registered = vec![];
+    let mut suggestions = vec![];
+    for element in new_elements {
+        let Some(path) = element.exe_path.clone().or(element.lnk_path.clone()) else {
+            continue;
+        };
+        let Some(root) = find_library_root(roots, Path::new(&path)) else {
+            continue;
+        };
+        let confidence = get_match_confidence(&element.gamename, &path);
+        if confidence >= threshold {
+            registered.push(element);
+        } else {
+            suggestions.push(NewLibrarySuggestion::new(
+                root.id.clone(),
+                path,
+                element.id.value,
+                element.gamename,
+                confidence,
+            ));
+        }
+    }
+    (registered, suggestions)
+}
+
+/// 削除されたパスの下にあったインストール先を持つゲーム
+pub fn find_removed_installs(
+    elements: &[CollectionElement],
+    removed_paths: &[PathBuf],
+) -> Vec<(Id<CollectionElement>, String)> {
+    elements
+        .iter()
+        .filter_map(|element| {
+            [element.exe_path.as_ref(), element.lnk_path.as_ref()]
+                .into_iter()
+                .flatten()
+                .find(|path| {
+                    removed_paths
+                        .iter()
+                        .any(|removed| is_install_removed(path, removed))
+                })
+                .map(|path| (element.id.clone(), path.clone()))
+        })
+        .collect()
+}
+
+/// 監視するライブラリのフォルダと、見つけたゲームの候補・削除されたインストール先の管理
+#[derive(new)]
+pub struct LibraryUseCase<R: RepositoriesExt> {
+    repositories: Arc<R>,
+}
+
+impl<R: RepositoriesExt> LibraryUseCase<R> {
+    pub async fn get_roots(&self) -> anyhow::Result<Vec<LibraryRoot>> {
+        self.repositories
+            .library_repository()
+            .get_library_roots()
+            .await
+    }
+
+    pub async fn add_root(&self, path: &str) -> anyhow::Result<Id<LibraryRoot>> {
+        let path = path.trim();
+        if !Path::new(path).is_dir() {
+            return Err(UseCaseError::LibraryRootIsNotDirectory(path.to_string()).into());
+        }
+        let repository = self.repositories.library_repository();
+        if repository.get_library_root_by_path(path).await?.is_some() {
+            return Err(UseCaseError::LibraryRootIsAlreadyExist.into());
+        }
+        repository.add_library_root(path).await
+    }
+
+    pub async fn remove_root(&self, id: &Id<LibraryRoot>) -> anyhow::Result<()> {
+        self.repositories
+            .library_repository()
+            .delete_library_root(id)
+            .await
+    }
+
+    pub async fn get_auto_register_threshold(&self) -> anyhow::Result<f32> {
+        let value = self
+            .repositories
+            .collection_repository()
+            .get_app_setting(LIBRARY_AUTO_REGISTER_THRESHOLD_SETTING_KEY.to_string())
+            .await?;
+        Ok(parse_library_auto_register_threshold(value.as_deref()))
+    }
+
+    /// 監視中のフォルダにあり、まだ登録されていないファイル
+    pub async fn filter_unregistered_files(
+        &self,
+        files: Vec<String>,
+    ) -> anyhow::Result<Vec<String>> {
+        let roots = self.get_roots().await?;
+        let registered: HashSet<String> = self
+            .repositories
+            .collection_repository()
+            .get_all_elements()
+            .await?
+            .into_iter()
+            .flat_map(|element| [element.exe_path, element.lnk_path])
+            .flatten()
+            .collect();
+        Ok(files
+            .into_iter()
+            .filter(|file| {
+                !registered.contains(file) && find_library_root(&roots, Path::new(file)).is_some()
+            })
+            .collect())
+    }
+
+    /// 登録済みのゲームを除き、自動で登録するものと候補に分ける
+    pub async fn classify_new_elements(
+        &self,
+        new_elements: Vec<NewCollectionElement>,
+    ) -> anyhow::Result<(Vec<NewCollectionElement>, Vec<NewLibrarySuggestion>)> {
+        let registered_ids: HashSet<i32> = self
+            .repositories
+            .collection_repository()
+            .get_all_elements()
+            .await?
+            .into_iter()
+            .map(|element| element.id.value)
+            .collect();
+        let new_elements = new_elements
+            .into_iter()
+            .filter(|element| !registered_ids.contains(&element.id.value))
+            .collect();
+        Ok(classify_new_elements(
+            new_elements,
+            &self.get_roots().await?,
+            self.get_auto_register_threshold().await?,
+        ))
+    }
+
+    pub async fn get_suggestions(&self) -> anyhow::Result<Vec<LibrarySuggestion>> {
+        self.repositories
+            .library_repository()
+            .get_library_suggestions()
+            .await
+    }
+
+    pub async fn get_suggestion(
+        &self,
+        id: &Id<LibrarySuggestion>,
+    ) -> anyhow::Result<LibrarySuggestion> {
+        Ok(self
+            .repositories
+            .library_repository()
+            .get_library_suggestion_by_id(id)
+            .await?
+            .ok_or(UseCaseError::LibrarySuggestionIsNotFound)?)
+    }
+
+    pub async fn save_suggestions(
+        &self,
+        suggestions: &[NewLibrarySuggestion],
+    ) -> anyhow::Result<()> {
+        self.repositories
+            .library_repository()
+            .upsert_library_suggestions(suggestions)
+            .await
+    }
+
+    pub async fn dismiss_suggestion(&self, id: &Id<LibrarySuggestion>) -> anyhow::Result<()> {
+        self.repositories
+            .library_repository()
+            .delete_library_suggestion(id)
+            .await
+    }
+
+    /// 登録したゲームの候補と削除済みの印を消す
+    pub async fn mark_registered(&self, game_ids: &[i32]) -> anyhow::Result<()> {
+        let repository = self.repositories.library_repository();
+        repository
+            .delete_library_suggestions_by_game_ids(game_ids)
+            .await?;
+        repository
+            .clear_removed_installs(
+                &game_ids
+                    .iter()
+                    .map(|id| Id::new(*id))
+                    .collect::<Vec<Id<CollectionElement>>>(),
+            )
+            .await
+    }
+
+    pub async fn get_removed_installs(&self) -> anyhow::Result<Vec<RemovedInstall>> {
+        self.repositories
+            .library_repository()
+            .get_removed_installs()
+            .await
+    }
+
+    pub async fn dismiss_removed_install(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<()> {
+        self.repositories
+            .library_repository()
+            .clear_removed_installs(std::slice::from_ref(element_id))
+            .await
+    }
+
+    /// 削除されたパスの下にインストール先があったゲームに印を付け、その数を返す
+    pub async fn flag_removed_installs(&self, removed_paths: &[PathBuf]) -> anyhow::Result<usize> {
+        if removed_paths.is_empty() {
+            return Ok(0);
+        }
+        let elements = self
+            .repositories
+            .collection_repository()
+            .get_all_elements()
+            .await?;
+        let removed = find_removed_installs(&elements, removed_paths);
+        self.repositories
+            .library_repository()
+            .flag_removed_installs(&removed)
+            .await?;
+        Ok(removed.len())
+    }
+
+    /// 監視していない間に削除されたインストール先に印を付け、戻ってきたものの印を消す
+    pub async fn check_removed_installs(&self) -> anyhow::Result<()> {
+        let roots: Vec<PathBuf> = self
+            .get_roots()
+            .await?
+            .into_iter()
+            .map(|root| PathBuf::from(root.path))
+            .collect();
+        self.flag_removed_installs(&roots).await?;
+
+        let restored: Vec<Id<CollectionElement>> = self
+            .get_removed_installs()
+            .await?
+            .into_iter()
+            .filter(|install| Path::new(&install.path).exists())
+            .map(|install| install.collection_element_id)
+            .collect();
+        self.repositories
+            .library_repository()
+            .clear_removed_installs(&restored)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    fn new_element(id: i32, gamename: &str, exe_path: &str) -> NewCollectionElement {
+        NewCollectionElement::new(
+            Id::new(id),
+            gamename.to_string(),
+            Some(exe_path.to_string()),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_classify_new_elements() {
+        let roots = vec![LibraryRoot::new(
+            Id::new(1),
+            "games".to_string(),
+            Local::now(),
+        )];
+        let new_elements = vec![
+            new_element(10, "サクラノ刻", "games/サクラノ刻/game.exe"),
+            new_element(20, "サクラノ刻", "games/tools/sakura_launcher.exe"),
+            new_element(30, "サクラノ刻", "other/サクラノ刻/game.exe"),
+        ];
+
+        let (registered, suggestions) = classify_new_elements(new_elements, &roots, 0.9);
+
+        assert_eq!(registered.len(), 1);
+        assert_eq!(registered[0].id.value, 10);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].game_id, 20);
+        assert_eq!(suggestions[0].library_root_id.value, 1);
+        assert_eq!(suggestions[0].file_path, "games/tools/sakura_launcher.exe");
+        assert!(suggestions[0].confidence < 0.9);
+    }
+
+    #[test]
+    fn test_find_removed_installs() {
+        let dir = std::env::temp_dir().join("launcherg_test_find_removed_installs");
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("kept.exe");
+        std::fs::write(&existing, b"").unwrap();
+        let element = |id: i32, exe_path: &Path| {
+            CollectionElement::new(
+                Id::new(id),
+                "game".to_string(),
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                false,
+                Some(exe_path.to_string_lossy().to_string()),
+                None,
+                None,
+                None,
+                None,
+                None,
+                0,
+                0,
+                None,
+                None,
+                Local::now(),
+                Local::now(),
+                vec![],
+            )
+        };
+        let elements = vec![
+            element(1, &existing),
+            element(2, &dir.join("removed").join("game.exe")),
+            element(3, Path::new("other/game.exe")),
+        ];
+
+        let removed = find_removed_installs(&elements, std::slice::from_ref(&dir));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].0.value, 2);
+    }
+}
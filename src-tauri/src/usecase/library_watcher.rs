@@ -0,0 +1,68 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::domain::library::LibraryRoot;
+
+/// ライブラリのフォルダを監視し、変更のあったパスを受け取り側に送る
+///
+/// フォルダを追加・削除するたびに監視を作り直す。受け取り側は起動時に一度だけ取り出す
+pub struct LibraryWatcher {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    sender: UnboundedSender<Vec<PathBuf>>,
+    receiver: Mutex<Option<UnboundedReceiver<Vec<PathBuf>>>>,
+}
+
+impl LibraryWatcher {
+    pub fn new() -> Self {
+        let (sender, receiver) = unbounded_channel();
+        Self {
+            watcher: Mutex::new(None),
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+        }
+    }
+
+    pub fn take_receiver(&self) -> Option<UnboundedReceiver<Vec<PathBuf>>> {
+        self.receiver.lock().ok().and_then(|mut v| v.take())
+    }
+
+    /// 監視するフォルダを置き換える。存在しないフォルダは飛ばす
+    pub fn watch(&self, roots: &[LibraryRoot]) -> anyhow::Result<()> {
+        let mut current = self
+            .watcher
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Library watcher lock was poisoned"))?;
+        *current = None;
+        if roots.is_empty() {
+            return Ok(());
+        }
+
+        let sender = self.sender.clone();
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<Event, notify::Error>| match res {
+                Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                    let _ = sender.send(event.paths);
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("LibraryWatcher: Watch error: {}", e),
+            },
+            Config::default(),
+        )?;
+        for root in roots {
+            if let Err(e) = watcher.watch(Path::new(&root.path), RecursiveMode::Recursive) {
+                eprintln!("LibraryWatcher: Failed to watch {}: {}", root.path, e);
+            }
+        }
+        *current = Some(watcher);
+        Ok(())
+    }
+}
+
+impl Default for LibraryWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
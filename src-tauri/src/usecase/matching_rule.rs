@@ -0,0 +1,110 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::Local;
+
+use super::error::UseCaseError;
+use crate::domain::{
+    matching_rule::{MatchingRuleOverride, MatchingRules, NewMatchingRuleOverride},
+    repository::{matching_rule::MatchingRuleRepository, repositories::RepositoriesExt},
+    Id,
+};
+
+/// 同梱の規則にユーザーの上書きを重ね、検証する
+pub fn merge_matching_rules(
+    bundled: MatchingRules,
+    overrides: &[MatchingRuleOverride],
+) -> anyhow::Result<MatchingRules> {
+    let rules = bundled.apply_overrides(overrides);
+    rules.validate()?;
+    Ok(rules)
+}
+
+/// ファイルとゲームを紐づける規則と、そのユーザーによる上書きの管理
+pub struct MatchingRuleUseCase<R: RepositoriesExt> {
+    repositories: Arc<R>,
+    /// 走査で使う規則。上書きを読み込むまでは同梱の規則
+    current: Mutex<Arc<MatchingRules>>,
+}
+
+impl<R: RepositoriesExt> MatchingRuleUseCase<R> {
+    pub fn new(repositories: Arc<R>) -> anyhow::Result<Self> {
+        Ok(Self {
+            repositories,
+            current: Mutex::new(Arc::new(MatchingRules::bundled()?)),
+        })
+    }
+
+    /// 走査で使っている規則
+    pub fn current(&self) -> Arc<MatchingRules> {
+        self.current
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub async fn get_overrides(&self) -> anyhow::Result<Vec<MatchingRuleOverride>> {
+        self.repositories
+            .matching_rule_repository()
+            .get_matching_rule_overrides()
+            .await
+    }
+
+    /// 規則を読み込み直し、走査で使う規則を置き換える
+    ///
+    /// 上書きを重ねた規則が不正な場合は同梱の規則を使う
+    pub async fn reload(&self) -> anyhow::Result<MatchingRules> {
+        let bundled = MatchingRules::bundled()?;
+        let overrides = self.get_overrides().await?;
+        let rules = match merge_matching_rules(bundled.clone(), &overrides) {
+            Ok(rules) => rules,
+            Err(e) => {
+                eprintln!("Ignored invalid matching rule overrides: {}", e);
+                bundled
+            }
+        };
+        *self
+            .current
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(rules.clone());
+        Ok(rules)
+    }
+
+    /// 上書きを重ねた規則を検証してから保存する
+    pub async fn add_override(
+        &self,
+        new_override: &NewMatchingRuleOverride,
+    ) -> anyhow::Result<MatchingRules> {
+        new_override
+            .validate()
+            .map_err(|e| UseCaseError::MatchingRuleIsInvalid(e.to_string()))?;
+        let mut overrides = self.get_overrides().await?;
+        overrides.push(MatchingRuleOverride::new(
+            Id::new(0),
+            new_override.kind,
+            new_override.action,
+            new_override.value.clone(),
+            new_override.grandparent.clone(),
+            new_override.game_id,
+            Local::now(),
+        ));
+        merge_matching_rules(MatchingRules::bundled()?, &overrides)
+            .map_err(|e| UseCaseError::MatchingRuleIsInvalid(e.to_string()))?;
+
+        self.repositories
+            .matching_rule_repository()
+            .add_matching_rule_override(new_override)
+            .await?;
+        self.reload().await
+    }
+
+    pub async fn delete_override(
+        &self,
+        id: &Id<MatchingRuleOverride>,
+    ) -> anyhow::Result<MatchingRules> {
+        self.repositories
+            .matching_rule_repository()
+            .delete_matching_rule_override(id)
+            .await?;
+        self.reload().await
+    }
+}
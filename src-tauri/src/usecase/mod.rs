@@ -2,11 +2,23 @@ pub mod all_game_cache;
 pub mod collection;
 pub mod error;
 pub mod explored_cache;
+pub mod external_play_time;
 pub mod file;
 mod file_test;
 pub mod game_tracker;
+pub mod idle_detector;
+pub mod launch_hook;
+pub mod learned_match;
+pub mod legacy_play_history;
+pub mod library;
+pub mod library_watcher;
+pub mod matching_rule;
 pub mod models;
 
 pub mod pause_manager;
+pub mod play_session_editor;
+pub mod play_stats;
+pub mod play_time;
 pub mod process;
 pub mod screenshot_watcher;
+pub mod tag;
@@ -1,86 +1,256 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, MutexGuard};
 
-fn lock_bool(lock: &Mutex<bool>) -> MutexGuard<'_, bool> {
-    lock.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+/// ポーズした理由
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PauseReason {
+    /// ショートカットや画面からの操作
+    Manual,
+    /// 一定時間入力がなかったための自動ポーズ
+    Idle,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TrackingSession {
     pub game_id: i32,
-    pub process_id: u32,
+    /// ゲームのプロセスが見つかるまでは None
+    pub process_id: Option<u32>,
+    pub is_paused: bool,
+    pub pause_reason: Option<PauseReason>,
+}
+
+struct SessionState {
+    process_id: Option<u32>,
+    pause_reason: Option<PauseReason>,
+    stop_token: CancellationToken,
+    started_order: u64,
+}
+
+#[derive(Default)]
+struct State {
+    sessions: HashMap<i32, SessionState>,
+    /// 起動の準備中で、まだセッションを始めていないゲーム
+    reserved: HashSet<i32>,
+    /// ショートカットの対象になるセッション
+    focused_game_id: Option<i32>,
+    next_order: u64,
+}
+
+impl State {
+    fn to_session(game_id: i32, session: &SessionState) -> TrackingSession {
+        TrackingSession {
+            game_id,
+            process_id: session.process_id,
+            is_paused: session.pause_reason.is_some(),
+            pause_reason: session.pause_reason,
+        }
+    }
+
+    /// 明示的に選ばれたセッションがなければ最後に始まったセッションをフォーカスする
+    fn focused_game_id(&self) -> Option<i32> {
+        self.focused_game_id
+            .filter(|id| self.sessions.contains_key(id))
+            .or_else(|| {
+                self.sessions
+                    .iter()
+                    .max_by_key(|(_, session)| session.started_order)
+                    .map(|(id, _)| *id)
+            })
+    }
 }
 
-#[derive(Clone)]
+/// 追跡中のゲームのセッションとポーズ状態を管理する。セッションはエレメントIDごとに持つ
+#[derive(Clone, Default)]
 pub struct PauseManager {
-    is_paused: Arc<Mutex<bool>>,
-    is_tracking: Arc<Mutex<bool>>,
-    tracking_session: Arc<Mutex<Option<TrackingSession>>>,
+    state: Arc<Mutex<State>>,
 }
 
 impl PauseManager {
     pub fn new() -> Self {
-        Self {
-            is_paused: Arc::new(Mutex::new(false)),
-            is_tracking: Arc::new(Mutex::new(false)),
-            tracking_session: Arc::new(Mutex::new(None)),
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 起動の準備を始める前にゲームを予約し、同じゲームの二重起動を防ぐ
+    ///
+    /// 予約は `begin_session` でセッションに引き継ぐか、`release_reservation` で解放する
+    pub fn reserve_session(&self, game_id: i32) -> Result<(), String> {
+        let mut state = self.lock();
+        if state.sessions.contains_key(&game_id) || !state.reserved.insert(game_id) {
+            return Err(format!("Game {} is already being tracked", game_id));
+        }
+        Ok(())
+    }
+
+    pub fn release_reservation(&self, game_id: i32) {
+        self.lock().reserved.remove(&game_id);
+    }
+
+    /// 予約したゲームのセッションを開始する。新しいセッションがフォーカスされる
+    ///
+    /// 返したトークンはセッションの終了要求を受け取る
+    pub fn begin_session(&self, game_id: i32) -> Result<CancellationToken, String> {
+        let mut state = self.lock();
+        if !state.reserved.remove(&game_id) {
+            return Err(format!("Game {} is not reserved", game_id));
         }
+        let token = CancellationToken::new();
+        let started_order = state.next_order;
+        state.next_order += 1;
+        state.sessions.insert(
+            game_id,
+            SessionState {
+                process_id: None,
+                pause_reason: None,
+                stop_token: token.clone(),
+                started_order,
+            },
+        );
+        state.focused_game_id = Some(game_id);
+        Ok(token)
     }
 
-    pub fn is_paused(&self) -> bool {
-        *lock_bool(&self.is_paused)
+    pub fn set_session_process(&self, game_id: i32, process_id: u32) {
+        if let Some(session) = self.lock().sessions.get_mut(&game_id) {
+            session.process_id = Some(process_id);
+        }
     }
 
-    pub fn set_paused(&self, paused: bool) {
-        *lock_bool(&self.is_paused) = paused;
+    pub fn end_session(&self, game_id: i32) {
+        let mut state = self.lock();
+        state.sessions.remove(&game_id);
+        if state.focused_game_id == Some(game_id) {
+            state.focused_game_id = None;
+        }
     }
 
     pub fn is_tracking(&self) -> bool {
-        *lock_bool(&self.is_tracking)
+        !self.lock().sessions.is_empty()
+    }
+
+    pub fn is_tracking_game(&self, game_id: i32) -> bool {
+        self.lock().sessions.contains_key(&game_id)
+    }
+
+    pub fn is_paused(&self, game_id: i32) -> bool {
+        self.lock()
+            .sessions
+            .get(&game_id)
+            .is_some_and(|session| session.pause_reason.is_some())
     }
 
-    pub fn set_tracking(&self, tracking: bool) {
-        *lock_bool(&self.is_tracking) = tracking;
-        // Reset pause state when tracking changes
-        if !tracking {
-            self.set_paused(false);
-            self.clear_tracking_session();
+    pub fn pause_reason(&self, game_id: i32) -> Option<PauseReason> {
+        self.lock()
+            .sessions
+            .get(&game_id)
+            .and_then(|session| session.pause_reason)
+    }
+
+    /// 操作によるポーズを設定する
+    pub fn set_paused(&self, game_id: i32, paused: bool) -> Result<(), String> {
+        match self.lock().sessions.get_mut(&game_id) {
+            Some(session) => {
+                session.pause_reason = paused.then_some(PauseReason::Manual);
+                Ok(())
+            }
+            None => Err("No active game tracking session".to_string()),
         }
     }
 
-    pub fn set_tracking_session(&self, game_id: i32, process_id: u32) {
-        *self
-            .tracking_session
-            .lock()
-            .unwrap_or_else(|poisoned| poisoned.into_inner()) =
-            Some(TrackingSession {
-                game_id,
-                process_id,
-            });
-        self.set_tracking(true);
+    /// ポーズの理由が `current` のままであれば `new` に置き換える。置き換えた場合は true
+    ///
+    /// 自動ポーズの判定中に操作でポーズが切り替えられた場合に上書きしないために使う
+    pub fn replace_pause_reason(
+        &self,
+        game_id: i32,
+        current: Option<PauseReason>,
+        new: Option<PauseReason>,
+    ) -> bool {
+        match self.lock().sessions.get_mut(&game_id) {
+            Some(session) if session.pause_reason == current => {
+                session.pause_reason = new;
+                true
+            }
+            _ => false,
+        }
     }
 
-    pub fn clear_tracking_session(&self) {
-        *self
-            .tracking_session
-            .lock()
-            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    /// 開始順のセッション一覧
+    pub fn sessions(&self) -> Vec<TrackingSession> {
+        let state = self.lock();
+        let mut sessions: Vec<(&i32, &SessionState)> = state.sessions.iter().collect();
+        sessions.sort_by_key(|(_, session)| session.started_order);
+        sessions
+            .into_iter()
+            .map(|(id, session)| State::to_session(*id, session))
+            .collect()
     }
 
-    pub fn tracking_session(&self) -> Option<TrackingSession> {
-        self.tracking_session
-            .lock()
-            .unwrap_or_else(|poisoned| poisoned.into_inner())
-            .clone()
+    pub fn focused_session(&self) -> Option<TrackingSession> {
+        let state = self.lock();
+        let game_id = state.focused_game_id()?;
+        state
+            .sessions
+            .get(&game_id)
+            .map(|session| State::to_session(game_id, session))
     }
 
-    pub fn toggle(&self) -> Result<bool, String> {
-        if !self.is_tracking() {
+    pub fn set_focused_session(&self, game_id: i32) -> Result<(), String> {
+        let mut state = self.lock();
+        if !state.sessions.contains_key(&game_id) {
             return Err("No active game tracking session".to_string());
         }
+        state.focused_game_id = Some(game_id);
+        Ok(())
+    }
+
+    pub fn stop_token(&self, game_id: i32) -> Option<CancellationToken> {
+        self.lock()
+            .sessions
+            .get(&game_id)
+            .map(|session| session.stop_token.clone())
+    }
+
+    /// セッションに終了を要求する。game_id が None の場合はフォーカス中のセッション
+    pub fn request_stop(&self, game_id: Option<i32>) -> Result<(), String> {
+        let state = self.lock();
+        let game_id = game_id
+            .or_else(|| state.focused_game_id())
+            .ok_or_else(|| "No active game tracking session".to_string())?;
+        match state.sessions.get(&game_id) {
+            Some(session) => {
+                session.stop_token.cancel();
+                Ok(())
+            }
+            None => Err("No active game tracking session".to_string()),
+        }
+    }
 
-        let mut paused = lock_bool(&self.is_paused);
-        *paused = !*paused;
-        Ok(*paused)
+    /// ポーズを切り替え、切り替え後の状態と対象のゲームを返す。game_id が None の場合はフォーカス中のセッション
+    pub fn toggle(&self, game_id: Option<i32>) -> Result<(i32, bool), String> {
+        let mut state = self.lock();
+        let game_id = game_id
+            .or_else(|| state.focused_game_id())
+            .ok_or_else(|| "No active game tracking session".to_string())?;
+        match state.sessions.get_mut(&game_id) {
+            Some(session) => {
+                session.pause_reason = match session.pause_reason {
+                    Some(_) => None,
+                    None => Some(PauseReason::Manual),
+                };
+                Ok((game_id, session.pause_reason.is_some()))
+            }
+            None => Err("No active game tracking session".to_string()),
+        }
     }
 }
 
@@ -89,80 +259,164 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_new_creates_unpaused_not_tracking() {
+    fn test_new_creates_not_tracking() {
         let manager = PauseManager::new();
-        assert!(!manager.is_paused());
         assert!(!manager.is_tracking());
+        assert!(!manager.is_paused(1));
+        assert_eq!(manager.focused_session(), None);
     }
 
     #[test]
-    fn test_set_paused() {
+    fn test_set_paused_is_per_session() {
         let manager = PauseManager::new();
+        manager.reserve_session(1).unwrap();
+        manager.begin_session(1).unwrap();
+        manager.reserve_session(2).unwrap();
+        manager.begin_session(2).unwrap();
 
-        manager.set_paused(true);
-        assert!(manager.is_paused());
+        manager.set_paused(1, true).unwrap();
+        assert!(manager.is_paused(1));
+        assert!(!manager.is_paused(2));
 
-        manager.set_paused(false);
-        assert!(!manager.is_paused());
+        manager.set_paused(1, false).unwrap();
+        assert!(!manager.is_paused(1));
+        assert!(manager.set_paused(3, true).is_err());
     }
 
     #[test]
-    fn test_set_tracking_resets_pause_when_stopped() {
+    fn test_begin_session_rejects_same_game() {
         let manager = PauseManager::new();
 
-        manager.set_tracking(true);
-        manager.set_tracking_session(12, 34);
-        manager.set_paused(true);
-        assert!(manager.is_paused());
-        assert_eq!(
-            manager.tracking_session(),
-            Some(TrackingSession {
-                game_id: 12,
-                process_id: 34
-            })
-        );
+        manager.reserve_session(1).unwrap();
+        assert!(manager.begin_session(1).is_ok());
+        assert!(manager.reserve_session(1).is_err());
+        assert!(manager.begin_session(1).is_err());
+    }
+
+    #[test]
+    fn test_reserve_session_rejects_same_game_until_released() {
+        let manager = PauseManager::new();
 
-        manager.set_tracking(false);
-        assert!(!manager.is_paused());
-        assert_eq!(manager.tracking_session(), None);
+        manager.reserve_session(1).unwrap();
+        assert!(manager.reserve_session(1).is_err());
+        assert!(!manager.is_tracking());
+
+        manager.release_reservation(1);
+        manager.reserve_session(1).unwrap();
+        manager.begin_session(1).unwrap();
+        assert!(manager.is_tracking_game(1));
+        assert!(manager.reserve_session(1).is_err());
+        // 予約はセッションに引き継いだので二度は使えない
+        assert!(manager.begin_session(1).is_err());
     }
 
     #[test]
-    fn test_set_tracking_session_marks_tracking() {
+    fn test_end_session_keeps_other_sessions() {
         let manager = PauseManager::new();
+        manager.reserve_session(12).unwrap();
+        manager.begin_session(12).unwrap();
+        manager.reserve_session(13).unwrap();
+        manager.begin_session(13).unwrap();
+        manager.set_session_process(12, 34);
+        manager.set_paused(12, true).unwrap();
 
-        manager.set_tracking_session(1, 2);
+        manager.end_session(13);
 
         assert!(manager.is_tracking());
+        assert!(!manager.is_tracking_game(13));
         assert_eq!(
-            manager.tracking_session(),
-            Some(TrackingSession {
-                game_id: 1,
-                process_id: 2
-            })
+            manager.sessions(),
+            vec![TrackingSession {
+                game_id: 12,
+                process_id: Some(34),
+                is_paused: true,
+                pause_reason: Some(PauseReason::Manual),
+            }]
         );
+
+        manager.end_session(12);
+        assert!(!manager.is_tracking());
+        assert!(!manager.is_paused(12));
+    }
+
+    #[test]
+    fn test_focused_session_defaults_to_latest() {
+        let manager = PauseManager::new();
+        manager.reserve_session(1).unwrap();
+        manager.begin_session(1).unwrap();
+        manager.reserve_session(2).unwrap();
+        manager.begin_session(2).unwrap();
+        assert_eq!(manager.focused_session().map(|v| v.game_id), Some(2));
+
+        manager.set_focused_session(1).unwrap();
+        assert_eq!(manager.focused_session().map(|v| v.game_id), Some(1));
+
+        manager.end_session(1);
+        assert_eq!(manager.focused_session().map(|v| v.game_id), Some(2));
+        assert!(manager.set_focused_session(1).is_err());
     }
 
     #[test]
     fn test_toggle_fails_when_not_tracking() {
         let manager = PauseManager::new();
 
-        let result = manager.toggle();
+        let result = manager.toggle(None);
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), "No active game tracking session");
     }
 
     #[test]
-    fn test_toggle_switches_pause_state() {
+    fn test_toggle_switches_focused_pause_state() {
+        let manager = PauseManager::new();
+        manager.reserve_session(1).unwrap();
+        manager.begin_session(1).unwrap();
+        manager.reserve_session(2).unwrap();
+        manager.begin_session(2).unwrap();
+
+        assert_eq!(manager.toggle(None), Ok((2, true)));
+        assert_eq!(manager.toggle(None), Ok((2, false)));
+        assert_eq!(manager.toggle(Some(1)), Ok((1, true)));
+        assert!(!manager.is_paused(2));
+    }
+
+    #[test]
+    fn test_request_stop_fails_when_not_tracking() {
+        let manager = PauseManager::new();
+
+        assert!(manager.request_stop(None).is_err());
+    }
+
+    #[test]
+    fn test_request_stop_cancels_only_target_session() {
+        let manager = PauseManager::new();
+        manager.reserve_session(1).unwrap();
+        let token1 = manager.begin_session(1).unwrap();
+        manager.reserve_session(2).unwrap();
+        let token2 = manager.begin_session(2).unwrap();
+
+        assert!(manager.request_stop(Some(1)).is_ok());
+        assert!(token1.is_cancelled());
+        assert!(!token2.is_cancelled());
+
+        assert!(manager.request_stop(None).is_ok());
+        assert!(token2.is_cancelled());
+    }
+
+    #[test]
+    fn test_replace_pause_reason_only_when_unchanged() {
         let manager = PauseManager::new();
-        manager.set_tracking(true);
+        manager.reserve_session(1).unwrap();
+        manager.begin_session(1).unwrap();
 
-        let result1 = manager.toggle();
-        assert!(result1.is_ok());
-        assert!(result1.unwrap()); // false -> true
+        assert!(manager.replace_pause_reason(1, None, Some(PauseReason::Idle)));
+        assert!(manager.is_paused(1));
+        assert_eq!(manager.pause_reason(1), Some(PauseReason::Idle));
 
-        let result2 = manager.toggle();
-        assert!(result2.is_ok());
-        assert!(!result2.unwrap()); // true -> false
+        // 操作でポーズを解除した後は自動ポーズの解除で上書きしない
+        assert_eq!(manager.toggle(Some(1)), Ok((1, false)));
+        assert!(!manager.replace_pause_reason(1, Some(PauseReason::Idle), None));
+        assert_eq!(manager.toggle(Some(1)), Ok((1, true)));
+        assert_eq!(manager.pause_reason(1), Some(PauseReason::Manual));
+        assert!(!manager.replace_pause_reason(2, None, Some(PauseReason::Idle)));
     }
 }
@@ -0,0 +1,343 @@
+//! プレイセッションの手動編集モジュール
+//!
+//! 過去のセッションの登録・編集・削除・分割を、合計と日ごとのプレイ時間、
+//! 最初と最後のプレイ日時の変更量に変換する
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local, NaiveDate};
+
+use crate::domain::{
+    collection::CollectionElement,
+    play_day::{split_played_ranges_by_local_date, DayBoundary},
+    play_session::{
+        recompute_play_at, ManualPlaySession, PlaySession, PlaySessionAudit, PlaySessionEdit,
+        PlaySessionEditAction, PlayedRange,
+    },
+    Id,
+};
+
+/// 編集の対象になるエレメントの現在の記録
+#[derive(Debug, Clone)]
+pub struct ElementPlayState {
+    pub element_id: Id<CollectionElement>,
+    pub first_play_at: Option<DateTime<Local>>,
+    pub last_play_at: Option<DateTime<Local>>,
+    pub daily_play_times: Vec<(NaiveDate, i32)>,
+    pub sessions: Vec<PlaySession>,
+}
+
+/// `removed` を削除して `added` を登録する編集の内容を求める
+///
+/// 日ごとのプレイ時間は、セッションのプレイしていた範囲があれば範囲で振り分ける。
+/// `elements` には removed と added のすべてのエレメントの現在の記録を渡す
+pub fn build_play_session_edit(
+    action: PlaySessionEditAction,
+    removed: Vec<PlaySession>,
+    added: Vec<ManualPlaySession>,
+    elements: &[ElementPlayState],
+    day_boundary: DayBoundary,
+    undo_of: Option<Id<PlaySessionAudit>>,
+) -> PlaySessionEdit {
+    let mut totals: BTreeMap<i32, i32> = BTreeMap::new();
+    let mut daily: BTreeMap<(i32, NaiveDate), i32> = BTreeMap::new();
+    let mut apply = |element_id: i32,
+                     played_ranges: &[PlayedRange],
+                     ended_at: DateTime<Local>,
+                     seconds: i32,
+                     sign: i32| {
+        *totals.entry(element_id).or_default() += sign * seconds;
+        for (play_date, daily_seconds) in
+            split_played_ranges_by_local_date(played_ranges, ended_at, seconds, day_boundary)
+        {
+            *daily.entry((element_id, play_date)).or_default() += sign * daily_seconds;
+        }
+    };
+    for session in removed.iter() {
+        if let Some(ended_at) = session.ended_at {
+            apply(
+                session.collection_element_id.value,
+                &session.played_ranges,
+                ended_at,
+                session.played_seconds,
+                -1,
+            );
+        }
+    }
+    for session in added.iter() {
+        apply(
+            session.collection_element_id.value,
+            &session.played_ranges,
+            session.ended_at,
+            session.played_seconds,
+            1,
+        );
+    }
+
+    let play_at = elements
+        .iter()
+        .map(|element| {
+            let id = element.element_id.value;
+            let sessions: Vec<PlaySession> = element
+                .sessions
+                .iter()
+                .filter(|v| !removed.iter().any(|r| r.id.value == v.id.value))
+                .cloned()
+                .chain(
+                    added
+                        .iter()
+                        .filter(|v| v.collection_element_id.value == id)
+                        .map(|v| v.to_play_session(Id::new(0))),
+                )
+                .collect();
+            let mut daily_after: BTreeMap<NaiveDate, i32> =
+                element.daily_play_times.iter().copied().collect();
+            for ((_, play_date), seconds) in
+                daily.range((id, NaiveDate::MIN)..=(id, NaiveDate::MAX))
+            {
+                *daily_after.entry(*play_date).or_default() += seconds;
+            }
+            let earliest_play_date = daily_after
+                .into_iter()
+                .find(|(_, seconds)| *seconds > 0)
+                .map(|(play_date, _)| play_date);
+            let (first_play_at, last_play_at) = recompute_play_at(
+                &sessions,
+                element.first_play_at,
+                element.last_play_at,
+                earliest_play_date,
+                day_boundary,
+            );
+            (element.element_id.clone(), first_play_at, last_play_at)
+        })
+        .collect();
+
+    PlaySessionEdit {
+        action,
+        removed,
+        added,
+        play_time_deltas: totals
+            .into_iter()
+            .filter(|(_, seconds)| *seconds != 0)
+            .map(|(id, seconds)| (Id::new(id), seconds))
+            .collect(),
+        daily_play_time_deltas: daily
+            .into_iter()
+            .filter(|(_, seconds)| *seconds != 0)
+            .map(|((id, play_date), seconds)| (Id::new(id), play_date, seconds))
+            .collect(),
+        play_at,
+        undo_of,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 1, day, hour, 0, 0).unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 1, day).unwrap()
+    }
+
+    fn session(id: i32, element_id: i32, started_at: DateTime<Local>, hours: i64) -> PlaySession {
+        PlaySession::new(
+            Id::new(id),
+            Id::new(element_id),
+            started_at,
+            Some(started_at + chrono::Duration::hours(hours)),
+            None,
+            None,
+            (hours * 3600) as i32,
+            0,
+        )
+    }
+
+    fn element(element_id: i32, sessions: Vec<PlaySession>) -> ElementPlayState {
+        let daily_play_times = sessions
+            .iter()
+            .map(|v| (v.started_at.date_naive(), v.played_seconds))
+            .collect();
+        ElementPlayState {
+            element_id: Id::new(element_id),
+            first_play_at: sessions.first().map(|v| v.started_at),
+            last_play_at: sessions.last().and_then(|v| v.ended_at),
+            daily_play_times,
+            sessions,
+        }
+    }
+
+    #[test]
+    fn test_build_add_edit_updates_totals_and_play_at() {
+        let existing = session(1, 1, at(5, 10), 1);
+        let elements = vec![element(1, vec![existing.clone()])];
+        let added =
+            ManualPlaySession::new(None, Id::new(1), at(2, 20), at(2, 22), None, None, 7200, 0);
+
+        let edit = build_play_session_edit(
+            PlaySessionEditAction::Add,
+            vec![],
+            vec![added],
+            &elements,
+            DayBoundary::default(),
+            None,
+        );
+
+        assert_eq!(
+            edit.play_time_deltas
+                .iter()
+                .map(|(id, seconds)| (id.value, *seconds))
+                .collect::<Vec<_>>(),
+            vec![(1, 7200)]
+        );
+        assert_eq!(
+            edit.daily_play_time_deltas
+                .iter()
+                .map(|(id, play_date, seconds)| (id.value, *play_date, *seconds))
+                .collect::<Vec<_>>(),
+            vec![(1, date(2), 7200)]
+        );
+        assert_eq!(edit.play_at.len(), 1);
+        assert_eq!(edit.play_at[0].1, Some(at(2, 20)));
+        assert_eq!(edit.play_at[0].2, existing.ended_at);
+    }
+
+    #[test]
+    fn test_build_delete_edit_clears_play_at() {
+        let existing = session(1, 1, at(5, 10), 1);
+        let elements = vec![element(1, vec![existing.clone()])];
+
+        let edit = build_play_session_edit(
+            PlaySessionEditAction::Delete,
+            vec![existing],
+            vec![],
+            &elements,
+            DayBoundary::default(),
+            None,
+        );
+
+        assert_eq!(edit.play_time_deltas[0].1, -3600);
+        assert_eq!(edit.daily_play_time_deltas[0].2, -3600);
+        assert_eq!(edit.play_at[0].1, None);
+        assert_eq!(edit.play_at[0].2, None);
+    }
+
+    #[test]
+    fn test_build_split_edit_moves_time_between_elements() {
+        let existing = session(1, 1, at(5, 10), 2);
+        let elements = vec![
+            element(1, vec![existing.clone()]),
+            element(2, vec![session(2, 2, at(1, 10), 1)]),
+        ];
+        let (first, second) =
+            crate::domain::play_session::split_play_session(&existing, at(5, 11), Id::new(2))
+                .unwrap();
+
+        let edit = build_play_session_edit(
+            PlaySessionEditAction::Split,
+            vec![existing],
+            vec![first, second],
+            &elements,
+            DayBoundary::default(),
+            None,
+        );
+
+        assert_eq!(
+            edit.play_time_deltas
+                .iter()
+                .map(|(id, seconds)| (id.value, *seconds))
+                .collect::<Vec<_>>(),
+            vec![(1, -3600), (2, 3600)]
+        );
+        assert_eq!(edit.play_at[0].2, Some(at(5, 11)));
+        assert_eq!(edit.play_at[1].1, Some(at(1, 10)));
+        assert_eq!(edit.play_at[1].2, Some(at(5, 12)));
+    }
+
+    #[test]
+    fn test_build_edit_uses_day_boundary() {
+        let elements = vec![element(1, vec![])];
+        let added =
+            ManualPlaySession::new(None, Id::new(1), at(2, 1), at(2, 3), None, None, 7200, 0);
+
+        let edit = build_play_session_edit(
+            PlaySessionEditAction::Add,
+            vec![],
+            vec![added],
+            &elements,
+            DayBoundary::parse("04:00").unwrap(),
+            None,
+        );
+
+        assert_eq!(edit.daily_play_time_deltas[0].1, date(1));
+    }
+
+    #[test]
+    fn test_build_delete_edit_uses_played_ranges_of_paused_session() {
+        // 22:00 から 02:00 までのうち 23:00 から 01:00 までポーズしていた追跡のセッション
+        let mut existing = session(1, 1, at(1, 22), 4);
+        existing.pid = Some(100);
+        existing.played_seconds = 7200;
+        existing.paused_seconds = 7200;
+        existing.played_ranges = vec![(at(1, 22), at(1, 23)), (at(2, 1), at(2, 2))];
+        let elements = vec![ElementPlayState {
+            daily_play_times: vec![(date(1), 3600), (date(2), 3600)],
+            ..element(1, vec![existing.clone()])
+        }];
+        let daily_deltas = |edit: &PlaySessionEdit| {
+            edit.daily_play_time_deltas
+                .iter()
+                .map(|(id, play_date, seconds)| (id.value, *play_date, *seconds))
+                .collect::<Vec<_>>()
+        };
+
+        let delete = build_play_session_edit(
+            PlaySessionEditAction::Delete,
+            vec![existing.clone()],
+            vec![],
+            &elements,
+            DayBoundary::default(),
+            None,
+        );
+        // 終了まで続けてプレイしたものとして 2 日に 2 時間を減らさない
+        assert_eq!(
+            daily_deltas(&delete),
+            vec![(1, date(1), -3600), (1, date(2), -3600)]
+        );
+
+        let undo = build_play_session_edit(
+            PlaySessionEditAction::Undo,
+            vec![],
+            vec![ManualPlaySession::try_from(&existing).unwrap()],
+            &[element(1, vec![])],
+            DayBoundary::default(),
+            Some(Id::new(1)),
+        );
+        assert_eq!(
+            daily_deltas(&undo),
+            vec![(1, date(1), 3600), (1, date(2), 3600)]
+        );
+        assert_eq!(undo.added[0].played_ranges, existing.played_ranges);
+
+        // ポーズ中の 00:00 で分けると、後半に移るのは 2 日にプレイした 1 時間だけ
+        let (first, second) =
+            crate::domain::play_session::split_play_session(&existing, at(2, 0), Id::new(2))
+                .unwrap();
+        let split = build_play_session_edit(
+            PlaySessionEditAction::Split,
+            vec![existing],
+            vec![first, second],
+            &[elements[0].clone(), element(2, vec![])],
+            DayBoundary::default(),
+            None,
+        );
+        assert_eq!(
+            daily_deltas(&split),
+            vec![(1, date(2), -3600), (2, date(2), 3600)]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use derive_new::new;
+
+use super::error::UseCaseError;
+use crate::domain::{
+    collection::CollectionElement,
+    play_day::{DayBoundary, DAY_BOUNDARY_SETTING_KEY},
+    play_session::PlaySessionSummary,
+    play_stats::{GamePlayTime, GroupPlayTime, PlayStatsPeriod, PlayStreak, PlayTimeBucket},
+    repository::{
+        collection::CollectionRepository, play_stats::PlayStatsRepository,
+        repositories::RepositoriesExt,
+    },
+    Id,
+};
+
+/// プレイ時間の統計
+///
+/// 期間はプレイ日で指定し、`from` と `to` はどちらもその日を含む。
+/// プレイ日ごとの記録は日付の区切りで振り分け済みのため、セッションの集計も同じ区切りで期間を求める
+#[derive(new)]
+pub struct PlayStatsUseCase<R: RepositoriesExt> {
+    repositories: Arc<R>,
+}
+
+impl<R: RepositoriesExt> PlayStatsUseCase<R> {
+    fn validate_range(from: NaiveDate, to: NaiveDate) -> anyhow::Result<()> {
+        if from > to {
+            return Err(UseCaseError::InvalidPlayStatsRange.into());
+        }
+        Ok(())
+    }
+
+    pub async fn get_play_time_buckets(
+        &self,
+        period: PlayStatsPeriod,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<Vec<PlayTimeBucket>> {
+        Self::validate_range(from, to)?;
+        self.repositories
+            .play_stats_repository()
+            .get_play_time_buckets(period, from, to)
+            .await
+    }
+
+    pub async fn get_top_games(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: i32,
+    ) -> anyhow::Result<Vec<GamePlayTime>> {
+        Self::validate_range(from, to)?;
+        if limit <= 0 {
+            return Ok(vec![]);
+        }
+        self.repositories
+            .play_stats_repository()
+            .get_top_games(from, to, limit)
+            .await
+    }
+
+    pub async fn get_brand_play_times(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<Vec<GroupPlayTime>> {
+        Self::validate_range(from, to)?;
+        self.repositories
+            .play_stats_repository()
+            .get_brand_play_times(from, to)
+            .await
+    }
+
+    pub async fn get_sellday_year_play_times(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<Vec<GroupPlayTime>> {
+        Self::validate_range(from, to)?;
+        self.repositories
+            .play_stats_repository()
+            .get_sellday_year_play_times(from, to)
+            .await
+    }
+
+    pub async fn get_longest_play_streak(
+        &self,
+        element_id: Option<&Id<CollectionElement>>,
+    ) -> anyhow::Result<Option<PlayStreak>> {
+        self.repositories
+            .play_stats_repository()
+            .get_longest_play_streak(element_id)
+            .await
+    }
+
+    /// 期間内に終了したセッションの回数と平均の長さ
+    pub async fn get_play_session_summary(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<PlaySessionSummary> {
+        Self::validate_range(from, to)?;
+        let day_boundary = DayBoundary::from_setting(
+            self.repositories
+                .collection_repository()
+                .get_app_setting(DAY_BOUNDARY_SETTING_KEY.to_string())
+                .await?
+                .as_deref(),
+        );
+        let (since, until) = day_boundary
+            .date_range(from, to)
+            .ok_or(UseCaseError::InvalidPlayStatsRange)?;
+        self.repositories
+            .play_stats_repository()
+            .get_play_session_summary(since, until)
+            .await
+    }
+}
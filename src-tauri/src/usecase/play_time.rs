@@ -0,0 +1,327 @@
+//! プレイ時間の計測モジュール
+//!
+//! 監視ループの tick ごとの経過時間をプレイ時間・ポーズ時間に振り分ける。
+//! スリープや休止状態で tick の間隔が大きく空いた場合はその時間をプレイ時間として扱わない
+
+use tokio::time::{Duration, Instant};
+
+/// 現在時刻の取得元。テストでは時刻を進められる実装に差し替える
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+pub const SUSPEND_GAP_POLICY_SETTING_KEY: &str = "suspend_gap_policy";
+
+/// スリープとみなした空白時間の扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SuspendGapPolicy {
+    /// 空白時間をすべて捨てる
+    #[default]
+    Discard,
+    /// tick 1回分の時間だけをプレイ時間として数える
+    Cap,
+}
+
+impl SuspendGapPolicy {
+    /// アプリ設定の値から読み込む。未設定や不明な値の場合は Discard
+    pub fn from_setting(value: Option<&str>) -> Self {
+        match value {
+            Some("cap") => SuspendGapPolicy::Cap,
+            _ => SuspendGapPolicy::Discard,
+        }
+    }
+}
+
+/// 1回の tick で振り分けた時間
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TickAccounting {
+    pub played_seconds: i32,
+    pub paused_seconds: i32,
+    /// スリープとみなして記録しなかった時間
+    pub suspended_seconds: i32,
+}
+
+/// tick の経過時間をプレイ時間・ポーズ時間・スリープ時間に振り分ける
+pub struct PlayTimeAccumulator {
+    accounted_until: Instant,
+    tick_interval: Duration,
+    policy: SuspendGapPolicy,
+}
+
+impl PlayTimeAccumulator {
+    /// tick の間隔がこの倍数を超えたらスリープとみなす
+    pub const SUSPEND_GAP_FACTOR: u32 = 3;
+
+    pub fn new(started_at: Instant, tick_interval: Duration, policy: SuspendGapPolicy) -> Self {
+        Self {
+            accounted_until: started_at,
+            tick_interval,
+            policy,
+        }
+    }
+
+    pub fn suspend_threshold(&self) -> Duration {
+        self.tick_interval * Self::SUSPEND_GAP_FACTOR
+    }
+
+    pub fn advance(&mut self, now: Instant, is_paused: bool) -> TickAccounting {
+        let elapsed = now.saturating_duration_since(self.accounted_until);
+        self.accounted_until = now;
+        let seconds = elapsed.as_secs() as i32;
+
+        if elapsed > self.suspend_threshold() {
+            let credited = match self.policy {
+                SuspendGapPolicy::Discard => 0,
+                SuspendGapPolicy::Cap => self.tick_interval.as_secs() as i32,
+            };
+            let mut accounting = TickAccounting {
+                suspended_seconds: seconds - credited,
+                ..Default::default()
+            };
+            if is_paused {
+                accounting.paused_seconds = credited;
+            } else {
+                accounting.played_seconds = credited;
+            }
+            return accounting;
+        }
+
+        if is_paused {
+            TickAccounting {
+                paused_seconds: seconds,
+                ..Default::default()
+            }
+        } else {
+            TickAccounting {
+                played_seconds: seconds,
+                ..Default::default()
+            }
+        }
+    }
+
+    /// 起動してから監視を始めるまでの時間を振り分ける
+    ///
+    /// ランチャーの待ち時間やプロセスの検索で空いた時間のため、tick の間隔が空いてもスリープとみなさない
+    pub fn advance_launch(&mut self, now: Instant, is_paused: bool) -> TickAccounting {
+        let seconds = now
+            .saturating_duration_since(self.accounted_until)
+            .as_secs() as i32;
+        self.accounted_until = self.accounted_until.max(now);
+        if is_paused {
+            TickAccounting {
+                paused_seconds: seconds,
+                ..Default::default()
+            }
+        } else {
+            TickAccounting {
+                played_seconds: seconds,
+                ..Default::default()
+            }
+        }
+    }
+
+    /// `last_alive_at` までを振り分け、そこから `now` までの時間は記録せずに進める
+    ///
+    /// ゲームのプロセスが見つからなかった tick で、最後に動いていた時刻より後を記録しないために使う
+    pub fn advance_until(
+        &mut self,
+        last_alive_at: Instant,
+        now: Instant,
+        is_paused: bool,
+    ) -> TickAccounting {
+        let accounting = if last_alive_at > self.accounted_until {
+            self.advance(last_alive_at, is_paused)
+        } else {
+            TickAccounting::default()
+        };
+        self.accounted_until = self.accounted_until.max(now);
+        accounting
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct FakeClock {
+        now: Mutex<Instant>,
+    }
+
+    impl FakeClock {
+        fn new() -> Self {
+            Self {
+                now: Mutex::new(Instant::now()),
+            }
+        }
+
+        fn advance(&self, duration: Duration) {
+            *self.now.lock().unwrap() += duration;
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            *self.now.lock().unwrap()
+        }
+    }
+
+    fn accumulator(clock: &FakeClock, policy: SuspendGapPolicy) -> PlayTimeAccumulator {
+        PlayTimeAccumulator::new(clock.now(), Duration::from_secs(10), policy)
+    }
+
+    #[test]
+    fn test_advance_counts_regular_ticks() {
+        let clock = FakeClock::new();
+        let mut accumulator = accumulator(&clock, SuspendGapPolicy::Discard);
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(
+            accumulator.advance(clock.now(), false),
+            TickAccounting {
+                played_seconds: 10,
+                ..Default::default()
+            }
+        );
+
+        clock.advance(Duration::from_secs(11));
+        assert_eq!(
+            accumulator.advance(clock.now(), true),
+            TickAccounting {
+                paused_seconds: 11,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_advance_launch_credits_long_process_search() {
+        let clock = FakeClock::new();
+        let mut accumulator = accumulator(&clock, SuspendGapPolicy::Discard);
+
+        // プロセスの検索に 3 分かかってもスリープとはみなさない
+        clock.advance(Duration::from_secs(181));
+        assert_eq!(
+            accumulator.advance_launch(clock.now(), false),
+            TickAccounting {
+                played_seconds: 181,
+                ..Default::default()
+            }
+        );
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(
+            accumulator.advance(clock.now(), false),
+            TickAccounting {
+                played_seconds: 10,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_advance_until_skips_time_after_last_alive() {
+        let clock = FakeClock::new();
+        let mut accumulator = accumulator(&clock, SuspendGapPolicy::Discard);
+        let last_alive_at = clock.now() + Duration::from_secs(4);
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(
+            accumulator.advance_until(last_alive_at, clock.now(), false),
+            TickAccounting {
+                played_seconds: 4,
+                ..Default::default()
+            }
+        );
+
+        // 最後に動いていた時刻より後は、次の tick でも記録しない
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(
+            accumulator.advance_until(last_alive_at, clock.now(), false),
+            TickAccounting::default()
+        );
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(
+            accumulator.advance(clock.now(), false),
+            TickAccounting {
+                played_seconds: 10,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_advance_discards_suspend_gap() {
+        let clock = FakeClock::new();
+        let mut accumulator = accumulator(&clock, SuspendGapPolicy::Discard);
+
+        clock.advance(Duration::from_secs(8 * 60 * 60));
+        assert_eq!(
+            accumulator.advance(clock.now(), false),
+            TickAccounting {
+                suspended_seconds: 8 * 60 * 60,
+                ..Default::default()
+            }
+        );
+
+        // 復帰後は通常どおり数える
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(accumulator.advance(clock.now(), false).played_seconds, 10);
+    }
+
+    #[test]
+    fn test_advance_caps_suspend_gap() {
+        let clock = FakeClock::new();
+        let mut accumulator = accumulator(&clock, SuspendGapPolicy::Cap);
+
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(
+            accumulator.advance(clock.now(), false),
+            TickAccounting {
+                played_seconds: 10,
+                suspended_seconds: 3590,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_advance_threshold_is_inclusive() {
+        let clock = FakeClock::new();
+        let mut accumulator = accumulator(&clock, SuspendGapPolicy::Discard);
+
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(accumulator.advance(clock.now(), false).played_seconds, 30);
+
+        clock.advance(Duration::from_secs(31));
+        assert_eq!(
+            accumulator.advance(clock.now(), false).suspended_seconds,
+            31
+        );
+    }
+
+    #[test]
+    fn test_suspend_gap_policy_from_setting() {
+        assert_eq!(
+            SuspendGapPolicy::from_setting(Some("cap")),
+            SuspendGapPolicy::Cap
+        );
+        assert_eq!(
+            SuspendGapPolicy::from_setting(Some("discard")),
+            SuspendGapPolicy::Discard
+        );
+        assert_eq!(
+            SuspendGapPolicy::from_setting(None),
+            SuspendGapPolicy::Discard
+        );
+    }
+}
@@ -11,17 +11,51 @@ use crate::domain::repository::repositories::RepositoriesExt;
 use crate::domain::repository::screenshot::ScreenshotRepository;
 use crate::domain::Id;
 
+use super::pause_manager::PauseManager;
+
+/// スクリーンショットフォルダを監視し、追加された画像を追跡中のゲームに取り込む
+///
+/// フォルダの監視は追跡中のゲームで共有し、画像はフォーカス中のセッションのゲームに取り込む
 #[derive(new, Clone)]
 pub struct ScreenshotWatcher<R: RepositoriesExt> {
     repositories: Arc<R>,
+    pause_manager: Arc<PauseManager>,
     watcher: Arc<Mutex<Option<RecommendedWatcher>>>,
+    /// 監視を開始した順のゲーム
+    game_ids: Arc<Mutex<Vec<i32>>>,
+}
+
+/// スクリーンショットを取り込むゲームを決める。フォーカス中のゲームがなければ最後に監視を始めたゲーム
+pub fn resolve_screenshot_target(focused_game_id: Option<i32>, game_ids: &[i32]) -> Option<i32> {
+    focused_game_id
+        .filter(|id| game_ids.contains(id))
+        .or_else(|| game_ids.last().copied())
 }
 
 impl<R: RepositoriesExt + Send + Sync + 'static> ScreenshotWatcher<R> {
     pub fn start_watching(&self, handle: Arc<AppHandle>, game_id: i32) -> anyhow::Result<()> {
+        {
+            let mut game_ids = self
+                .game_ids
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Screenshot watcher lock was poisoned"))?;
+            if !game_ids.contains(&game_id) {
+                game_ids.push(game_id);
+            }
+        }
+        let is_watching = self
+            .watcher
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Screenshot watcher lock was poisoned"))?
+            .is_some();
+        if is_watching {
+            return Ok(());
+        }
+
         let repositories = self.repositories.clone();
+        let pause_manager = self.pause_manager.clone();
+        let game_ids = self.game_ids.clone();
         let handle = handle.clone();
-        let game_id: Id<crate::domain::collection::CollectionElement> = Id::new(game_id);
 
         let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
 
@@ -102,6 +136,21 @@ impl<R: RepositoriesExt + Send + Sync + 'static> ScreenshotWatcher<R> {
                                         // Wait a bit for the file to be fully written
                                         tokio::time::sleep(Duration::from_secs(1)).await;
 
+                                        let target = game_ids.lock().ok().and_then(|ids| {
+                                            resolve_screenshot_target(
+                                                pause_manager
+                                                    .focused_session()
+                                                    .map(|session| session.game_id),
+                                                &ids,
+                                            )
+                                        });
+                                        let Some(target) = target else {
+                                            continue;
+                                        };
+                                        let game_id: Id<
+                                            crate::domain::collection::CollectionElement,
+                                        > = Id::new(target);
+
                                         // Copy to game folder
                                         match copy_screenshot(
                                             &handle,
@@ -136,7 +185,21 @@ impl<R: RepositoriesExt + Send + Sync + 'static> ScreenshotWatcher<R> {
         Ok(())
     }
 
-    pub fn stop_watching(&self) {
+    /// ゲームの監視を終了する。監視中のゲームがなくなったらフォルダの監視も止める
+    pub fn stop_watching(&self, game_id: i32) {
+        let is_empty = match self.game_ids.lock() {
+            Ok(mut game_ids) => {
+                game_ids.retain(|id| *id != game_id);
+                game_ids.is_empty()
+            }
+            Err(e) => {
+                eprintln!("ScreenshotWatcher: Failed to stop watcher: {}", e);
+                return;
+            }
+        };
+        if !is_empty {
+            return;
+        }
         match self.watcher.lock() {
             Ok(mut watcher) => {
                 *watcher = None;
@@ -188,3 +251,20 @@ async fn copy_screenshot<R: RepositoriesExt>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_screenshot_target_prefers_focused_game() {
+        assert_eq!(resolve_screenshot_target(Some(1), &[1, 2]), Some(1));
+    }
+
+    #[test]
+    fn test_resolve_screenshot_target_falls_back_to_latest() {
+        assert_eq!(resolve_screenshot_target(Some(3), &[1, 2]), Some(2));
+        assert_eq!(resolve_screenshot_target(None, &[1, 2]), Some(2));
+        assert_eq!(resolve_screenshot_target(None, &[]), None);
+    }
+}
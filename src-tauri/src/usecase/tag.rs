@@ -0,0 +1,270 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use derive_new::new;
+
+use super::error::UseCaseError;
+use crate::domain::{
+    collection::CollectionElement,
+    repository::{
+        collection::CollectionRepository, repositories::RepositoriesExt, tag::TagRepository,
+    },
+    tag::{Tag, TagExpression},
+    Id,
+};
+
+#[derive(new)]
+pub struct TagUseCase<R: RepositoriesExt> {
+    repositories: Arc<R>,
+}
+
+impl<R: RepositoriesExt> TagUseCase<R> {
+    pub async fn get_all_tags(&self) -> anyhow::Result<Vec<Tag>> {
+        self.repositories.tag_repository().get_all().await
+    }
+
+    pub async fn get_element_tags(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<Vec<Tag>> {
+        self.repositories
+            .tag_repository()
+            .get_by_element_id(element_id)
+            .await
+    }
+
+    pub async fn add_element_tags(
+        &self,
+        element_id: &Id<CollectionElement>,
+        names: Vec<String>,
+    ) -> anyhow::Result<()> {
+        self.ensure_element_exists(element_id).await?;
+        for name in normalize_tag_names(names)? {
+            let tag_id = self
+                .repositories
+                .tag_repository()
+                .get_or_create(&name)
+                .await?;
+            self.repositories
+                .tag_repository()
+                .add_to_element(element_id, &tag_id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn remove_element_tag(
+        &self,
+        element_id: &Id<CollectionElement>,
+        name: String,
+    ) -> anyhow::Result<()> {
+        let tag = self.get_tag_by_name(&name).await?;
+        self.repositories
+            .tag_repository()
+            .remove_from_element(element_id, &tag.id)
+            .await
+    }
+
+    /// 1つのエレメントに付いているタグだけを別のタグに付け替える
+    pub async fn rename_element_tag(
+        &self,
+        element_id: &Id<CollectionElement>,
+        from: String,
+        to: String,
+    ) -> anyhow::Result<()> {
+        let to = validate_tag_name(&to)?;
+        let from = self.get_tag_by_name(&from).await?;
+        let to_id = self
+            .repositories
+            .tag_repository()
+            .get_or_create(&to)
+            .await?;
+        self.repositories
+            .tag_repository()
+            .remove_from_element(element_id, &from.id)
+            .await?;
+        self.repositories
+            .tag_repository()
+            .add_to_element(element_id, &to_id)
+            .await
+    }
+
+    /// ライブラリ全体でタグ名を変更する。変更先の名前がすでにある場合は `merge_tags` を使う
+    pub async fn rename_tag(&self, id: &Id<Tag>, name: String) -> anyhow::Result<Tag> {
+        let name = validate_tag_name(&name)?;
+        let current = self.get_tag_by_id(id).await?;
+        if let Some(existed) = self
+            .repositories
+            .tag_repository()
+            .get_by_name(&name)
+            .await?
+        {
+            if existed.id.value != current.id.value {
+                return Err(UseCaseError::TagIsAlreadyExist.into());
+            }
+        }
+        self.repositories.tag_repository().rename(id, &name).await?;
+        self.get_tag_by_id(id).await
+    }
+
+    pub async fn merge_tags(&self, source: &Id<Tag>, target: &Id<Tag>) -> anyhow::Result<Tag> {
+        self.get_tag_by_id(source).await?;
+        self.get_tag_by_id(target).await?;
+        if source.value != target.value {
+            self.repositories
+                .tag_repository()
+                .merge(source, target)
+                .await?;
+        }
+        self.get_tag_by_id(target).await
+    }
+
+    pub async fn delete_tag(&self, id: &Id<Tag>) -> anyhow::Result<()> {
+        self.get_tag_by_id(id).await?;
+        self.repositories.tag_repository().delete(id).await
+    }
+
+    pub async fn get_element_ids_by_tag_expression(
+        &self,
+        expression: String,
+    ) -> anyhow::Result<Vec<Id<CollectionElement>>> {
+        let expression =
+            TagExpression::parse(&expression).map_err(UseCaseError::InvalidTagExpression)?;
+        let element_ids = self
+            .repositories
+            .collection_repository()
+            .get_all_element_ids()
+            .await?;
+        let element_tag_names = self
+            .repositories
+            .tag_repository()
+            .get_element_tag_names()
+            .await?;
+        Ok(filter_element_ids_by_tag_expression(
+            &expression,
+            element_ids,
+            &element_tag_names,
+        ))
+    }
+
+    async fn get_tag_by_id(&self, id: &Id<Tag>) -> anyhow::Result<Tag> {
+        Ok(self
+            .repositories
+            .tag_repository()
+            .get_by_id(id)
+            .await?
+            .ok_or(UseCaseError::TagIsNotFound)?)
+    }
+
+    async fn get_tag_by_name(&self, name: &str) -> anyhow::Result<Tag> {
+        Ok(self
+            .repositories
+            .tag_repository()
+            .get_by_name(name.trim())
+            .await?
+            .ok_or(UseCaseError::TagIsNotFound)?)
+    }
+
+    async fn ensure_element_exists(
+        &self,
+        element_id: &Id<CollectionElement>,
+    ) -> anyhow::Result<()> {
+        self.repositories
+            .collection_repository()
+            .get_element_by_element_id(element_id)
+            .await?
+            .ok_or(UseCaseError::CollectionElementIsNotFound)?;
+        Ok(())
+    }
+}
+
+fn validate_tag_name(name: &str) -> anyhow::Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(UseCaseError::TagNameIsEmpty.into());
+    }
+    Ok(name.to_string())
+}
+
+fn normalize_tag_names(names: Vec<String>) -> anyhow::Result<Vec<String>> {
+    let mut seen = HashSet::new();
+    let mut normalized = vec![];
+    for name in names {
+        let name = validate_tag_name(&name)?;
+        if seen.insert(name.clone()) {
+            normalized.push(name);
+        }
+    }
+    Ok(normalized)
+}
+
+fn filter_element_ids_by_tag_expression(
+    expression: &TagExpression,
+    element_ids: Vec<Id<CollectionElement>>,
+    element_tag_names: &[(Id<CollectionElement>, String)],
+) -> Vec<Id<CollectionElement>> {
+    let mut tags_by_element: HashMap<i32, HashSet<&str>> = HashMap::new();
+    for (element_id, name) in element_tag_names.iter() {
+        tags_by_element
+            .entry(element_id.value)
+            .or_default()
+            .insert(name.as_str());
+    }
+    let empty = HashSet::new();
+    let mut matched: Vec<Id<CollectionElement>> = element_ids
+        .into_iter()
+        .filter(|id| expression.matches(tags_by_element.get(&id.value).unwrap_or(&empty)))
+        .collect();
+    matched.sort_by_key(|v| v.value);
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tag_names() {
+        let names = normalize_tag_names(vec![
+            " 泣きゲー ".to_string(),
+            "club-pick".to_string(),
+            "泣きゲー".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(names, vec!["泣きゲー", "club-pick"]);
+    }
+
+    #[test]
+    fn test_normalize_tag_names_rejects_empty() {
+        let err = normalize_tag_names(vec!["a".to_string(), "  ".to_string()]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::TagNameIsEmpty)
+        ));
+    }
+
+    #[test]
+    fn test_filter_element_ids_by_tag_expression() {
+        let element_tag_names = vec![
+            (Id::new(1), "泣きゲー".to_string()),
+            (Id::new(1), "club-pick".to_string()),
+            (Id::new(2), "泣きゲー".to_string()),
+            (Id::new(3), "needs patch".to_string()),
+        ];
+        let element_ids = vec![Id::new(3), Id::new(2), Id::new(1), Id::new(4)];
+
+        let expression = TagExpression::parse("泣きゲー AND NOT club-pick").unwrap();
+        let ids = filter_element_ids_by_tag_expression(
+            &expression,
+            element_ids.clone(),
+            &element_tag_names,
+        );
+        assert_eq!(ids.iter().map(|v| v.value).collect::<Vec<_>>(), vec![2]);
+
+        // タグが1つも付いていないエレメントも NOT の対象になる
+        let expression = TagExpression::parse("NOT 泣きゲー").unwrap();
+        let ids =
+            filter_element_ids_by_tag_expression(&expression, element_ids, &element_tag_names);
+        assert_eq!(ids.iter().map(|v| v.value).collect::<Vec<_>>(), vec![3, 4]);
+    }
+}